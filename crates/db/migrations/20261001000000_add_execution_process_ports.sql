@@ -0,0 +1,16 @@
+-- Ports allocated to dev server execution processes.
+-- A row exists only while the process is running; it is deleted when the
+-- process stops so the port returns to the pool.
+CREATE TABLE execution_process_ports (
+    execution_process_id BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    repo_id              BLOB NOT NULL,
+    port                 INTEGER NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_execution_process_ports_port ON execution_process_ports(port);
+CREATE INDEX idx_execution_process_ports_workspace_id ON execution_process_ports(workspace_id);
@@ -0,0 +1,13 @@
+-- Dev server readiness detection, health checks and auto-restart
+
+-- Per-repo settings: custom listening-URL regex, health check path and
+-- whether to restart the dev server when it exits unexpectedly
+ALTER TABLE repos ADD COLUMN dev_server_ready_pattern TEXT;
+ALTER TABLE repos ADD COLUMN dev_server_health_path TEXT;
+ALTER TABLE repos ADD COLUMN dev_server_auto_restart INTEGER NOT NULL DEFAULT 0;
+
+-- Runtime state reported on the dev server's execution process
+ALTER TABLE execution_processes ADD COLUMN ready_url TEXT;
+ALTER TABLE execution_processes ADD COLUMN ready_at TEXT;
+ALTER TABLE execution_processes ADD COLUMN health_status TEXT
+    CHECK (health_status IN ('healthy', 'unhealthy'));
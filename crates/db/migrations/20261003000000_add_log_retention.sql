@@ -0,0 +1,3 @@
+-- Log retention: remember which execution processes have already had their
+-- raw logs pruned (or compacted down to the normalized conversation)
+ALTER TABLE execution_processes ADD COLUMN logs_pruned_at TEXT;
@@ -0,0 +1,14 @@
+-- Execution process logs stored as zstd-compressed JSONL chunks instead of one
+-- row per line. Legacy rows in execution_process_logs are migrated in the
+-- background and stay readable until then.
+CREATE TABLE execution_process_log_chunks (
+    execution_id      BLOB NOT NULL,
+    chunk_index       INTEGER NOT NULL,
+    byte_offset       INTEGER NOT NULL,   -- offset of the chunk in the uncompressed JSONL
+    byte_size         INTEGER NOT NULL,   -- uncompressed size
+    line_count        INTEGER NOT NULL,
+    data              BLOB NOT NULL,      -- zstd frame
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (execution_id, chunk_index),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
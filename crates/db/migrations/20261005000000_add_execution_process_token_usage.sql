@@ -0,0 +1,16 @@
+-- Tokens billed per model for each execution process, as last reported by the
+-- agent. Executor and variant are copied from the process's action so usage can
+-- be priced and grouped without parsing executor_action.
+CREATE TABLE execution_process_token_usage (
+    execution_process_id BLOB NOT NULL,
+    model                TEXT NOT NULL,
+    executor             TEXT NOT NULL,
+    variant              TEXT,
+    input_tokens         INTEGER NOT NULL DEFAULT 0,   -- excludes cache reads
+    output_tokens        INTEGER NOT NULL DEFAULT 0,
+    cached_input_tokens  INTEGER NOT NULL DEFAULT 0,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (execution_process_id, model),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
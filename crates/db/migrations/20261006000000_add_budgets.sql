@@ -0,0 +1,26 @@
+-- Token and cost budgets. Each budget has exactly one scope: a project, a task
+-- or an executor profile (executor plus optional variant). Project and task
+-- budgets cap the usage of all their execution processes combined, profile
+-- budgets cap each execution process run with that profile.
+CREATE TABLE budgets (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB,
+    task_id     BLOB,
+    executor    TEXT,
+    variant     TEXT,
+    unit        TEXT NOT NULL CHECK (unit IN ('tokens', 'usd')),
+    soft_limit  REAL,
+    hard_limit  REAL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    CHECK ((project_id IS NOT NULL) + (task_id IS NOT NULL) + (executor IS NOT NULL) = 1),
+    CHECK (variant IS NULL OR executor IS NOT NULL),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_budgets_project_id ON budgets(project_id);
+CREATE INDEX idx_budgets_task_id ON budgets(task_id);
+
+-- Why a process was stopped by vibe-kanban rather than finishing on its own
+ALTER TABLE execution_processes ADD COLUMN completion_reason TEXT;
@@ -0,0 +1,13 @@
+-- Follow-up messages waiting for the session's running turn to finish, sent
+-- one at a time in position order
+CREATE TABLE queued_messages (
+    id          BLOB PRIMARY KEY,
+    session_id  BLOB NOT NULL,
+    position    INTEGER NOT NULL,
+    data        TEXT NOT NULL,   -- DraftFollowUpData JSON
+    queued_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_queued_messages_session_position ON queued_messages(session_id, position);
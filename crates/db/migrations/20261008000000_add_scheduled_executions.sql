@@ -0,0 +1,15 @@
+-- Coding agent starts waiting for a free slot under the concurrency limits.
+-- Their execution processes already exist with status 'running'; a row here
+-- means the agent has not been spawned yet. Higher priority goes first, then
+-- the oldest start.
+CREATE TABLE scheduled_executions (
+    execution_process_id BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    executor             TEXT NOT NULL,
+    priority             INTEGER NOT NULL DEFAULT 0,
+    queued_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_scheduled_executions_order ON scheduled_executions(priority DESC, queued_at);
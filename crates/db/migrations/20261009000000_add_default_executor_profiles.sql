@@ -0,0 +1,4 @@
+-- Default coding agent profile (an ExecutorProfileId as JSON) for workspaces
+-- of a project or repository, taking precedence over the global default
+ALTER TABLE projects ADD COLUMN default_executor_profile_id TEXT;
+ALTER TABLE repos ADD COLUMN default_executor_profile_id TEXT;
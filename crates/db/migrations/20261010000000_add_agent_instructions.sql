@@ -0,0 +1,4 @@
+-- Instructions handed to coding agents in workspaces of a project or
+-- repository, alongside the task prompt
+ALTER TABLE projects ADD COLUMN agent_instructions TEXT;
+ALTER TABLE repos ADD COLUMN agent_instructions TEXT;
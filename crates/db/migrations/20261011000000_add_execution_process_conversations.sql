@@ -0,0 +1,9 @@
+-- Normalized conversation of a finished execution process, stored when its run
+-- ends so exports and handoffs don't have to re-normalize the raw logs.
+CREATE TABLE execution_process_conversations (
+    execution_id      BLOB NOT NULL PRIMARY KEY,
+    entry_count       INTEGER NOT NULL,
+    data              BLOB NOT NULL,      -- zstd frame of compacted JsonPatch JSONL
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
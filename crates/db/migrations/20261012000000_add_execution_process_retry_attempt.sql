@@ -0,0 +1,3 @@
+-- Which automatic retry after a transient provider error a coding agent run is,
+-- 0 for runs that aren't one
+ALTER TABLE execution_processes ADD COLUMN retry_attempt INTEGER NOT NULL DEFAULT 0;
@@ -100,6 +100,31 @@ impl DBService {
         Ok(DBService { pool })
     }
 
+    /// Return pages freed by deleted rows to the filesystem. The first call switches
+    /// the database to incremental auto-vacuum, which takes one full `VACUUM`; later
+    /// calls only release the free pages.
+    pub async fn reclaim_space(&self) -> Result<(), Error> {
+        // 2 = INCREMENTAL, see https://www.sqlite.org/pragma.html#pragma_auto_vacuum
+        let auto_vacuum: i64 = sqlx::query_scalar("PRAGMA auto_vacuum")
+            .fetch_one(&self.pool)
+            .await?;
+
+        if auto_vacuum == 2 {
+            sqlx::query("PRAGMA incremental_vacuum")
+                .execute(&self.pool)
+                .await?;
+        } else {
+            tracing::info!("Switching database to incremental auto-vacuum, running full VACUUM");
+            let mut conn = self.pool.acquire().await?;
+            sqlx::query("PRAGMA auto_vacuum = INCREMENTAL")
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query("VACUUM").execute(&mut *conn).await?;
+        }
+
+        Ok(())
+    }
+
     async fn create_pool<F>(after_connect: Option<Arc<F>>) -> Result<Pool<Sqlite>, Error>
     where
         F: for<'a> Fn(
@@ -0,0 +1,260 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum BudgetError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Budget not found")]
+    NotFound,
+    #[error("Validation error: {0}")]
+    ValidationError(String),
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "budget_unit", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetUnit {
+    /// Input plus output tokens; cache reads are not counted
+    Tokens,
+    /// Cost priced with the configured `token_prices`
+    Usd,
+}
+
+/// Usage cap for a project, a task or an executor profile. Project and task
+/// budgets cover all their execution processes together, profile budgets each
+/// process run with the profile.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Budget {
+    pub id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub executor: Option<String>,
+    /// Only with `executor`; unset covers every variant
+    pub variant: Option<String>,
+    pub unit: BudgetUnit,
+    /// Usage at which the agent is warned
+    pub soft_limit: Option<f64>,
+    /// Usage at which the agent is stopped
+    pub hard_limit: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateBudget {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub executor: Option<String>,
+    pub variant: Option<String>,
+    pub unit: BudgetUnit,
+    pub soft_limit: Option<f64>,
+    pub hard_limit: Option<f64>,
+}
+
+/// Replaces the limits of a budget; its scope can't change
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateBudget {
+    pub unit: BudgetUnit,
+    pub soft_limit: Option<f64>,
+    pub hard_limit: Option<f64>,
+}
+
+fn validate_limits(soft_limit: Option<f64>, hard_limit: Option<f64>) -> Result<(), BudgetError> {
+    if soft_limit.is_none() && hard_limit.is_none() {
+        return Err(BudgetError::ValidationError(
+            "A budget needs a soft or hard limit".to_string(),
+        ));
+    }
+    if [soft_limit, hard_limit]
+        .into_iter()
+        .flatten()
+        .any(|limit| !limit.is_finite() || limit <= 0.0)
+    {
+        return Err(BudgetError::ValidationError(
+            "Budget limits must be positive".to_string(),
+        ));
+    }
+    if let (Some(soft), Some(hard)) = (soft_limit, hard_limit)
+        && soft > hard
+    {
+        return Err(BudgetError::ValidationError(
+            "The soft limit can't exceed the hard limit".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl Budget {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Budget,
+            r#"SELECT
+                id as "id!: Uuid",
+                project_id as "project_id?: Uuid",
+                task_id as "task_id?: Uuid",
+                executor,
+                variant,
+                unit as "unit!: BudgetUnit",
+                soft_limit,
+                hard_limit,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM budgets
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Budget,
+            r#"SELECT
+                id as "id!: Uuid",
+                project_id as "project_id?: Uuid",
+                task_id as "task_id?: Uuid",
+                executor,
+                variant,
+                unit as "unit!: BudgetUnit",
+                soft_limit,
+                hard_limit,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM budgets
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Budgets covering a process of `task_id` in `project_id` run with the given
+    /// executor profile
+    pub async fn find_applicable(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        task_id: Uuid,
+        executor: &str,
+        variant: Option<&str>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Budget,
+            r#"SELECT
+                id as "id!: Uuid",
+                project_id as "project_id?: Uuid",
+                task_id as "task_id?: Uuid",
+                executor,
+                variant,
+                unit as "unit!: BudgetUnit",
+                soft_limit,
+                hard_limit,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM budgets
+               WHERE project_id = $1
+                  OR task_id = $2
+                  OR (executor = $3 AND (variant IS NULL OR variant = $4))
+               ORDER BY created_at ASC"#,
+            project_id,
+            task_id,
+            executor,
+            variant
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreateBudget) -> Result<Self, BudgetError> {
+        let scopes = [
+            data.project_id.is_some(),
+            data.task_id.is_some(),
+            data.executor.is_some(),
+        ];
+        if scopes.into_iter().filter(|set| *set).count() != 1 {
+            return Err(BudgetError::ValidationError(
+                "A budget applies to exactly one of a project, a task or an executor".to_string(),
+            ));
+        }
+        if data.variant.is_some() && data.executor.is_none() {
+            return Err(BudgetError::ValidationError(
+                "A variant needs an executor".to_string(),
+            ));
+        }
+        validate_limits(data.soft_limit, data.hard_limit)?;
+
+        let id = Uuid::new_v4();
+        let budget = sqlx::query_as!(
+            Budget,
+            r#"INSERT INTO budgets (id, project_id, task_id, executor, variant, unit, soft_limit, hard_limit)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING
+                id as "id!: Uuid",
+                project_id as "project_id?: Uuid",
+                task_id as "task_id?: Uuid",
+                executor,
+                variant,
+                unit as "unit!: BudgetUnit",
+                soft_limit,
+                hard_limit,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.task_id,
+            data.executor,
+            data.variant,
+            data.unit,
+            data.soft_limit,
+            data.hard_limit
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(budget)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateBudget,
+    ) -> Result<Self, BudgetError> {
+        validate_limits(data.soft_limit, data.hard_limit)?;
+
+        sqlx::query_as!(
+            Budget,
+            r#"UPDATE budgets
+               SET unit = $2, soft_limit = $3, hard_limit = $4, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING
+                id as "id!: Uuid",
+                project_id as "project_id?: Uuid",
+                task_id as "task_id?: Uuid",
+                executor,
+                variant,
+                unit as "unit!: BudgetUnit",
+                soft_limit,
+                hard_limit,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.unit,
+            data.soft_limit,
+            data.hard_limit
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(BudgetError::NotFound)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM budgets WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -60,6 +60,35 @@ pub enum ExecutionProcessRunReason {
     DevServer,
 }
 
+/// Result of the latest HTTP health check against a dev server
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "dev_server_health", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum DevServerHealth {
+    Healthy,
+    Unhealthy,
+}
+
+/// Why a process was stopped without the user asking for it
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(
+    type_name = "execution_process_completion_reason",
+    rename_all = "snake_case"
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionProcessCompletionReason {
+    /// A hard budget limit was reached
+    BudgetExceeded,
+    /// The process ran longer than its maximum runtime
+    TimedOut,
+    /// The process went too long without printing anything
+    IdleTimeout,
+    /// A process was killed for going over its memory limit
+    OutOfMemory,
+    /// The process failed after hitting its process-count limit
+    ProcessLimit,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct ExecutionProcess {
     pub id: Uuid,
@@ -73,6 +102,15 @@ pub struct ExecutionProcess {
     /// history view (due to restore/trimming). Hidden from logs/timeline;
     /// still listed in the Processes tab.
     pub dropped: bool,
+    /// Listening URL detected in a dev server's output; set once it is ready.
+    pub ready_url: Option<String>,
+    pub ready_at: Option<DateTime<Utc>>,
+    /// Only set for dev servers with a health check path configured.
+    pub health_status: Option<DevServerHealth>,
+    /// Set when the process was stopped on a limit rather than by the user or on its own.
+    pub completion_reason: Option<ExecutionProcessCompletionReason>,
+    /// Automatic retry after a transient provider error this run is, 0 if it isn't one
+    pub retry_attempt: i64,
     pub started_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
@@ -145,6 +183,11 @@ impl ExecutionProcess {
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
                     ep.dropped as "dropped!: bool",
+                    ep.ready_url,
+                    ep.ready_at as "ready_at?: DateTime<Utc>",
+                    ep.health_status as "health_status?: DevServerHealth",
+                    ep.completion_reason as "completion_reason?: ExecutionProcessCompletionReason",
+                    ep.retry_attempt as "retry_attempt!: i64",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
@@ -219,6 +262,11 @@ impl ExecutionProcess {
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
                     ep.dropped as "dropped!: bool",
+                    ep.ready_url,
+                    ep.ready_at as "ready_at?: DateTime<Utc>",
+                    ep.health_status as "health_status?: DevServerHealth",
+                    ep.completion_reason as "completion_reason?: ExecutionProcessCompletionReason",
+                    ep.retry_attempt as "retry_attempt!: i64",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
@@ -246,6 +294,11 @@ impl ExecutionProcess {
                       ep.status          as "status!: ExecutionProcessStatus",
                       ep.exit_code,
                       ep.dropped as "dropped!: bool",
+                      ep.ready_url,
+                      ep.ready_at as "ready_at?: DateTime<Utc>",
+                      ep.health_status as "health_status?: DevServerHealth",
+                      ep.completion_reason as "completion_reason?: ExecutionProcessCompletionReason",
+                      ep.retry_attempt as "retry_attempt!: i64",
                       ep.started_at      as "started_at!: DateTime<Utc>",
                       ep.completed_at    as "completed_at?: DateTime<Utc>",
                       ep.created_at      as "created_at!: DateTime<Utc>",
@@ -273,6 +326,11 @@ impl ExecutionProcess {
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
                     ep.dropped as "dropped!: bool",
+                    ep.ready_url,
+                    ep.ready_at as "ready_at?: DateTime<Utc>",
+                    ep.health_status as "health_status?: DevServerHealth",
+                    ep.completion_reason as "completion_reason?: ExecutionProcessCompletionReason",
+                    ep.retry_attempt as "retry_attempt!: i64",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
@@ -292,7 +350,7 @@ impl ExecutionProcess {
             ExecutionProcess,
             r#"SELECT ep.id as "id!: Uuid", ep.session_id as "session_id!: Uuid", ep.run_reason as "run_reason!: ExecutionProcessRunReason", ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                       ep.status as "status!: ExecutionProcessStatus", ep.exit_code,
-                      ep.dropped as "dropped!: bool", ep.started_at as "started_at!: DateTime<Utc>", ep.completed_at as "completed_at?: DateTime<Utc>", ep.created_at as "created_at!: DateTime<Utc>", ep.updated_at as "updated_at!: DateTime<Utc>"
+                      ep.dropped as "dropped!: bool", ep.ready_url, ep.ready_at as "ready_at?: DateTime<Utc>", ep.health_status as "health_status?: DevServerHealth", ep.completion_reason as "completion_reason?: ExecutionProcessCompletionReason", ep.retry_attempt as "retry_attempt!: i64", ep.started_at as "started_at!: DateTime<Utc>", ep.completed_at as "completed_at?: DateTime<Utc>", ep.created_at as "created_at!: DateTime<Utc>", ep.updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
                JOIN workspaces w ON s.workspace_id = w.id
@@ -340,6 +398,11 @@ impl ExecutionProcess {
             ep.status as "status!: ExecutionProcessStatus",
             ep.exit_code,
             ep.dropped as "dropped!: bool",
+            ep.ready_url,
+            ep.ready_at as "ready_at?: DateTime<Utc>",
+            ep.health_status as "health_status?: DevServerHealth",
+            ep.completion_reason as "completion_reason?: ExecutionProcessCompletionReason",
+            ep.retry_attempt as "retry_attempt!: i64",
             ep.started_at as "started_at!: DateTime<Utc>",
             ep.completed_at as "completed_at?: DateTime<Utc>",
             ep.created_at as "created_at!: DateTime<Utc>",
@@ -373,6 +436,11 @@ impl ExecutionProcess {
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
                     ep.dropped as "dropped!: bool",
+                    ep.ready_url,
+                    ep.ready_at as "ready_at?: DateTime<Utc>",
+                    ep.health_status as "health_status?: DevServerHealth",
+                    ep.completion_reason as "completion_reason?: ExecutionProcessCompletionReason",
+                    ep.retry_attempt as "retry_attempt!: i64",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
@@ -403,6 +471,11 @@ impl ExecutionProcess {
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
                     ep.dropped as "dropped!: bool",
+                    ep.ready_url,
+                    ep.ready_at as "ready_at?: DateTime<Utc>",
+                    ep.health_status as "health_status?: DevServerHealth",
+                    ep.completion_reason as "completion_reason?: ExecutionProcessCompletionReason",
+                    ep.retry_attempt as "retry_attempt!: i64",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
@@ -500,6 +573,84 @@ impl ExecutionProcess {
         Ok(())
     }
 
+    /// Mark a dev server as ready, keeping the first detected URL
+    pub async fn mark_ready(pool: &SqlitePool, id: Uuid, url: &str) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET ready_url = $1, ready_at = $2, updated_at = $2
+               WHERE id = $3 AND ready_at IS NULL"#,
+            url,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record the outcome of a dev server health check
+    pub async fn update_health_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        health_status: DevServerHealth,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET health_status = $1, updated_at = $2
+               WHERE id = $3"#,
+            health_status,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_completion_reason(
+        pool: &SqlitePool,
+        id: Uuid,
+        reason: ExecutionProcessCompletionReason,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET completion_reason = $1, updated_at = $2
+               WHERE id = $3"#,
+            reason,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_retry_attempt(
+        pool: &SqlitePool,
+        id: Uuid,
+        attempt: i64,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET retry_attempt = $1, updated_at = $2
+               WHERE id = $3"#,
+            attempt,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub fn executor_action(&self) -> Result<&ExecutorAction, anyhow::Error> {
         match &self.executor_action.0 {
             ExecutorActionField::ExecutorAction(action) => Ok(action),
@@ -630,6 +781,11 @@ impl ExecutionProcess {
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
                     ep.dropped as "dropped!: bool",
+                    ep.ready_url,
+                    ep.ready_at as "ready_at?: DateTime<Utc>",
+                    ep.health_status as "health_status?: DevServerHealth",
+                    ep.completion_reason as "completion_reason?: ExecutionProcessCompletionReason",
+                    ep.retry_attempt as "retry_attempt!: i64",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
@@ -0,0 +1,72 @@
+use std::io;
+
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use super::execution_process_log_chunk::{COMPRESSION_LEVEL, LogChunkError, parse_jsonl};
+
+/// The compacted normalized conversation of a finished execution process, stored as a
+/// zstd-compressed JSONL of `JsonPatch` messages so it can be read back without
+/// re-running the executor's log normalizer.
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessConversation {
+    pub execution_id: Uuid,
+    pub entry_count: i64,
+    pub data: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExecutionProcessConversation {
+    /// Decompress and parse the stored conversation patches
+    pub fn messages(&self) -> Result<Vec<LogMsg>, LogChunkError> {
+        let bytes = zstd::decode_all(self.data.as_slice())?;
+        let jsonl = String::from_utf8(bytes).map_err(|e| {
+            LogChunkError::Compression(io::Error::new(io::ErrorKind::InvalidData, e))
+        })?;
+        Ok(parse_jsonl(&jsonl)?)
+    }
+
+    pub async fn find_by_execution_id(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessConversation,
+            r#"SELECT
+                execution_id as "execution_id!: Uuid",
+                entry_count,
+                data,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM execution_process_conversations
+               WHERE execution_id = $1"#,
+            execution_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Store (or replace) the conversation of an execution process from its JSONL
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        jsonl: &str,
+    ) -> Result<(), LogChunkError> {
+        let data = zstd::encode_all(jsonl.as_bytes(), COMPRESSION_LEVEL)?;
+        let entry_count = jsonl.lines().count() as i64;
+        sqlx::query!(
+            r#"INSERT INTO execution_process_conversations (execution_id, entry_count, data)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(execution_id) DO UPDATE SET
+                   entry_count = excluded.entry_count,
+                   data = excluded.data"#,
+            execution_id,
+            entry_count,
+            data
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+use std::io;
+
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, FromRow, Sqlite, SqliteConnection, SqlitePool};
+use thiserror::Error;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+/// Uncompressed size at which a chunk is sealed and the next one started
+pub const CHUNK_TARGET_BYTES: usize = 64 * 1024;
+
+pub(crate) const COMPRESSION_LEVEL: i32 = 3;
+
+#[derive(Debug, Error)]
+pub enum LogChunkError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Failed to compress or decompress log chunk: {0}")]
+    Compression(#[from] io::Error),
+    #[error("Invalid log line: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A zstd-compressed block of JSONL log lines for an execution process.
+/// `byte_offset` and `byte_size` locate the block within the uncompressed log.
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessLogChunk {
+    pub execution_id: Uuid,
+    pub chunk_index: i64,
+    pub byte_offset: i64,
+    pub byte_size: i64,
+    pub line_count: i64,
+    pub data: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExecutionProcessLogChunk {
+    /// Decompress the chunk back into JSONL
+    pub fn jsonl(&self) -> Result<String, LogChunkError> {
+        let bytes = zstd::decode_all(self.data.as_slice())?;
+        String::from_utf8(bytes)
+            .map_err(|e| LogChunkError::Compression(io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
+
+    /// Decompress and parse the chunk's log messages
+    pub fn messages(&self) -> Result<Vec<LogMsg>, LogChunkError> {
+        Ok(parse_jsonl(&self.jsonl()?)?)
+    }
+
+    pub async fn find_by_execution_id<'e, E>(
+        executor: E,
+        execution_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as!(
+            ExecutionProcessLogChunk,
+            r#"SELECT
+                execution_id as "execution_id!: Uuid",
+                chunk_index,
+                byte_offset,
+                byte_size,
+                line_count,
+                data,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM execution_process_log_chunks
+               WHERE execution_id = $1
+               ORDER BY chunk_index ASC"#,
+            execution_id
+        )
+        .fetch_all(executor)
+        .await
+    }
+
+    pub async fn find_by_index(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        chunk_index: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessLogChunk,
+            r#"SELECT
+                execution_id as "execution_id!: Uuid",
+                chunk_index,
+                byte_offset,
+                byte_size,
+                line_count,
+                data,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM execution_process_log_chunks
+               WHERE execution_id = $1 AND chunk_index = $2"#,
+            execution_id,
+            chunk_index
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Write (or rewrite) a chunk from its uncompressed JSONL
+    pub async fn upsert<'e, E>(
+        executor: E,
+        execution_id: Uuid,
+        chunk_index: i64,
+        byte_offset: i64,
+        jsonl: &str,
+    ) -> Result<(), LogChunkError>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let data = zstd::encode_all(jsonl.as_bytes(), COMPRESSION_LEVEL)?;
+        let byte_size = jsonl.len() as i64;
+        let line_count = jsonl.lines().count() as i64;
+        sqlx::query!(
+            r#"INSERT INTO execution_process_log_chunks
+                   (execution_id, chunk_index, byte_offset, byte_size, line_count, data)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT(execution_id, chunk_index) DO UPDATE SET
+                   byte_size = excluded.byte_size,
+                   line_count = excluded.line_count,
+                   data = excluded.data"#,
+            execution_id,
+            chunk_index,
+            byte_offset,
+            byte_size,
+            line_count,
+            data
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store JSONL as new chunks after the existing ones, split at line boundaries
+    pub async fn insert_jsonl(
+        conn: &mut SqliteConnection,
+        execution_id: Uuid,
+        jsonl: &str,
+    ) -> Result<(), LogChunkError> {
+        let end = sqlx::query!(
+            r#"SELECT
+                COALESCE(MAX(chunk_index) + 1, 0) as "next_index!: i64",
+                COALESCE(MAX(byte_offset + byte_size), 0) as "next_offset!: i64"
+               FROM execution_process_log_chunks
+               WHERE execution_id = $1"#,
+            execution_id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        let (mut chunk_index, mut byte_offset) = (end.next_index, end.next_offset);
+        for chunk in split_jsonl(jsonl, CHUNK_TARGET_BYTES) {
+            Self::upsert(&mut *conn, execution_id, chunk_index, byte_offset, chunk).await?;
+            chunk_index += 1;
+            byte_offset += chunk.len() as i64;
+        }
+
+        Ok(())
+    }
+
+    /// Append JSONL after the last chunk, for messages written outside a [`LogChunkWriter`]
+    pub async fn append(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        jsonl: &str,
+    ) -> Result<(), LogChunkError> {
+        let mut tx = pool.begin().await?;
+        Self::insert_jsonl(&mut *tx, execution_id, jsonl).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// Buffers a running process's log lines into its open chunk. Each flush rewrites the
+/// open chunk, which is sealed once it reaches [`CHUNK_TARGET_BYTES`].
+pub struct LogChunkWriter {
+    execution_id: Uuid,
+    chunk_index: i64,
+    byte_offset: i64,
+    buffer: String,
+    dirty: bool,
+}
+
+impl LogChunkWriter {
+    pub fn new(execution_id: Uuid) -> Self {
+        Self {
+            execution_id,
+            chunk_index: 0,
+            byte_offset: 0,
+            buffer: String::new(),
+            dirty: false,
+        }
+    }
+
+    /// Buffer a JSONL line (without trailing newline)
+    pub fn push_line(&mut self, line: &str) {
+        self.buffer.push_str(line);
+        self.buffer.push('\n');
+        self.dirty = true;
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.buffer.len() >= CHUNK_TARGET_BYTES
+    }
+
+    /// Persist buffered lines, sealing the open chunk if it is full
+    pub async fn flush(&mut self, pool: &SqlitePool) -> Result<(), LogChunkError> {
+        if self.dirty {
+            ExecutionProcessLogChunk::upsert(
+                pool,
+                self.execution_id,
+                self.chunk_index,
+                self.byte_offset,
+                &self.buffer,
+            )
+            .await?;
+            self.dirty = false;
+        }
+
+        if self.is_full() {
+            self.chunk_index += 1;
+            self.byte_offset += self.buffer.len() as i64;
+            self.buffer.clear();
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse JSONL back into log messages, skipping blank lines
+pub fn parse_jsonl(jsonl: &str) -> Result<Vec<LogMsg>, serde_json::Error> {
+    jsonl
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect()
+}
+
+/// Split JSONL into pieces of roughly `target_bytes`, never breaking a line
+fn split_jsonl(jsonl: &str, target_bytes: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    for (newline, _) in jsonl.match_indices('\n') {
+        if newline + 1 - start >= target_bytes {
+            chunks.push(&jsonl[start..=newline]);
+            start = newline + 1;
+        }
+    }
+    if start < jsonl.len() {
+        chunks.push(&jsonl[start..]);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_keeps_lines_whole() {
+        let jsonl = "aaaa\nbb\ncccccc\nd";
+        let chunks = split_jsonl(jsonl, 6);
+        assert_eq!(chunks, vec!["aaaa\nbb\n", "cccccc\n", "d"]);
+        assert_eq!(chunks.concat(), jsonl);
+    }
+
+    #[test]
+    fn split_small_input_is_single_chunk() {
+        assert_eq!(split_jsonl("a\nb\n", 1024), vec!["a\nb\n"]);
+        assert!(split_jsonl("", 1024).is_empty());
+    }
+
+    #[test]
+    fn chunk_round_trips_messages() {
+        let jsonl = [
+            LogMsg::Stdout("hello".into()),
+            LogMsg::Stderr("oops".into()),
+        ]
+        .iter()
+        .map(|msg| serde_json::to_string(msg).unwrap() + "\n")
+        .collect::<String>();
+        let chunk = ExecutionProcessLogChunk {
+            execution_id: Uuid::new_v4(),
+            chunk_index: 0,
+            byte_offset: 0,
+            byte_size: jsonl.len() as i64,
+            line_count: 2,
+            data: zstd::encode_all(jsonl.as_bytes(), COMPRESSION_LEVEL).unwrap(),
+            created_at: Utc::now(),
+        };
+
+        assert_eq!(chunk.jsonl().unwrap(), jsonl);
+        let messages = chunk.messages().unwrap();
+        assert!(matches!(&messages[0], LogMsg::Stdout(s) if s == "hello"));
+        assert!(matches!(&messages[1], LogMsg::Stderr(s) if s == "oops"));
+    }
+}
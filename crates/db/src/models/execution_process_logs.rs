@@ -5,6 +5,10 @@ use ts_rs::TS;
 use utils::log_msg::LogMsg;
 use uuid::Uuid;
 
+use super::execution_process_log_chunk::{ExecutionProcessLogChunk, LogChunkError};
+
+/// Legacy one-row-per-line log storage. New logs are written as compressed
+/// [`ExecutionProcessLogChunk`]s; remaining rows are migrated in the background.
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct ExecutionProcessLogs {
     pub execution_id: Uuid,
@@ -13,6 +17,20 @@ pub struct ExecutionProcessLogs {
     pub inserted_at: DateTime<Utc>,
 }
 
+/// Log storage used by a project's execution processes
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectLogStorage {
+    pub project_id: Uuid,
+    pub execution_process_count: i64,
+    /// Processes whose raw logs were removed or compacted by the retention policy
+    pub pruned_execution_process_count: i64,
+    pub log_row_count: i64,
+    /// Uncompressed size of the stored logs
+    pub log_bytes: i64,
+    /// Space the logs take up in the database after compression
+    pub stored_bytes: i64,
+}
+
 impl ExecutionProcessLogs {
     /// Find logs by execution process ID
     pub async fn find_by_execution_id(
@@ -28,7 +46,7 @@ impl ExecutionProcessLogs {
                 inserted_at as "inserted_at!: DateTime<Utc>"
                FROM execution_process_logs 
                WHERE execution_id = $1
-               ORDER BY inserted_at ASC"#,
+               ORDER BY inserted_at ASC, rowid ASC"#,
             execution_id
         )
         .fetch_all(pool)
@@ -47,23 +65,197 @@ impl ExecutionProcessLogs {
         Ok(messages)
     }
 
-    /// Append a JSONL line to the logs for an execution process
-    pub async fn append_log_line(
+    /// Load every stored message of an execution process, from legacy rows not yet
+    /// migrated followed by the compressed chunks
+    pub async fn load_messages(
         pool: &SqlitePool,
         execution_id: Uuid,
-        jsonl_line: &str,
-    ) -> Result<(), sqlx::Error> {
-        let byte_size = jsonl_line.len() as i64;
+    ) -> Result<Vec<LogMsg>, LogChunkError> {
+        let records = Self::find_by_execution_id(pool, execution_id).await?;
+        let mut messages = Self::parse_logs(&records)?;
+        for chunk in ExecutionProcessLogChunk::find_by_execution_id(pool, execution_id).await? {
+            messages.extend(chunk.messages()?);
+        }
+        Ok(messages)
+    }
+
+    /// Move the legacy rows of up to `limit` execution processes into compressed chunks
+    /// placed ahead of any existing ones, returning how many processes were migrated
+    pub async fn migrate_to_chunks(pool: &SqlitePool, limit: i64) -> Result<usize, LogChunkError> {
+        let execution_ids = sqlx::query_scalar!(
+            r#"SELECT DISTINCT execution_id as "execution_id!: Uuid"
+               FROM execution_process_logs
+               LIMIT $1"#,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+
+        for execution_id in &execution_ids {
+            let mut tx = pool.begin().await?;
+
+            let rows = sqlx::query_scalar!(
+                r#"SELECT logs FROM execution_process_logs
+                   WHERE execution_id = $1
+                   ORDER BY inserted_at ASC, rowid ASC"#,
+                execution_id
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+
+            // Legacy rows predate any chunk, so they go first, matching `load_messages`
+            let mut jsonl = String::with_capacity(rows.iter().map(String::len).sum());
+            for row in &rows {
+                jsonl.push_str(row);
+                if !row.ends_with('\n') {
+                    jsonl.push('\n');
+                }
+            }
+            for chunk in
+                ExecutionProcessLogChunk::find_by_execution_id(&mut *tx, *execution_id).await?
+            {
+                jsonl.push_str(&chunk.jsonl()?);
+            }
+
+            sqlx::query!(
+                "DELETE FROM execution_process_log_chunks WHERE execution_id = $1",
+                execution_id
+            )
+            .execute(&mut *tx)
+            .await?;
+            ExecutionProcessLogChunk::insert_jsonl(&mut *tx, *execution_id, &jsonl).await?;
+
+            sqlx::query!(
+                "DELETE FROM execution_process_logs WHERE execution_id = $1",
+                execution_id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+        }
+
+        Ok(execution_ids.len())
+    }
+
+    /// Find finished execution processes whose logs are due for pruning: completed more
+    /// than `retention_days` ago, or (optionally) belonging to an archived workspace.
+    pub async fn find_expired_execution_ids(
+        pool: &SqlitePool,
+        retention_days: i64,
+        include_archived: bool,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT ep.id as "id!: Uuid"
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE ep.logs_pruned_at IS NULL
+                 AND ep.completed_at IS NOT NULL
+                 AND (
+                    datetime(ep.completed_at) < datetime('now', '-' || $1 || ' days')
+                    OR ($2 AND w.archived = 1)
+                 )
+                 AND (
+                    EXISTS (SELECT 1 FROM execution_process_log_chunks c WHERE c.execution_id = ep.id)
+                    OR EXISTS (SELECT 1 FROM execution_process_logs l WHERE l.execution_id = ep.id)
+                 )
+               ORDER BY ep.completed_at ASC
+               LIMIT $3"#,
+            retention_days,
+            include_archived,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Drop the stored logs for an execution process, optionally keeping `retained_jsonl`
+    /// (e.g. the compacted conversation) in their place, and mark the process as pruned.
+    pub async fn prune(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        retained_jsonl: Option<&str>,
+    ) -> Result<(), LogChunkError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM execution_process_logs WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM execution_process_log_chunks WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM execution_process_conversations WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if let Some(jsonl) = retained_jsonl.filter(|jsonl| !jsonl.is_empty()) {
+            ExecutionProcessLogChunk::insert_jsonl(&mut *tx, execution_id, jsonl).await?;
+        }
+
         sqlx::query!(
-            r#"INSERT INTO execution_process_logs (execution_id, logs, byte_size, inserted_at)
-               VALUES ($1, $2, $3, datetime('now', 'subsec'))"#,
-            execution_id,
-            jsonl_line,
-            byte_size
+            "UPDATE execution_processes SET logs_pruned_at = datetime('now', 'subsec') WHERE id = $1",
+            execution_id
         )
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        tx.commit().await?;
         Ok(())
     }
+
+    /// Summarize how much log storage a project's execution processes use
+    pub async fn storage_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<ProjectLogStorage, sqlx::Error> {
+        let usage = sqlx::query!(
+            r#"SELECT
+                COUNT(ep.id) as "execution_process_count!: i64",
+                COALESCE(SUM(ep.logs_pruned_at IS NOT NULL), 0) as "pruned_execution_process_count!: i64",
+                COALESCE(SUM(l.row_count), 0) + COALESCE(SUM(c.row_count), 0) as "log_row_count!: i64",
+                COALESCE(SUM(l.bytes), 0) + COALESCE(SUM(c.bytes), 0) as "log_bytes!: i64",
+                COALESCE(SUM(l.bytes), 0) + COALESCE(SUM(c.stored_bytes), 0) as "stored_bytes!: i64"
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               LEFT JOIN (
+                    SELECT execution_id, COUNT(*) as row_count, SUM(byte_size) as bytes
+                    FROM execution_process_logs
+                    GROUP BY execution_id
+               ) l ON l.execution_id = ep.id
+               LEFT JOIN (
+                    SELECT execution_id,
+                           COUNT(*) as row_count,
+                           SUM(byte_size) as bytes,
+                           SUM(length(data)) as stored_bytes
+                    FROM execution_process_log_chunks
+                    GROUP BY execution_id
+               ) c ON c.execution_id = ep.id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(ProjectLogStorage {
+            project_id,
+            execution_process_count: usage.execution_process_count,
+            pruned_execution_process_count: usage.pruned_execution_process_count,
+            log_row_count: usage.log_row_count,
+            log_bytes: usage.log_bytes,
+            stored_bytes: usage.stored_bytes,
+        })
+    }
 }
@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A port allocated to a running dev server execution process.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionProcessPort {
+    pub execution_process_id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub port: i64,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExecutionProcessPort {
+    /// Record a port allocation. Fails with a unique violation if the port is already taken.
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        port: i64,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessPort,
+            r#"INSERT INTO execution_process_ports (execution_process_id, workspace_id, repo_id, port)
+               VALUES ($1, $2, $3, $4)
+               RETURNING execution_process_id as "execution_process_id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         port,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            execution_process_id,
+            workspace_id,
+            repo_id,
+            port
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// All ports currently allocated, across every workspace
+    pub async fn find_allocated_ports(pool: &SqlitePool) -> Result<Vec<i64>, sqlx::Error> {
+        sqlx::query_scalar!(r#"SELECT port FROM execution_process_ports ORDER BY port ASC"#)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessPort,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      port,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM execution_process_ports
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Ports held by running dev servers of a workspace, newest first
+    pub async fn find_running_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessPort,
+            r#"SELECT epp.execution_process_id as "execution_process_id!: Uuid",
+                      epp.workspace_id as "workspace_id!: Uuid",
+                      epp.repo_id as "repo_id!: Uuid",
+                      epp.port,
+                      epp.created_at as "created_at!: DateTime<Utc>"
+               FROM execution_process_ports epp
+               JOIN execution_processes ep ON ep.id = epp.execution_process_id
+               WHERE epp.workspace_id = $1
+                 AND ep.status = 'running'
+               ORDER BY epp.created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Release the port held by an execution process (no-op if none)
+    pub async fn delete_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM execution_process_ports WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Release ports whose execution process is no longer running.
+    /// Used at startup to recover allocations left behind by a crash.
+    pub async fn delete_stale(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM execution_process_ports
+               WHERE execution_process_id IN (
+                   SELECT id FROM execution_processes WHERE status != 'running'
+               )"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use executors::logs::ModelTokenUsage;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Tokens billed for one model during an execution process
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionProcessTokenUsage {
+    pub execution_process_id: Uuid,
+    pub model: String,
+    pub executor: String,
+    pub variant: Option<String>,
+    /// Input tokens not served from the prompt cache
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cached_input_tokens: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Usage summed over the processes matching a [`TokenUsageFilter`], per executor
+/// variant and model
+#[derive(Debug, Clone, FromRow)]
+pub struct TokenUsageTotal {
+    pub executor: String,
+    pub variant: Option<String>,
+    pub model: String,
+    pub execution_process_count: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cached_input_tokens: i64,
+}
+
+/// Restricts usage totals to a session, task or project and to processes started
+/// within `[from, to)`. Unset fields don't filter.
+#[derive(Debug, Clone, Default)]
+pub struct TokenUsageFilter {
+    pub session_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Leave out one process, e.g. a running one whose usage is tracked live
+    pub exclude_execution_process_id: Option<Uuid>,
+}
+
+impl ExecutionProcessTokenUsage {
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessTokenUsage,
+            r#"SELECT
+                execution_process_id as "execution_process_id!: Uuid",
+                model,
+                executor,
+                variant,
+                input_tokens,
+                output_tokens,
+                cached_input_tokens,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_process_token_usage
+               WHERE execution_process_id = $1
+               ORDER BY model ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record the latest cumulative usage reported for a model. Counts only grow
+    /// within a process, so a late or repeated report never lowers them.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        executor: &str,
+        variant: Option<&str>,
+        usage: &ModelTokenUsage,
+    ) -> Result<(), sqlx::Error> {
+        let input_tokens = usage.input_tokens as i64;
+        let output_tokens = usage.output_tokens as i64;
+        let cached_input_tokens = usage.cached_input_tokens as i64;
+        sqlx::query!(
+            r#"INSERT INTO execution_process_token_usage
+                   (execution_process_id, model, executor, variant,
+                    input_tokens, output_tokens, cached_input_tokens)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT(execution_process_id, model) DO UPDATE SET
+                   input_tokens = MAX(input_tokens, excluded.input_tokens),
+                   output_tokens = MAX(output_tokens, excluded.output_tokens),
+                   cached_input_tokens = MAX(cached_input_tokens, excluded.cached_input_tokens),
+                   updated_at = datetime('now', 'subsec')"#,
+            execution_process_id,
+            usage.model,
+            executor,
+            variant,
+            input_tokens,
+            output_tokens,
+            cached_input_tokens
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn totals(
+        pool: &SqlitePool,
+        filter: &TokenUsageFilter,
+    ) -> Result<Vec<TokenUsageTotal>, sqlx::Error> {
+        sqlx::query_as!(
+            TokenUsageTotal,
+            r#"SELECT
+                u.executor,
+                u.variant,
+                u.model,
+                COUNT(DISTINCT u.execution_process_id) as "execution_process_count!: i64",
+                SUM(u.input_tokens) as "input_tokens!: i64",
+                SUM(u.output_tokens) as "output_tokens!: i64",
+                SUM(u.cached_input_tokens) as "cached_input_tokens!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE ($1 IS NULL OR s.id = $1)
+                 AND ($2 IS NULL OR w.task_id = $2)
+                 AND ($3 IS NULL OR t.project_id = $3)
+                 AND ($4 IS NULL OR datetime(ep.started_at) >= datetime($4))
+                 AND ($5 IS NULL OR datetime(ep.started_at) < datetime($5))
+                 AND ($6 IS NULL OR u.execution_process_id != $6)
+               GROUP BY u.executor, u.variant, u.model
+               ORDER BY u.executor, u.variant, u.model"#,
+            filter.session_id,
+            filter.task_id,
+            filter.project_id,
+            filter.from,
+            filter.to,
+            filter.exclude_execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -1,13 +1,20 @@
+pub mod budget;
 pub mod coding_agent_turn;
 pub mod execution_process;
+pub mod execution_process_conversation;
+pub mod execution_process_log_chunk;
 pub mod execution_process_logs;
+pub mod execution_process_port;
 pub mod execution_process_repo_state;
+pub mod execution_process_token_usage;
 pub mod image;
 pub mod merge;
 pub mod migration_state;
 pub mod project;
 pub mod project_repo;
+pub mod queued_message;
 pub mod repo;
+pub mod scheduled_execution;
 pub mod scratch;
 pub mod session;
 pub mod tag;
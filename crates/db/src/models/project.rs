@@ -1,5 +1,7 @@
 use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
 use serde::{Deserialize, Serialize};
+use serde_with::rust::double_option;
 use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
@@ -22,6 +24,12 @@ pub struct Project {
     pub id: Uuid,
     pub name: String,
     pub default_agent_working_dir: Option<String>,
+    /// Coding agent profile for workspaces of this project, ahead of the
+    /// global default
+    #[ts(type = "ExecutorProfileId | null")]
+    pub default_executor_profile_id: Option<sqlx::types::Json<ExecutorProfileId>>,
+    /// Instructions for coding agents working on this project's tasks
+    pub agent_instructions: Option<String>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -37,6 +45,12 @@ pub struct CreateProject {
 #[derive(Debug, Deserialize, TS)]
 pub struct UpdateProject {
     pub name: Option<String>,
+    #[serde(default, with = "double_option")]
+    #[ts(optional, type = "ExecutorProfileId | null")]
+    pub default_executor_profile_id: Option<Option<ExecutorProfileId>>,
+    #[serde(default, with = "double_option")]
+    #[ts(optional, type = "string | null")]
+    pub agent_instructions: Option<Option<String>>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -69,6 +83,8 @@ impl Project {
             r#"SELECT id as "id!: Uuid",
                       name,
                       default_agent_working_dir,
+                      default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                      agent_instructions,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -85,6 +101,8 @@ impl Project {
             r#"
             SELECT p.id as "id!: Uuid", p.name,
                    p.default_agent_working_dir,
+                   p.default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                   p.agent_instructions,
                    p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
             WHERE p.id IN (
@@ -107,6 +125,8 @@ impl Project {
             r#"SELECT id as "id!: Uuid",
                       name,
                       default_agent_working_dir,
+                      default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                      agent_instructions,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -123,6 +143,8 @@ impl Project {
             r#"SELECT id as "id!: Uuid",
                       name,
                       default_agent_working_dir,
+                      default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                      agent_instructions,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -149,6 +171,8 @@ impl Project {
                 RETURNING id as "id!: Uuid",
                           name,
                           default_agent_working_dir,
+                          default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                          agent_instructions,
                           created_at as "created_at!: DateTime<Utc>",
                           updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
@@ -168,19 +192,34 @@ impl Project {
             .ok_or(sqlx::Error::RowNotFound)?;
 
         let name = payload.name.clone().unwrap_or(existing.name);
+        // None = don't update, Some(None) = clear
+        let default_executor_profile_id = match &payload.default_executor_profile_id {
+            None => existing.default_executor_profile_id,
+            Some(v) => v.clone().map(sqlx::types::Json),
+        };
+        let agent_instructions = match &payload.agent_instructions {
+            None => existing.agent_instructions,
+            Some(v) => v.clone(),
+        };
 
         sqlx::query_as!(
             Project,
             r#"UPDATE projects
-               SET name = $2
+               SET name = $2,
+                   default_executor_profile_id = $3,
+                   agent_instructions = $4
                WHERE id = $1
                RETURNING id as "id!: Uuid",
                          name,
                          default_agent_working_dir,
+                         default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                         agent_instructions,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
+            default_executor_profile_id,
+            agent_instructions,
         )
         .fetch_one(pool)
         .await
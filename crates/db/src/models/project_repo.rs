@@ -1,6 +1,7 @@
 use std::path::Path;
 
 use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool};
 use thiserror::Error;
@@ -83,8 +84,13 @@ impl ProjectRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.dev_server_ready_pattern,
+                      r.dev_server_health_path,
+                      r.dev_server_auto_restart as "dev_server_auto_restart!: bool",
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                      r.agent_instructions,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -0,0 +1,204 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::scratch::DraftFollowUpData;
+
+/// A follow-up message waiting for the session's running turn to finish
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct QueuedMessage {
+    pub id: Uuid,
+    /// The session this message is queued for
+    pub session_id: Uuid,
+    /// The follow-up data (message + variant)
+    #[ts(type = "DraftFollowUpData")]
+    pub data: sqlx::types::Json<DraftFollowUpData>,
+    /// Timestamp when the message was queued
+    pub queued_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl QueuedMessage {
+    /// Messages queued for a session, next to be sent first
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"SELECT
+                id as "id!: Uuid",
+                session_id as "session_id!: Uuid",
+                data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                queued_at as "queued_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM queued_messages
+               WHERE session_id = $1
+               ORDER BY position ASC, queued_at ASC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"SELECT
+                id as "id!: Uuid",
+                session_id as "session_id!: Uuid",
+                data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                queued_at as "queued_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM queued_messages
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Append a message to the end of the session's queue
+    pub async fn create(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        data: &DraftFollowUpData,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let data = sqlx::types::Json(data);
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"INSERT INTO queued_messages (id, session_id, position, data)
+               VALUES (
+                   $1, $2,
+                   (SELECT COALESCE(MAX(position) + 1, 0) FROM queued_messages WHERE session_id = $2),
+                   $3
+               )
+               RETURNING
+                id as "id!: Uuid",
+                session_id as "session_id!: Uuid",
+                data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                queued_at as "queued_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            session_id,
+            data
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &DraftFollowUpData,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let data = sqlx::types::Json(data);
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"UPDATE queued_messages
+               SET data = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING
+                id as "id!: Uuid",
+                session_id as "session_id!: Uuid",
+                data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                queued_at as "queued_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Move a message to the front of its session's queue
+    pub async fn move_to_front(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE queued_messages
+               SET position = (
+                       SELECT MIN(q.position) - 1 FROM queued_messages q
+                       WHERE q.session_id = queued_messages.session_id
+                   ),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Put the session's messages in the order of `ids`, which must list each of
+    /// them exactly once
+    pub async fn reorder(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for (position, id) in ids.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                r#"UPDATE queued_messages
+                   SET position = $3, updated_at = datetime('now', 'subsec')
+                   WHERE id = $1 AND session_id = $2"#,
+                id,
+                session_id,
+                position
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM queued_messages WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM queued_messages WHERE session_id = $1",
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Remove and return the next message of the session's queue
+    pub async fn take_next(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"DELETE FROM queued_messages
+               WHERE id = (
+                   SELECT id FROM queued_messages
+                   WHERE session_id = $1
+                   ORDER BY position ASC, queued_at ASC
+                   LIMIT 1
+               )
+               RETURNING
+                id as "id!: Uuid",
+                session_id as "session_id!: Uuid",
+                data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                queued_at as "queued_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
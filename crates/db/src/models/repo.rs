@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
 use serde::{Deserialize, Serialize};
 use serde_with::rust::double_option;
 use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
@@ -28,8 +29,20 @@ pub struct Repo {
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
     pub dev_server_script: Option<String>,
+    /// Custom regex for the dev server's "listening" line. A named `url` or
+    /// `port` group (or the first group) locates the address.
+    pub dev_server_ready_pattern: Option<String>,
+    /// Path polled over HTTP once the dev server is ready, e.g. `/healthz`
+    pub dev_server_health_path: Option<String>,
+    pub dev_server_auto_restart: bool,
     pub default_target_branch: Option<String>,
     pub default_working_dir: Option<String>,
+    /// Coding agent profile for workspaces of this repo, ahead of the
+    /// project and global defaults
+    #[ts(type = "ExecutorProfileId | null")]
+    pub default_executor_profile_id: Option<sqlx::types::Json<ExecutorProfileId>>,
+    /// Instructions for coding agents working in this repo
+    pub agent_instructions: Option<String>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -95,6 +108,30 @@ pub struct UpdateRepo {
     #[ts(optional, type = "string | null")]
     pub dev_server_script: Option<Option<String>>,
 
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub dev_server_ready_pattern: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub dev_server_health_path: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "boolean | null")]
+    pub dev_server_auto_restart: Option<Option<bool>>,
+
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -110,6 +147,22 @@ pub struct UpdateRepo {
     )]
     #[ts(optional, type = "string | null")]
     pub default_working_dir: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "ExecutorProfileId | null")]
+    pub default_executor_profile_id: Option<Option<ExecutorProfileId>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub agent_instructions: Option<Option<String>>,
 }
 
 impl Repo {
@@ -128,8 +181,13 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      dev_server_ready_pattern,
+                      dev_server_health_path,
+                      dev_server_auto_restart as "dev_server_auto_restart!: bool",
                       default_target_branch,
                       default_working_dir,
+                      default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                      agent_instructions,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -169,8 +227,13 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      dev_server_ready_pattern,
+                      dev_server_health_path,
+                      dev_server_auto_restart as "dev_server_auto_restart!: bool",
                       default_target_branch,
                       default_working_dir,
+                      default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                      agent_instructions,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -227,8 +290,13 @@ impl Repo {
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
+                         dev_server_ready_pattern,
+                         dev_server_health_path,
+                         dev_server_auto_restart as "dev_server_auto_restart!: bool",
                          default_target_branch,
                          default_working_dir,
+                         default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                         agent_instructions,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -264,8 +332,13 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      dev_server_ready_pattern,
+                      dev_server_health_path,
+                      dev_server_auto_restart as "dev_server_auto_restart!: bool",
                       default_target_branch,
                       default_working_dir,
+                      default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                      agent_instructions,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -290,8 +363,13 @@ impl Repo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.dev_server_ready_pattern,
+                      r.dev_server_health_path,
+                      r.dev_server_auto_restart as "dev_server_auto_restart!: bool",
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                      r.agent_instructions,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -346,6 +424,18 @@ impl Repo {
             None => existing.dev_server_script,
             Some(v) => v.clone(),
         };
+        let dev_server_ready_pattern = match &payload.dev_server_ready_pattern {
+            None => existing.dev_server_ready_pattern,
+            Some(v) => v.clone(),
+        };
+        let dev_server_health_path = match &payload.dev_server_health_path {
+            None => existing.dev_server_health_path,
+            Some(v) => v.clone(),
+        };
+        let dev_server_auto_restart = match &payload.dev_server_auto_restart {
+            None => existing.dev_server_auto_restart,
+            Some(v) => v.unwrap_or(false),
+        };
         let default_target_branch = match &payload.default_target_branch {
             None => existing.default_target_branch,
             Some(v) => v.clone(),
@@ -354,6 +444,14 @@ impl Repo {
             None => existing.default_working_dir,
             Some(v) => v.clone(),
         };
+        let default_executor_profile_id = match &payload.default_executor_profile_id {
+            None => existing.default_executor_profile_id,
+            Some(v) => v.clone().map(sqlx::types::Json),
+        };
+        let agent_instructions = match &payload.agent_instructions {
+            None => existing.agent_instructions,
+            Some(v) => v.clone(),
+        };
 
         sqlx::query_as!(
             Repo,
@@ -365,10 +463,15 @@ impl Repo {
                    copy_files = $5,
                    parallel_setup_script = $6,
                    dev_server_script = $7,
-                   default_target_branch = $8,
-                   default_working_dir = $9,
+                   dev_server_ready_pattern = $8,
+                   dev_server_health_path = $9,
+                   dev_server_auto_restart = $10,
+                   default_target_branch = $11,
+                   default_working_dir = $12,
+                   default_executor_profile_id = $13,
+                   agent_instructions = $14,
                    updated_at = datetime('now', 'subsec')
-               WHERE id = $10
+               WHERE id = $15
                RETURNING id as "id!: Uuid",
                          path,
                          name,
@@ -379,8 +482,13 @@ impl Repo {
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
+                         dev_server_ready_pattern,
+                         dev_server_health_path,
+                         dev_server_auto_restart as "dev_server_auto_restart!: bool",
                          default_target_branch,
                          default_working_dir,
+                         default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                         agent_instructions,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             display_name,
@@ -390,8 +498,13 @@ impl Repo {
             copy_files,
             parallel_setup_script,
             dev_server_script,
+            dev_server_ready_pattern,
+            dev_server_health_path,
+            dev_server_auto_restart,
             default_target_branch,
             default_working_dir,
+            default_executor_profile_id,
+            agent_instructions,
             id
         )
         .fetch_one(pool)
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A coding agent start waiting for a free slot under the concurrency limits.
+/// Its execution process already exists; the agent is spawned once dispatched.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ScheduledExecution {
+    pub execution_process_id: Uuid,
+    pub workspace_id: Uuid,
+    /// Executor the agent runs with, e.g. `CLAUDE_CODE`
+    pub executor: String,
+    /// Higher priorities are dispatched first
+    pub priority: i64,
+    pub queued_at: DateTime<Utc>,
+}
+
+impl ScheduledExecution {
+    /// Scheduled starts in dispatch order
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ScheduledExecution,
+            r#"SELECT
+                execution_process_id as "execution_process_id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                executor,
+                priority as "priority!: i64",
+                queued_at as "queued_at!: DateTime<Utc>"
+               FROM scheduled_executions
+               ORDER BY priority DESC, queued_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ScheduledExecution,
+            r#"SELECT
+                execution_process_id as "execution_process_id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                executor,
+                priority as "priority!: i64",
+                queued_at as "queued_at!: DateTime<Utc>"
+               FROM scheduled_executions
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        workspace_id: Uuid,
+        executor: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ScheduledExecution,
+            r#"INSERT INTO scheduled_executions (execution_process_id, workspace_id, executor)
+               VALUES ($1, $2, $3)
+               RETURNING
+                execution_process_id as "execution_process_id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                executor,
+                priority as "priority!: i64",
+                queued_at as "queued_at!: DateTime<Utc>""#,
+            execution_process_id,
+            workspace_id,
+            executor
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn set_priority(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        priority: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ScheduledExecution,
+            r#"UPDATE scheduled_executions
+               SET priority = $2
+               WHERE execution_process_id = $1
+               RETURNING
+                execution_process_id as "execution_process_id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                executor,
+                priority as "priority!: i64",
+                queued_at as "queued_at!: DateTime<Utc>""#,
+            execution_process_id,
+            priority
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, execution_process_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM scheduled_executions WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
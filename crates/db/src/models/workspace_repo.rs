@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool};
 use ts_rs::TS;
@@ -121,8 +122,13 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.dev_server_ready_pattern,
+                      r.dev_server_health_path,
+                      r.dev_server_auto_restart as "dev_server_auto_restart!: bool",
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                      r.agent_instructions,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -150,8 +156,13 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.dev_server_ready_pattern,
+                      r.dev_server_health_path,
+                      r.dev_server_auto_restart as "dev_server_auto_restart!: bool",
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                      r.agent_instructions,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>",
                       wr.target_branch
@@ -178,8 +189,13 @@ impl WorkspaceRepo {
                     copy_files: row.copy_files,
                     parallel_setup_script: row.parallel_setup_script,
                     dev_server_script: row.dev_server_script,
+                    dev_server_ready_pattern: row.dev_server_ready_pattern,
+                    dev_server_health_path: row.dev_server_health_path,
+                    dev_server_auto_restart: row.dev_server_auto_restart,
                     default_target_branch: row.default_target_branch,
                     default_working_dir: row.default_working_dir,
+                    default_executor_profile_id: row.default_executor_profile_id,
+                    agent_instructions: row.agent_instructions,
                     created_at: row.created_at,
                     updated_at: row.updated_at,
                 },
@@ -267,8 +283,13 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.dev_server_ready_pattern,
+                      r.dev_server_health_path,
+                      r.dev_server_auto_restart as "dev_server_auto_restart!: bool",
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.default_executor_profile_id as "default_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
+                      r.agent_instructions,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -58,6 +58,7 @@ impl Executable for CodingAgentFollowUpRequest {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let effective_dir = self.effective_dir(current_dir);
+        let prompt = env.prompt_with_instructions(&self.prompt, true);
 
         #[cfg(feature = "qa-mode")]
         {
@@ -66,7 +67,7 @@ impl Executable for CodingAgentFollowUpRequest {
             return executor
                 .spawn_follow_up(
                     &effective_dir,
-                    &self.prompt,
+                    &prompt,
                     &self.session_id,
                     self.reset_to_message_id.as_deref(),
                     env,
@@ -88,7 +89,7 @@ impl Executable for CodingAgentFollowUpRequest {
             agent
                 .spawn_follow_up(
                     &effective_dir,
-                    &self.prompt,
+                    &prompt,
                     &self.session_id,
                     self.reset_to_message_id.as_deref(),
                     env,
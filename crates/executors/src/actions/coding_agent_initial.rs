@@ -50,12 +50,13 @@ impl Executable for CodingAgentInitialRequest {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let effective_dir = self.effective_dir(current_dir);
+        let prompt = env.prompt_with_instructions(&self.prompt, false);
 
         #[cfg(feature = "qa-mode")]
         {
             tracing::info!("QA mode: using mock executor instead of real agent");
             let executor = crate::executors::qa_mock::QaMockExecutor;
-            return executor.spawn(&effective_dir, &self.prompt, env).await;
+            return executor.spawn(&effective_dir, &prompt, env).await;
         }
 
         #[cfg(not(feature = "qa-mode"))]
@@ -69,7 +70,7 @@ impl Executable for CodingAgentInitialRequest {
 
             agent.use_approvals(approvals.clone());
 
-            agent.spawn(&effective_dir, &self.prompt, env).await
+            agent.spawn(&effective_dir, &prompt, env).await
         }
     }
 }
@@ -10,6 +10,7 @@ use workspace_utils::shell::get_shell_command;
 use crate::{
     actions::Executable,
     approvals::ExecutorApprovalService,
+    command::CommandParts,
     env::ExecutionEnv,
     executors::{ExecutorError, SpawnedChild},
 };
@@ -53,15 +54,21 @@ impl Executable for ScriptRequest {
             None => current_dir.to_path_buf(),
         };
 
-        let (shell_cmd, shell_arg) = get_shell_command();
-        let mut command = Command::new(shell_cmd);
+        // The local shell may not exist in a container image or remote host
+        let (shell_cmd, shell_arg) = if env.container.is_some() || env.remote.is_some() {
+            ("sh".to_string(), "-c")
+        } else {
+            get_shell_command()
+        };
+        let parts = CommandParts::new(shell_cmd, vec![shell_arg.to_string(), self.script.clone()]);
+        let (program, args) = env.resolve(parts, &effective_dir).await?;
+        let mut command = Command::new(program);
         command
             .kill_on_drop(true)
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
-            .arg(shell_arg)
-            .arg(&self.script)
+            .args(&args)
             .current_dir(&effective_dir);
 
         // Apply environment variables
@@ -31,6 +31,10 @@ impl CommandParts {
         Self { program, args }
     }
 
+    pub fn into_parts(self) -> (String, Vec<String>) {
+        (self.program, self.args)
+    }
+
     pub async fn into_resolved(self) -> Result<(PathBuf, Vec<String>), ExecutorError> {
         let CommandParts { program, args } = self;
         let executable = resolve_executable_path(&program)
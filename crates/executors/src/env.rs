@@ -1,9 +1,19 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use git::GitService;
 use tokio::process::Command;
 
-use crate::command::CmdOverrides;
+use crate::{
+    command::{CmdOverrides, CommandParts},
+    executors::ExecutorError,
+    instructions::AgentInstructions,
+    oci::OciContainer,
+    remote::RemoteHost,
+    sandbox::SandboxPolicy,
+};
 
 /// Repository context for executor operations
 #[derive(Debug, Clone, Default)]
@@ -81,6 +91,43 @@ pub struct ExecutionEnv {
     pub repo_context: RepoContext,
     pub commit_reminder: bool,
     pub commit_reminder_prompt: String,
+    /// Isolation for the spawned process, if it runs sandboxed
+    pub sandbox: Option<SandboxPolicy>,
+    /// Socket of the proxy a sandbox with a network allowlist reaches the
+    /// network through
+    pub sandbox_proxy: Option<PathBuf>,
+    /// Container the process runs in instead of the host; takes the place
+    /// of the sandbox
+    pub container: Option<OciContainer>,
+    /// SSH host the process runs on instead of this machine; takes the
+    /// place of the container and the sandbox
+    pub remote: Option<RemoteHost>,
+    /// Project and repository instructions for the agent's prompts
+    pub instructions: Option<AgentInstructions>,
+    /// cgroup v2 group the spawned process joins before it executes, so it and
+    /// everything it forks run under the group's resource limits
+    pub cgroup: Option<PathBuf>,
+    /// rlimits the spawned process sets on itself before it executes when
+    /// there is no cgroup or joining it fails
+    pub rlimits: Option<Rlimits>,
+}
+
+/// Per-process resource limits, the fallback where cgroup v2 can't be used.
+/// Unset fields leave the inherited limit alone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rlimits {
+    /// `RLIMIT_AS`: bytes of address space of each process
+    pub address_space: Option<u64>,
+    /// `RLIMIT_NPROC`: processes and threads of the user running the process
+    pub processes: Option<u64>,
+    /// `RLIMIT_CPU`: seconds of CPU time of each process
+    pub cpu_secs: Option<u64>,
+}
+
+impl Rlimits {
+    pub fn is_empty(&self) -> bool {
+        self.address_space.is_none() && self.processes.is_none() && self.cpu_secs.is_none()
+    }
 }
 
 impl ExecutionEnv {
@@ -94,6 +141,21 @@ impl ExecutionEnv {
             repo_context,
             commit_reminder,
             commit_reminder_prompt,
+            sandbox: None,
+            sandbox_proxy: None,
+            container: None,
+            remote: None,
+            instructions: None,
+            cgroup: None,
+            rlimits: None,
+        }
+    }
+
+    /// `prompt` with the project and repository instructions applied
+    pub fn prompt_with_instructions(&self, prompt: &str, follow_up: bool) -> String {
+        match &self.instructions {
+            Some(instructions) => instructions.apply(prompt, follow_up),
+            None => prompt.to_string(),
         }
     }
 
@@ -123,11 +185,53 @@ impl ExecutionEnv {
         }
     }
 
-    /// Apply all environment variables to a Command
+    /// Apply all environment variables to a Command, and have it join the
+    /// cgroup or set the rlimits when there are any
     pub fn apply_to_command(&self, command: &mut Command) {
         for (key, value) in &self.vars {
             command.env(key, value);
         }
+        #[cfg(target_os = "linux")]
+        limit_before_exec(command, self.cgroup.as_deref(), self.rlimits);
+        if let Some(container) = &self.container
+            && let Err(e) = container.write_env_file(self.vars.keys())
+        {
+            tracing::warn!("Failed to pass environment into container: {}", e);
+        }
+    }
+
+    /// Resolve `parts` to the program and arguments to spawn in
+    /// `current_dir`, run remotely, in the container or in the sandbox when
+    /// one is configured
+    pub async fn resolve(
+        &self,
+        parts: CommandParts,
+        current_dir: &Path,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        if let Some(remote) = &self.remote {
+            let (program, args) = parts.into_parts();
+            return remote.wrap(program, args, current_dir, &self.vars).await;
+        }
+        if let Some(container) = &self.container {
+            let (program, args) = parts.into_parts();
+            return container
+                .wrap(&self.repo_context, program, args, current_dir)
+                .await;
+        }
+        let (program, args) = parts.into_resolved().await?;
+        match &self.sandbox {
+            Some(policy) => {
+                policy
+                    .wrap(
+                        &self.repo_context,
+                        self.sandbox_proxy.as_deref(),
+                        program,
+                        args,
+                    )
+                    .await
+            }
+            None => Ok((program, args)),
+        }
     }
 
     pub fn contains_key(&self, key: &str) -> bool {
@@ -139,6 +243,91 @@ impl ExecutionEnv {
     }
 }
 
+/// Put the child under its limits between fork and exec: it moves itself into
+/// `cgroup` and sets `rlimits` instead when there is no cgroup or it can't
+/// join. A child that ends up with neither fails to spawn rather than running
+/// without its limits.
+#[cfg(target_os = "linux")]
+fn limit_before_exec(command: &mut Command, cgroup: Option<&Path>, rlimits: Option<Rlimits>) {
+    use std::{ffi::CString, io, os::unix::ffi::OsStrExt};
+
+    let procs = cgroup.and_then(|cgroup| {
+        CString::new(cgroup.join("cgroup.procs").as_os_str().as_bytes())
+            .inspect_err(|e| tracing::warn!("Invalid cgroup path {}: {}", cgroup.display(), e))
+            .ok()
+    });
+    let rlimits = rlimits.filter(|rlimits| !rlimits.is_empty());
+    if procs.is_none() && rlimits.is_none() {
+        return;
+    }
+    let settings = rlimits.map(|rlimits| {
+        [
+            (libc::RLIMIT_AS, rlimits.address_space),
+            (libc::RLIMIT_NPROC, rlimits.processes),
+            (libc::RLIMIT_CPU, rlimits.cpu_secs),
+        ]
+    });
+
+    // SAFETY: the hook only makes the async-signal-safe open, write, close,
+    // getrlimit and setrlimit calls, on data allocated before the fork
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(procs) = &procs {
+                match join_cgroup(procs) {
+                    Ok(()) => return Ok(()),
+                    Err(e) if settings.is_none() => return Err(e),
+                    Err(_) => {}
+                }
+            }
+            for (resource, value) in settings.iter().flatten() {
+                let Some(value) = *value else {
+                    continue;
+                };
+                let mut limit = libc::rlimit {
+                    rlim_cur: 0,
+                    rlim_max: 0,
+                };
+                if libc::getrlimit(*resource, &mut limit) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                // Lowering the hard limit too keeps the process from raising
+                // it again; raising it is not allowed
+                let value = (value as libc::rlim_t).min(limit.rlim_max);
+                limit.rlim_cur = value;
+                limit.rlim_max = value;
+                if libc::setrlimit(*resource, &limit) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Move the calling process into the cgroup whose `cgroup.procs` is `procs`.
+/// Only makes async-signal-safe calls, for use between fork and exec.
+#[cfg(target_os = "linux")]
+unsafe fn join_cgroup(procs: &std::ffi::CStr) -> std::io::Result<()> {
+    use std::io;
+
+    // SAFETY: `procs` is a valid C string and the buffers outlive the calls
+    unsafe {
+        let fd = libc::open(procs.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // Writing 0 moves the writing process itself
+        let written = libc::write(fd, b"0".as_ptr().cast(), 1);
+        let result = if written == 1 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        };
+        libc::close(fd);
+        result
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,264 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use derivative::Derivative;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ts_rs::TS;
+use workspace_utils::{msg_store::MsgStore, shell::resolve_executable_path_blocking};
+
+use super::AcpAgentHarness;
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::{CmdOverrides, CommandBuildError, CommandBuilder, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+    },
+};
+
+/// Any agent speaking the Agent Client Protocol over stdio
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct AcpAgent {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Command",
+        description = "Command that starts the agent in ACP mode, e.g. `npx -y @zed-industries/claude-code-acp`"
+    )]
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Model",
+        description = "Model id selected through the session, when the agent supports it"
+    )]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Mode",
+        description = "Session mode id to switch to, e.g. `plan`, when the agent supports it"
+    )]
+    pub mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Auto Approve",
+        description = "Grant every permission request instead of asking for approval"
+    )]
+    pub auto_approve: Option<bool>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl AcpAgent {
+    fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
+        apply_overrides(CommandBuilder::new(self.command.clone()), &self.cmd)
+    }
+
+    /// Session histories are kept per agent command, so one configured agent
+    /// never resumes a session recorded by another
+    fn session_namespace(&self) -> String {
+        let command = self
+            .cmd
+            .base_command_override
+            .as_deref()
+            .unwrap_or(&self.command);
+        let digest = Sha256::digest(command.trim().as_bytes());
+        let hex: String = digest[..8].iter().map(|b| format!("{b:02x}")).collect();
+        format!("acp_sessions_{hex}")
+    }
+
+    fn harness(&self) -> AcpAgentHarness {
+        let mut harness = AcpAgentHarness::with_session_namespace(self.session_namespace());
+        if let Some(model) = &self.model {
+            harness = harness.with_model(model);
+        }
+        if let Some(mode) = &self.mode {
+            harness = harness.with_mode(mode);
+        }
+        harness
+    }
+
+    fn approvals(&self) -> Option<Arc<dyn ExecutorApprovalService>> {
+        if self.auto_approve.unwrap_or(false) {
+            None
+        } else {
+            self.approvals.clone()
+        }
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for AcpAgent {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command = self.build_command_builder()?.build_initial()?;
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        self.harness()
+            .spawn_with_command(
+                current_dir,
+                combined_prompt,
+                command,
+                env,
+                &self.cmd,
+                self.approvals(),
+            )
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        _reset_to_message_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command = self.build_command_builder()?.build_follow_up(&[])?;
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        self.harness()
+            .spawn_follow_up_with_command(
+                current_dir,
+                combined_prompt,
+                session_id,
+                command,
+                env,
+                &self.cmd,
+                self.approvals(),
+            )
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        super::normalize_logs(msg_store, worktree_path);
+    }
+
+    fn default_mcp_config_path(&self) -> Option<PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let program = self
+            .build_command_builder()
+            .and_then(|builder| builder.build_initial())
+            .map(|parts| parts.into_parts().0);
+        match program {
+            Ok(program) if resolve_executable_path_blocking(&program).is_some() => {
+                AvailabilityInfo::InstallationFound
+            }
+            _ => AvailabilityInfo::NotFound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(config: serde_json::Value) -> AcpAgent {
+        serde_json::from_value(config).unwrap()
+    }
+
+    #[test]
+    fn profile_definition_deserializes() {
+        let acp = agent(serde_json::json!({
+            "command": "npx -y @zed-industries/claude-code-acp",
+            "model": "sonnet",
+            "mode": "plan",
+            "auto_approve": true,
+            "additional_params": ["--verbose"],
+            "env": { "ACP_DEBUG": "1" }
+        }));
+
+        assert_eq!(acp.model.as_deref(), Some("sonnet"));
+        assert_eq!(acp.mode.as_deref(), Some("plan"));
+        assert_eq!(acp.auto_approve, Some(true));
+        assert_eq!(
+            acp.cmd.additional_params.as_deref(),
+            Some(&["--verbose".to_string()][..])
+        );
+        assert_eq!(
+            acp.cmd
+                .env
+                .as_ref()
+                .and_then(|env| env.get("ACP_DEBUG"))
+                .map(String::as_str),
+            Some("1")
+        );
+
+        let plain = agent(serde_json::json!({ "command": "acme-acp" }));
+        assert_eq!(plain.model, None);
+        assert_eq!(plain.mode, None);
+        assert_eq!(plain.auto_approve, None);
+        assert!(serde_json::from_value::<AcpAgent>(serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn initial_and_follow_up_commands_match() {
+        let acp = agent(serde_json::json!({
+            "command": "npx -y @zed-industries/claude-code-acp",
+            "additional_params": ["--verbose"]
+        }));
+        let builder = acp.build_command_builder().unwrap();
+
+        let (program, args) = builder.build_initial().unwrap().into_parts();
+        assert_eq!(program, "npx");
+        assert_eq!(args, ["-y", "@zed-industries/claude-code-acp", "--verbose"]);
+
+        let (follow_up_program, follow_up_args) =
+            builder.build_follow_up(&[]).unwrap().into_parts();
+        assert_eq!(follow_up_program, program);
+        assert_eq!(follow_up_args, args);
+
+        let overridden = agent(serde_json::json!({
+            "command": "npx -y @zed-industries/claude-code-acp",
+            "base_command_override": "/opt/acme/bin/acme-acp --stdio"
+        }));
+        let (program, args) = overridden
+            .build_command_builder()
+            .unwrap()
+            .build_initial()
+            .unwrap()
+            .into_parts();
+        assert_eq!(program, "/opt/acme/bin/acme-acp");
+        assert_eq!(args, ["--stdio"]);
+    }
+
+    #[test]
+    fn sessions_resume_only_within_the_same_agent() {
+        let claude = agent(serde_json::json!({
+            "command": "npx -y @zed-industries/claude-code-acp"
+        }));
+        let claude_plan = agent(serde_json::json!({
+            "command": "npx -y @zed-industries/claude-code-acp",
+            "mode": "plan",
+            "model": "opus"
+        }));
+        let goose = agent(serde_json::json!({ "command": "goose acp" }));
+        let overridden = agent(serde_json::json!({
+            "command": "npx -y @zed-industries/claude-code-acp",
+            "base_command_override": "goose acp"
+        }));
+
+        // Switching model or mode keeps the recorded sessions resumable
+        assert_eq!(claude.session_namespace(), claude_plan.session_namespace());
+        assert_ne!(claude.session_namespace(), goose.session_namespace());
+        assert_eq!(overridden.session_namespace(), goose.session_namespace());
+    }
+}
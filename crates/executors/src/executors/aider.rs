@@ -0,0 +1,222 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum_macros::AsRefStr;
+use tokio::process::Command;
+use ts_rs::TS;
+use uuid::Uuid;
+use workspace_utils::{msg_store::MsgStore, shell::resolve_executable_path_blocking};
+
+use crate::{
+    command::{CmdOverrides, CommandBuildError, CommandBuilder, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+    },
+    logs::utils::EntryIndexProvider,
+    stdout_dup::tee_stdout_with_appender,
+};
+
+pub mod normalize_logs;
+
+use normalize_logs::{SESSION_MARKER, normalize_logs};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS, JsonSchema, AsRefStr)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+#[ts(rename = "AiderEditFormat")]
+pub enum EditFormat {
+    Whole,
+    Diff,
+    DiffFenced,
+    Udiff,
+    Architect,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct Aider {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Model",
+        description = "Model to use, e.g. sonnet, gpt-4o or deepseek/deepseek-chat"
+    )]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Edit Format",
+        description = "How the model writes edits; defaults to the model's preferred format"
+    )]
+    pub edit_format: Option<EditFormat>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+}
+
+impl Aider {
+    pub fn base_command() -> &'static str {
+        "aider"
+    }
+
+    fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
+        // Vibe Kanban commits the worktree itself, and the terminal UI
+        // features only garble captured output
+        let mut builder = CommandBuilder::new(Self::base_command()).params([
+            "--yes-always",
+            "--no-auto-commits",
+            "--no-dirty-commits",
+            "--no-gitignore",
+            "--no-pretty",
+            "--no-fancy-input",
+            "--no-check-update",
+            "--no-show-release-notes",
+            "--analytics-disable",
+        ]);
+
+        if let Some(model) = &self.model {
+            builder = builder.extend_params(["--model", model]);
+        }
+
+        if let Some(edit_format) = &self.edit_format {
+            builder = builder.extend_params(["--edit-format", edit_format.as_ref()]);
+        }
+
+        apply_overrides(builder, &self.cmd)
+    }
+
+    /// Run one message against the chat history of `session_id`
+    async fn spawn_session(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        restore_history: bool,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let sessions_dir = sessions_dir().map_err(ExecutorError::Io)?;
+        let history = |suffix: &str| {
+            sessions_dir
+                .join(format!("{session_id}.{suffix}"))
+                .to_string_lossy()
+                .into_owned()
+        };
+        let mut session_args = vec![
+            "--chat-history-file".to_string(),
+            history("chat.md"),
+            "--input-history-file".to_string(),
+            history("input"),
+        ];
+        if restore_history {
+            session_args.push("--restore-chat-history".to_string());
+        }
+        session_args.push("--message".to_string());
+        session_args.push(self.append_prompt.combine_prompt(prompt));
+
+        let command_parts = self
+            .build_command_builder()?
+            .build_follow_up(&session_args)?;
+        let (executable_path, args) = env.resolve(command_parts, current_dir).await?;
+
+        let mut command = Command::new(executable_path);
+        command
+            .kill_on_drop(true)
+            // Aider never reads stdin when given --message
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .env("PYTHONUNBUFFERED", "1")
+            .args(&args);
+
+        env.clone()
+            .with_profile(&self.cmd)
+            .apply_to_command(&mut command);
+
+        let mut child = command.group_spawn()?;
+
+        // Aider doesn't print anything identifying the history file, so
+        // announce the session for log normalization
+        let (_, appender) = tee_stdout_with_appender(&mut child)?;
+        appender.append_line(format!("{SESSION_MARKER}{session_id}"));
+
+        Ok(child.into())
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for Aider {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let session_id = Uuid::new_v4().to_string();
+        self.spawn_session(current_dir, prompt, &session_id, false, env)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        _reset_to_message_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_session(current_dir, prompt, session_id, true, env)
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        normalize_logs(
+            msg_store.clone(),
+            worktree_path,
+            EntryIndexProvider::start_from(&msg_store),
+        );
+    }
+
+    fn default_mcp_config_path(&self) -> Option<PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let program = self
+            .build_command_builder()
+            .and_then(|builder| builder.build_initial())
+            .map(|parts| parts.into_parts().0);
+        match program {
+            Ok(program) if resolve_executable_path_blocking(&program).is_some() => {
+                AvailabilityInfo::InstallationFound
+            }
+            _ => AvailabilityInfo::NotFound,
+        }
+    }
+}
+
+/// Directory holding Aider's chat and input histories, one pair per session
+fn sessions_dir() -> io::Result<PathBuf> {
+    let mut vk_dir = dirs::home_dir()
+        .ok_or_else(|| io::Error::other("Could not determine home directory"))?
+        .join(".vibe-kanban");
+    if cfg!(debug_assertions) {
+        vk_dir = vk_dir.join("dev");
+    }
+    let dir = vk_dir.join("aider_sessions");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Whether `path`, relative to the worktree root, is one of the caches Aider
+/// keeps there. They stay out of the task's commits.
+pub fn is_aider_file(path: &str) -> bool {
+    path.starts_with(".aider")
+}
@@ -0,0 +1,419 @@
+//! Normalization of Aider's plain-text output. The model's reply is shown as
+//! assistant messages, except for the edit blocks it contains (SEARCH/REPLACE
+//! or unified diff), which become file edits completed once Aider reports
+//! applying them.
+
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use futures::StreamExt;
+use workspace_utils::{
+    diff::{create_unified_diff, normalize_unified_diff},
+    msg_store::MsgStore,
+    path::make_path_relative,
+};
+
+use crate::logs::{
+    ActionType, FileChange, NormalizedEntry, NormalizedEntryType, ToolStatus,
+    stderr_processor::normalize_stderr_logs,
+    utils::{
+        EntryIndexProvider,
+        patch::{add_normalized_entry, replace_normalized_entry},
+    },
+};
+
+/// Line the executor adds to stdout to announce the chat history in use
+pub const SESSION_MARKER: &str = "[vibe-kanban] aider session: ";
+
+/// Status lines Aider prints around the model's reply
+const SYSTEM_PREFIXES: &[&str] = &[
+    "Aider v",
+    "Main model:",
+    "Weak model:",
+    "Editor model:",
+    "Git repo:",
+    "Repo-map:",
+    "Tokens:",
+    "Cost:",
+    "Added ",
+    "Restored previous conversation history",
+    "Warning:",
+];
+
+const APPLIED_PREFIX: &str = "Applied edit to ";
+
+#[derive(Debug)]
+enum AiderEvent {
+    Text(String),
+    System(String),
+    Edit { path: String, change: FileChange },
+    Applied(String),
+}
+
+/// Edit block being read
+enum Block {
+    SearchReplace {
+        path: String,
+        search: Vec<String>,
+        replace: Vec<String>,
+        in_replace: bool,
+    },
+    Udiff(Vec<String>),
+}
+
+/// Turns output lines into events. Lines that may open an edit block (a file
+/// name or a code fence) are held back until the next line shows whether
+/// they belong to the reply's text.
+#[derive(Default)]
+struct AiderOutputParser {
+    held: Vec<String>,
+    block: Option<Block>,
+    /// Drop the fence closing the block that just ended
+    skip_fence: bool,
+}
+
+impl AiderOutputParser {
+    fn push_line(&mut self, line: &str) -> Vec<AiderEvent> {
+        let trimmed = line.trim();
+        let mut events = Vec::new();
+
+        if let Some(block) = &mut self.block {
+            match block {
+                Block::SearchReplace {
+                    path,
+                    search,
+                    replace,
+                    in_replace,
+                } => {
+                    if trimmed.starts_with(">>>>>>> REPLACE") {
+                        let path = std::mem::take(path);
+                        let old = lines_to_text(search);
+                        let new = lines_to_text(replace);
+                        events.push(AiderEvent::Edit {
+                            change: FileChange::Edit {
+                                unified_diff: create_unified_diff(&path, &old, &new),
+                                has_line_numbers: false,
+                            },
+                            path,
+                        });
+                        self.block = None;
+                        self.skip_fence = true;
+                    } else if !*in_replace && trimmed == "=======" {
+                        *in_replace = true;
+                    } else if *in_replace {
+                        replace.push(line.to_string());
+                    } else {
+                        search.push(line.to_string());
+                    }
+                }
+                Block::Udiff(lines) => {
+                    if trimmed.starts_with("```") {
+                        events.extend(udiff_edits(lines));
+                        self.block = None;
+                    } else {
+                        lines.push(line.to_string());
+                    }
+                }
+            }
+            return events;
+        }
+
+        if std::mem::take(&mut self.skip_fence) && trimmed == "```" {
+            return events;
+        }
+
+        if trimmed.starts_with("<<<<<<< SEARCH") {
+            let path = self
+                .held
+                .drain(..)
+                .rev()
+                .find(|held| !held.trim().starts_with("```"))
+                .map(|held| held.trim().to_string())
+                .unwrap_or_default();
+            self.block = Some(Block::SearchReplace {
+                path,
+                search: Vec::new(),
+                replace: Vec::new(),
+                in_replace: false,
+            });
+            return events;
+        }
+
+        if line.starts_with("--- ")
+            && self
+                .held
+                .last()
+                .is_some_and(|held| held.trim().starts_with("```diff"))
+        {
+            self.held.clear();
+            self.block = Some(Block::Udiff(vec![line.to_string()]));
+            return events;
+        }
+
+        if trimmed.starts_with("```") || looks_like_path(trimmed) {
+            if self.held.len() == 2 {
+                events.push(AiderEvent::Text(self.held.remove(0)));
+            }
+            self.held.push(line.to_string());
+            return events;
+        }
+
+        events.extend(self.flush());
+        if let Some(path) = trimmed.strip_prefix(APPLIED_PREFIX) {
+            events.push(AiderEvent::Applied(path.trim().to_string()));
+        } else if SYSTEM_PREFIXES
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix))
+        {
+            events.push(AiderEvent::System(trimmed.to_string()));
+        } else {
+            events.push(AiderEvent::Text(line.to_string()));
+        }
+        events
+    }
+
+    /// Release held lines as text
+    fn flush(&mut self) -> Vec<AiderEvent> {
+        self.held.drain(..).map(AiderEvent::Text).collect()
+    }
+}
+
+fn lines_to_text(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+/// Bare relative or absolute file path, as Aider writes above edit blocks
+fn looks_like_path(line: &str) -> bool {
+    !line.is_empty()
+        && line.len() < 256
+        && !line.contains(char::is_whitespace)
+        && (line.contains('.') || line.contains('/'))
+        && !line.ends_with([':', ',', '!', '?'])
+}
+
+/// One edit per file in a unified diff block
+fn udiff_edits(lines: &[String]) -> Vec<AiderEvent> {
+    let mut edits = Vec::new();
+    let mut start = 0;
+    for end in 1..=lines.len() {
+        let next_file = lines.get(end).is_some_and(|line| line.starts_with("--- "))
+            && lines
+                .get(end + 1)
+                .is_some_and(|line| line.starts_with("+++ "));
+        if end < lines.len() && !next_file {
+            continue;
+        }
+        let file = &lines[start..end];
+        let path = file
+            .iter()
+            .find_map(|line| line.strip_prefix("+++ "))
+            .or_else(|| file.first().and_then(|line| line.strip_prefix("--- ")))
+            .map(|path| {
+                let path = path.trim();
+                path.strip_prefix("b/")
+                    .or_else(|| path.strip_prefix("a/"))
+                    .unwrap_or(path)
+                    .to_string()
+            })
+            .unwrap_or_default();
+        edits.push(AiderEvent::Edit {
+            change: FileChange::Edit {
+                unified_diff: normalize_unified_diff(&path, &lines_to_text(file)),
+                has_line_numbers: false,
+            },
+            path,
+        });
+        start = end;
+    }
+    edits
+}
+
+fn file_edit(path: String, changes: Vec<FileChange>, status: ToolStatus) -> NormalizedEntry {
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::ToolUse {
+            tool_name: "edit".to_string(),
+            action_type: ActionType::FileEdit {
+                path: path.clone(),
+                changes,
+            },
+            status,
+        },
+        content: path,
+        metadata: None,
+    }
+}
+
+pub fn normalize_logs(
+    msg_store: Arc<MsgStore>,
+    worktree_path: &Path,
+    entry_index_provider: EntryIndexProvider,
+) {
+    normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+
+    let worktree_path = worktree_path.to_string_lossy().into_owned();
+    tokio::spawn(async move {
+        let mut lines = msg_store.stdout_lines_stream();
+        let mut parser = AiderOutputParser::default();
+        // Entry being extended by consecutive lines of the same kind
+        let mut current: Option<(usize, NormalizedEntry)> = None;
+        // Path -> edits not yet reported as applied
+        let mut pending_edits: HashMap<String, Vec<(usize, NormalizedEntry)>> = HashMap::new();
+
+        while let Some(Ok(line)) = lines.next().await {
+            if let Some(session_id) = line.strip_prefix(SESSION_MARKER) {
+                msg_store.push_session_id(session_id.trim().to_string());
+                continue;
+            }
+
+            for event in parser.push_line(&line) {
+                let (entry_type, text) = match event {
+                    AiderEvent::Text(text) => (NormalizedEntryType::AssistantMessage, text),
+                    AiderEvent::System(text) => (NormalizedEntryType::SystemMessage, text),
+                    AiderEvent::Edit { path, change } => {
+                        current = None;
+                        let path = make_path_relative(&path, &worktree_path);
+                        let entry = file_edit(path.clone(), vec![change], ToolStatus::Created);
+                        let index =
+                            add_normalized_entry(&msg_store, &entry_index_provider, entry.clone());
+                        pending_edits.entry(path).or_default().push((index, entry));
+                        continue;
+                    }
+                    AiderEvent::Applied(path) => {
+                        current = None;
+                        let path = make_path_relative(&path, &worktree_path);
+                        match pending_edits.remove(&path) {
+                            Some(edits) => {
+                                for (index, entry) in edits {
+                                    if let Some(applied) =
+                                        entry.with_tool_status(ToolStatus::Success)
+                                    {
+                                        replace_normalized_entry(&msg_store, index, applied);
+                                    }
+                                }
+                            }
+                            // Whole-file edits aren't parsed; record that the file changed
+                            None => {
+                                add_normalized_entry(
+                                    &msg_store,
+                                    &entry_index_provider,
+                                    file_edit(path, Vec::new(), ToolStatus::Success),
+                                );
+                            }
+                        }
+                        continue;
+                    }
+                };
+
+                if let Some((index, entry)) = current.as_mut().filter(|(_, entry)| {
+                    std::mem::discriminant(&entry.entry_type) == std::mem::discriminant(&entry_type)
+                }) {
+                    entry.content.push('\n');
+                    entry.content.push_str(&text);
+                    replace_normalized_entry(&msg_store, *index, entry.clone());
+                    continue;
+                }
+                if text.trim().is_empty() {
+                    continue;
+                }
+                let entry = NormalizedEntry {
+                    timestamp: None,
+                    entry_type,
+                    content: text,
+                    metadata: None,
+                };
+                let index = add_normalized_entry(&msg_store, &entry_index_provider, entry.clone());
+                current = Some((index, entry));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(output: &str) -> Vec<AiderEvent> {
+        let mut parser = AiderOutputParser::default();
+        let mut events: Vec<_> = output
+            .lines()
+            .flat_map(|line| parser.push_line(line))
+            .collect();
+        events.extend(parser.flush());
+        events
+    }
+
+    #[test]
+    fn search_replace_blocks_become_edits() {
+        let events = parse(
+            "Aider v0.86.1\n\
+             I'll rename the function.\n\
+             \n\
+             src/lib.rs\n\
+             ```rust\n\
+             <<<<<<< SEARCH\n\
+             fn old() {}\n\
+             =======\n\
+             fn new() {}\n\
+             >>>>>>> REPLACE\n\
+             ```\n\
+             Applied edit to src/lib.rs\n\
+             Tokens: 2.1k sent, 120 received.",
+        );
+
+        assert!(matches!(&events[0], AiderEvent::System(text) if text == "Aider v0.86.1"));
+        assert!(
+            matches!(&events[1], AiderEvent::Text(text) if text == "I'll rename the function.")
+        );
+        let AiderEvent::Edit {
+            path,
+            change: FileChange::Edit { unified_diff, .. },
+        } = &events[3]
+        else {
+            panic!("expected an edit, got {:?}", events[3]);
+        };
+        assert_eq!(path, "src/lib.rs");
+        assert!(unified_diff.contains("-fn old() {}"));
+        assert!(unified_diff.contains("+fn new() {}"));
+        assert!(matches!(&events[4], AiderEvent::Applied(path) if path == "src/lib.rs"));
+        assert!(matches!(events[5], AiderEvent::System(_)));
+        assert_eq!(events.len(), 6);
+    }
+
+    #[test]
+    fn unified_diff_blocks_split_per_file() {
+        let events = parse(
+            "```diff\n\
+             --- a/one.py\n\
+             +++ b/one.py\n\
+             @@ ... @@\n\
+             -a = 1\n\
+             +a = 2\n\
+             --- a/two.py\n\
+             +++ b/two.py\n\
+             @@ ... @@\n\
+             +b = 3\n\
+             ```",
+        );
+
+        let paths: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                AiderEvent::Edit { path, .. } => Some(path.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(paths, ["one.py", "two.py"]);
+    }
+
+    #[test]
+    fn held_lines_fall_back_to_text() {
+        let texts: Vec<_> = parse("See README.md\nREADME.md\nfor details")
+            .into_iter()
+            .map(|event| match event {
+                AiderEvent::Text(text) => text,
+                other => panic!("expected text, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(texts, ["See README.md", "README.md", "for details"]);
+    }
+}
@@ -52,7 +52,7 @@ impl StandardCodingAgentExecutor for Amp {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let command_parts = self.build_command_builder()?.build_initial()?;
-        let (executable_path, args) = command_parts.into_resolved().await?;
+        let (executable_path, args) = env.resolve(command_parts, current_dir).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
@@ -96,16 +96,19 @@ impl StandardCodingAgentExecutor for Amp {
             "fork".to_string(),
             session_id.to_string(),
         ])?;
-        let (fork_program, fork_args) = fork_line.into_resolved().await?;
-        let fork_output = Command::new(fork_program)
+        let (fork_program, fork_args) = env.resolve(fork_line, current_dir).await?;
+        let mut fork_command = Command::new(fork_program);
+        fork_command
             .kill_on_drop(true)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .env("NPM_CONFIG_LOGLEVEL", "error")
-            .args(&fork_args)
-            .output()
-            .await?;
+            .args(&fork_args);
+        env.clone()
+            .with_profile(&self.cmd)
+            .apply_to_command(&mut fork_command);
+        let fork_output = fork_command.output().await?;
         let stdout_str = String::from_utf8_lossy(&fork_output.stdout);
         let new_thread_id = stdout_str
             .lines()
@@ -128,7 +131,7 @@ impl StandardCodingAgentExecutor for Amp {
             "continue".to_string(),
             new_thread_id.clone(),
         ])?;
-        let (continue_program, continue_args) = continue_line.into_resolved().await?;
+        let (continue_program, continue_args) = env.resolve(continue_line, current_dir).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
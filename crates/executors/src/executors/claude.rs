@@ -38,8 +38,8 @@ use crate::{
         codex::client::LogWriter, utils::reorder_slash_commands,
     },
     logs::{
-        ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
-        TodoItem, ToolStatus,
+        ActionType, FileChange, ModelTokenUsage, NormalizedEntry, NormalizedEntryError,
+        NormalizedEntryType, TodoItem, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
             EntryIndexProvider,
@@ -264,15 +264,20 @@ impl StandardCodingAgentExecutor for ClaudeCode {
     async fn available_slash_commands(
         &self,
         current_dir: &Path,
+        env: &ExecutionEnv,
     ) -> Result<futures::stream::BoxStream<'static, json_patch::Patch>, ExecutorError> {
         let defaults = Self::hardcoded_slash_commands();
         let this = self.clone();
         let current_dir = current_dir.to_path_buf();
+        let env = env.clone();
 
         let initial = patch::slash_commands(defaults.clone(), true, None);
 
         let discovery_stream = futures::stream::once(async move {
-            match this.discover_available_slash_commands(&current_dir).await {
+            match this
+                .discover_available_slash_commands(&current_dir, &env)
+                .await
+            {
                 Ok(commands) => {
                     let merged = reorder_slash_commands([commands, defaults].concat());
                     patch::slash_commands(merged, false, None)
@@ -298,7 +303,7 @@ impl ClaudeCode {
         command_parts: CommandParts,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = env.resolve(command_parts, current_dir).await?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
         let mut command = Command::new(program_path);
@@ -381,6 +386,7 @@ impl ClaudeCode {
             child,
             exit_signal: None,
             cancel: Some(cancel),
+            msg_store: None,
         })
     }
 }
@@ -410,6 +416,8 @@ pub struct ClaudeLogProcessor {
     main_model_name: Option<String>,
     main_model_context_window: u32,
     context_tokens_used: u32,
+    // Billed tokens per model for this run, replaced by the result message's exact totals
+    run_usage: Vec<ModelTokenUsage>,
 }
 
 impl ClaudeLogProcessor {
@@ -429,6 +437,7 @@ impl ClaudeLogProcessor {
             last_assistant_message: None,
             main_model_context_window: DEFAULT_CLAUDE_CONTEXT_WINDOW,
             context_tokens_used: 0,
+            run_usage: Vec::new(),
         }
     }
 
@@ -1334,6 +1343,7 @@ impl ClaudeLogProcessor {
                         let output_tokens = usage.output_tokens.unwrap_or(0);
                         let total_tokens = input_tokens + output_tokens;
                         self.context_tokens_used = total_tokens as u32;
+                        self.add_run_usage(usage);
 
                         patches.push(self.add_token_usage_entry(entry_index_provider));
                     }
@@ -1353,25 +1363,62 @@ impl ClaudeLogProcessor {
                 ..
             } => {
                 // get the real model context window and correct the context usage entry
-                if let Some(context_window) = model_usage.as_ref().and_then(|model_usage| {
+                let context_window = model_usage.as_ref().and_then(|model_usage| {
                     self.main_model_name
                         .as_ref()
                         .and_then(|name| model_usage.get(name))
                         .and_then(|usage| usage.context_window)
-                }) {
+                });
+                if let Some(context_window) = context_window {
                     self.main_model_context_window = context_window;
+                }
+                // the result reports exact totals, including subagents
+                let run_usage = model_usage
+                    .as_ref()
+                    .map(|model_usage| {
+                        let mut run_usage = model_usage
+                            .iter()
+                            .map(|(model, usage)| usage.to_model_token_usage(model))
+                            .collect::<Vec<_>>();
+                        run_usage.sort_by(|a, b| a.model.cmp(&b.model));
+                        run_usage
+                    })
+                    .filter(|run_usage| !run_usage.is_empty());
+                if let Some(run_usage) = run_usage {
+                    self.run_usage = run_usage;
+                }
+                if context_window.is_some() || !self.run_usage.is_empty() {
                     patches.push(self.add_token_usage_entry(entry_index_provider));
                 }
 
                 if matches!(self.strategy, HistoryStrategy::AmpResume) && is_error.unwrap_or(false)
                 {
+                    let content =
+                        serde_json::to_string(claude_json).unwrap_or_else(|_| "error".to_string());
                     let entry = NormalizedEntry {
                         timestamp: None,
                         entry_type: NormalizedEntryType::ErrorMessage {
-                            error_type: NormalizedEntryError::Other,
+                            error_type: NormalizedEntryError::classify(&content),
                         },
-                        content: serde_json::to_string(claude_json)
-                            .unwrap_or_else(|_| "error".to_string()),
+                        content,
+                        metadata: Some(
+                            serde_json::to_value(claude_json).unwrap_or(serde_json::Value::Null),
+                        ),
+                    };
+                    let idx = entry_index_provider.next();
+                    patches.push(ConversationPatch::add_normalized_entry(idx, entry));
+                } else if is_error.unwrap_or(false)
+                    && let Some(text) = result.as_ref().and_then(|v| v.as_str())
+                    && NormalizedEntryError::classify(text) == NormalizedEntryError::Retryable
+                {
+                    // API errors arrive as the result text; surface transient ones
+                    // as errors so the run can be retried
+                    let entry = NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::ErrorMessage {
+                            error_type: NormalizedEntryError::Retryable,
+                        },
+                        content: text.to_string(),
                         metadata: Some(
                             serde_json::to_value(claude_json).unwrap_or(serde_json::Value::Null),
                         ),
@@ -1543,6 +1590,29 @@ impl ClaudeLogProcessor {
         }
     }
 
+    /// Add a main agent message's usage to the running totals
+    fn add_run_usage(&mut self, usage: &ClaudeUsage) {
+        let model = self
+            .main_model_name
+            .clone()
+            .unwrap_or_else(|| "claude".to_string());
+        let index = match self.run_usage.iter().position(|u| u.model == model) {
+            Some(index) => index,
+            None => {
+                self.run_usage.push(ModelTokenUsage {
+                    model,
+                    ..Default::default()
+                });
+                self.run_usage.len() - 1
+            }
+        };
+        let totals = &mut self.run_usage[index];
+        totals.input_tokens +=
+            usage.input_tokens.unwrap_or(0) + usage.cache_creation_input_tokens.unwrap_or(0);
+        totals.output_tokens += usage.output_tokens.unwrap_or(0);
+        totals.cached_input_tokens += usage.cache_read_input_tokens.unwrap_or(0);
+    }
+
     fn add_token_usage_entry(
         &mut self,
         entry_index_provider: &EntryIndexProvider,
@@ -1552,6 +1622,7 @@ impl ClaudeLogProcessor {
             entry_type: NormalizedEntryType::TokenUsageInfo(crate::logs::TokenUsageInfo {
                 total_tokens: self.context_tokens_used,
                 model_context_window: self.main_model_context_window,
+                run_usage: self.run_usage.clone(),
             }),
             content: format!(
                 "Tokens used: {} / Context window: {}",
@@ -1964,6 +2035,27 @@ pub struct ClaudeUsage {
 pub struct ClaudeModelUsage {
     #[serde(default)]
     pub context_window: Option<u32>,
+    #[serde(default)]
+    pub input_tokens: Option<u64>,
+    #[serde(default)]
+    pub output_tokens: Option<u64>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<u64>,
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<u64>,
+}
+
+impl ClaudeModelUsage {
+    /// Cache writes are billed as input, cache reads are reported as cached input
+    fn to_model_token_usage(&self, model: &str) -> ModelTokenUsage {
+        ModelTokenUsage {
+            model: model.to_string(),
+            input_tokens: self.input_tokens.unwrap_or(0)
+                + self.cache_creation_input_tokens.unwrap_or(0),
+            output_tokens: self.output_tokens.unwrap_or(0),
+            cached_input_tokens: self.cache_read_input_tokens.unwrap_or(0),
+        }
+    }
 }
 
 /// Structured tool data for Claude tools based on real samples
@@ -2260,6 +2352,45 @@ mod tests {
         assert_eq!(entries[0].content, "Final result");
     }
 
+    #[test]
+    fn test_result_api_error_is_retryable() {
+        let result_json = r#"{"type":"result","subtype":"success","is_error":true,"result":"API Error: 529 {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}"}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+
+        let entries = normalize(&parsed, "");
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::Retryable,
+            }
+        ));
+    }
+
+    #[test]
+    fn test_result_model_usage_reports_run_usage() {
+        let result_json = r#"{"type":"result","subtype":"success","is_error":false,"result":"Done","modelUsage":{"claude-sonnet-4":{"inputTokens":120,"outputTokens":40,"cacheReadInputTokens":900,"cacheCreationInputTokens":30,"contextWindow":200000}}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+
+        let entries = normalize(&parsed, "");
+        let usage = entries
+            .iter()
+            .find_map(|entry| match &entry.entry_type {
+                NormalizedEntryType::TokenUsageInfo(usage) => Some(usage),
+                _ => None,
+            })
+            .expect("token usage entry");
+        assert_eq!(
+            usage.run_usage,
+            vec![ModelTokenUsage {
+                model: "claude-sonnet-4".to_string(),
+                input_tokens: 150,
+                output_tokens: 40,
+                cached_input_tokens: 900,
+            }]
+        );
+    }
+
     #[test]
     fn test_thinking_content() {
         let thinking_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"Let me think about this..."}]}}"#;
@@ -16,7 +16,7 @@ use walkdir::WalkDir;
 use super::{ClaudeCode, ClaudeJson, ClaudePlugin, base_command};
 use crate::{
     command::{CommandBuildError, CommandBuilder, apply_overrides},
-    env::{ExecutionEnv, RepoContext},
+    env::ExecutionEnv,
     executors::{
         BaseCodingAgent, ExecutorError, SlashCommandDescription,
         utils::{SlashCommandCache, SlashCommandCacheKey},
@@ -198,12 +198,13 @@ impl ClaudeCode {
     async fn discover_available_command_and_plugins(
         &self,
         current_dir: &Path,
+        env: &ExecutionEnv,
     ) -> Result<(Vec<String>, Vec<ClaudePlugin>), ExecutorError> {
         let command_builder = self
             .build_slash_commands_discovery_command_builder()
             .await?;
         let command_parts = command_builder.build_initial()?;
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = env.resolve(command_parts, current_dir).await?;
 
         let mut command = Command::new(program_path);
         command
@@ -214,7 +215,7 @@ impl ClaudeCode {
             .current_dir(current_dir)
             .args(&args);
 
-        ExecutionEnv::new(RepoContext::default(), false, String::new())
+        env.clone()
             .with_profile(&self.cmd)
             .apply_to_command(&mut command);
 
@@ -264,6 +265,7 @@ impl ClaudeCode {
     pub async fn discover_available_slash_commands(
         &self,
         current_dir: &Path,
+        env: &ExecutionEnv,
     ) -> Result<Vec<SlashCommandDescription>, ExecutorError> {
         let key = SlashCommandCacheKey::new(current_dir, &BaseCodingAgent::ClaudeCode);
         if let Some(cached) = SlashCommandCache::instance().get(&key) {
@@ -272,7 +274,7 @@ impl ClaudeCode {
 
         // Run claude-code to discover commands and plugins
         let (names, plugins) = self
-            .discover_available_command_and_plugins(current_dir)
+            .discover_available_command_and_plugins(current_dir, env)
             .await?;
 
         // Run file walk to discover command descriptions, including from plugins
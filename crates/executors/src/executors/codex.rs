@@ -172,6 +172,7 @@ impl StandardCodingAgentExecutor for Codex {
     async fn available_slash_commands(
         &self,
         _workdir: &Path,
+        _env: &ExecutionEnv,
     ) -> Result<futures::stream::BoxStream<'static, json_patch::Patch>, ExecutorError> {
         let commands = vec![
             SlashCommandDescription {
@@ -450,7 +451,7 @@ impl Codex {
         F: FnOnce(Arc<AppServerClient>, ExitSignalSender) -> Fut + Send + 'static,
         Fut: std::future::Future<Output = Result<(), ExecutorError>> + Send + 'static,
     {
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = env.resolve(command_parts, current_dir).await?;
 
         let mut process = Command::new(program_path);
         process
@@ -557,6 +558,7 @@ impl Codex {
             child,
             exit_signal: Some(exit_signal_rx),
             cancel: Some(cancel),
+            msg_store: None,
         })
     }
 }
@@ -16,8 +16,8 @@ use codex_protocol::{
         ErrorEvent, EventMsg, ExecApprovalRequestEvent, ExecCommandBeginEvent, ExecCommandEndEvent,
         ExecCommandOutputDeltaEvent, ExecOutputStream, FileChange as CodexProtoFileChange,
         McpInvocation, McpToolCallBeginEvent, McpToolCallEndEvent, PatchApplyBeginEvent,
-        PatchApplyEndEvent, StreamErrorEvent, ViewImageToolCallEvent, WarningEvent,
-        WebSearchBeginEvent, WebSearchEndEvent,
+        PatchApplyEndEvent, StreamErrorEvent, TokenUsage as CodexTokenUsage,
+        ViewImageToolCallEvent, WarningEvent, WebSearchBeginEvent, WebSearchEndEvent,
     },
 };
 use futures::StreamExt;
@@ -33,9 +33,9 @@ use crate::{
     approvals::ToolCallMetadata,
     executors::codex::session::SessionHandler,
     logs::{
-        ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
-        NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolResultValueType,
-        ToolStatus,
+        ActionType, CommandExitStatus, CommandRunResult, FileChange, ModelTokenUsage,
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult,
+        ToolResultValueType, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
             ConversationPatch, EntryIndexProvider,
@@ -214,6 +214,9 @@ struct LogState {
     mcp_tools: HashMap<String, McpToolState>,
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
+    model: Option<String>,
+    /// Session usage that predates this run, e.g. from the turns of a resumed session
+    usage_baseline: Option<ModelTokenUsage>,
 }
 
 enum StreamingTextKind {
@@ -231,6 +234,38 @@ impl LogState {
             mcp_tools: HashMap::new(),
             patches: HashMap::new(),
             web_searches: HashMap::new(),
+            model: None,
+            usage_baseline: None,
+        }
+    }
+
+    /// Codex reports usage for the whole session, so subtract what was used before
+    /// this run's first request to get the run's own usage
+    fn run_token_usage(
+        &mut self,
+        total: &CodexTokenUsage,
+        last: &CodexTokenUsage,
+    ) -> ModelTokenUsage {
+        let model = self.model.clone().unwrap_or_else(|| "codex".to_string());
+        let total = model_token_usage(&model, total);
+        let baseline = self.usage_baseline.get_or_insert_with(|| {
+            let last = model_token_usage(&model, last);
+            ModelTokenUsage {
+                model: model.clone(),
+                input_tokens: total.input_tokens.saturating_sub(last.input_tokens),
+                output_tokens: total.output_tokens.saturating_sub(last.output_tokens),
+                cached_input_tokens: total
+                    .cached_input_tokens
+                    .saturating_sub(last.cached_input_tokens),
+            }
+        });
+        ModelTokenUsage {
+            model,
+            input_tokens: total.input_tokens.saturating_sub(baseline.input_tokens),
+            output_tokens: total.output_tokens.saturating_sub(baseline.output_tokens),
+            cached_input_tokens: total
+                .cached_input_tokens
+                .saturating_sub(baseline.cached_input_tokens),
         }
     }
 
@@ -386,6 +421,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     server_notification
                 {
                     msg_store.push_session_id(session_configured.session_id.to_string());
+                    state.model = Some(session_configured.model.clone());
                     handle_model_params(
                         session_configured.model,
                         session_configured.reasoning_effort,
@@ -424,6 +460,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             match event {
                 EventMsg::SessionConfigured(payload) => {
                     msg_store.push_session_id(payload.session_id.to_string());
+                    state.model = Some(payload.model.clone());
                     handle_model_params(
                         payload.model,
                         payload.reasoning_effort,
@@ -684,18 +721,17 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     codex_error_info,
                     ..
                 }) => {
-                    add_normalized_entry(
-                        &msg_store,
-                        &entry_index,
+                    add_normalized_entry(&msg_store, &entry_index, {
+                        let content = format!("Stream error: {message} {codex_error_info:?}");
                         NormalizedEntry {
                             timestamp: None,
                             entry_type: NormalizedEntryType::ErrorMessage {
-                                error_type: NormalizedEntryError::Other,
+                                error_type: NormalizedEntryError::classify(&content),
                             },
-                            content: format!("Stream error: {message} {codex_error_info:?}"),
+                            content,
                             metadata: None,
-                        },
-                    );
+                        }
+                    });
                 }
                 EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
                     call_id,
@@ -979,21 +1015,22 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     message,
                     codex_error_info,
                 }) => {
-                    add_normalized_entry(
-                        &msg_store,
-                        &entry_index,
+                    add_normalized_entry(&msg_store, &entry_index, {
+                        let content = format!("Error: {message} {codex_error_info:?}");
                         NormalizedEntry {
                             timestamp: None,
                             entry_type: NormalizedEntryType::ErrorMessage {
-                                error_type: NormalizedEntryError::Other,
+                                error_type: NormalizedEntryError::classify(&content),
                             },
-                            content: format!("Error: {message} {codex_error_info:?}"),
+                            content,
                             metadata: None,
-                        },
-                    );
+                        }
+                    });
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        let run_usage =
+                            state.run_token_usage(&info.total_token_usage, &info.last_token_usage);
                         add_normalized_entry(
                             &msg_store,
                             &entry_index,
@@ -1006,6 +1043,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                             .model_context_window
                                             .unwrap_or_default()
                                             as u32,
+                                        run_usage: vec![run_usage],
                                     },
                                 ),
                                 content: format!(
@@ -1101,6 +1139,17 @@ fn handle_jsonrpc_response(
     );
 }
 
+/// Codex counts cached tokens as part of the input, split them out
+fn model_token_usage(model: &str, usage: &CodexTokenUsage) -> ModelTokenUsage {
+    let cached_input_tokens = usage.cached_input_tokens as u64;
+    ModelTokenUsage {
+        model: model.to_string(),
+        input_tokens: (usage.input_tokens as u64).saturating_sub(cached_input_tokens),
+        output_tokens: usage.output_tokens as u64,
+        cached_input_tokens,
+    }
+}
+
 fn handle_model_params(
     model: String,
     reasoning_effort: Option<ReasoningEffort>,
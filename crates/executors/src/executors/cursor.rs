@@ -81,7 +81,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
 
         let command_parts = self.build_command_builder()?.build_initial()?;
 
-        let (executable_path, args) = command_parts.into_resolved().await?;
+        let (executable_path, args) = env.resolve(command_parts, current_dir).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
@@ -122,7 +122,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
         let command_parts = self
             .build_command_builder()?
             .build_follow_up(&["--resume".to_string(), session_id.to_string()])?;
-        let (executable_path, args) = command_parts.into_resolved().await?;
+        let (executable_path, args) = env.resolve(command_parts, current_dir).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
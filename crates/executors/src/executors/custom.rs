@@ -0,0 +1,296 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command};
+use ts_rs::TS;
+use uuid::Uuid;
+use workspace_utils::{msg_store::MsgStore, shell::resolve_executable_path_blocking};
+
+use crate::{
+    command::{CmdOverrides, CommandBuildError, CommandBuilder, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+    },
+    logs::utils::EntryIndexProvider,
+};
+
+pub mod normalize_logs;
+
+use normalize_logs::{JsonlMapping, normalize_logs};
+
+/// How the prompt is handed to the agent
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename = "CustomPromptInput")]
+pub enum PromptInput {
+    /// Written to stdin, which is then closed
+    #[default]
+    Stdin,
+    /// Passed as the last argument, after `prompt_flag` when set
+    Argument,
+    /// Written to a file in the workspace whose path is passed like an argument
+    File,
+}
+
+/// How the agent's stdout is turned into conversation entries
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(tag = "format", rename_all = "snake_case")]
+#[ts(rename = "CustomOutput")]
+pub enum Output {
+    /// Stdout is shown as assistant messages, split where the output pauses
+    PlainText {
+        /// Regex whose first capture group is the session id on a stdout line
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        session_id_pattern: Option<String>,
+    },
+    /// One JSON object per stdout line, mapped onto entries by rules
+    Jsonl(JsonlMapping),
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Output::PlainText {
+            session_id_pattern: None,
+        }
+    }
+}
+
+/// Agent CLI defined entirely in `profiles.json`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct Custom {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Command",
+        description = "Command that runs the agent non-interactively, e.g. `acme-agent run --json`"
+    )]
+    pub command: String,
+    #[serde(default)]
+    #[schemars(title = "Prompt Input", description = "How the prompt is passed")]
+    pub prompt_input: PromptInput,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Prompt Flag",
+        description = "Argument placed before the prompt or prompt file, e.g. `--prompt`"
+    )]
+    pub prompt_flag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Follow-up Arguments",
+        description = "Arguments continuing a session, with `{session_id}` replaced. Follow-ups are disabled when unset"
+    )]
+    pub follow_up_args: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Resume Arguments",
+        description = "Arguments continuing a session from an earlier message, with `{session_id}` and `{message_id}` replaced. Follow-up arguments are used when unset"
+    )]
+    pub resume_args: Option<Vec<String>>,
+    #[serde(default)]
+    #[schemars(title = "Output", description = "Format of the agent's stdout")]
+    pub output: Output,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+}
+
+impl Custom {
+    fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
+        apply_overrides(CommandBuilder::new(self.command.clone()), &self.cmd)
+    }
+
+    /// Start the agent with `session_args` followed by the prompt arguments
+    async fn spawn_with_args(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_args: Vec<String>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+
+        let mut args = session_args;
+        let prompt_value = match self.prompt_input {
+            PromptInput::Stdin => None,
+            PromptInput::Argument => Some(combined_prompt.clone()),
+            PromptInput::File => {
+                let path = prompt_file_path(env);
+                tokio::fs::write(&path, &combined_prompt).await?;
+                Some(path.to_string_lossy().into_owned())
+            }
+        };
+        if let Some(value) = prompt_value {
+            args.extend(self.prompt_flag.clone());
+            args.push(value);
+        }
+
+        let command_parts = self.build_command_builder()?.build_follow_up(&args)?;
+        let (executable_path, args) = env.resolve(command_parts, current_dir).await?;
+
+        let mut command = Command::new(executable_path);
+        command
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .args(&args);
+
+        env.clone()
+            .with_profile(&self.cmd)
+            .apply_to_command(&mut command);
+
+        let mut child = command.group_spawn()?;
+
+        if let Some(mut stdin) = child.inner().stdin.take() {
+            if self.prompt_input == PromptInput::Stdin {
+                stdin.write_all(combined_prompt.as_bytes()).await?;
+            }
+            stdin.shutdown().await?;
+        }
+
+        Ok(child.into())
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for Custom {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_with_args(current_dir, prompt, Vec::new(), env)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        reset_to_message_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let template = match (reset_to_message_id, &self.resume_args) {
+            (Some(_), Some(resume_args)) => resume_args,
+            _ => self.follow_up_args.as_ref().ok_or_else(|| {
+                ExecutorError::FollowUpNotSupported(
+                    "the custom executor has no follow_up_args".to_string(),
+                )
+            })?,
+        };
+        let session_args = fill_template(template, session_id, reset_to_message_id);
+        self.spawn_with_args(current_dir, prompt, session_args, env)
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        normalize_logs(
+            &self.output,
+            msg_store.clone(),
+            worktree_path,
+            EntryIndexProvider::start_from(&msg_store),
+        );
+    }
+
+    fn default_mcp_config_path(&self) -> Option<PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let program = self
+            .build_command_builder()
+            .and_then(|builder| builder.build_initial())
+            .map(|parts| parts.into_parts().0);
+        match program {
+            Ok(program) if resolve_executable_path_blocking(&program).is_some() => {
+                AvailabilityInfo::InstallationFound
+            }
+            _ => AvailabilityInfo::NotFound,
+        }
+    }
+}
+
+/// `template` with `{session_id}` and `{message_id}` replaced
+fn fill_template(template: &[String], session_id: &str, message_id: Option<&str>) -> Vec<String> {
+    template
+        .iter()
+        .map(|arg| {
+            arg.replace("{session_id}", session_id)
+                .replace("{message_id}", message_id.unwrap_or_default())
+        })
+        .collect()
+}
+
+/// Prompt files live next to the worktrees rather than inside them, so they
+/// stay out of diffs but are visible wherever the workspace is mounted
+fn prompt_file_path(env: &ExecutionEnv) -> PathBuf {
+    let workspace_root = &env.repo_context.workspace_root;
+    let dir = if workspace_root.as_os_str().is_empty() {
+        std::env::temp_dir()
+    } else {
+        workspace_root.clone()
+    };
+    dir.join(format!(".vibe-kanban-prompt-{}.md", Uuid::new_v4()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_definition_deserializes() {
+        let custom: Custom = serde_json::from_value(serde_json::json!({
+            "command": "acme-agent run",
+            "prompt_input": "argument",
+            "prompt_flag": "--prompt",
+            "follow_up_args": ["--session", "{session_id}"],
+            "resume_args": ["--session", "{session_id}", "--from", "{message_id}"],
+            "output": {
+                "format": "jsonl",
+                "session_id": "/session",
+                "rules": [{ "when": { "/type": "text" }, "entry": "assistant_message", "content": "/text" }]
+            },
+            "env": { "ACME_QUIET": "1" }
+        }))
+        .unwrap();
+
+        assert_eq!(custom.prompt_input, PromptInput::Argument);
+        assert!(matches!(custom.output, Output::Jsonl(ref mapping) if mapping.rules.len() == 1));
+        assert_eq!(
+            custom
+                .cmd
+                .env
+                .unwrap()
+                .get("ACME_QUIET")
+                .map(String::as_str),
+            Some("1")
+        );
+
+        let plain: Custom = serde_json::from_str(r#"{"command": "acme-agent"}"#).unwrap();
+        assert_eq!(plain.prompt_input, PromptInput::Stdin);
+        assert_eq!(plain.output, Output::default());
+    }
+
+    #[test]
+    fn templates_fill_session_and_message_ids() {
+        let template = [
+            "--session".to_string(),
+            "{session_id}@{message_id}".to_string(),
+        ];
+        assert_eq!(
+            fill_template(&template, "s1", Some("m7")),
+            ["--session", "s1@m7"]
+        );
+        assert_eq!(fill_template(&template, "s1", None), ["--session", "s1@"]);
+    }
+}
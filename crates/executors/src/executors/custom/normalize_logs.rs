@@ -0,0 +1,476 @@
+//! Log normalization for the custom executor: plain text through
+//! `PlainTextLogProcessor`, or JSONL mapped onto entries by declarative rules.
+
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
+
+use futures::StreamExt;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+use workspace_utils::{msg_store::MsgStore, path::make_path_relative};
+
+use super::Output;
+use crate::logs::{
+    ActionType, CommandExitStatus, CommandRunResult, NormalizedEntry, NormalizedEntryError,
+    NormalizedEntryType, ToolResult, ToolStatus,
+    plain_text_processor::PlainTextLogProcessor,
+    stderr_processor::normalize_stderr_logs,
+    utils::{
+        EntryIndexProvider,
+        patch::{add_normalized_entry, replace_normalized_entry},
+    },
+};
+
+/// Mapping from JSONL output lines onto conversation entries
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[ts(rename = "CustomJsonlMapping")]
+pub struct JsonlMapping {
+    /// JSON pointer to the session id follow-ups continue, e.g. `/session_id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// JSON pointer to a message id that resume arguments can refer to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    /// Checked in order; the first rule matching a line decides its entry.
+    /// Lines no rule matches are dropped.
+    #[serde(default)]
+    pub rules: Vec<JsonlRule>,
+}
+
+/// Turns matching output lines into one kind of entry. Fields other than
+/// `when` and `entry` are JSON pointers into the line.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[ts(rename = "CustomJsonlRule")]
+pub struct JsonlRule {
+    /// Values the line must contain, keyed by JSON pointer. Strings are
+    /// compared as is, other values by their JSON text.
+    #[serde(default)]
+    pub when: HashMap<String, String>,
+    pub entry: JsonlEntry,
+    /// Text of the entry, or the output of a tool result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    /// What a tool use does
+    #[serde(default)]
+    pub action: JsonlAction,
+    /// The path, command, query or URL of a tool use, depending on `action`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Pairs a tool result with its tool use
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// Boolean marking a tool result as failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename = "CustomJsonlEntry")]
+pub enum JsonlEntry {
+    AssistantMessage,
+    Thinking,
+    SystemMessage,
+    ErrorMessage,
+    ToolUse,
+    /// Completes the tool use with the same `tool_call_id`
+    ToolResult,
+    /// Drops the line, e.g. to hide events a later rule would match
+    Ignore,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename = "CustomJsonlAction")]
+pub enum JsonlAction {
+    FileRead,
+    FileEdit,
+    CommandRun,
+    Search,
+    WebFetch,
+    #[default]
+    Tool,
+    Other,
+}
+
+impl JsonlMapping {
+    fn rule_for(&self, line: &Value) -> Option<&JsonlRule> {
+        self.rules.iter().find(|rule| {
+            rule.when.iter().all(|(pointer, expected)| {
+                text_at(line, pointer).as_deref() == Some(expected.as_str())
+            })
+        })
+    }
+}
+
+impl JsonlRule {
+    fn field(&self, line: &Value, pointer: &Option<String>) -> Option<String> {
+        pointer
+            .as_deref()
+            .and_then(|pointer| text_at(line, pointer))
+    }
+
+    /// The entry this rule makes of `line`; tool results and ignored lines
+    /// don't produce one
+    fn entry(&self, line: &Value, worktree_path: &str) -> Option<NormalizedEntry> {
+        let content = self.field(line, &self.content).unwrap_or_default();
+        let entry_type = match self.entry {
+            JsonlEntry::AssistantMessage => NormalizedEntryType::AssistantMessage,
+            JsonlEntry::Thinking => NormalizedEntryType::Thinking,
+            JsonlEntry::SystemMessage => NormalizedEntryType::SystemMessage,
+            JsonlEntry::ErrorMessage => NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::classify(&content),
+            },
+            JsonlEntry::ToolUse => {
+                let tool_name = self
+                    .field(line, &self.tool_name)
+                    .unwrap_or_else(|| "tool".to_string());
+                let target = self.field(line, &self.target).unwrap_or_default();
+                let action_type = match self.action {
+                    JsonlAction::FileRead => ActionType::FileRead {
+                        path: make_path_relative(&target, worktree_path),
+                    },
+                    JsonlAction::FileEdit => ActionType::FileEdit {
+                        path: make_path_relative(&target, worktree_path),
+                        changes: Vec::new(),
+                    },
+                    JsonlAction::CommandRun => ActionType::CommandRun {
+                        command: target.clone(),
+                        result: None,
+                    },
+                    JsonlAction::Search => ActionType::Search {
+                        query: target.clone(),
+                    },
+                    JsonlAction::WebFetch => ActionType::WebFetch {
+                        url: target.clone(),
+                    },
+                    JsonlAction::Tool => ActionType::Tool {
+                        tool_name: tool_name.clone(),
+                        arguments: None,
+                        result: None,
+                    },
+                    JsonlAction::Other => ActionType::Other {
+                        description: target.clone(),
+                    },
+                };
+                let content = if target.is_empty() {
+                    tool_name.clone()
+                } else {
+                    target
+                };
+                return Some(NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::ToolUse {
+                        tool_name,
+                        action_type,
+                        status: ToolStatus::Created,
+                    },
+                    content,
+                    metadata: None,
+                });
+            }
+            JsonlEntry::ToolResult | JsonlEntry::Ignore => return None,
+        };
+        (!content.trim().is_empty()).then_some(NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content,
+            metadata: None,
+        })
+    }
+
+    /// `tool_use` completed by this rule's tool result in `line`
+    fn complete(&self, tool_use: &NormalizedEntry, line: &Value) -> Option<NormalizedEntry> {
+        let NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+            ..
+        } = &tool_use.entry_type
+        else {
+            return None;
+        };
+        let failed = self
+            .is_error
+            .as_deref()
+            .and_then(|pointer| line.pointer(pointer))
+            .is_some_and(|value| value.as_bool().unwrap_or(false));
+        let output = self.field(line, &self.content);
+
+        let action_type = match action_type.clone() {
+            ActionType::CommandRun { command, .. } => ActionType::CommandRun {
+                command,
+                result: Some(CommandRunResult {
+                    exit_status: Some(CommandExitStatus::Success { success: !failed }),
+                    output,
+                }),
+            },
+            ActionType::Tool {
+                tool_name,
+                arguments,
+                ..
+            } => ActionType::Tool {
+                tool_name,
+                arguments,
+                result: output.map(ToolResult::markdown),
+            },
+            other => other,
+        };
+        Some(NormalizedEntry {
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: tool_name.clone(),
+                action_type,
+                status: if failed {
+                    ToolStatus::Failed
+                } else {
+                    ToolStatus::Success
+                },
+            },
+            ..tool_use.clone()
+        })
+    }
+}
+
+/// Value at `pointer` as text: strings as is, other values as JSON, `null`
+/// as absent
+fn text_at(line: &Value, pointer: &str) -> Option<String> {
+    match line.pointer(pointer)? {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+pub fn normalize_logs(
+    output: &Output,
+    msg_store: Arc<MsgStore>,
+    worktree_path: &Path,
+    entry_index_provider: EntryIndexProvider,
+) {
+    normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+
+    match output {
+        Output::PlainText { session_id_pattern } => normalize_plain_text(
+            session_id_pattern.as_deref(),
+            msg_store,
+            entry_index_provider,
+        ),
+        Output::Jsonl(mapping) => normalize_jsonl(
+            mapping.clone(),
+            msg_store,
+            worktree_path,
+            entry_index_provider,
+        ),
+    }
+}
+
+fn normalize_plain_text(
+    session_id_pattern: Option<&str>,
+    msg_store: Arc<MsgStore>,
+    entry_index_provider: EntryIndexProvider,
+) {
+    match session_id_pattern.map(Regex::new) {
+        Some(Ok(pattern)) => {
+            let msg_store = msg_store.clone();
+            tokio::spawn(async move {
+                let mut lines = msg_store.stdout_lines_stream();
+                while let Some(Ok(line)) = lines.next().await {
+                    if let Some(session_id) = pattern.captures(&line).and_then(|c| c.get(1)) {
+                        msg_store.push_session_id(session_id.as_str().to_string());
+                        break;
+                    }
+                }
+            });
+        }
+        Some(Err(e)) => tracing::warn!("Invalid custom executor session_id_pattern: {}", e),
+        None => {}
+    }
+
+    tokio::spawn(async move {
+        let mut stdout = msg_store.stdout_chunked_stream();
+        let mut processor = PlainTextLogProcessor::builder()
+            .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content: strip_ansi_escapes::strip_str(&content),
+                metadata: None,
+            }))
+            .time_gap(Duration::from_secs(2))
+            .index_provider(entry_index_provider)
+            .build();
+
+        while let Some(Ok(chunk)) = stdout.next().await {
+            for patch in processor.process(chunk) {
+                msg_store.push_patch(patch);
+            }
+        }
+    });
+}
+
+fn normalize_jsonl(
+    mapping: JsonlMapping,
+    msg_store: Arc<MsgStore>,
+    worktree_path: &Path,
+    entry_index_provider: EntryIndexProvider,
+) {
+    let worktree_path = worktree_path.to_string_lossy().into_owned();
+    tokio::spawn(async move {
+        let mut lines = msg_store.stdout_lines_stream();
+        let mut session_id_reported = false;
+        let mut last_message_id: Option<String> = None;
+        // Tool call id -> index and entry of its tool use
+        let mut tool_uses: HashMap<String, (usize, NormalizedEntry)> = HashMap::new();
+
+        while let Some(Ok(line)) = lines.next().await {
+            let Ok(value) = serde_json::from_str::<Value>(&line) else {
+                // Non-JSON output is shown as is
+                if !line.trim().is_empty() {
+                    add_normalized_entry(
+                        &msg_store,
+                        &entry_index_provider,
+                        NormalizedEntry {
+                            timestamp: None,
+                            entry_type: NormalizedEntryType::SystemMessage,
+                            content: line,
+                            metadata: None,
+                        },
+                    );
+                }
+                continue;
+            };
+
+            if !session_id_reported
+                && let Some(session_id) = mapping
+                    .session_id
+                    .as_deref()
+                    .and_then(|pointer| text_at(&value, pointer))
+            {
+                msg_store.push_session_id(session_id);
+                session_id_reported = true;
+            }
+            if let Some(message_id) = mapping
+                .message_id
+                .as_deref()
+                .and_then(|pointer| text_at(&value, pointer))
+                && last_message_id.as_ref() != Some(&message_id)
+            {
+                msg_store.push_message_id(message_id.clone());
+                last_message_id = Some(message_id);
+            }
+
+            let Some(rule) = mapping.rule_for(&value) else {
+                continue;
+            };
+            let tool_call_id = rule.field(&value, &rule.tool_call_id);
+
+            if rule.entry == JsonlEntry::ToolResult {
+                if let Some((index, tool_use)) = tool_call_id.and_then(|id| tool_uses.remove(&id))
+                    && let Some(completed) = rule.complete(&tool_use, &value)
+                {
+                    replace_normalized_entry(&msg_store, index, completed);
+                }
+                continue;
+            }
+
+            if let Some(entry) = rule.entry(&value, &worktree_path) {
+                let index = add_normalized_entry(&msg_store, &entry_index_provider, entry.clone());
+                if rule.entry == JsonlEntry::ToolUse
+                    && let Some(id) = tool_call_id
+                {
+                    tool_uses.insert(id, (index, entry));
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn mapping() -> JsonlMapping {
+        serde_json::from_value(json!({
+            "session_id": "/session",
+            "rules": [
+                { "when": { "/type": "text", "/partial": "true" }, "entry": "ignore" },
+                { "when": { "/type": "text" }, "entry": "assistant_message", "content": "/text" },
+                {
+                    "when": { "/type": "call", "/tool": "shell" },
+                    "entry": "tool_use",
+                    "tool_name": "/tool",
+                    "action": "command_run",
+                    "target": "/input/cmd",
+                    "tool_call_id": "/id"
+                },
+                {
+                    "when": { "/type": "result" },
+                    "entry": "tool_result",
+                    "content": "/output",
+                    "tool_call_id": "/id",
+                    "is_error": "/error"
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn first_matching_rule_decides_the_entry() {
+        let mapping = mapping();
+
+        let partial = json!({ "type": "text", "partial": true, "text": "Hel" });
+        assert_eq!(
+            mapping.rule_for(&partial).map(|rule| rule.entry),
+            Some(JsonlEntry::Ignore)
+        );
+
+        let text = json!({ "type": "text", "text": "Hello" });
+        let entry = mapping
+            .rule_for(&text)
+            .unwrap()
+            .entry(&text, "/ws")
+            .unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+        assert_eq!(entry.content, "Hello");
+
+        assert!(mapping.rule_for(&json!({ "type": "usage" })).is_none());
+    }
+
+    #[test]
+    fn tool_results_complete_their_tool_use() {
+        let mapping = mapping();
+        let call = json!({ "type": "call", "tool": "shell", "id": "c1", "input": { "cmd": "cargo test" } });
+        let tool_use = mapping
+            .rule_for(&call)
+            .unwrap()
+            .entry(&call, "/ws")
+            .unwrap();
+        assert_eq!(tool_use.content, "cargo test");
+
+        let result = json!({ "type": "result", "id": "c1", "output": "1 failed", "error": true });
+        let completed = mapping
+            .rule_for(&result)
+            .unwrap()
+            .complete(&tool_use, &result)
+            .unwrap();
+        let NormalizedEntryType::ToolUse {
+            action_type: ActionType::CommandRun { command, result },
+            status,
+            ..
+        } = completed.entry_type
+        else {
+            panic!("expected a command run");
+        };
+        assert_eq!(command, "cargo test");
+        assert!(matches!(status, ToolStatus::Failed));
+        assert_eq!(result.unwrap().output.as_deref(), Some("1 failed"));
+    }
+}
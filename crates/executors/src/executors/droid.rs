@@ -109,7 +109,7 @@ async fn spawn_droid(
     env: &ExecutionEnv,
     cmd_overrides: &crate::command::CmdOverrides,
 ) -> Result<SpawnedChild, ExecutorError> {
-    let (program_path, args) = command_parts.into_resolved().await?;
+    let (program_path, args) = env.resolve(command_parts, current_dir).await?;
 
     let mut command = Command::new(program_path);
     command
@@ -51,7 +51,7 @@ pub fn normalize_logs(
                         let entry = NormalizedEntry {
                             timestamp: None,
                             entry_type: NormalizedEntryType::ErrorMessage {
-                                error_type: NormalizedEntryError::Other,
+                                error_type: NormalizedEntryError::classify(&error.message),
                             },
                             content: error.message,
                             metadata: None,
@@ -656,7 +656,7 @@ pub fn normalize_logs(
                     let entry = NormalizedEntry {
                         timestamp: None,
                         entry_type: NormalizedEntryType::ErrorMessage {
-                            error_type: NormalizedEntryError::Other,
+                            error_type: NormalizedEntryError::classify(message),
                         },
                         content: message.clone(),
                         metadata: None,
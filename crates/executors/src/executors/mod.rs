@@ -21,22 +21,26 @@ use crate::{
     command::CommandBuildError,
     env::ExecutionEnv,
     executors::{
-        amp::Amp, auggie::Auggie, claude::ClaudeCode, codex::Codex, copilot::Copilot,
-        cursor::CursorAgent, droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
+        acp::AcpAgent, aider::Aider, amp::Amp, auggie::Auggie, claude::ClaudeCode, codex::Codex,
+        copilot::Copilot, cursor::CursorAgent, custom::Custom, droid::Droid, gemini::Gemini,
+        openai_compatible::OpenaiCompatible, opencode::Opencode, qwen::QwenCode,
     },
     logs::utils::patch,
     mcp_config::McpConfig,
 };
 
 pub mod acp;
+pub mod aider;
 pub mod amp;
 pub mod auggie;
 pub mod claude;
 pub mod codex;
 pub mod copilot;
 pub mod cursor;
+pub mod custom;
 pub mod droid;
 pub mod gemini;
+pub mod openai_compatible;
 pub mod opencode;
 #[cfg(feature = "qa-mode")]
 pub mod qa_mock;
@@ -88,6 +92,8 @@ pub enum ExecutorError {
     SetupHelperNotSupported,
     #[error("Auth required: {0}")]
     AuthRequired(String),
+    #[error("Sandboxed execution is only supported on Linux")]
+    SandboxUnsupported,
 }
 
 #[enum_dispatch]
@@ -119,6 +125,10 @@ pub enum CodingAgent {
     Copilot,
     Droid,
     Auggie,
+    Aider,
+    Acp(AcpAgent),
+    OpenaiCompatible,
+    Custom,
     #[cfg(feature = "qa-mode")]
     QaMock(QaMockExecutor),
 }
@@ -189,11 +199,15 @@ impl CodingAgent {
                 BaseAgentCapability::SetupHelper,
                 BaseAgentCapability::ContextUsage,
             ],
-            Self::Amp(_) | Self::Gemini(_) | Self::QwenCode(_) | Self::Auggie(_) => {
+            Self::Amp(_) | Self::Gemini(_) | Self::QwenCode(_) | Self::Auggie(_) | Self::Acp(_) => {
                 vec![BaseAgentCapability::SessionFork]
             }
             Self::CursorAgent(_) => vec![BaseAgentCapability::SetupHelper],
-            Self::Copilot(_) | Self::Droid(_) => vec![],
+            Self::Copilot(_)
+            | Self::Droid(_)
+            | Self::Aider(_)
+            | Self::OpenaiCompatible(_)
+            | Self::Custom(_) => vec![],
             #[cfg(feature = "qa-mode")]
             Self::QaMock(_) => vec![], // QA mock doesn't need special capabilities
         }
@@ -226,6 +240,7 @@ pub trait StandardCodingAgentExecutor {
     async fn available_slash_commands(
         &self,
         _workdir: &Path,
+        _env: &ExecutionEnv,
     ) -> Result<BoxStream<'static, json_patch::Patch>, ExecutorError> {
         Ok(Box::pin(futures::stream::once(async move {
             patch::slash_commands(Vec::new(), false, None)
@@ -313,6 +328,9 @@ pub struct SpawnedChild {
     pub exit_signal: Option<ExecutorExitSignal>,
     /// Container → Executor: signals when container wants to cancel the execution
     pub cancel: Option<CancellationToken>,
+    /// Executor → Container: store the executor pushes its log messages to
+    /// itself, used in place of a new one
+    pub msg_store: Option<Arc<MsgStore>>,
 }
 
 impl From<AsyncGroupChild> for SpawnedChild {
@@ -321,6 +339,7 @@ impl From<AsyncGroupChild> for SpawnedChild {
             child,
             exit_signal: None,
             cancel: None,
+            msg_store: None,
         }
     }
 }
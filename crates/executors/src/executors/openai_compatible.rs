@@ -0,0 +1,185 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use derivative::Derivative;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use ts_rs::TS;
+use workspace_utils::msg_store::MsgStore;
+
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::CmdOverrides,
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, ExecutorExitResult, SpawnedChild,
+        StandardCodingAgentExecutor,
+    },
+    logs::{stderr_processor::normalize_stderr_logs, utils::EntryIndexProvider},
+    stdout_dup::spawn_local_output_process,
+};
+
+pub mod agent;
+pub mod client;
+pub mod tools;
+
+use agent::{LogWriter, LoopConfig, SessionStore};
+use client::ChatClient;
+
+const DEFAULT_MAX_TURNS: u32 = 50;
+
+/// Agent loop run by Vibe Kanban itself against an OpenAI-compatible chat
+/// completions endpoint, such as llama.cpp server, vLLM or Ollama
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct OpenaiCompatible {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Base URL",
+        description = "Base URL of the API, e.g. `http://localhost:11434/v1` for Ollama"
+    )]
+    pub base_url: String,
+    #[schemars(title = "Model", description = "Model name sent with each request")]
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "API Key Variable",
+        description = "Environment variable holding the API key, for servers that require one"
+    )]
+    pub api_key_env: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(title = "Temperature")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Max Turns",
+        description = "Model requests per run before giving up (default 50)"
+    )]
+    pub max_turns: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Auto Approve",
+        description = "Run commands without asking for approval"
+    )]
+    pub auto_approve: Option<bool>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl OpenaiCompatible {
+    async fn spawn_loop(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        resume_session: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let env = env.clone().with_profile(&self.cmd);
+        let api_key = self
+            .api_key_env
+            .as_deref()
+            .and_then(|name| env.get(name).cloned().or_else(|| std::env::var(name).ok()));
+        let client = ChatClient::new(
+            &self.base_url,
+            api_key,
+            self.model.clone(),
+            self.temperature,
+        )?;
+        let sessions = SessionStore::new(SessionStore::default_dir().map_err(ExecutorError::Io)?)
+            .map_err(ExecutorError::Io)?;
+        let approvals = if self.auto_approve.unwrap_or(false) {
+            None
+        } else {
+            self.approvals.clone()
+        };
+        let config = LoopConfig {
+            client,
+            sessions,
+            root: current_dir.to_path_buf(),
+            env,
+            prompt: self.append_prompt.combine_prompt(prompt),
+            resume_session: resume_session.map(str::to_string),
+            max_turns: self.max_turns.unwrap_or(DEFAULT_MAX_TURNS),
+            approvals,
+        };
+
+        // The loop runs in this process and pushes its entries straight into
+        // the execution's store; the placeholder process only lets the
+        // execution be stopped like any other
+        let (mut spawned, _) = spawn_local_output_process()?;
+        let msg_store = Arc::new(MsgStore::new());
+        let log_writer = LogWriter::new(msg_store.clone());
+        let (exit_signal_tx, exit_signal_rx) = tokio::sync::oneshot::channel();
+        let cancel = CancellationToken::new();
+        let cancel_for_task = cancel.clone();
+
+        tokio::spawn(async move {
+            let exit_result = match agent::run(config, &log_writer, cancel_for_task).await {
+                Ok(()) => ExecutorExitResult::Success,
+                Err(err) => {
+                    log_writer.log_error(format!("Agent loop failed: {err}"));
+                    ExecutorExitResult::Failure
+                }
+            };
+            let _ = exit_signal_tx.send(exit_result);
+        });
+
+        spawned.exit_signal = Some(exit_signal_rx);
+        spawned.cancel = Some(cancel);
+        spawned.msg_store = Some(msg_store);
+        Ok(spawned)
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for OpenaiCompatible {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_loop(current_dir, prompt, None, env).await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        _reset_to_message_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_loop(current_dir, prompt, Some(session_id), env)
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, _worktree_path: &Path) {
+        normalize_stderr_logs(
+            msg_store.clone(),
+            EntryIndexProvider::start_from(&msg_store),
+        );
+    }
+
+    fn default_mcp_config_path(&self) -> Option<PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        // Nothing to install; whether the server is up is only known per run
+        AvailabilityInfo::InstallationFound
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tokio_util::sync::CancellationToken;
+use workspace_utils::{approvals::ApprovalStatus, msg_store::MsgStore};
+
+use super::{
+    client::{ChatClient, ChatMessage},
+    tools::{self, Tool},
+};
+use crate::{
+    approvals::{ExecutorApprovalError, ExecutorApprovalService, ToolCallMetadata},
+    env::ExecutionEnv,
+    executors::ExecutorError,
+    logs::{
+        ActionType, NormalizedEntry, NormalizedEntryError, NormalizedEntryType, ToolStatus,
+        utils::{
+            EntryIndexProvider,
+            patch::{add_normalized_entry, replace_normalized_entry},
+        },
+    },
+};
+
+/// Pushes the loop's session and conversation entries straight into the
+/// execution's store
+#[derive(Clone)]
+pub struct LogWriter {
+    msg_store: Arc<MsgStore>,
+    entry_index: EntryIndexProvider,
+}
+
+impl LogWriter {
+    pub fn new(msg_store: Arc<MsgStore>) -> Self {
+        let entry_index = EntryIndexProvider::start_from(&msg_store);
+        Self {
+            msg_store,
+            entry_index,
+        }
+    }
+
+    pub fn start_session(&self, session_id: String) {
+        self.msg_store.push_session_id(session_id);
+    }
+
+    /// Add a new entry and return the index that updates it
+    pub fn add_entry(&self, entry: NormalizedEntry) -> usize {
+        add_normalized_entry(&self.msg_store, &self.entry_index, entry)
+    }
+
+    pub fn update_entry(&self, index: usize, entry: NormalizedEntry) {
+        replace_normalized_entry(&self.msg_store, index, entry);
+    }
+
+    pub fn log_error(&self, message: String) {
+        self.add_entry(text_entry(
+            NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::classify(&message),
+            },
+            message,
+        ));
+    }
+}
+
+/// Conversation histories, one JSON file per session. A follow-up copies the
+/// history into a new session, so resetting to an earlier process resumes
+/// from that process's history.
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(dir: PathBuf) -> io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    pub fn default_dir() -> io::Result<PathBuf> {
+        let mut vk_dir = dirs::home_dir()
+            .ok_or_else(|| io::Error::other("Could not determine home directory"))?
+            .join(".vibe-kanban");
+        if cfg!(debug_assertions) {
+            vk_dir = vk_dir.join("dev");
+        }
+        Ok(vk_dir.join("openai_compatible_sessions"))
+    }
+
+    fn path(&self, session_id: &str) -> PathBuf {
+        self.dir.join(format!("{session_id}.json"))
+    }
+
+    pub fn load(&self, session_id: &str) -> Result<Vec<ChatMessage>, ExecutorError> {
+        let raw = std::fs::read_to_string(self.path(session_id)).map_err(ExecutorError::Io)?;
+        Ok(serde_json::from_str(&raw)?)
+    }
+
+    pub fn save(&self, session_id: &str, messages: &[ChatMessage]) -> Result<(), ExecutorError> {
+        let raw = serde_json::to_string(messages)?;
+        std::fs::write(self.path(session_id), raw).map_err(ExecutorError::Io)
+    }
+}
+
+pub struct LoopConfig {
+    pub client: ChatClient,
+    pub sessions: SessionStore,
+    pub root: PathBuf,
+    pub env: ExecutionEnv,
+    pub prompt: String,
+    pub resume_session: Option<String>,
+    pub max_turns: u32,
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+/// Run the conversation until the model answers without calling a tool
+pub async fn run(
+    config: LoopConfig,
+    log_writer: &LogWriter,
+    cancel: CancellationToken,
+) -> Result<(), ExecutorError> {
+    let mut messages = match &config.resume_session {
+        Some(session_id) => config.sessions.load(session_id)?,
+        None => vec![ChatMessage::system(system_prompt(&config.root))],
+    };
+    messages.push(ChatMessage::user(config.prompt.clone()));
+
+    let session_id = uuid::Uuid::new_v4().to_string();
+    config.sessions.save(&session_id, &messages)?;
+    log_writer.start_session(session_id.clone());
+    log_writer.add_entry(text_entry(
+        NormalizedEntryType::UserMessage,
+        config.prompt.clone(),
+    ));
+
+    let tool_definitions = tools::definitions();
+    for _ in 0..config.max_turns {
+        let reply = tokio::select! {
+            _ = cancel.cancelled() => return Ok(()),
+            reply = config.client.complete(&messages, &tool_definitions) => reply?,
+        };
+        messages.push(reply.clone());
+        config.sessions.save(&session_id, &messages)?;
+
+        if let Some(content) = reply.content.as_deref().map(str::trim)
+            && !content.is_empty()
+        {
+            log_writer.add_entry(text_entry(
+                NormalizedEntryType::AssistantMessage,
+                content.to_string(),
+            ));
+        }
+        if reply.tool_calls.is_empty() {
+            return Ok(());
+        }
+
+        for call in &reply.tool_calls {
+            let output = match Tool::parse(&call.function.name, &call.function.arguments) {
+                Ok(tool) => match run_tool(&config, log_writer, &tool, &call.id, &cancel).await? {
+                    Some(output) => output,
+                    None => return Ok(()),
+                },
+                Err(message) => {
+                    log_writer.add_entry(tool_entry(
+                        &call.function.name,
+                        ActionType::Other {
+                            description: message.clone(),
+                        },
+                        ToolStatus::Failed,
+                        call.function.name.clone(),
+                        &call.id,
+                    ));
+                    message
+                }
+            };
+            messages.push(ChatMessage::tool_result(call.id.clone(), output));
+        }
+        config.sessions.save(&session_id, &messages)?;
+    }
+
+    Err(ExecutorError::Io(io::Error::other(format!(
+        "stopped after {} turns without a final answer",
+        config.max_turns
+    ))))
+}
+
+/// Run one tool call, after approval when it needs one, and return what the
+/// model is told. `None` when the run was cancelled while waiting.
+async fn run_tool(
+    config: &LoopConfig,
+    log_writer: &LogWriter,
+    tool: &Tool,
+    tool_call_id: &str,
+    cancel: &CancellationToken,
+) -> Result<Option<String>, ExecutorError> {
+    let pending = tool_entry(
+        tool.name(),
+        tool.action(),
+        ToolStatus::Created,
+        tool.summary(),
+        tool_call_id,
+    );
+    let index = log_writer.add_entry(pending.clone());
+
+    if tool.needs_approval()
+        && let Some(approvals) = &config.approvals
+    {
+        let status = match approvals
+            .request_tool_approval(
+                tool.name(),
+                tool.approval_input(),
+                tool_call_id,
+                cancel.clone(),
+            )
+            .await
+        {
+            Ok(status) => status,
+            Err(ExecutorApprovalError::Cancelled) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let refusal = match &status {
+            ApprovalStatus::Approved => None,
+            ApprovalStatus::Denied { reason } => Some(match reason {
+                Some(reason) if !reason.trim().is_empty() => {
+                    format!("The user denied this command: {reason}")
+                }
+                _ => "The user denied this command".to_string(),
+            }),
+            ApprovalStatus::TimedOut | ApprovalStatus::Pending => {
+                Some("Approval for this command timed out".to_string())
+            }
+        };
+        if let Some(refusal) = refusal {
+            let status = ToolStatus::from_approval_status(&status).unwrap_or(ToolStatus::TimedOut);
+            if let Some(entry) = pending.with_tool_status(status) {
+                log_writer.update_entry(index, entry);
+            }
+            return Ok(Some(refusal));
+        }
+    }
+
+    let outcome = tool.run(&config.root, &config.env).await;
+    let status = if outcome.success {
+        ToolStatus::Success
+    } else {
+        ToolStatus::Failed
+    };
+    log_writer.update_entry(
+        index,
+        tool_entry(
+            tool.name(),
+            outcome.action,
+            status,
+            tool.summary(),
+            tool_call_id,
+        ),
+    );
+    Ok(Some(outcome.output))
+}
+
+fn system_prompt(root: &Path) -> String {
+    format!(
+        "You are a coding agent working in the repository at {}. Use the tools to inspect \
+         and change files and to run commands; paths are relative to the repository root. \
+         Make the changes yourself instead of describing them. When the task is done, reply \
+         with a short summary of what you changed.",
+        root.display()
+    )
+}
+
+fn text_entry(entry_type: NormalizedEntryType, content: String) -> NormalizedEntry {
+    NormalizedEntry {
+        timestamp: None,
+        entry_type,
+        content,
+        metadata: None,
+    }
+}
+
+fn tool_entry(
+    tool_name: &str,
+    action_type: ActionType,
+    status: ToolStatus,
+    content: String,
+    tool_call_id: &str,
+) -> NormalizedEntry {
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::ToolUse {
+            tool_name: tool_name.to_string(),
+            action_type,
+            status,
+        },
+        content,
+        metadata: serde_json::to_value(ToolCallMetadata {
+            tool_call_id: tool_call_id.to_string(),
+        })
+        .ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+    use workspace_utils::log_msg::LogMsg;
+
+    use super::*;
+    use crate::{env::RepoContext, logs::utils::patch::extract_normalized_entry_from_patch};
+
+    /// Serve `replies` as chat completion responses, one per connection
+    async fn stub_server(replies: Vec<serde_json::Value>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for reply in replies {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut reader = BufReader::new(&mut socket);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).await.unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).await.unwrap();
+
+                let body = serde_json::json!({ "choices": [{ "message": reply }] }).to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{addr}/v1")
+    }
+
+    #[tokio::test]
+    async fn loop_runs_tools_until_the_model_answers() {
+        let root = std::env::temp_dir().join(format!("vk-loop-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+
+        let base_url = stub_server(vec![
+            serde_json::json!({
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {
+                        "name": "write_file",
+                        "arguments": "{\"path\":\"hello.txt\",\"content\":\"hi\\n\"}"
+                    }
+                }]
+            }),
+            serde_json::json!({ "role": "assistant", "content": "Created hello.txt" }),
+        ])
+        .await;
+
+        let msg_store = Arc::new(MsgStore::new());
+        let log_writer = LogWriter::new(msg_store.clone());
+        let config = LoopConfig {
+            client: ChatClient::new(&base_url, None, "stub".to_string(), None).unwrap(),
+            sessions: SessionStore::new(root.join("sessions")).unwrap(),
+            root: root.clone(),
+            env: ExecutionEnv::new(RepoContext::default(), false, String::new()),
+            prompt: "Create hello.txt".to_string(),
+            resume_session: None,
+            max_turns: 5,
+            approvals: None,
+        };
+        run(config, &log_writer, CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(root.join("hello.txt")).unwrap(),
+            "hi\n"
+        );
+
+        let history = msg_store.get_history();
+        let session_id = history
+            .iter()
+            .find_map(|msg| match msg {
+                LogMsg::SessionId(id) => Some(id.clone()),
+                _ => None,
+            })
+            .expect("the session is recorded");
+        let entries: Vec<(usize, NormalizedEntry)> = history
+            .iter()
+            .filter_map(|msg| match msg {
+                LogMsg::JsonPatch(patch) => extract_normalized_entry_from_patch(patch),
+                _ => None,
+            })
+            .collect();
+        // The write is added as pending and replaced once it ran
+        let tool_updates: Vec<_> = entries
+            .iter()
+            .filter_map(|(index, entry)| match &entry.entry_type {
+                NormalizedEntryType::ToolUse { status, .. } => Some((*index, status)),
+                _ => None,
+            })
+            .collect();
+        assert!(matches!(
+            tool_updates.as_slice(),
+            [(1, ToolStatus::Created), (1, ToolStatus::Success)]
+        ));
+        assert!(matches!(
+            entries.last(),
+            Some((2, entry)) if entry.content == "Created hello.txt"
+        ));
+
+        // The history holds the system prompt, the task, both replies and the
+        // tool result
+        let history = SessionStore::new(root.join("sessions"))
+            .unwrap()
+            .load(&session_id)
+            .unwrap();
+        assert_eq!(history.len(), 5);
+        assert_eq!(history[3].tool_call_id.as_deref(), Some("call_1"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,153 @@
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::executors::ExecutorError;
+
+/// A message of the chat completions API, as sent and as stored in session
+/// history
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChatMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::text("system", content)
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::text("user", content)
+    }
+
+    pub fn tool_result(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            tool_call_id: Some(tool_call_id.into()),
+            ..Self::text("tool", content)
+        }
+    }
+
+    fn text(role: &str, content: impl Into<String>) -> Self {
+        Self {
+            role: role.to_string(),
+            content: Some(content.into()),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(default = "function_type")]
+    pub r#type: String,
+    pub function: FunctionCall,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FunctionCall {
+    pub name: String,
+    /// JSON-encoded arguments, as produced by the model
+    #[serde(default)]
+    pub arguments: String,
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: ChatMessage,
+}
+
+/// Minimal client for `POST {base_url}/chat/completions`
+pub struct ChatClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    temperature: Option<f32>,
+}
+
+impl ChatClient {
+    pub fn new(
+        base_url: &str,
+        api_key: Option<String>,
+        model: String,
+        temperature: Option<f32>,
+    ) -> Result<Self, ExecutorError> {
+        let http = reqwest::Client::builder()
+            .build()
+            .map_err(|err| ExecutorError::Io(io::Error::other(err)))?;
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model,
+            temperature,
+        })
+    }
+
+    /// Send the conversation and return the assistant's reply
+    pub async fn complete(
+        &self,
+        messages: &[ChatMessage],
+        tools: &Value,
+    ) -> Result<ChatMessage, ExecutorError> {
+        let mut body = serde_json::json!({
+            "model": self.model,
+            "messages": messages,
+            "tools": tools,
+        });
+        if let Some(temperature) = self.temperature {
+            body["temperature"] = serde_json::json!(temperature);
+        }
+
+        let mut request = self
+            .http
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|err| ExecutorError::Io(io::Error::other(err)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(ExecutorError::Io(io::Error::other(format!(
+                "chat completion failed with {status}: {text}"
+            ))));
+        }
+
+        let response: ChatCompletionResponse = response
+            .json()
+            .await
+            .map_err(|err| ExecutorError::Io(io::Error::other(err)))?;
+        response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message)
+            .ok_or_else(|| {
+                ExecutorError::Io(io::Error::other("chat completion returned no choices"))
+            })
+    }
+}
@@ -0,0 +1,741 @@
+use std::{
+    path::{Component, Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::{io::AsyncWriteExt, process::Command};
+use walkdir::WalkDir;
+use workspace_utils::{diff::create_unified_diff, shell::get_shell_command};
+
+use crate::{
+    command::CommandParts,
+    env::ExecutionEnv,
+    logs::{ActionType, CommandExitStatus, CommandRunResult, FileChange},
+};
+
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(600);
+/// Longest tool output handed back to the model; local models have small
+/// context windows
+const MAX_OUTPUT_CHARS: usize = 20_000;
+const MAX_READ_LINES: usize = 2_000;
+const MAX_SEARCH_MATCHES: usize = 200;
+const MAX_SEARCH_FILE_BYTES: u64 = 1024 * 1024;
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target", "dist", "build"];
+
+/// Tool call requested by the model, with its parsed arguments
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(tag = "name", content = "arguments", rename_all = "snake_case")]
+pub enum Tool {
+    ReadFile {
+        path: String,
+        #[serde(default)]
+        offset: Option<usize>,
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    WriteFile {
+        path: String,
+        content: String,
+    },
+    EditFile {
+        path: String,
+        old_text: String,
+        new_text: String,
+    },
+    RunCommand {
+        command: String,
+    },
+    Search {
+        pattern: String,
+        #[serde(default)]
+        path: Option<String>,
+    },
+}
+
+/// Result of running a tool: what the model is told, and what is shown
+pub struct ToolOutcome {
+    pub output: String,
+    pub success: bool,
+    pub action: ActionType,
+}
+
+impl Tool {
+    pub fn parse(name: &str, arguments: &str) -> Result<Self, String> {
+        let arguments: Value = if arguments.trim().is_empty() {
+            Value::Object(Default::default())
+        } else {
+            serde_json::from_str(arguments)
+                .map_err(|err| format!("Arguments of `{name}` are not valid JSON: {err}"))?
+        };
+        serde_json::from_value(serde_json::json!({ "name": name, "arguments": arguments }))
+            .map_err(|err| format!("Invalid call to `{name}`: {err}"))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::ReadFile { .. } => "read_file",
+            Tool::WriteFile { .. } => "write_file",
+            Tool::EditFile { .. } => "edit_file",
+            Tool::RunCommand { .. } => "run_command",
+            Tool::Search { .. } => "search",
+        }
+    }
+
+    /// Commands go through the approval service; file tools are confined to
+    /// the worktree. Every tool works on the worktree where `env` runs
+    /// processes: in its container or on its remote host when it has one.
+    pub fn needs_approval(&self) -> bool {
+        matches!(self, Tool::RunCommand { .. })
+    }
+
+    pub fn approval_input(&self) -> Value {
+        match self {
+            Tool::RunCommand { command } => serde_json::json!({ "command": command }),
+            _ => Value::Null,
+        }
+    }
+
+    /// Action shown while the tool is pending
+    pub fn action(&self) -> ActionType {
+        match self {
+            Tool::ReadFile { path, .. } => ActionType::FileRead { path: path.clone() },
+            Tool::WriteFile { path, content } => ActionType::FileEdit {
+                path: path.clone(),
+                changes: vec![FileChange::Write {
+                    content: content.clone(),
+                }],
+            },
+            Tool::EditFile { path, .. } => ActionType::FileEdit {
+                path: path.clone(),
+                changes: vec![],
+            },
+            Tool::RunCommand { command } => ActionType::CommandRun {
+                command: command.clone(),
+                result: None,
+            },
+            Tool::Search { pattern, .. } => ActionType::Search {
+                query: pattern.clone(),
+            },
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match self {
+            Tool::ReadFile { path, .. }
+            | Tool::WriteFile { path, .. }
+            | Tool::EditFile { path, .. } => path.clone(),
+            Tool::RunCommand { command } => command.clone(),
+            Tool::Search { pattern, .. } => pattern.clone(),
+        }
+    }
+
+    pub async fn run(&self, root: &Path, env: &ExecutionEnv) -> ToolOutcome {
+        let result = match self {
+            Tool::ReadFile {
+                path,
+                offset,
+                limit,
+            } => read_file(root, env, path, *offset, *limit).await,
+            Tool::WriteFile { path, content } => write_file(root, env, path, content).await,
+            Tool::EditFile {
+                path,
+                old_text,
+                new_text,
+            } => edit_file(root, env, path, old_text, new_text).await,
+            Tool::RunCommand { command } => return run_command(root, command, env).await,
+            Tool::Search { pattern, path } => search(root, env, pattern, path.as_deref()).await,
+        };
+        match result {
+            Ok(outcome) => outcome,
+            Err(output) => ToolOutcome {
+                output,
+                success: false,
+                action: self.action(),
+            },
+        }
+    }
+}
+
+/// JSON schemas of the tools, in the chat completions `tools` format
+pub fn definitions() -> Value {
+    let function = |name: &str, description: &str, parameters: Value| {
+        serde_json::json!({
+            "type": "function",
+            "function": { "name": name, "description": description, "parameters": parameters },
+        })
+    };
+    serde_json::json!([
+        function(
+            "read_file",
+            "Read a text file. Lines are numbered from 1.",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Path relative to the workspace" },
+                    "offset": { "type": "integer", "description": "First line to read" },
+                    "limit": { "type": "integer", "description": "Number of lines to read" },
+                },
+                "required": ["path"],
+            }),
+        ),
+        function(
+            "write_file",
+            "Create or overwrite a file with the given content.",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Path relative to the workspace" },
+                    "content": { "type": "string" },
+                },
+                "required": ["path", "content"],
+            }),
+        ),
+        function(
+            "edit_file",
+            "Replace text in a file. `old_text` must appear exactly once.",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Path relative to the workspace" },
+                    "old_text": { "type": "string" },
+                    "new_text": { "type": "string" },
+                },
+                "required": ["path", "old_text", "new_text"],
+            }),
+        ),
+        function(
+            "run_command",
+            "Run a shell command in the workspace and return its exit code and output.",
+            serde_json::json!({
+                "type": "object",
+                "properties": { "command": { "type": "string" } },
+                "required": ["command"],
+            }),
+        ),
+        function(
+            "search",
+            "Search file contents with a regular expression. Returns `path:line: text` matches.",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string" },
+                    "path": { "type": "string", "description": "Directory to search, relative to the workspace" },
+                },
+                "required": ["pattern"],
+            }),
+        ),
+    ])
+}
+
+/// Resolve `path` against `root`, refusing anything outside of it
+fn resolve_path(root: &Path, path: &str) -> Result<PathBuf, String> {
+    let mut resolved = root.to_path_buf();
+    let candidate = Path::new(path);
+    let relative = if candidate.is_absolute() {
+        candidate
+            .strip_prefix(root)
+            .map_err(|_| format!("`{path}` is outside of the workspace"))?
+    } else {
+        candidate
+    };
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir if resolved != root => {
+                resolved.pop();
+            }
+            _ => return Err(format!("`{path}` is outside of the workspace")),
+        }
+    }
+    Ok(resolved)
+}
+
+/// Whether processes of `env` see the worktree somewhere other than this
+/// machine's filesystem, so files have to be reached through it
+fn runs_elsewhere(env: &ExecutionEnv) -> bool {
+    env.remote.is_some() || env.container.is_some()
+}
+
+/// Run `script` with `sh` where `env` runs processes, passing `args` as its
+/// positional parameters and `input` on stdin
+async fn run_script(
+    root: &Path,
+    env: &ExecutionEnv,
+    script: &str,
+    args: &[&str],
+    input: Option<&str>,
+) -> Result<std::process::Output, String> {
+    let mut script_args = vec!["-c".to_string(), script.to_string(), "sh".to_string()];
+    script_args.extend(args.iter().map(|arg| arg.to_string()));
+    let (program, args) = env
+        .resolve(CommandParts::new("sh".to_string(), script_args), root)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let mut process = Command::new(program);
+    process
+        .args(&args)
+        .current_dir(root)
+        .kill_on_drop(true)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    env.apply_to_command(&mut process);
+
+    let mut child = process.spawn().map_err(|err| err.to_string())?;
+    if let Some(input) = input
+        && let Some(mut stdin) = child.stdin.take()
+    {
+        stdin
+            .write_all(input.as_bytes())
+            .await
+            .map_err(|err| err.to_string())?;
+    }
+    let output = tokio::time::timeout(COMMAND_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| format!("timed out after {} seconds", COMMAND_TIMEOUT.as_secs()))?
+        .map_err(|err| err.to_string())?;
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    match output.status.success() {
+        true => Ok(output),
+        false if stderr.is_empty() => Err(format!("exited with {}", output.status)),
+        false => Err(stderr),
+    }
+}
+
+async fn read_text(root: &Path, env: &ExecutionEnv, file: &Path) -> Result<String, String> {
+    if runs_elsewhere(env) {
+        return read_through(root, env, file).await;
+    }
+    tokio::fs::read_to_string(file)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+async fn write_text(
+    root: &Path,
+    env: &ExecutionEnv,
+    file: &Path,
+    content: &str,
+) -> Result<(), String> {
+    if runs_elsewhere(env) {
+        return write_through(root, env, file, content).await;
+    }
+    if let Some(parent) = file.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|err| format!("failed to create `{}`: {err}", parent.display()))?;
+    }
+    tokio::fs::write(file, content)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+async fn read_through(root: &Path, env: &ExecutionEnv, file: &Path) -> Result<String, String> {
+    let output = run_script(
+        root,
+        env,
+        r#"cat -- "$1""#,
+        &[&file.to_string_lossy()],
+        None,
+    )
+    .await?;
+    String::from_utf8(output.stdout).map_err(|_| "not a UTF-8 text file".to_string())
+}
+
+async fn write_through(
+    root: &Path,
+    env: &ExecutionEnv,
+    file: &Path,
+    content: &str,
+) -> Result<(), String> {
+    run_script(
+        root,
+        env,
+        r#"mkdir -p -- "$(dirname -- "$1")" && cat > "$1""#,
+        &[&file.to_string_lossy()],
+        Some(content),
+    )
+    .await
+    .map(|_| ())
+}
+
+async fn read_file(
+    root: &Path,
+    env: &ExecutionEnv,
+    path: &str,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<ToolOutcome, String> {
+    let file = resolve_path(root, path)?;
+    let content = read_text(root, env, &file)
+        .await
+        .map_err(|err| format!("Failed to read `{path}`: {err}"))?;
+
+    let first = offset.unwrap_or(1).max(1);
+    let limit = limit.unwrap_or(MAX_READ_LINES).min(MAX_READ_LINES);
+    let mut output = content
+        .lines()
+        .enumerate()
+        .skip(first - 1)
+        .take(limit)
+        .map(|(i, line)| format!("{:>6}\t{line}", i + 1))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let remaining = content.lines().count().saturating_sub(first - 1 + limit);
+    if remaining > 0 {
+        output.push_str(&format!("\n… {remaining} more lines"));
+    }
+
+    Ok(ToolOutcome {
+        output,
+        success: true,
+        action: ActionType::FileRead {
+            path: path.to_string(),
+        },
+    })
+}
+
+async fn write_file(
+    root: &Path,
+    env: &ExecutionEnv,
+    path: &str,
+    content: &str,
+) -> Result<ToolOutcome, String> {
+    let file = resolve_path(root, path)?;
+    write_text(root, env, &file, content)
+        .await
+        .map_err(|err| format!("Failed to write `{path}`: {err}"))?;
+
+    Ok(ToolOutcome {
+        output: format!("Wrote {} bytes to {path}", content.len()),
+        success: true,
+        action: ActionType::FileEdit {
+            path: path.to_string(),
+            changes: vec![FileChange::Write {
+                content: content.to_string(),
+            }],
+        },
+    })
+}
+
+async fn edit_file(
+    root: &Path,
+    env: &ExecutionEnv,
+    path: &str,
+    old_text: &str,
+    new_text: &str,
+) -> Result<ToolOutcome, String> {
+    let file = resolve_path(root, path)?;
+    let old_content = read_text(root, env, &file)
+        .await
+        .map_err(|err| format!("Failed to read `{path}`: {err}"))?;
+    match old_content.matches(old_text).count() {
+        0 => return Err(format!("`old_text` was not found in `{path}`")),
+        1 => {}
+        n => {
+            return Err(format!(
+                "`old_text` appears {n} times in `{path}`; include more context"
+            ));
+        }
+    }
+    let new_content = old_content.replacen(old_text, new_text, 1);
+    write_text(root, env, &file, &new_content)
+        .await
+        .map_err(|err| format!("Failed to write `{path}`: {err}"))?;
+
+    Ok(ToolOutcome {
+        output: format!("Edited {path}"),
+        success: true,
+        action: ActionType::FileEdit {
+            path: path.to_string(),
+            changes: vec![FileChange::Edit {
+                unified_diff: create_unified_diff(path, &old_content, &new_content),
+                has_line_numbers: true,
+            }],
+        },
+    })
+}
+
+/// Run `command` through the shell, in the container, sandbox or remote host
+/// of `env` when one is configured
+async fn run_command(root: &Path, command: &str, env: &ExecutionEnv) -> ToolOutcome {
+    let failed = |output: String| ToolOutcome {
+        output,
+        success: false,
+        action: ActionType::CommandRun {
+            command: command.to_string(),
+            result: None,
+        },
+    };
+
+    let (shell, flag) = get_shell_command();
+    let parts = CommandParts::new(shell, vec![flag.to_string(), command.to_string()]);
+    let (program, args) = match env.resolve(parts, root).await {
+        Ok(resolved) => resolved,
+        Err(err) => return failed(format!("Failed to start command: {err}")),
+    };
+
+    let mut process = Command::new(program);
+    process
+        .args(&args)
+        .current_dir(root)
+        .kill_on_drop(true)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    env.apply_to_command(&mut process);
+
+    let output = match tokio::time::timeout(COMMAND_TIMEOUT, process.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(err)) => return failed(format!("Failed to start command: {err}")),
+        Err(_) => {
+            return failed(format!(
+                "Command timed out after {} seconds",
+                COMMAND_TIMEOUT.as_secs()
+            ));
+        }
+    };
+
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    let text = truncate_start(&text, MAX_OUTPUT_CHARS);
+    let code = output.status.code().unwrap_or(-1);
+
+    ToolOutcome {
+        output: format!("Exit code: {code}\n{text}"),
+        success: output.status.success(),
+        action: ActionType::CommandRun {
+            command: command.to_string(),
+            result: Some(CommandRunResult {
+                exit_status: Some(CommandExitStatus::ExitCode { code }),
+                output: Some(text),
+            }),
+        },
+    }
+}
+
+async fn search(
+    root: &Path,
+    env: &ExecutionEnv,
+    pattern: &str,
+    path: Option<&str>,
+) -> Result<ToolOutcome, String> {
+    let regex = Regex::new(pattern).map_err(|err| format!("Invalid pattern: {err}"))?;
+    let dir = resolve_path(root, path.unwrap_or("."))?;
+
+    let matches = if runs_elsewhere(env) {
+        search_through(root, env, &dir, pattern).await?
+    } else {
+        let root = root.to_path_buf();
+        tokio::task::spawn_blocking(move || search_blocking(&root, &dir, &regex))
+            .await
+            .map_err(|err| format!("Search failed: {err}"))?
+    };
+
+    let output = if matches.is_empty() {
+        "No matches".to_string()
+    } else if matches.len() >= MAX_SEARCH_MATCHES {
+        format!(
+            "{}\n… stopped after {MAX_SEARCH_MATCHES} matches",
+            matches.join("\n")
+        )
+    } else {
+        matches.join("\n")
+    };
+
+    Ok(ToolOutcome {
+        output,
+        success: true,
+        action: ActionType::Search {
+            query: pattern.to_string(),
+        },
+    })
+}
+
+fn search_blocking(root: &Path, dir: &Path, regex: &Regex) -> Vec<String> {
+    let mut matches = Vec::new();
+    let entries = WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| SKIPPED_DIRS.contains(&name))
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file());
+
+    for entry in entries {
+        if !matches!(entry.metadata(), Ok(meta) if meta.len() <= MAX_SEARCH_FILE_BYTES) {
+            continue;
+        }
+        // Binary and non-UTF-8 files are skipped
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let display = entry
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .into_owned();
+        for (i, line) in content.lines().enumerate() {
+            if regex.is_match(line) {
+                matches.push(format!("{display}:{}: {}", i + 1, line.trim_end()));
+                if matches.len() >= MAX_SEARCH_MATCHES {
+                    return matches;
+                }
+            }
+        }
+    }
+    matches
+}
+
+/// Search with `grep` where `env` runs processes, in the same `path:line: text`
+/// form as [`search_blocking`]
+async fn search_through(
+    root: &Path,
+    env: &ExecutionEnv,
+    dir: &Path,
+    pattern: &str,
+) -> Result<Vec<String>, String> {
+    let excluded: String = SKIPPED_DIRS
+        .iter()
+        .map(|name| format!(" --exclude-dir={name}"))
+        .collect();
+    // grep exits with 1 when nothing matches
+    let script = format!(r#"grep -rnIE{excluded} -e "$1" -- "$2"; [ $? -le 1 ]"#);
+    let output = run_script(root, env, &script, &[pattern, &dir.to_string_lossy()], None)
+        .await
+        .map_err(|err| format!("Search failed: {err}"))?;
+
+    let prefix = format!("{}/", root.to_string_lossy().trim_end_matches('/'));
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let line = line.strip_prefix(&prefix).unwrap_or(line);
+            let (file, rest) = line.split_once(':')?;
+            let (number, text) = rest.split_once(':')?;
+            Some(format!("{file}:{number}: {}", text.trim_end()))
+        })
+        .take(MAX_SEARCH_MATCHES)
+        .collect())
+}
+
+/// Keep the last `max` characters of `text`, where errors usually are
+fn truncate_start(text: &str, max: usize) -> String {
+    let count = text.chars().count();
+    if count <= max {
+        return text.to_string();
+    }
+    let tail: String = text.chars().skip(count - max).collect();
+    format!("… {} characters omitted\n{tail}", count - max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::RepoContext;
+
+    #[test]
+    fn paths_stay_inside_the_workspace() {
+        let root = Path::new("/work/repo");
+        assert_eq!(
+            resolve_path(root, "src/../lib.rs").unwrap(),
+            Path::new("/work/repo/lib.rs")
+        );
+        assert_eq!(
+            resolve_path(root, "/work/repo/a.txt").unwrap(),
+            Path::new("/work/repo/a.txt")
+        );
+        assert!(resolve_path(root, "../other/file").is_err());
+        assert!(resolve_path(root, "/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn tool_calls_parse_from_arguments() {
+        assert_eq!(
+            Tool::parse("run_command", r#"{"command": "cargo test"}"#).unwrap(),
+            Tool::RunCommand {
+                command: "cargo test".to_string()
+            }
+        );
+        assert!(Tool::parse("read_file", "{}").is_err());
+        assert!(Tool::parse("delete_everything", "{}").is_err());
+    }
+
+    fn host_env() -> ExecutionEnv {
+        ExecutionEnv::new(RepoContext::default(), false, String::new())
+    }
+
+    #[tokio::test]
+    async fn edit_requires_a_unique_match() {
+        let dir = std::env::temp_dir().join(format!("vk-edit-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "one\ntwo\ntwo\n").unwrap();
+        let env = host_env();
+
+        assert!(
+            edit_file(&dir, &env, "a.txt", "two", "three")
+                .await
+                .is_err()
+        );
+        let outcome = edit_file(&dir, &env, "a.txt", "one", "uno").await.unwrap();
+        assert!(outcome.success);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("a.txt")).unwrap(),
+            "uno\ntwo\ntwo\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Files and searches reached through the environment's shell behave like
+    /// the ones on this machine; an environment without a container or remote
+    /// host runs that shell locally
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn tools_work_through_the_environment_shell() {
+        let dir = std::env::temp_dir().join(format!("vk-through-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("node_modules")).unwrap();
+        std::fs::write(dir.join("node_modules/dep.js"), "needle\n").unwrap();
+        let env = host_env();
+
+        let file = dir.join("src/it's here.txt");
+        write_through(&dir, &env, &file, "first\nneedle in $HOME\n")
+            .await
+            .unwrap();
+        assert_eq!(
+            read_through(&dir, &env, &file).await.unwrap(),
+            "first\nneedle in $HOME\n"
+        );
+        assert!(
+            read_through(&dir, &env, &dir.join("missing.txt"))
+                .await
+                .is_err()
+        );
+
+        assert_eq!(
+            search_through(&dir, &env, &dir, "need+le").await.unwrap(),
+            ["src/it's here.txt:2: needle in $HOME"]
+        );
+        assert!(
+            search_through(&dir, &env, &dir, "nowhere")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
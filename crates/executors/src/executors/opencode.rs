@@ -99,7 +99,7 @@ impl Opencode {
         env: &ExecutionEnv,
     ) -> Result<(AsyncGroupChild, ServerPassword), ExecutorError> {
         let command_parts = self.build_command_builder()?.build_initial()?;
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = env.resolve(command_parts, current_dir).await?;
 
         let server_password = generate_server_password();
 
@@ -242,6 +242,7 @@ impl Opencode {
             child,
             exit_signal: Some(exit_signal_rx),
             cancel: Some(cancel),
+            msg_store: None,
         })
     }
 }
@@ -317,15 +318,17 @@ impl StandardCodingAgentExecutor for Opencode {
     async fn available_slash_commands(
         &self,
         current_dir: &Path,
+        env: &ExecutionEnv,
     ) -> Result<futures::stream::BoxStream<'static, json_patch::Patch>, ExecutorError> {
         let defaults = hardcoded_slash_commands();
         let this = self.clone();
         let current_dir = current_dir.to_path_buf();
+        let env = env.clone();
 
         let initial = patch::slash_commands(defaults.clone(), true, None);
 
         let discovery_stream = futures::stream::once(async move {
-            match this.discover_slash_commands(&current_dir).await {
+            match this.discover_slash_commands(&current_dir, &env).await {
                 Ok(commands) => patch::slash_commands(commands, false, None),
                 Err(e) => {
                     tracing::warn!("Failed to discover OpenCode slash commands: {}", e);
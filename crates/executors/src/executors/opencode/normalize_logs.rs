@@ -80,6 +80,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             entry_type: NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
                                 total_tokens,
                                 model_context_window,
+                                run_usage: Vec::new(),
                             }),
                             content: format!(
                                 "Tokens used: {} / Context window: {}",
@@ -134,7 +135,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             NormalizedEntry {
                                 timestamp: None,
                                 entry_type: NormalizedEntryType::ErrorMessage {
-                                    error_type: NormalizedEntryError::Other,
+                                    error_type: NormalizedEntryError::classify(&message),
                                 },
                                 content: message,
                                 metadata: None,
@@ -247,10 +248,10 @@ impl LogState {
                         err.message()
                             .unwrap_or_else(|| format!("OpenCode session error: {}", err.raw)),
                     ),
-                    Some(err) => (
-                        NormalizedEntryError::Other,
-                        format!("OpenCode session error: {}", err.raw),
-                    ),
+                    Some(err) => {
+                        let message = format!("OpenCode session error: {}", err.raw);
+                        (NormalizedEntryError::classify(&message), message)
+                    }
                     None => (
                         NormalizedEntryError::Other,
                         "OpenCode session error".to_string(),
@@ -25,7 +25,7 @@ use super::{
     types::OpencodeExecutorEvent,
 };
 use crate::{
-    env::{ExecutionEnv, RepoContext},
+    env::ExecutionEnv,
     executors::{
         BaseCodingAgent, ExecutorError, SlashCommandDescription,
         opencode::Opencode,
@@ -58,14 +58,14 @@ impl Opencode {
     pub async fn discover_slash_commands(
         &self,
         current_dir: &Path,
+        env: &ExecutionEnv,
     ) -> Result<Vec<SlashCommandDescription>, ExecutorError> {
         let key = SlashCommandCacheKey::new(current_dir, &BaseCodingAgent::Opencode);
         if let Some(cached) = SlashCommandCache::instance().get(&key) {
             return Ok((*cached).clone());
         }
 
-        let env = ExecutionEnv::new(RepoContext::default(), false, String::new());
-        let server = self.spawn_server(current_dir, &env).await?;
+        let server = self.spawn_server(current_dir, env).await?;
         let commands = sdk::discover_commands(&server, current_dir).await?;
 
         let defaults = hardcoded_slash_commands();
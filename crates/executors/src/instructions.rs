@@ -0,0 +1,75 @@
+//! Project and repository instructions handed to coding agents alongside the
+//! user's prompt.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Native instruction files of the agents, written into the worktree of a
+/// single-repository workspace
+pub const INSTRUCTION_FILES: [&str; 3] = ["CLAUDE.md", "AGENTS.md", "GEMINI.md"];
+
+/// How instructions reach the agent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum InstructionsDelivery {
+    /// Before the prompt
+    #[default]
+    Prepend,
+    /// After the prompt
+    Append,
+    /// In the agents' instruction files, falling back to `Prepend` when the
+    /// workspace has several repositories or the repository has its own files
+    File,
+}
+
+/// Instructions for one coding agent run
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentInstructions {
+    pub text: String,
+    pub delivery: InstructionsDelivery,
+    /// Whether follow-up prompts carry the instructions too
+    pub follow_ups: bool,
+}
+
+impl AgentInstructions {
+    /// `prompt` with the instructions added, when they go into this prompt
+    pub fn apply(&self, prompt: &str, follow_up: bool) -> String {
+        if follow_up && !self.follow_ups {
+            return prompt.to_string();
+        }
+        match self.delivery {
+            InstructionsDelivery::Prepend => format!("{}\n\n{prompt}", self.text),
+            InstructionsDelivery::Append => format!("{prompt}\n\n{}", self.text),
+            InstructionsDelivery::File => prompt.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_to_follow_ups_only_when_enabled() {
+        let mut instructions = AgentInstructions {
+            text: "Run make lint.".to_string(),
+            delivery: InstructionsDelivery::Prepend,
+            follow_ups: false,
+        };
+        assert_eq!(
+            instructions.apply("Fix the bug", false),
+            "Run make lint.\n\nFix the bug"
+        );
+        assert_eq!(instructions.apply("Now add a test", true), "Now add a test");
+
+        instructions.delivery = InstructionsDelivery::Append;
+        instructions.follow_ups = true;
+        assert_eq!(
+            instructions.apply("Now add a test", true),
+            "Now add a test\n\nRun make lint."
+        );
+
+        instructions.delivery = InstructionsDelivery::File;
+        assert_eq!(instructions.apply("Fix the bug", false), "Fix the bug");
+    }
+}
@@ -3,7 +3,11 @@ pub mod approvals;
 pub mod command;
 pub mod env;
 pub mod executors;
+pub mod instructions;
 pub mod logs;
 pub mod mcp_config;
+pub mod oci;
 pub mod profile;
+pub mod remote;
+pub mod sandbox;
 pub mod stdout_dup;
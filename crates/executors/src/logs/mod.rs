@@ -67,9 +67,41 @@ pub struct NormalizedConversation {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum NormalizedEntryError {
     SetupRequired,
+    /// Transient provider failure, such as a rate limit or an overloaded API,
+    /// that is worth retrying after a while
+    Retryable,
     Other,
 }
 
+/// Lowercase fragments of provider errors that go away when retried later
+const RETRYABLE_ERROR_PATTERNS: &[&str] = &[
+    "rate limit",
+    "rate_limit",
+    "rate-limit",
+    "ratelimit",
+    "too many requests",
+    "overloaded",
+    "service unavailable",
+    "temporarily unavailable",
+];
+
+impl NormalizedEntryError {
+    /// `Retryable` for transient provider errors, `Other` for the rest. Only for
+    /// errors an executor's structured output reports; plain stderr can mention
+    /// a rate limit without the run having hit one.
+    pub fn classify(message: &str) -> Self {
+        let message = message.to_lowercase();
+        if RETRYABLE_ERROR_PATTERNS
+            .iter()
+            .any(|pattern| message.contains(pattern))
+        {
+            Self::Retryable
+        } else {
+            Self::Other
+        }
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -102,6 +134,22 @@ pub enum NormalizedEntryType {
 pub struct TokenUsageInfo {
     pub total_tokens: u32,
     pub model_context_window: u32,
+    /// Tokens billed so far in this run, per model, when the agent reports them
+    #[serde(default)]
+    pub run_usage: Vec<ModelTokenUsage>,
+}
+
+/// Cumulative token counts for one model over an execution process.
+/// `input_tokens` excludes tokens served from the prompt cache.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, PartialEq)]
+pub struct ModelTokenUsage {
+    pub model: String,
+    #[ts(type = "number")]
+    pub input_tokens: u64,
+    #[ts(type = "number")]
+    pub output_tokens: u64,
+    #[ts(type = "number")]
+    pub cached_input_tokens: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -32,34 +32,33 @@ impl EntryIndexProvider {
         self.0.store(0, Ordering::Relaxed);
     }
 
-    /// Create a provider starting from the maximum existing normalized-entry index
-    /// observed in prior JSON patches in `MsgStore`.
+    /// Provider shared by everything that adds normalized entries to `msg_store`, so
+    /// notices pushed alongside the executor's normalizer never reuse its indices. The
+    /// first call starts after the maximum normalized-entry index observed in prior
+    /// JSON patches in `MsgStore`.
     pub fn start_from(msg_store: &MsgStore) -> Self {
-        let provider = EntryIndexProvider::new();
-
-        let max_index: Option<usize> = msg_store
-            .get_history()
-            .iter()
-            .filter_map(|msg| {
-                if let LogMsg::JsonPatch(patch) = msg {
-                    patch.iter().find_map(|op| {
-                        if let PatchOperation::Add(add) = op {
-                            add.path
-                                .strip_prefix("/entries/")
-                                .and_then(|n_str| n_str.parse::<usize>().ok())
-                        } else {
-                            None
-                        }
-                    })
-                } else {
-                    None
-                }
-            })
-            .max();
-
-        let start_at = max_index.map_or(0, |n| n.saturating_add(1));
-        provider.0.store(start_at, Ordering::Relaxed);
-        provider
+        Self(msg_store.entry_index(|| {
+            msg_store
+                .get_history()
+                .iter()
+                .filter_map(|msg| {
+                    if let LogMsg::JsonPatch(patch) = msg {
+                        patch.iter().find_map(|op| {
+                            if let PatchOperation::Add(add) = op {
+                                add.path
+                                    .strip_prefix("/entries/")
+                                    .and_then(|n_str| n_str.parse::<usize>().ok())
+                            } else {
+                                None
+                            }
+                        })
+                    } else {
+                        None
+                    }
+                })
+                .max()
+                .map_or(0, |n| n.saturating_add(1))
+        }))
     }
 }
 
@@ -99,6 +98,17 @@ mod tests {
         assert_eq!(provider1.next(), 2);
     }
 
+    #[test]
+    fn providers_of_one_store_share_indices() {
+        let msg_store = MsgStore::new();
+        let normalizer = EntryIndexProvider::start_from(&msg_store);
+        let notices = EntryIndexProvider::start_from(&msg_store);
+
+        assert_eq!(normalizer.next(), 0);
+        assert_eq!(notices.next(), 1);
+        assert_eq!(normalizer.next(), 2);
+    }
+
     #[test]
     fn test_current_index() {
         let provider = EntryIndexProvider::test_new();
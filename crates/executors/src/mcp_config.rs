@@ -397,7 +397,13 @@ impl CodingAgent {
         use Adapter::*;
 
         let adapter = match self {
-            CodingAgent::ClaudeCode(_) | CodingAgent::Amp(_) | CodingAgent::Droid(_) => Passthrough,
+            CodingAgent::ClaudeCode(_)
+            | CodingAgent::Amp(_)
+            | CodingAgent::Droid(_)
+            | CodingAgent::Aider(_)
+            | CodingAgent::Acp(_)
+            | CodingAgent::OpenaiCompatible(_)
+            | CodingAgent::Custom(_) => Passthrough,
             CodingAgent::QwenCode(_) | CodingAgent::Gemini(_) | CodingAgent::Auggie(_) => Gemini,
             CodingAgent::CursorAgent(_) => Cursor,
             CodingAgent::Codex(_) => Codex,
@@ -0,0 +1,270 @@
+//! Running execution processes inside OCI containers. The engine CLI, pointed
+//! at the engine's socket, attaches the process's stdio; everything else about
+//! the containers goes through the engine API. The workspace and the git
+//! directories behind its worktrees are bind-mounted at their host paths, so
+//! output, diffs and commits look the same as for processes on the host, and
+//! the agents' state and credentials are mounted from the home directory.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ts_rs::TS;
+use workspace_utils::shell::resolve_executable_path;
+
+use crate::{
+    env::RepoContext,
+    executors::ExecutorError,
+    sandbox::{WRITABLE_HOME_PATHS, git_common_dir},
+};
+
+/// Label carried by every execution container, set to its name
+pub const CONTAINER_LABEL: &str = "vibe-kanban.execution";
+
+/// Label carried by every execution container, set to its workspace's id
+pub const WORKSPACE_LABEL: &str = "vibe-kanban.workspace";
+
+/// Where cached setup images keep what the setup script wrote into each
+/// repository, in a directory named after the repository
+pub const SETUP_CACHE_DIR: &str = "/var/cache/vibe-kanban/setup";
+
+/// Repository of the images committed after setup scripts
+const SETUP_CACHE_REPOSITORY: &str = "vibe-kanban-setup";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerEngine {
+    Docker,
+    Podman,
+}
+
+impl ContainerEngine {
+    pub fn program(self) -> &'static str {
+        match self {
+            ContainerEngine::Docker => "docker",
+            ContainerEngine::Podman => "podman",
+        }
+    }
+}
+
+/// Container one execution process runs in. The container is kept after the
+/// process exits so it can be inspected and committed, then removed by name.
+#[derive(Debug, Clone)]
+pub struct OciContainer {
+    pub engine: ContainerEngine,
+    /// Engine API socket the CLI talks to
+    pub socket: PathBuf,
+    pub image: String,
+    pub name: String,
+    pub workspace_id: String,
+    /// Network the container joins; `host` keeps loopback services such as
+    /// the MCP server and OpenCode's API reachable
+    pub network: String,
+    /// Lists the names of the variables passed into the container; their
+    /// values come from the engine CLI's own environment
+    pub env_file: PathBuf,
+    /// Additional `run` arguments, such as resource limits or mounts
+    pub run_args: Vec<String>,
+}
+
+impl OciContainer {
+    /// The engine CLI running `program` with `args` in this container.
+    /// `program` is looked up on the image's `PATH`.
+    pub async fn wrap(
+        &self,
+        repo_context: &RepoContext,
+        program: String,
+        args: Vec<String>,
+        current_dir: &Path,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        let engine = resolve_executable_path(self.engine.program())
+            .await
+            .ok_or_else(|| ExecutorError::ExecutableNotFound {
+                program: self.engine.program().to_string(),
+            })?;
+
+        let mut mounts = vec![repo_context.workspace_root.clone()];
+        for git_dir in repo_context
+            .repo_paths()
+            .iter()
+            .filter_map(|path| git_common_dir(path))
+        {
+            if !mounts.contains(&git_dir) {
+                mounts.push(git_dir);
+            }
+        }
+        // Agents find their logins and settings under the same home directory
+        let home = dirs::home_dir();
+        if let Some(home) = &home {
+            for path in WRITABLE_HOME_PATHS {
+                let path = home.join(path);
+                if path.exists() && !mounts.contains(&path) {
+                    mounts.push(path);
+                }
+            }
+        }
+        let owner = owner_of(&repo_context.workspace_root);
+        Ok((
+            engine,
+            self.run_args(&mounts, home.is_some(), owner, current_dir, program, args),
+        ))
+    }
+
+    /// Write the names of `vars` to the env file
+    pub fn write_env_file<'a>(&self, vars: impl Iterator<Item = &'a String>) -> io::Result<()> {
+        if let Some(parent) = self.env_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let names: String = vars.map(|name| format!("{name}\n")).collect();
+        std::fs::write(&self.env_file, names)
+    }
+
+    fn run_args(
+        &self,
+        mounts: &[PathBuf],
+        pass_home: bool,
+        owner: Option<(u32, u32)>,
+        current_dir: &Path,
+        program: String,
+        args: Vec<String>,
+    ) -> Vec<String> {
+        let host_flag = match self.engine {
+            ContainerEngine::Docker => "--host",
+            ContainerEngine::Podman => "--url",
+        };
+        let mut run: Vec<String> = vec![
+            host_flag.to_string(),
+            format!("unix://{}", self.socket.to_string_lossy()),
+            "run".to_string(),
+            "--interactive".to_string(),
+            "--init".to_string(),
+            "--name".to_string(),
+            self.name.clone(),
+            "--label".to_string(),
+            format!("{CONTAINER_LABEL}={}", self.name),
+            "--label".to_string(),
+            format!("{WORKSPACE_LABEL}={}", self.workspace_id),
+            "--network".to_string(),
+            self.network.clone(),
+            "--env-file".to_string(),
+            self.env_file.to_string_lossy().into_owned(),
+        ];
+        if pass_home {
+            // Without a value the CLI passes on its own HOME
+            run.push("--env".to_string());
+            run.push("HOME".to_string());
+        }
+        // Files written to the bind mounts must belong to the workspace's owner
+        match (self.engine, owner) {
+            (ContainerEngine::Podman, _) => run.push("--userns=keep-id".to_string()),
+            (ContainerEngine::Docker, Some((uid, gid))) => {
+                run.push("--user".to_string());
+                run.push(format!("{uid}:{gid}"));
+            }
+            (ContainerEngine::Docker, None) => {}
+        }
+        for mount in mounts {
+            let mount = mount.to_string_lossy();
+            run.push("--volume".to_string());
+            run.push(format!("{mount}:{mount}"));
+        }
+        run.push("--workdir".to_string());
+        run.push(current_dir.to_string_lossy().into_owned());
+        run.extend(self.run_args.iter().cloned());
+        run.push(self.image.clone());
+        run.push(program);
+        run.extend(args);
+        run
+    }
+}
+
+/// Image committed after `setup_script` succeeded in a container of `image`
+pub fn setup_cache_tag(image: &str, setup_script: &str) -> String {
+    let digest = Sha256::digest(format!("{image}\n{setup_script}").as_bytes());
+    let hex: String = digest[..8].iter().map(|b| format!("{b:02x}")).collect();
+    format!("{SETUP_CACHE_REPOSITORY}:{hex}")
+}
+
+#[cfg(unix)]
+fn owner_of(path: &Path) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.uid(), metadata.gid()))
+}
+
+#[cfg(not(unix))]
+fn owner_of(_path: &Path) -> Option<(u32, u32)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(engine: ContainerEngine) -> OciContainer {
+        OciContainer {
+            engine,
+            socket: PathBuf::from("/var/run/docker.sock"),
+            image: "node:22".to_string(),
+            name: "vk-123".to_string(),
+            workspace_id: "ws-1".to_string(),
+            network: "host".to_string(),
+            env_file: PathBuf::from("/tmp/vk-123.env"),
+            run_args: vec!["--memory".to_string(), "4096m".to_string()],
+        }
+    }
+
+    #[test]
+    fn runs_program_in_image_with_workspace_mounted() {
+        let mounts = [PathBuf::from("/ws/abc"), PathBuf::from("/src/app/.git")];
+        let args = container(ContainerEngine::Docker)
+            .run_args(
+                &mounts,
+                true,
+                Some((1000, 1000)),
+                Path::new("/ws/abc/app"),
+                "npx".to_string(),
+                vec!["-y".to_string(), "@anthropic-ai/claude-code".to_string()],
+            )
+            .join(" ");
+
+        assert!(args.starts_with(
+            "--host unix:///var/run/docker.sock run --interactive --init --name vk-123 "
+        ));
+        assert!(args.contains("--label vibe-kanban.execution=vk-123"));
+        assert!(args.contains("--label vibe-kanban.workspace=ws-1"));
+        assert!(args.contains("--env HOME"));
+        assert!(args.contains("--user 1000:1000"));
+        assert!(args.contains("--volume /ws/abc:/ws/abc --volume /src/app/.git:/src/app/.git"));
+        assert!(args.ends_with(
+            "--workdir /ws/abc/app --memory 4096m node:22 npx -y @anthropic-ai/claude-code"
+        ));
+
+        let podman = container(ContainerEngine::Podman)
+            .run_args(
+                &mounts,
+                false,
+                Some((1000, 1000)),
+                Path::new("/ws/abc"),
+                "sh".to_string(),
+                vec![],
+            )
+            .join(" ");
+        assert!(podman.starts_with("--url unix:///var/run/docker.sock run "));
+        assert!(!podman.contains("--env HOME"));
+        assert!(podman.contains("--userns=keep-id"));
+        assert!(!podman.contains("--user "));
+    }
+
+    #[test]
+    fn setup_cache_tag_tracks_image_and_script() {
+        let tag = setup_cache_tag("node:22", "npm ci");
+        assert!(tag.starts_with("vibe-kanban-setup:"));
+        assert_eq!(tag, setup_cache_tag("node:22", "npm ci"));
+        assert_ne!(tag, setup_cache_tag("node:20", "npm ci"));
+        assert_ne!(tag, setup_cache_tag("node:22", "pnpm install"));
+    }
+}
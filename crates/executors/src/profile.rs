@@ -7,11 +7,13 @@ use std::{
 
 use convert_case::{Case, Casing};
 use serde::{Deserialize, Deserializer, Serialize, de::Error as DeError};
+use serde_json::{Map, Value};
 use thiserror::Error;
 use ts_rs::TS;
 
-use crate::executors::{
-    AvailabilityInfo, BaseCodingAgent, CodingAgent, StandardCodingAgentExecutor,
+use crate::{
+    executors::{AvailabilityInfo, BaseCodingAgent, CodingAgent, StandardCodingAgentExecutor},
+    sandbox::SandboxPolicy,
 };
 
 /// Return the canonical form for variant keys.
@@ -54,6 +56,9 @@ pub enum ProfileError {
 static EXECUTOR_PROFILES_CACHE: LazyLock<RwLock<ExecutorConfigs>> =
     LazyLock::new(|| RwLock::new(ExecutorConfigs::load()));
 
+static RAW_EXECUTOR_PROFILES_CACHE: LazyLock<RwLock<RawExecutorConfigs>> =
+    LazyLock::new(|| RwLock::new(RawExecutorConfigs::load()));
+
 // New format default profiles (v3 - flattened)
 const DEFAULT_PROFILES_JSON: &str = include_str!("../default_profiles.json");
 
@@ -170,31 +175,53 @@ impl ExecutorConfig {
     }
 }
 
+/// CPU, memory and process-count caps for one execution process and its
+/// children; unset fields are unlimited
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+pub struct ResourceLimits {
+    /// CPU time as a number of cores, e.g. `2.5`
+    pub cpus: Option<f64>,
+    /// Memory in MiB; going over it gets the process killed
+    pub memory_mb: Option<u32>,
+    /// Maximum number of processes and threads
+    pub max_processes: Option<u32>,
+}
+
+/// Settings of a configuration that aren't options of its executor. They sit
+/// next to the executor's fields in `profiles.json` and are inherited through
+/// `extends`, across executors too.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+pub struct ProfileSettings {
+    /// Profiles tried in order when this one is unavailable or its run fails on
+    /// authentication or a usage limit
+    #[serde(default)]
+    pub fallbacks: Vec<ExecutorProfileId>,
+    /// Stop the agent after running this many seconds
+    #[serde(default)]
+    pub max_runtime_secs: Option<u32>,
+    /// Stop the agent after this many seconds without stdout or stderr output
+    #[serde(default)]
+    pub max_idle_secs: Option<u32>,
+    /// Caps applied to the agent on Linux
+    #[serde(default)]
+    pub resource_limits: Option<ResourceLimits>,
+    /// Runs the agent inside a bubblewrap sandbox on Linux
+    #[serde(default)]
+    pub sandbox_policy: Option<SandboxPolicy>,
+    /// Automatic retries after a transient provider error; none unless set
+    #[serde(default)]
+    pub max_retries: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ExecutorConfigs {
     pub executors: HashMap<BaseCodingAgent, ExecutorConfig>,
+    /// Settings of the configurations that set any
+    #[serde(default)]
+    pub settings: HashMap<BaseCodingAgent, HashMap<String, ProfileSettings>>,
 }
 
 impl ExecutorConfigs {
-    /// Normalise all variant keys in-place
-    fn canonicalise(&mut self) {
-        for profile in self.executors.values_mut() {
-            let mut replacements = Vec::new();
-            for key in profile.configurations.keys().cloned().collect::<Vec<_>>() {
-                let canon = canonical_variant_key(&key);
-                if canon != key {
-                    replacements.push((key, canon));
-                }
-            }
-            for (old, new) in replacements {
-                if let Some(cfg) = profile.configurations.remove(&old) {
-                    // If both lowercase and canonical forms existed, keep canonical one
-                    profile.configurations.entry(new).or_insert(cfg);
-                }
-            }
-        }
-    }
-
     /// Get cached executor profiles
     pub fn get_cached() -> ExecutorConfigs {
         EXECUTOR_PROFILES_CACHE.read().unwrap().clone()
@@ -202,152 +229,25 @@ impl ExecutorConfigs {
 
     /// Reload executor profiles cache
     pub fn reload() {
-        let mut cache = EXECUTOR_PROFILES_CACHE.write().unwrap();
-        *cache = Self::load();
+        let raw = RawExecutorConfigs::load();
+        let resolved = Self::resolve_or_defaults(&raw);
+        *RAW_EXECUTOR_PROFILES_CACHE.write().unwrap() = raw;
+        *EXECUTOR_PROFILES_CACHE.write().unwrap() = resolved;
     }
 
-    /// Load executor profiles from file or defaults
+    /// Load executor profiles from file or defaults, with inheritance resolved
     pub fn load() -> Self {
-        let profiles_path = workspace_utils::assets::profiles_path();
-
-        // Load defaults first
-        let mut defaults = Self::from_defaults();
-        defaults.canonicalise();
-
-        // Try to load user overrides
-        let content = match fs::read_to_string(&profiles_path) {
-            Ok(content) => content,
-            Err(_) => {
-                tracing::info!("No user profiles.json found, using defaults only");
-                return defaults;
-            }
-        };
-
-        // Parse user overrides
-        match serde_json::from_str::<Self>(&content) {
-            Ok(mut user_overrides) => {
-                tracing::info!("Loaded user profile overrides from profiles.json");
-                user_overrides.canonicalise();
-                Self::merge_with_defaults(defaults, user_overrides)
-            }
-            Err(e) => {
-                tracing::error!(
-                    "Failed to parse user profiles.json: {}, using defaults only",
-                    e
-                );
-                defaults
-            }
-        }
-    }
-
-    /// Save user profile overrides to file (only saves what differs from defaults)
-    pub fn save_overrides(&self) -> Result<(), ProfileError> {
-        let profiles_path = workspace_utils::assets::profiles_path();
-        let mut defaults = Self::from_defaults();
-        defaults.canonicalise();
-
-        // Canonicalise current config before computing overrides
-        let mut self_clone = self.clone();
-        self_clone.canonicalise();
-
-        // Compute differences from defaults
-        let overrides = Self::compute_overrides(&defaults, &self_clone)?;
-
-        // Validate the merged result would be valid
-        let merged = Self::merge_with_defaults(defaults, overrides.clone());
-        Self::validate_merged(&merged)?;
-
-        // Write overrides directly to file
-        let content = serde_json::to_string_pretty(&overrides)?;
-        fs::write(&profiles_path, content)?;
-
-        tracing::info!("Saved profile overrides to {:?}", profiles_path);
-        Ok(())
-    }
-
-    /// Deep merge defaults with user overrides
-    fn merge_with_defaults(mut defaults: Self, overrides: Self) -> Self {
-        for (executor_key, override_profile) in overrides.executors {
-            match defaults.executors.get_mut(&executor_key) {
-                Some(default_profile) => {
-                    // Merge configurations (user configs override defaults, new ones are added)
-                    for (config_name, config) in override_profile.configurations {
-                        default_profile.configurations.insert(config_name, config);
-                    }
-                }
-                None => {
-                    // New executor, add completely
-                    defaults.executors.insert(executor_key, override_profile);
-                }
-            }
-        }
-        defaults
+        Self::resolve_or_defaults(&RawExecutorConfigs::load())
     }
 
-    /// Compute what overrides are needed to transform defaults into current config
-    fn compute_overrides(defaults: &Self, current: &Self) -> Result<Self, ProfileError> {
-        let mut overrides = Self {
-            executors: HashMap::new(),
-        };
-
-        // Fast scan for any illegal deletions BEFORE allocating/cloning
-        for (executor_key, default_profile) in &defaults.executors {
-            // Check if executor was removed entirely
-            if !current.executors.contains_key(executor_key) {
-                return Err(ProfileError::CannotDeleteExecutor {
-                    executor: *executor_key,
-                });
-            }
-
-            let current_profile = &current.executors[executor_key];
-
-            // Check if ANY built-in configuration was removed
-            for config_name in default_profile.configurations.keys() {
-                if !current_profile.configurations.contains_key(config_name) {
-                    return Err(ProfileError::CannotDeleteBuiltInConfig {
-                        executor: *executor_key,
-                        variant: config_name.clone(),
-                    });
-                }
-            }
-        }
-
-        for (executor_key, current_profile) in &current.executors {
-            if let Some(default_profile) = defaults.executors.get(executor_key) {
-                let mut override_configurations = HashMap::new();
-
-                // Check each configuration in current profile
-                for (config_name, current_config) in &current_profile.configurations {
-                    if let Some(default_config) = default_profile.configurations.get(config_name) {
-                        // Only include if different from default
-                        if current_config != default_config {
-                            override_configurations
-                                .insert(config_name.clone(), current_config.clone());
-                        }
-                    } else {
-                        // New configuration, always include
-                        override_configurations.insert(config_name.clone(), current_config.clone());
-                    }
-                }
-
-                // Only include executor if there are actual differences
-                if !override_configurations.is_empty() {
-                    overrides.executors.insert(
-                        *executor_key,
-                        ExecutorConfig {
-                            configurations: override_configurations,
-                        },
-                    );
-                }
-            } else {
-                // New executor, include completely
-                overrides
-                    .executors
-                    .insert(*executor_key, current_profile.clone());
-            }
-        }
-
-        Ok(overrides)
+    fn resolve_or_defaults(raw: &RawExecutorConfigs) -> Self {
+        raw.resolve().unwrap_or_else(|e| {
+            tracing::error!(
+                "Failed to resolve executor profiles: {}, using defaults only",
+                e
+            );
+            Self::from_defaults()
+        })
     }
 
     /// Validate that merged profiles are consistent and valid
@@ -381,10 +281,12 @@ impl ExecutorConfigs {
 
     /// Load from the new v3 defaults
     pub fn from_defaults() -> Self {
-        serde_json::from_str(DEFAULT_PROFILES_JSON).unwrap_or_else(|e| {
-            tracing::error!("Failed to parse embedded default_profiles.json: {}", e);
-            panic!("Default profiles v3 JSON is invalid")
-        })
+        RawExecutorConfigs::from_defaults()
+            .resolve()
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to resolve embedded default_profiles.json: {}", e);
+                panic!("Default profiles v3 JSON is invalid")
+            })
     }
 
     pub fn get_coding_agent(&self, executor_profile_id: &ExecutorProfileId) -> Option<CodingAgent> {
@@ -401,6 +303,20 @@ impl ExecutorConfigs {
             .cloned()
     }
 
+    /// Settings of `executor_profile_id`, or of its executor's default
+    /// configuration when the variant doesn't exist
+    pub fn get_settings(&self, executor_profile_id: &ExecutorProfileId) -> ProfileSettings {
+        let variant = match &executor_profile_id.variant {
+            Some(variant) if self.get_coding_agent(executor_profile_id).is_some() => variant,
+            _ => "DEFAULT",
+        };
+        self.settings
+            .get(&executor_profile_id.executor)
+            .and_then(|settings| settings.get(variant))
+            .cloned()
+            .unwrap_or_default()
+    }
+
     pub fn get_coding_agent_or_default(
         &self,
         executor_profile_id: &ExecutorProfileId,
@@ -476,9 +392,443 @@ impl ExecutorConfigs {
     }
 }
 
+/// Key of a variant that inherits the fields it doesn't set
+const EXTENDS_KEY: &str = "extends";
+
+/// Fields every executor has, inherited from another executor along with the
+/// settings fields
+const SHARED_FIELDS: &[&str] = &[
+    "append_prompt",
+    "base_command_override",
+    "additional_params",
+    "env",
+];
+
+/// Fields of a configuration that are read into its [`ProfileSettings`]
+const SETTINGS_FIELDS: &[&str] = &[
+    "fallbacks",
+    "max_runtime_secs",
+    "max_idle_secs",
+    "resource_limits",
+    "sandbox_policy",
+    "max_retries",
+];
+
+/// Executor profiles as written in `profiles.json`, before inheritance is
+/// resolved. A variant may set `extends` to another variant of the same
+/// executor (`"PLAN"`) or of another executor (`"CODEX:DEFAULT"`) and list
+/// only the fields it overrides.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RawExecutorConfigs {
+    pub executors: HashMap<BaseCodingAgent, HashMap<String, Value>>,
+}
+
+impl RawExecutorConfigs {
+    /// Normalise all variant keys in-place
+    fn canonicalise(&mut self) {
+        for configurations in self.executors.values_mut() {
+            let mut replacements = Vec::new();
+            for key in configurations.keys().cloned().collect::<Vec<_>>() {
+                let canon = canonical_variant_key(&key);
+                if canon != key {
+                    replacements.push((key, canon));
+                }
+            }
+            for (old, new) in replacements {
+                if let Some(cfg) = configurations.remove(&old) {
+                    // If both lowercase and canonical forms existed, keep canonical one
+                    configurations.entry(new).or_insert(cfg);
+                }
+            }
+        }
+    }
+
+    /// Get cached executor profiles as written, with `extends` kept
+    pub fn get_cached() -> RawExecutorConfigs {
+        RAW_EXECUTOR_PROFILES_CACHE.read().unwrap().clone()
+    }
+
+    /// Load the defaults merged with the user overrides from file
+    pub fn load() -> Self {
+        let profiles_path = workspace_utils::assets::profiles_path();
+
+        // Load defaults first
+        let mut defaults = Self::from_defaults();
+        defaults.canonicalise();
+
+        // Try to load user overrides
+        let content = match fs::read_to_string(&profiles_path) {
+            Ok(content) => content,
+            Err(_) => {
+                tracing::info!("No user profiles.json found, using defaults only");
+                return defaults;
+            }
+        };
+
+        // Parse user overrides
+        match serde_json::from_str::<Self>(&content) {
+            Ok(mut user_overrides) => {
+                tracing::info!("Loaded user profile overrides from profiles.json");
+                user_overrides.canonicalise();
+                Self::merge_with_defaults(defaults, user_overrides)
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to parse user profiles.json: {}, using defaults only",
+                    e
+                );
+                defaults
+            }
+        }
+    }
+
+    /// Save user profile overrides to file (only saves what differs from defaults)
+    pub fn save_overrides(&self) -> Result<(), ProfileError> {
+        let profiles_path = workspace_utils::assets::profiles_path();
+        let mut defaults = Self::from_defaults();
+        defaults.canonicalise();
+
+        // Canonicalise current config before computing overrides
+        let mut self_clone = self.clone();
+        self_clone.canonicalise();
+
+        // Compute differences from defaults
+        let overrides = Self::compute_overrides(&defaults, &self_clone)?;
+
+        // Validate the merged result would resolve
+        Self::merge_with_defaults(defaults, overrides.clone()).resolve()?;
+
+        // Write overrides directly to file
+        let content = serde_json::to_string_pretty(&overrides)?;
+        fs::write(&profiles_path, content)?;
+
+        tracing::info!("Saved profile overrides to {:?}", profiles_path);
+        Ok(())
+    }
+
+    /// Merge defaults with user overrides, user variants replacing default ones
+    fn merge_with_defaults(mut defaults: Self, overrides: Self) -> Self {
+        for (executor_key, override_configurations) in overrides.executors {
+            defaults
+                .executors
+                .entry(executor_key)
+                .or_default()
+                .extend(override_configurations);
+        }
+        defaults
+    }
+
+    /// Compute what overrides are needed to transform defaults into current config
+    fn compute_overrides(defaults: &Self, current: &Self) -> Result<Self, ProfileError> {
+        // Fast scan for any illegal deletions BEFORE allocating/cloning
+        for (executor_key, default_configurations) in &defaults.executors {
+            // Check if executor was removed entirely
+            let Some(current_configurations) = current.executors.get(executor_key) else {
+                return Err(ProfileError::CannotDeleteExecutor {
+                    executor: *executor_key,
+                });
+            };
+
+            // Check if ANY built-in configuration was removed
+            for config_name in default_configurations.keys() {
+                if !current_configurations.contains_key(config_name) {
+                    return Err(ProfileError::CannotDeleteBuiltInConfig {
+                        executor: *executor_key,
+                        variant: config_name.clone(),
+                    });
+                }
+            }
+        }
+
+        let mut overrides = Self::default();
+        for (executor_key, current_configurations) in &current.executors {
+            let default_configurations = defaults.executors.get(executor_key);
+            // Only include configurations that are new or differ from the default
+            let override_configurations: HashMap<String, Value> = current_configurations
+                .iter()
+                .filter(|(config_name, current_config)| {
+                    default_configurations
+                        .and_then(|defaults| defaults.get(*config_name))
+                        .is_none_or(|default_config| default_config != *current_config)
+                })
+                .map(|(config_name, config)| (config_name.clone(), config.clone()))
+                .collect();
+
+            // Only include executor if there are actual differences
+            if !override_configurations.is_empty() {
+                overrides
+                    .executors
+                    .insert(*executor_key, override_configurations);
+            }
+        }
+
+        Ok(overrides)
+    }
+
+    /// Resolve `extends` into complete configurations and validate them
+    pub fn resolve(&self) -> Result<ExecutorConfigs, ProfileError> {
+        let mut executors = HashMap::new();
+        let mut settings = HashMap::new();
+        for (executor_key, configurations) in &self.executors {
+            let mut resolved = HashMap::new();
+            let mut resolved_settings = HashMap::new();
+            for (config_name, config) in configurations {
+                let mut fields =
+                    self.resolve_fields(*executor_key, config_name, &mut Vec::new())?;
+                let settings_fields = SETTINGS_FIELDS
+                    .iter()
+                    .filter_map(|field| fields.remove_entry(*field))
+                    .collect::<Map<_, _>>();
+                if !settings_fields.is_empty() {
+                    let config_settings =
+                        serde_json::from_value::<ProfileSettings>(Value::Object(settings_fields))
+                            .map_err(|e| {
+                            ProfileError::Validation(format!(
+                                "Settings of '{executor_key}:{config_name}' are invalid: {e}"
+                            ))
+                        })?;
+                    resolved_settings.insert(config_name.clone(), config_settings);
+                }
+                // Keep the variant's own tag so mismatches still fail validation
+                let tag = config
+                    .as_object()
+                    .and_then(|tagged| tagged.keys().next().cloned())
+                    .unwrap_or_else(|| executor_key.to_string());
+                let tagged = Value::Object(Map::from_iter([(tag, Value::Object(fields))]));
+                let agent = serde_json::from_value::<CodingAgent>(tagged).map_err(|e| {
+                    ProfileError::Validation(format!(
+                        "Configuration '{executor_key}:{config_name}' is invalid: {e}"
+                    ))
+                })?;
+                resolved.insert(config_name.clone(), agent);
+            }
+            executors.insert(
+                *executor_key,
+                ExecutorConfig {
+                    configurations: resolved,
+                },
+            );
+            if !resolved_settings.is_empty() {
+                settings.insert(*executor_key, resolved_settings);
+            }
+        }
+
+        let resolved = ExecutorConfigs {
+            executors,
+            settings,
+        };
+        ExecutorConfigs::validate_merged(&resolved)?;
+        Ok(resolved)
+    }
+
+    /// Fields of a configuration with its `extends` chain applied, parents first
+    fn resolve_fields(
+        &self,
+        executor: BaseCodingAgent,
+        variant: &str,
+        visiting: &mut Vec<(BaseCodingAgent, String)>,
+    ) -> Result<Map<String, Value>, ProfileError> {
+        let config = self
+            .executors
+            .get(&executor)
+            .and_then(|configurations| configurations.get(variant))
+            .ok_or_else(|| {
+                ProfileError::Validation(format!("Configuration '{executor}:{variant}' not found"))
+            })?;
+        let mut fields = match config.as_object().and_then(|tagged| tagged.values().next()) {
+            Some(Value::Object(fields)) => fields.clone(),
+            _ => {
+                return Err(ProfileError::Validation(format!(
+                    "Configuration '{executor}:{variant}' must be an object keyed by its executor"
+                )));
+            }
+        };
+
+        let Some(extends) = fields.remove(EXTENDS_KEY) else {
+            return Ok(fields);
+        };
+        let Value::String(extends) = extends else {
+            return Err(ProfileError::Validation(format!(
+                "'{EXTENDS_KEY}' of '{executor}:{variant}' must be a string"
+            )));
+        };
+        let (parent_executor, parent_variant) = match extends.split_once(':') {
+            Some((parent_executor, parent_variant)) => (
+                BaseCodingAgent::from_str(parent_executor).map_err(|_| {
+                    ProfileError::Validation(format!(
+                        "'{executor}:{variant}' extends unknown executor '{parent_executor}'"
+                    ))
+                })?,
+                canonical_variant_key(parent_variant),
+            ),
+            None => (executor, canonical_variant_key(&extends)),
+        };
+        if !self
+            .executors
+            .get(&parent_executor)
+            .is_some_and(|configurations| configurations.contains_key(&parent_variant))
+        {
+            return Err(ProfileError::Validation(format!(
+                "'{executor}:{variant}' extends unknown configuration '{parent_executor}:{parent_variant}'"
+            )));
+        }
+
+        visiting.push((executor, variant.to_string()));
+        if visiting.contains(&(parent_executor, parent_variant.clone())) {
+            let cycle = visiting
+                .iter()
+                .map(|(executor, variant)| format!("{executor}:{variant}"))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(ProfileError::Validation(format!(
+                "Configuration inheritance cycle: {cycle} -> {parent_executor}:{parent_variant}"
+            )));
+        }
+        let mut resolved = self.resolve_fields(parent_executor, &parent_variant, visiting)?;
+        visiting.pop();
+
+        if parent_executor != executor {
+            resolved.retain(|field, _| {
+                SHARED_FIELDS.contains(&field.as_str()) || SETTINGS_FIELDS.contains(&field.as_str())
+            });
+        }
+        resolved.extend(fields);
+        Ok(resolved)
+    }
+
+    /// Load from the new v3 defaults
+    pub fn from_defaults() -> Self {
+        serde_json::from_str(DEFAULT_PROFILES_JSON).unwrap_or_else(|e| {
+            tracing::error!("Failed to parse embedded default_profiles.json: {}", e);
+            panic!("Default profiles v3 JSON is invalid")
+        })
+    }
+}
+
 pub fn to_default_variant(id: &ExecutorProfileId) -> ExecutorProfileId {
     ExecutorProfileId {
         executor: id.executor,
         variant: None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(json: serde_json::Value) -> RawExecutorConfigs {
+        let mut configs = RawExecutorConfigs::from_defaults();
+        configs.canonicalise();
+        let overrides: RawExecutorConfigs = serde_json::from_value(json).unwrap();
+        RawExecutorConfigs::merge_with_defaults(configs, overrides)
+    }
+
+    fn fields(configs: &ExecutorConfigs, executor: BaseCodingAgent, variant: &str) -> Value {
+        let agent = configs.executors[&executor].get_variant(variant).unwrap();
+        serde_json::to_value(agent).unwrap()[executor.to_string()].clone()
+    }
+
+    #[test]
+    fn variants_inherit_fields_they_do_not_set() {
+        let configs = raw(serde_json::json!({
+            "executors": {
+                "CLAUDE_CODE": {
+                    "DEFAULT": {
+                        "CLAUDE_CODE": { "dangerously_skip_permissions": true, "env": { "A": "1" } }
+                    },
+                    "REVIEW": { "CLAUDE_CODE": { "extends": "default", "model": "opus" } },
+                    "STRICT_REVIEW": { "CLAUDE_CODE": { "extends": "REVIEW", "plan": true } }
+                },
+                "CODEX": {
+                    "SHARED_ENV": { "CODEX": { "extends": "CLAUDE_CODE:STRICT_REVIEW" } }
+                }
+            }
+        }))
+        .resolve()
+        .unwrap();
+
+        let strict = fields(&configs, BaseCodingAgent::ClaudeCode, "STRICT_REVIEW");
+        assert_eq!(strict["dangerously_skip_permissions"], true);
+        assert_eq!(strict["model"], "opus");
+        assert_eq!(strict["plan"], true);
+        assert_eq!(strict["env"]["A"], "1");
+        assert!(strict.get(EXTENDS_KEY).is_none());
+
+        // Only the shared fields cross executors
+        let codex = fields(&configs, BaseCodingAgent::Codex, "SHARED_ENV");
+        assert_eq!(codex["env"]["A"], "1");
+        assert!(codex.get("model").is_none());
+    }
+
+    #[test]
+    fn settings_are_inherited_and_kept_out_of_the_agent() {
+        let configs = raw(serde_json::json!({
+            "executors": {
+                "CLAUDE_CODE": {
+                    "LIMITED": {
+                        "CLAUDE_CODE": {
+                            "model": "opus",
+                            "max_idle_secs": 600,
+                            "fallbacks": [
+                                { "executor": "CODEX" },
+                                { "executor": "GEMINI", "variant": "FLASH" }
+                            ]
+                        }
+                    },
+                    "PLANNER": { "CLAUDE_CODE": { "extends": "LIMITED", "plan": true } }
+                },
+                "AMP": { "BACKUP": { "AMP": { "extends": "CLAUDE_CODE:PLANNER" } } }
+            }
+        }))
+        .resolve()
+        .unwrap();
+
+        let planner =
+            ExecutorProfileId::with_variant(BaseCodingAgent::ClaudeCode, "PLANNER".to_string());
+        let fallbacks = configs.get_settings(&planner).fallbacks;
+        assert_eq!(
+            fallbacks,
+            vec![
+                ExecutorProfileId::new(BaseCodingAgent::Codex),
+                ExecutorProfileId::with_variant(BaseCodingAgent::Gemini, "FLASH".to_string()),
+            ]
+        );
+        assert_eq!(configs.get_settings(&planner).max_idle_secs, Some(600));
+        assert!(
+            fields(&configs, BaseCodingAgent::ClaudeCode, "PLANNER")
+                .get("fallbacks")
+                .is_none()
+        );
+        let backup = ExecutorProfileId::with_variant(BaseCodingAgent::Amp, "BACKUP".to_string());
+        assert_eq!(configs.get_settings(&backup).fallbacks, fallbacks);
+
+        // Unknown variants use the executor's default configuration
+        let unknown =
+            ExecutorProfileId::with_variant(BaseCodingAgent::Codex, "MISSING".to_string());
+        assert!(configs.get_settings(&unknown).fallbacks.is_empty());
+    }
+
+    #[test]
+    fn rejects_cycles_and_unknown_parents() {
+        let cycle = raw(serde_json::json!({
+            "executors": {
+                "AMP": {
+                    "FIRST": { "AMP": { "extends": "SECOND" } },
+                    "SECOND": { "AMP": { "extends": "FIRST" } }
+                }
+            }
+        }));
+        assert!(matches!(
+            cycle.resolve(),
+            Err(ProfileError::Validation(message)) if message.contains("cycle")
+        ));
+
+        let unknown = raw(serde_json::json!({
+            "executors": { "AMP": { "ORPHAN": { "AMP": { "extends": "MISSING" } } } }
+        }));
+        assert!(matches!(
+            unknown.resolve(),
+            Err(ProfileError::Validation(_))
+        ));
+    }
+}
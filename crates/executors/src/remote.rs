@@ -0,0 +1,177 @@
+//! Running execution processes on an SSH host. The process's stdio is carried
+//! by the `ssh` client, so output streams back exactly like a local process.
+//! The remote deployment creates the workspace's worktrees at the same paths
+//! on the host before the process starts.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use workspace_utils::shell::resolve_executable_path;
+
+use crate::{command::CommandBuildError, executors::ExecutorError};
+
+/// SSH host and the name one execution process is tracked by on it
+#[derive(Debug, Clone)]
+pub struct RemoteHost {
+    /// `user@host` or a host alias from `~/.ssh/config`
+    pub destination: String,
+    pub port: Option<u16>,
+    pub identity_file: Option<PathBuf>,
+    /// Additional `ssh` arguments, e.g. `-o ProxyJump=bastion`
+    pub ssh_options: Vec<String>,
+    /// Names the file on the host holding the process group id
+    pub name: String,
+}
+
+impl RemoteHost {
+    /// `ssh` arguments up to and including the destination
+    pub fn ssh_args(&self) -> Vec<String> {
+        let mut args = self.connection_args();
+        args.push(self.destination.clone());
+        args
+    }
+
+    /// `ssh` options without the destination, as `GIT_SSH_COMMAND` takes
+    pub fn connection_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "-T".to_string(),
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            "ServerAliveInterval=15".to_string(),
+        ];
+        if let Some(port) = self.port {
+            args.push("-p".to_string());
+            args.push(port.to_string());
+        }
+        if let Some(identity_file) = &self.identity_file {
+            args.push("-i".to_string());
+            args.push(identity_file.to_string_lossy().into_owned());
+        }
+        args.extend(self.ssh_options.iter().cloned());
+        args
+    }
+
+    /// File on the host holding the process group id of the running process
+    pub fn pid_file(&self) -> String {
+        format!("/tmp/vibe-kanban-{}.pid", self.name)
+    }
+
+    /// The `ssh` client running `program` with `args` in `current_dir` on the
+    /// host, with `vars` set. `program` is looked up on the login `PATH` there.
+    pub async fn wrap(
+        &self,
+        program: String,
+        args: Vec<String>,
+        current_dir: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        let ssh = resolve_executable_path("ssh").await.ok_or_else(|| {
+            ExecutorError::ExecutableNotFound {
+                program: "ssh".to_string(),
+            }
+        })?;
+        let mut ssh_args = self.ssh_args();
+        ssh_args.push(self.remote_command(&program, &args, current_dir, vars)?);
+        Ok((ssh, ssh_args))
+    }
+
+    /// Shell command run on the host. The login shell records its pid, which
+    /// the program keeps through `exec`; as the session leader started by
+    /// sshd it also leads the process group that gets killed on stop.
+    fn remote_command(
+        &self,
+        program: &str,
+        args: &[String],
+        current_dir: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<String, ExecutorError> {
+        let quote = |value: &str| -> Result<String, ExecutorError> {
+            shlex::try_quote(value)
+                .map(|quoted| quoted.into_owned())
+                .map_err(|e| ExecutorError::CommandBuild(CommandBuildError::QuoteError(e)))
+        };
+
+        let mut vars: Vec<_> = vars.iter().collect();
+        vars.sort();
+        let mut command = vec![
+            format!("echo $$ > {}", quote(&self.pid_file())?),
+            "&&".to_string(),
+            "cd".to_string(),
+            quote(&current_dir.to_string_lossy())?,
+            "&&".to_string(),
+            "exec".to_string(),
+            "env".to_string(),
+        ];
+        for (key, value) in vars {
+            command.push(quote(&format!("{key}={value}"))?);
+        }
+        command.push(quote(program)?);
+        for arg in args {
+            command.push(quote(arg)?);
+        }
+        Ok(format!("exec sh -lc {}", quote(&command.join(" "))?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host() -> RemoteHost {
+        RemoteHost {
+            destination: "gpu@builder".to_string(),
+            port: Some(2222),
+            identity_file: Some(PathBuf::from("/home/me/.ssh/builder")),
+            ssh_options: vec!["-o".to_string(), "ProxyJump=bastion".to_string()],
+            name: "vk-123".to_string(),
+        }
+    }
+
+    #[test]
+    fn ssh_args_end_with_destination() {
+        assert_eq!(
+            host().ssh_args().join(" "),
+            "-T -o BatchMode=yes -o ServerAliveInterval=15 -p 2222 -i /home/me/.ssh/builder -o ProxyJump=bastion gpu@builder"
+        );
+    }
+
+    #[test]
+    fn remote_command_survives_two_shells() {
+        let vars = HashMap::from([("VK_TASK_ID".to_string(), "t 1".to_string())]);
+        let command = host()
+            .remote_command(
+                "claude",
+                &["-p".to_string(), "fix the 'bug'".to_string()],
+                Path::new("/work/ws 1"),
+                &vars,
+            )
+            .unwrap();
+
+        // What the host's shell hands to `sh -lc`
+        let inner = shlex::split(&command).unwrap();
+        assert_eq!(inner[..3], ["exec", "sh", "-lc"]);
+        let words = shlex::split(&inner[3]).unwrap();
+        assert_eq!(
+            words,
+            [
+                "echo",
+                "$$",
+                ">",
+                "/tmp/vibe-kanban-vk-123.pid",
+                "&&",
+                "cd",
+                "/work/ws 1",
+                "&&",
+                "exec",
+                "env",
+                "VK_TASK_ID=t 1",
+                "claude",
+                "-p",
+                "fix the 'bug'",
+            ]
+        );
+    }
+}
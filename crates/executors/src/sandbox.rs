@@ -0,0 +1,449 @@
+//! Filesystem and network isolation for coding agents and scripts, built on
+//! bubblewrap (`bwrap`). The host filesystem is visible read-only, the
+//! workspace and the git directories behind its worktrees stay writable and
+//! credential stores are hidden. A network allowlist runs the process in its
+//! own network namespace whose only way out is the filtering proxy, reached
+//! through a Unix socket bound into the sandbox and bridged to a loopback port
+//! by `socat`.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use workspace_utils::{assets::asset_dir, path::expand_tilde, shell::resolve_executable_path};
+
+use crate::{env::RepoContext, executors::ExecutorError};
+
+pub const BWRAP: &str = "bwrap";
+pub const SOCAT: &str = "socat";
+
+/// Loopback port the allowlist proxy is reachable on inside the sandbox's
+/// network namespace
+pub const PROXY_PORT: u16 = 3128;
+
+/// Where the allowlist proxy's socket is bound inside the sandbox
+const PROXY_SOCKET: &str = "/tmp/.vibe-kanban-proxy.sock";
+
+/// Prefix of the notices the sandbox adds to a process's output
+pub const NOTICE_PREFIX: &str = "[sandbox]";
+
+/// Credential stores hidden in every sandbox, relative to the home directory
+const HIDDEN_HOME_PATHS: &[&str] = &[
+    ".ssh",
+    ".gnupg",
+    ".aws",
+    ".azure",
+    ".config/gcloud",
+    ".config/gh",
+    ".kube",
+    ".docker/config.json",
+    ".netrc",
+    ".git-credentials",
+    ".cargo/credentials.toml",
+    ".pypirc",
+];
+
+/// Agent state and package caches that stay writable, relative to the home
+/// directory. Paths that don't exist yet are skipped.
+pub(crate) const WRITABLE_HOME_PATHS: &[&str] = &[
+    ".cache",
+    ".npm",
+    ".claude",
+    ".claude.json",
+    ".codex",
+    ".gemini",
+    ".qwen",
+    ".cursor",
+    ".factory",
+    ".copilot",
+    ".augment",
+    ".config/amp",
+    ".local/share/amp",
+    ".config/opencode",
+    ".local/share/opencode",
+    ".local/state",
+];
+
+/// How a sandboxed process may use the network
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum SandboxNetwork {
+    #[default]
+    Allow,
+    /// No network at all, including the host's loopback interface
+    Deny,
+    /// Only these hosts, through a filtering proxy. `*.example.com` matches
+    /// subdomains. Programs that ignore `HTTPS_PROXY` have no network access.
+    Allowlist { hosts: Vec<String> },
+}
+
+/// Isolation applied to a sandboxed process. `~` in paths expands to the home
+/// directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct SandboxPolicy {
+    /// Paths writable besides the workspace, its git directories and the
+    /// agents' own state directories
+    #[serde(default)]
+    pub writable_paths: Vec<String>,
+    /// Paths hidden besides SSH keys, cloud credentials and Vibe Kanban's data
+    #[serde(default)]
+    pub hidden_paths: Vec<String>,
+    #[serde(default)]
+    pub network: SandboxNetwork,
+}
+
+/// Host paths resolved for one sandboxed process
+#[derive(Debug, Default)]
+struct Mounts {
+    writable: Vec<PathBuf>,
+    hidden_dirs: Vec<PathBuf>,
+    hidden_files: Vec<PathBuf>,
+}
+
+/// Bridge from the sandbox's loopback port to the allowlist proxy's socket
+#[derive(Debug)]
+struct ProxyBridge {
+    socat: PathBuf,
+    socket: PathBuf,
+}
+
+impl SandboxPolicy {
+    /// `program` and `args` wrapped in bubblewrap under this policy.
+    /// `proxy_socket` is the allowlist proxy's socket; without one an
+    /// allowlist leaves the process offline.
+    pub async fn wrap(
+        &self,
+        repo_context: &RepoContext,
+        proxy_socket: Option<&Path>,
+        program: PathBuf,
+        args: Vec<String>,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        if !cfg!(target_os = "linux") {
+            return Err(ExecutorError::SandboxUnsupported);
+        }
+        let bwrap = resolve_executable_path(BWRAP).await.ok_or_else(|| {
+            ExecutorError::ExecutableNotFound {
+                program: BWRAP.to_string(),
+            }
+        })?;
+        let bridge = match (&self.network, proxy_socket) {
+            (SandboxNetwork::Allowlist { .. }, Some(socket)) => Some(ProxyBridge {
+                socat: resolve_executable_path(SOCAT).await.ok_or_else(|| {
+                    ExecutorError::ExecutableNotFound {
+                        program: SOCAT.to_string(),
+                    }
+                })?,
+                socket: socket.to_path_buf(),
+            }),
+            _ => None,
+        };
+        let mounts = self.mounts(repo_context);
+        Ok((
+            bwrap,
+            self.bwrap_args(&mounts, bridge.as_ref(), &program, args),
+        ))
+    }
+
+    /// Proxy variables for the sandboxed process, pointing at the bridged
+    /// loopback port
+    pub fn proxy_env_vars(&self) -> Vec<(&'static str, String)> {
+        if !matches!(self.network, SandboxNetwork::Allowlist { .. }) {
+            return Vec::new();
+        }
+        let url = format!("http://127.0.0.1:{PROXY_PORT}");
+        let mut vars: Vec<_> = [
+            "HTTP_PROXY",
+            "HTTPS_PROXY",
+            "ALL_PROXY",
+            "http_proxy",
+            "https_proxy",
+            "all_proxy",
+        ]
+        .into_iter()
+        .map(|key| (key, url.clone()))
+        .collect();
+        // Loopback isn't outbound network access
+        vars.extend(
+            ["NO_PROXY", "no_proxy"].map(|key| (key, "localhost,127.0.0.1,::1".to_string())),
+        );
+        vars
+    }
+
+    /// Explanation to add to the output when `line` looks like the sandbox
+    /// blocked an operation
+    pub fn blocked_notice(&self, line: &str) -> Option<&'static str> {
+        if line.contains("Read-only file system") || line.contains("EROFS") {
+            return Some(
+                "[sandbox] A write outside the workspace was blocked: the rest of the filesystem is read-only. Add the path to the sandbox's writable_paths to allow it.",
+            );
+        }
+        if self.network != SandboxNetwork::Allow
+            && [
+                "Network is unreachable",
+                "ENETUNREACH",
+                "Could not resolve host",
+                "Temporary failure in name resolution",
+                "EAI_AGAIN",
+            ]
+            .iter()
+            .any(|pattern| line.contains(pattern))
+        {
+            return Some(match self.network {
+                SandboxNetwork::Deny => {
+                    "[sandbox] A network request was blocked: network access is disabled."
+                }
+                _ => {
+                    "[sandbox] A network request was blocked: only hosts in the allowlist are reachable, through the proxy in HTTPS_PROXY."
+                }
+            });
+        }
+        None
+    }
+
+    fn mounts(&self, repo_context: &RepoContext) -> Mounts {
+        let home = dirs::home_dir();
+        let from_home = |paths: &[&str]| -> Vec<PathBuf> {
+            home.iter()
+                .flat_map(|home| paths.iter().map(|path| home.join(path)))
+                .collect()
+        };
+
+        let mut writable = vec![repo_context.workspace_root.clone()];
+        writable.extend(
+            repo_context
+                .repo_paths()
+                .iter()
+                .filter_map(|path| git_common_dir(path)),
+        );
+        writable.extend(from_home(WRITABLE_HOME_PATHS));
+        writable.extend(self.writable_paths.iter().map(|path| expand_tilde(path)));
+
+        let mut hidden = from_home(HIDDEN_HOME_PATHS);
+        hidden.push(asset_dir());
+        hidden.extend(self.hidden_paths.iter().map(|path| expand_tilde(path)));
+
+        let mut mounts = Mounts::default();
+        for path in writable {
+            if path.exists() && !mounts.writable.contains(&path) {
+                mounts.writable.push(path);
+            }
+        }
+        for path in hidden {
+            if path.is_dir() {
+                mounts.hidden_dirs.push(path);
+            } else if path.exists() {
+                mounts.hidden_files.push(path);
+            }
+        }
+        mounts
+    }
+
+    /// Later mounts cover earlier ones: hidden paths go over writable ones,
+    /// and writable paths inside a hidden directory are bound again on top
+    fn bwrap_args(
+        &self,
+        mounts: &Mounts,
+        bridge: Option<&ProxyBridge>,
+        program: &Path,
+        args: Vec<String>,
+    ) -> Vec<String> {
+        let mut bwrap_args: Vec<String> = [
+            "--die-with-parent",
+            "--ro-bind",
+            "/",
+            "/",
+            "--dev",
+            "/dev",
+            "--proc",
+            "/proc",
+            "--tmpfs",
+            "/tmp",
+        ]
+        .map(String::from)
+        .to_vec();
+
+        let (inside_hidden, writable): (Vec<&PathBuf>, Vec<&PathBuf>) = mounts
+            .writable
+            .iter()
+            .partition(|path| mounts.hidden_dirs.iter().any(|dir| path.starts_with(dir)));
+        let mut push = |parts: &[&Path]| {
+            bwrap_args.extend(parts.iter().map(|part| part.to_string_lossy().into_owned()));
+        };
+        for path in writable {
+            push(&[Path::new("--bind"), path, path]);
+        }
+        for dir in &mounts.hidden_dirs {
+            push(&[Path::new("--tmpfs"), dir]);
+        }
+        for file in &mounts.hidden_files {
+            push(&[Path::new("--ro-bind"), Path::new("/dev/null"), file]);
+        }
+        for path in inside_hidden {
+            push(&[Path::new("--bind"), path, path]);
+        }
+        if let Some(bridge) = bridge {
+            push(&[Path::new("--bind"), &bridge.socket, Path::new(PROXY_SOCKET)]);
+        }
+
+        if self.network != SandboxNetwork::Allow {
+            bwrap_args.push("--unshare-net".to_string());
+        }
+
+        bwrap_args.push("--".to_string());
+        if let Some(bridge) = bridge {
+            // socat listens on the namespace's loopback interface and forwards
+            // each connection to the proxy socket, then the program replaces
+            // the shell
+            bwrap_args.extend(
+                [
+                    "/bin/sh".to_string(),
+                    "-c".to_string(),
+                    format!(
+                        "\"$1\" TCP-LISTEN:{PROXY_PORT},bind=127.0.0.1,fork,reuseaddr UNIX-CONNECT:{PROXY_SOCKET} & shift; exec \"$@\""
+                    ),
+                    "sh".to_string(),
+                    bridge.socat.to_string_lossy().into_owned(),
+                ],
+            );
+        }
+        bwrap_args.push(program.to_string_lossy().into_owned());
+        bwrap_args.extend(args);
+        bwrap_args
+    }
+}
+
+/// Whether `host` may be reached under an allowlist
+pub fn host_allowed(allowlist: &[String], host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowlist.iter().any(|entry| {
+        let entry = entry.trim().to_ascii_lowercase();
+        match entry.strip_prefix("*.") {
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|sub| sub.ends_with('.')),
+            None => host == entry,
+        }
+    })
+}
+
+/// The repository's shared `.git` directory when `worktree` is a linked
+/// worktree; commits write their objects and refs there
+pub fn git_common_dir(worktree: &Path) -> Option<PathBuf> {
+    let dot_git = std::fs::read_to_string(worktree.join(".git")).ok()?;
+    let gitdir = PathBuf::from(dot_git.strip_prefix("gitdir:")?.trim());
+    let gitdir = if gitdir.is_absolute() {
+        gitdir
+    } else {
+        worktree.join(gitdir)
+    };
+    let common = std::fs::read_to_string(gitdir.join("commondir"))
+        .map(|common| gitdir.join(common.trim()))
+        .unwrap_or(gitdir);
+    std::fs::canonicalize(common).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writable_paths_are_bound_after_hidden_ones() {
+        let policy = SandboxPolicy {
+            network: SandboxNetwork::Deny,
+            ..Default::default()
+        };
+        let mounts = Mounts {
+            writable: vec![PathBuf::from("/data/vk/worktrees/ws")],
+            hidden_dirs: vec![PathBuf::from("/data/vk")],
+            hidden_files: vec![PathBuf::from("/home/me/.netrc")],
+        };
+
+        let args = policy
+            .bwrap_args(
+                &mounts,
+                None,
+                Path::new("/usr/bin/sh"),
+                vec!["-c".to_string(), "ls".to_string()],
+            )
+            .join(" ");
+        assert!(args.starts_with("--die-with-parent --ro-bind / / "));
+        assert!(args.contains("--ro-bind /dev/null /home/me/.netrc"));
+        let hide = args.find("--tmpfs /data/vk").unwrap();
+        let rebind = args.find("--bind /data/vk/worktrees/ws").unwrap();
+        assert!(rebind > hide);
+        assert!(args.ends_with("--unshare-net -- /usr/bin/sh -c ls"));
+    }
+
+    #[test]
+    fn allowlist_reaches_the_network_only_through_the_proxy() {
+        let policy = SandboxPolicy {
+            network: SandboxNetwork::Allowlist {
+                hosts: vec!["api.anthropic.com".to_string()],
+            },
+            ..Default::default()
+        };
+        let bridge = ProxyBridge {
+            socat: PathBuf::from("/usr/bin/socat"),
+            socket: PathBuf::from("/tmp/vk-sandbox-1.sock"),
+        };
+
+        let args = policy
+            .bwrap_args(
+                &Mounts::default(),
+                Some(&bridge),
+                Path::new("/usr/bin/claude"),
+                vec!["-p".to_string()],
+            )
+            .join(" ");
+        assert!(args.contains("--bind /tmp/vk-sandbox-1.sock /tmp/.vibe-kanban-proxy.sock"));
+        assert!(args.contains("--unshare-net -- /bin/sh -c "));
+        assert!(args.contains("UNIX-CONNECT:/tmp/.vibe-kanban-proxy.sock"));
+        assert!(args.ends_with("sh /usr/bin/socat /usr/bin/claude -p"));
+
+        // Without a proxy the allowlist leaves the process offline
+        let offline = policy
+            .bwrap_args(
+                &Mounts::default(),
+                None,
+                Path::new("/usr/bin/claude"),
+                vec![],
+            )
+            .join(" ");
+        assert!(offline.ends_with("--unshare-net -- /usr/bin/claude"));
+    }
+
+    #[test]
+    fn allowlist_matches_hosts_and_subdomains() {
+        let allowlist = vec!["api.anthropic.com".to_string(), "*.github.com".to_string()];
+        assert!(host_allowed(&allowlist, "api.anthropic.com"));
+        assert!(host_allowed(&allowlist, "API.Anthropic.com."));
+        assert!(host_allowed(&allowlist, "codeload.github.com"));
+        assert!(!host_allowed(&allowlist, "github.com"));
+        assert!(!host_allowed(&allowlist, "evilgithub.com"));
+        assert!(!host_allowed(&allowlist, "anthropic.com"));
+    }
+
+    #[test]
+    fn explains_blocked_operations() {
+        let policy = SandboxPolicy::default();
+        assert!(
+            policy
+                .blocked_notice("touch: cannot touch '/etc/x': Read-only file system")
+                .is_some()
+        );
+        assert_eq!(
+            policy.blocked_notice("curl: (6) Could not resolve host"),
+            None
+        );
+
+        let offline = SandboxPolicy {
+            network: SandboxNetwork::Deny,
+            ..Default::default()
+        };
+        assert!(
+            offline
+                .blocked_notice("curl: (6) Could not resolve host: example.com")
+                .is_some()
+        );
+    }
+}
@@ -230,6 +230,7 @@ pub fn spawn_local_output_process()
         child,
         exit_signal: None,
         cancel: None,
+        msg_store: None,
     };
 
     Ok((spawned, writer))
@@ -296,6 +296,14 @@ impl GitCli {
         Ok(())
     }
 
+    /// Take `paths` back out of the index, leaving the working tree as it is.
+    pub fn unstage(&self, worktree_path: &Path, paths: &[String]) -> Result<(), GitCliError> {
+        let mut args = vec!["reset", "-q", "--"];
+        args.extend(paths.iter().map(String::as_str));
+        self.git(worktree_path, args)?;
+        Ok(())
+    }
+
     pub fn list_worktrees(&self, repo_path: &Path) -> Result<Vec<WorktreeEntry>, GitCliError> {
         let out = self.git(repo_path, ["worktree", "list", "--porcelain"])?;
         let mut entries = Vec::new();
@@ -321,6 +321,37 @@ impl GitService {
         Ok(true)
     }
 
+    /// Like [`Self::commit`], but leaves the untracked `keep_out` paths out of
+    /// the commit. Returns false when they were the only changes.
+    pub fn commit_excluding(
+        &self,
+        path: &Path,
+        message: &str,
+        keep_out: &[String],
+    ) -> Result<bool, GitServiceError> {
+        if keep_out.is_empty() {
+            return self.commit(path, message);
+        }
+
+        let git = GitCli::new();
+        git.add_all(path)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
+        git.unstage(path, keep_out)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git reset failed: {e}")))?;
+        let has_staged = git
+            .has_staged_changes(path)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git diff failed: {e}")))?;
+        if !has_staged {
+            tracing::debug!("No changes to commit!");
+            return Ok(false);
+        }
+
+        self.ensure_cli_commit_identity(path)?;
+        git.commit(path, message)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git commit failed: {e}")))?;
+        Ok(true)
+    }
+
     /// Get diffs between branches or worktree changes
     pub fn get_diffs(
         &self,
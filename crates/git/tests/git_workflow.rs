@@ -256,6 +256,30 @@ fn commit_and_is_worktree_clean() {
     );
 }
 
+#[test]
+fn commit_excluding_leaves_kept_out_paths_untracked() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, ".aider.tags.cache.v4/cache.db", "cache\n");
+
+    let s = GitService::new();
+    let keep_out = vec![".aider.tags.cache.v4/".to_string()];
+    let committed = s
+        .commit_excluding(&repo_path, "cache only", &keep_out)
+        .unwrap();
+    assert!(!committed);
+
+    write_file(&repo_path, "foo.txt", "hello\n");
+    let committed = s
+        .commit_excluding(&repo_path, "add foo", &keep_out)
+        .unwrap();
+    assert!(committed);
+    let status = s.get_worktree_status(&repo_path).unwrap();
+    assert_eq!(status.entries.len(), 1);
+    assert!(status.entries[0].is_untracked);
+    assert_eq!(status.entries[0].path, b".aider.tags.cache.v4/");
+}
+
 #[test]
 fn commit_in_detached_head_succeeds_via_service() {
     let td = TempDir::new().unwrap();
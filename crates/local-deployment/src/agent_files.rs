@@ -0,0 +1,20 @@
+//! Files coding agents keep in a repository worktree that stay out of the
+//! task's commits, without touching the repository's ignore files.
+
+use std::path::Path;
+
+use executors::executors::aider::is_aider_file;
+use git::WorktreeStatus;
+
+use crate::instruction_files;
+
+/// The untracked paths of `worktree`'s `status` the auto-commit leaves alone
+pub(crate) fn uncommitted_agent_files(worktree: &Path, status: &WorktreeStatus) -> Vec<String> {
+    status
+        .entries
+        .iter()
+        .filter(|entry| entry.is_untracked)
+        .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+        .filter(|path| is_aider_file(path) || instruction_files::is_generated(worktree, path))
+        .collect()
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io,
     path::{Path, PathBuf},
     sync::Arc,
@@ -14,8 +14,10 @@ use db::{
     models::{
         coding_agent_turn::CodingAgentTurn,
         execution_process::{
-            ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+            ExecutionContext, ExecutionProcess, ExecutionProcessCompletionReason,
+            ExecutionProcessRunReason, ExecutionProcessStatus,
         },
+        execution_process_logs::ExecutionProcessLogs,
         execution_process_repo_state::ExecutionProcessRepoState,
         repo::Repo,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
@@ -30,26 +32,45 @@ use executors::{
     actions::{
         Executable, ExecutorAction, ExecutorActionType,
         coding_agent_follow_up::CodingAgentFollowUpRequest,
-        coding_agent_initial::CodingAgentInitialRequest,
+        coding_agent_initial::CodingAgentInitialRequest, script::ScriptContext,
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::{ExecutionEnv, RepoContext},
     executors::{BaseCodingAgent, CancellationToken, ExecutorExitResult, ExecutorExitSignal},
-    logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    instructions::{AgentInstructions, InstructionsDelivery},
+    logs::{
+        NormalizedEntryType,
+        utils::{
+            ConversationPatch, EntryIndexProvider, patch::extract_normalized_entry_from_patch,
+        },
+    },
+    profile::{ExecutorConfigs, ExecutorProfileId},
+    sandbox::{NOTICE_PREFIX, SandboxNetwork, SandboxPolicy},
 };
-use futures::{FutureExt, TryStreamExt, stream::select};
+use futures::{FutureExt, StreamExt, TryStreamExt, stream::select};
 use git::GitService;
 use services::services::{
+    agent_instructions,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
+    budget::BudgetWatcher,
     config::{Config, DEFAULT_COMMIT_REMINDER_PROMPT},
     container::{ContainerError, ContainerRef, ContainerService},
+    dev_server_monitor::DevServerMonitor,
+    dev_server_ports::DevServerPortService,
     diff_stream::{self, DiffStreamHandle},
+    execution_retry,
+    execution_scheduler::ExecutionScheduler,
+    executor_fallback,
     image::ImageService,
     notification::NotificationService,
     queued_message::QueuedMessageService,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
-use tokio::{sync::RwLock, task::JoinHandle};
+use tokio::{
+    sync::{RwLock, mpsc},
+    task::JoinHandle,
+    time::Instant,
+};
 use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
@@ -58,7 +79,12 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{command, copy};
+use crate::{
+    agent_files, command, copy, instruction_files,
+    resource_limits::AppliedLimits,
+    runtime::{HostRuntime, ProcessLaunch, ProcessRuntime},
+    sandbox_proxy::AllowlistProxy,
+};
 
 #[derive(Clone)]
 pub struct LocalContainerService {
@@ -70,12 +96,21 @@ pub struct LocalContainerService {
     /// When stopping execution, we await these to ensure logs are fully persisted.
     db_stream_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
     exit_monitor_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
+    /// Resource limits applied to running processes, released when they exit
+    resource_limits: Arc<RwLock<HashMap<Uuid, AppliedLimits>>>,
+    /// Network allowlist proxies of running sandboxed processes
+    sandbox_proxies: Arc<RwLock<HashMap<Uuid, AllowlistProxy>>>,
+    /// Where execution processes run
+    runtime: Arc<dyn ProcessRuntime>,
     config: Arc<RwLock<Config>>,
     git: GitService,
     image_service: ImageService,
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
     notification_service: NotificationService,
+    scheduler: ExecutionScheduler,
+    dev_server_ports: DevServerPortService,
+    dev_server_monitor: DevServerMonitor,
 }
 
 impl LocalContainerService {
@@ -88,12 +123,43 @@ impl LocalContainerService {
         image_service: ImageService,
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
+    ) -> Self {
+        Self::with_runtime(
+            db,
+            msg_stores,
+            config,
+            git,
+            image_service,
+            approvals,
+            queued_message_service,
+            Arc::new(HostRuntime),
+        )
+        .await
+    }
+
+    /// A service spawning execution processes through `runtime` rather than
+    /// on the host
+    #[allow(clippy::too_many_arguments)]
+    pub async fn with_runtime(
+        db: DBService,
+        msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+        config: Arc<RwLock<Config>>,
+        git: GitService,
+        image_service: ImageService,
+        approvals: Approvals,
+        queued_message_service: QueuedMessageService,
+        runtime: Arc<dyn ProcessRuntime>,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let cancellation_tokens = Arc::new(RwLock::new(HashMap::new()));
         let db_stream_handles = Arc::new(RwLock::new(HashMap::new()));
         let exit_monitor_handles = Arc::new(RwLock::new(HashMap::new()));
+        let resource_limits = Arc::new(RwLock::new(HashMap::new()));
+        let sandbox_proxies = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
+        let scheduler = ExecutionScheduler::new(db.pool.clone(), config.clone());
+        let dev_server_ports = DevServerPortService::new();
+        let dev_server_monitor = DevServerMonitor::new();
 
         let container = LocalContainerService {
             db,
@@ -102,15 +168,23 @@ impl LocalContainerService {
             msg_stores,
             db_stream_handles,
             exit_monitor_handles,
+            resource_limits,
+            sandbox_proxies,
+            runtime,
             config,
             git,
             image_service,
             approvals,
             queued_message_service,
             notification_service,
+            scheduler,
+            dev_server_ports,
+            dev_server_monitor,
         };
 
         container.spawn_workspace_cleanup();
+        container.spawn_log_retention();
+        container.spawn_legacy_log_migration();
 
         container
     }
@@ -237,6 +311,73 @@ impl LocalContainerService {
         });
     }
 
+    /// Periodically prune execution process logs per the retention policy, then
+    /// reclaim the space they occupied.
+    pub fn spawn_log_retention(&self) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            let mut retention_interval =
+                tokio::time::interval(tokio::time::Duration::from_secs(3600)); // 1 hour
+            loop {
+                retention_interval.tick().await;
+                let config = container.config.read().await.log_retention.clone();
+                if !config.enabled {
+                    continue;
+                }
+
+                let mut pruned = 0;
+                let mut kept = HashSet::new();
+                loop {
+                    let kept_before = kept.len();
+                    match container.prune_expired_logs(&config, &mut kept).await {
+                        Ok(0) if kept.len() == kept_before => break,
+                        Ok(count) => pruned += count,
+                        Err(e) => {
+                            tracing::error!("Failed to prune execution process logs: {}", e);
+                            break;
+                        }
+                    }
+                }
+
+                if pruned > 0 {
+                    tracing::info!("Pruned logs of {} execution processes", pruned);
+                    if let Err(e) = container.db.reclaim_space().await {
+                        tracing::error!("Failed to reclaim database space: {}", e);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Move logs still stored one row per line into compressed chunks, a few processes
+    /// at a time so the database isn't locked for long, then reclaim the space.
+    pub fn spawn_legacy_log_migration(&self) {
+        let db = self.db.clone();
+        tokio::spawn(async move {
+            let mut migrated = 0;
+            loop {
+                match ExecutionProcessLogs::migrate_to_chunks(&db.pool, 20).await {
+                    Ok(0) => break,
+                    Ok(count) => migrated += count,
+                    Err(e) => {
+                        tracing::error!("Failed to migrate execution process logs: {}", e);
+                        return;
+                    }
+                }
+            }
+
+            if migrated > 0 {
+                tracing::info!(
+                    "Migrated logs of {} execution processes to compressed chunks",
+                    migrated
+                );
+                if let Err(e) = db.reclaim_space().await {
+                    tracing::error!("Failed to reclaim database space: {}", e);
+                }
+            }
+        });
+    }
+
     /// Record the current HEAD commit for each repository as the "after" state.
     /// Errors are silently ignored since this runs after the main execution completes
     /// and failure should not block process finalization.
@@ -309,7 +450,7 @@ impl LocalContainerService {
         &self,
         workspace_root: &Path,
         repos: &[Repo],
-    ) -> Result<Vec<(Repo, PathBuf)>, ContainerError> {
+    ) -> Result<Vec<(Repo, PathBuf, Vec<String>)>, ContainerError> {
         let git = GitService::new();
         let mut repos_with_changes = Vec::new();
 
@@ -317,11 +458,13 @@ impl LocalContainerService {
             let worktree_path = workspace_root.join(&repo.name);
 
             match git.get_worktree_status(&worktree_path) {
-                Ok(ws) if !ws.entries.is_empty() => {
-                    repos_with_changes.push((repo.clone(), worktree_path));
-                }
-                Ok(_) => {
-                    tracing::debug!("No changes in repo '{}'", repo.name);
+                Ok(ws) => {
+                    let keep_out = agent_files::uncommitted_agent_files(&worktree_path, &ws);
+                    if ws.entries.len() > keep_out.len() {
+                        repos_with_changes.push((repo.clone(), worktree_path, keep_out));
+                    } else {
+                        tracing::debug!("No changes in repo '{}'", repo.name);
+                    }
                 }
                 Err(e) => {
                     return Err(ContainerError::Other(anyhow!(
@@ -366,17 +509,24 @@ impl LocalContainerService {
     }
 
     /// Commit changes to each repo. Logs failures but continues with other repos.
-    fn commit_repos(&self, repos_with_changes: Vec<(Repo, PathBuf)>, message: &str) -> bool {
+    fn commit_repos(
+        &self,
+        repos_with_changes: Vec<(Repo, PathBuf, Vec<String>)>,
+        message: &str,
+    ) -> bool {
         let mut any_committed = false;
 
-        for (repo, worktree_path) in repos_with_changes {
+        for (repo, worktree_path, keep_out) in repos_with_changes {
             tracing::debug!(
                 "Committing changes for repo '{}' at {:?}",
                 repo.name,
                 &worktree_path
             );
 
-            match self.git().commit(&worktree_path, message) {
+            match self
+                .git()
+                .commit_excluding(&worktree_path, message, &keep_out)
+            {
                 Ok(true) => {
                     any_committed = true;
                     tracing::info!("Committed changes in repo '{}'", repo.name);
@@ -455,11 +605,36 @@ impl LocalContainerService {
                 Err(_) => (None, ExecutionProcessStatus::Failed),
             };
 
-            if !ExecutionProcess::was_stopped(&db.pool, exec_id).await
-                && let Err(e) =
-                    ExecutionProcess::update_completion(&db.pool, exec_id, status, exit_code).await
-            {
-                tracing::error!("Failed to update execution process completion: {}", e);
+            let limit_reason = container.release_resource_limits(exec_id).await;
+            let container_reason = container
+                .runtime
+                .release(exec_id, status == ExecutionProcessStatus::Completed)
+                .await;
+            let limit_reason = limit_reason.or(container_reason);
+            container.sandbox_proxies.write().await.remove(&exec_id);
+
+            if !ExecutionProcess::was_stopped(&db.pool, exec_id).await {
+                if let Err(e) = ExecutionProcess::update_completion(
+                    &db.pool,
+                    exec_id,
+                    status.clone(),
+                    exit_code,
+                )
+                .await
+                {
+                    tracing::error!("Failed to update execution process completion: {}", e);
+                }
+                if status == ExecutionProcessStatus::Failed
+                    && let Some(reason) = limit_reason
+                    && let Err(e) =
+                        ExecutionProcess::set_completion_reason(&db.pool, exec_id, reason).await
+                {
+                    tracing::error!("Failed to record completion reason for {}: {}", exec_id, e);
+                }
+            }
+
+            if let Err(e) = container.dev_server_ports.release(&db.pool, exec_id).await {
+                tracing::warn!("Failed to release dev server port for {}: {}", exec_id, e);
             }
 
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
@@ -468,6 +643,12 @@ impl LocalContainerService {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
 
+                if ctx.execution_process.run_reason == ExecutionProcessRunReason::DevServer
+                    && ctx.execution_process.status != ExecutionProcessStatus::Killed
+                {
+                    container.schedule_dev_server_restart(&ctx).await;
+                }
+
                 let success = matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
@@ -522,53 +703,90 @@ impl LocalContainerService {
                     }
                 }
 
-                if container.should_finalize(&ctx) {
-                    // Only execute queued messages if the execution succeeded
-                    // If it failed or was killed, just clear the queue and finalize
+                // A run that failed on a retryable provider error is continued
+                // after a backoff; past its retries, one that failed on
+                // authentication or a usage limit goes on with the next profile
+                // of its fallback chain
+                let msg_store = msg_stores.read().await.get(&exec_id).cloned();
+                let failed = ctx.execution_process.status == ExecutionProcessStatus::Failed;
+                let retrying = failed
+                    && match &msg_store {
+                        Some(store) => container.schedule_retry(&ctx, store).await,
+                        None => false,
+                    };
+                let fell_back = failed
+                    && !retrying
+                    && msg_store.is_some_and(|store| executor_fallback::has_fallback_error(&store))
+                    && match container.start_executor_fallback(&ctx).await {
+                        Ok(started) => started.is_some(),
+                        Err(e) => {
+                            tracing::error!("Failed to start fallback executor: {}", e);
+                            false
+                        }
+                    };
+
+                if !retrying && !fell_back && container.should_finalize(&ctx) {
+                    // Drain the queue one message per finished turn. A failed or
+                    // killed turn pauses it; the rest stays queued for later.
                     let should_execute_queued = !matches!(
                         ctx.execution_process.status,
                         ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
                     );
+                    let queued_msg = if should_execute_queued {
+                        container
+                            .queued_message_service
+                            .take_next(ctx.session.id)
+                            .await
+                            .unwrap_or_else(|e| {
+                                tracing::error!(
+                                    "Failed to take queued message for session {}: {}",
+                                    ctx.session.id,
+                                    e
+                                );
+                                None
+                            })
+                    } else {
+                        tracing::info!(
+                            "Pausing message queue for session {} due to execution status {:?}",
+                            ctx.session.id,
+                            ctx.execution_process.status
+                        );
+                        None
+                    };
 
-                    if let Some(queued_msg) =
-                        container.queued_message_service.take_queued(ctx.session.id)
-                    {
-                        if should_execute_queued {
-                            tracing::info!(
-                                "Found queued message for session {}, starting follow-up execution",
-                                ctx.session.id
-                            );
+                    if let Some(queued_msg) = queued_msg {
+                        tracing::info!(
+                            "Found queued message for session {}, starting follow-up execution",
+                            ctx.session.id
+                        );
 
-                            // Delete the scratch since we're consuming the queued message
-                            if let Err(e) = Scratch::delete(
+                        // The draft mirrors the last queued message, so drop it once
+                        // that one is consumed
+                        if !container
+                            .queued_message_service
+                            .has_queued(ctx.session.id)
+                            .await
+                            .unwrap_or(true)
+                            && let Err(e) = Scratch::delete(
                                 &db.pool,
                                 ctx.session.id,
                                 &ScratchType::DraftFollowUp,
                             )
                             .await
-                            {
-                                tracing::warn!(
-                                    "Failed to delete scratch after consuming queued message: {}",
-                                    e
-                                );
-                            }
-
-                            // Execute the queued follow-up
-                            if let Err(e) = container
-                                .start_queued_follow_up(&ctx, &queued_msg.data)
-                                .await
-                            {
-                                tracing::error!("Failed to start queued follow-up: {}", e);
-                                // Fall back to finalization if follow-up fails
-                                container.finalize_task(&ctx).await;
-                            }
-                        } else {
-                            // Execution failed or was killed - discard the queued message and finalize
-                            tracing::info!(
-                                "Discarding queued message for session {} due to execution status {:?}",
-                                ctx.session.id,
-                                ctx.execution_process.status
+                        {
+                            tracing::warn!(
+                                "Failed to delete scratch after consuming queued message: {}",
+                                e
                             );
+                        }
+
+                        // Execute the queued follow-up
+                        if let Err(e) = container
+                            .start_queued_follow_up(&ctx, &queued_msg.data)
+                            .await
+                        {
+                            tracing::error!("Failed to start queued follow-up: {}", e);
+                            // Fall back to finalization if follow-up fails
                             container.finalize_task(&ctx).await;
                         }
                     } else {
@@ -592,9 +810,146 @@ impl LocalContainerService {
 
             // Cleanup child handle
             child_store.write().await.remove(&exec_id);
+
+            // The finished agent frees a slot for the next scheduled start
+            container.dispatch_scheduled().await;
         })
     }
 
+    /// Drop the resource limits of an exited process and report the one that
+    /// ended it, if any
+    async fn release_resource_limits(
+        &self,
+        execution_process_id: Uuid,
+    ) -> Option<ExecutionProcessCompletionReason> {
+        let applied = self
+            .resource_limits
+            .write()
+            .await
+            .remove(&execution_process_id)?;
+        let reason = applied.exceeded();
+        applied.release().await;
+        reason
+    }
+
+    /// Watch a coding agent's token usage against the budgets covering it and
+    /// stop it once it goes over a hard limit.
+    async fn spawn_budget_watcher(
+        &self,
+        execution_process_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+        executor_profile_id: ExecutorProfileId,
+        msg_store: Arc<MsgStore>,
+    ) {
+        let prices = self.config.read().await.token_prices.clone();
+        let watcher = match BudgetWatcher::load(
+            self.db.pool.clone(),
+            execution_process_id,
+            task_id,
+            project_id,
+            executor_profile_id,
+            prices,
+        )
+        .await
+        {
+            Ok(Some(watcher)) => watcher,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load budgets for execution {}: {}",
+                    execution_process_id,
+                    e
+                );
+                return;
+            }
+        };
+
+        let container = self.clone();
+        tokio::spawn(async move {
+            let Some(breach) = watcher.run(msg_store).await else {
+                return;
+            };
+            tracing::info!(
+                "Execution {} went over budget: {}",
+                execution_process_id,
+                breach.message()
+            );
+            container
+                .stop_on_limit(
+                    execution_process_id,
+                    ExecutionProcessCompletionReason::BudgetExceeded,
+                )
+                .await;
+        });
+    }
+
+    /// Stop a process that runs longer than `max_runtime` or goes `max_idle`
+    /// without writing to stdout or stderr.
+    fn spawn_timeout_watchdog(
+        &self,
+        execution_process_id: Uuid,
+        max_runtime: Option<Duration>,
+        max_idle: Option<Duration>,
+        msg_store: Arc<MsgStore>,
+    ) {
+        if max_runtime.is_none() && max_idle.is_none() {
+            return;
+        }
+
+        let container = self.clone();
+        tokio::spawn(async move {
+            if let Some(reason) = wait_for_timeout(&msg_store, max_runtime, max_idle).await {
+                container.stop_on_limit(execution_process_id, reason).await;
+            }
+        });
+    }
+
+    /// Stop a running process on behalf of a limit, recording why so it isn't
+    /// mistaken for a user stop or a failure.
+    async fn stop_on_limit(
+        &self,
+        execution_process_id: Uuid,
+        reason: ExecutionProcessCompletionReason,
+    ) {
+        let pool = &self.db.pool;
+        // The process may have finished while the limit was being checked
+        let process = match ExecutionProcess::find_by_id(pool, execution_process_id).await {
+            Ok(Some(process)) if process.status == ExecutionProcessStatus::Running => process,
+            Ok(_) => return,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load execution process {}: {}",
+                    execution_process_id,
+                    e
+                );
+                return;
+            }
+        };
+
+        tracing::info!("Stopping execution {}: {:?}", execution_process_id, reason);
+        if let Err(e) =
+            ExecutionProcess::set_completion_reason(pool, execution_process_id, reason).await
+        {
+            tracing::error!(
+                "Failed to record completion reason for execution {}: {}",
+                execution_process_id,
+                e
+            );
+        }
+        if let Err(e) = self
+            .stop_execution(&process, ExecutionProcessStatus::Killed)
+            .await
+        {
+            tracing::error!(
+                "Failed to stop execution {} after {:?}: {}",
+                execution_process_id,
+                reason,
+                e
+            );
+        }
+    }
+
     pub fn spawn_os_exit_watcher(
         &self,
         exec_id: Uuid,
@@ -637,8 +992,15 @@ impl LocalContainerService {
         format!("{}-{}", short_uuid(workspace_id), task_title_id)
     }
 
-    async fn track_child_msgs_in_store(&self, id: Uuid, child: &mut AsyncGroupChild) {
-        let store = Arc::new(MsgStore::new());
+    /// Forward the child's output into `store`, or a new store when the
+    /// executor doesn't log through one of its own
+    async fn track_child_msgs_in_store(
+        &self,
+        id: Uuid,
+        child: &mut AsyncGroupChild,
+        store: Option<Arc<MsgStore>>,
+    ) {
+        let store = store.unwrap_or_default();
 
         let out = child.inner().stdout.take().expect("no stdout");
         let err = child.inner().stderr.take().expect("no stderr");
@@ -819,6 +1181,243 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Allocate a port for a dev server and expose it to the script as `PORT`
+    /// (plus the configured extra variable, if any).
+    async fn assign_dev_server_port(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        repo: &Repo,
+        env: &mut ExecutionEnv,
+    ) -> Result<Option<u16>, ContainerError> {
+        let port_config = self.config.read().await.dev_server_ports.clone();
+        if !port_config.enabled {
+            return Ok(None);
+        }
+
+        let allocation = self
+            .dev_server_ports
+            .allocate(
+                &self.db.pool,
+                &port_config,
+                execution_process.id,
+                workspace.id,
+                repo.id,
+            )
+            .await?;
+
+        let port = allocation.port.to_string();
+        env.insert("PORT", &port);
+        if let Some(env_var) = port_config.env_var.as_deref().map(str::trim)
+            && !env_var.is_empty()
+        {
+            env.insert(env_var, &port);
+        }
+
+        Ok(u16::try_from(allocation.port).ok())
+    }
+
+    /// Restart a dev server that exited on its own, if its repo opted in.
+    /// Restarts back off exponentially and give up after repeated crashes.
+    async fn schedule_dev_server_restart(&self, ctx: &ExecutionContext) {
+        let Ok(action) = ctx.execution_process.executor_action() else {
+            return;
+        };
+        let Some(repo) = dev_server_repo(action, &ctx.repos) else {
+            return;
+        };
+        if !repo.dev_server_auto_restart {
+            return;
+        }
+
+        let Some((delay, attempt)) = self
+            .dev_server_monitor
+            .restart_backoff(&ctx.execution_process)
+            .await
+        else {
+            tracing::warn!(
+                "Dev server for repo {} in workspace {} keeps exiting, giving up on restarts",
+                repo.name,
+                ctx.workspace.id
+            );
+            return;
+        };
+
+        tracing::info!(
+            "Dev server {} exited unexpectedly, restarting in {:?} (attempt {})",
+            ctx.execution_process.id,
+            delay,
+            attempt
+        );
+
+        let container = self.clone();
+        let workspace = ctx.workspace.clone();
+        let session = ctx.session.clone();
+        let action = action.clone();
+        let repo_name = repo.name.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+
+            // Someone may have started this dev server again in the meantime
+            match ExecutionProcess::find_running_dev_servers_by_workspace(
+                &container.db.pool,
+                workspace.id,
+            )
+            .await
+            {
+                Ok(running) => {
+                    let already_running = running.iter().any(|process| {
+                        process
+                            .executor_action()
+                            .is_ok_and(|a| dev_server_working_dir(a) == Some(repo_name.as_str()))
+                    });
+                    if already_running {
+                        return;
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to check running dev servers: {}", e);
+                    return;
+                }
+            }
+
+            match container
+                .start_execution(
+                    &workspace,
+                    &session,
+                    &action,
+                    &ExecutionProcessRunReason::DevServer,
+                )
+                .await
+            {
+                Ok(process) => {
+                    container
+                        .dev_server_monitor
+                        .record_restart(process.id, attempt)
+                        .await;
+                }
+                Err(e) => tracing::error!("Failed to restart dev server: {}", e),
+            }
+        });
+    }
+
+    /// Continue a coding agent run that failed on a retryable provider error
+    /// once its backoff has passed, announcing the countdown in its
+    /// conversation. Returns whether a retry was scheduled.
+    async fn schedule_retry(&self, ctx: &ExecutionContext, msg_store: &Arc<MsgStore>) -> bool {
+        let Ok(action) = ctx.execution_process.executor_action() else {
+            return false;
+        };
+        let profile = match action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => &request.executor_profile_id,
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => &request.executor_profile_id,
+            _ => return false,
+        };
+        if !execution_retry::has_retryable_error(msg_store) {
+            return false;
+        }
+
+        let retries = self.config.read().await.execution_retries.clone();
+        let max_retries = ExecutorConfigs::get_cached()
+            .get_settings(profile)
+            .max_retries;
+        let Some((delay, attempt)) =
+            execution_retry::backoff(ctx.execution_process.retry_attempt, max_retries, &retries)
+        else {
+            tracing::warn!(
+                "Execution {} keeps failing on retryable errors, giving up on retries",
+                ctx.execution_process.id
+            );
+            return false;
+        };
+
+        tracing::info!(
+            "Execution {} failed on a retryable error, continuing in {:?} (attempt {})",
+            ctx.execution_process.id,
+            delay,
+            attempt
+        );
+        let index = EntryIndexProvider::start_from(msg_store).next();
+        msg_store.push_patch(ConversationPatch::add_normalized_entry(
+            index,
+            execution_retry::countdown_entry(delay, attempt, max_retries),
+        ));
+
+        let container = self.clone();
+        let failed_id = ctx.execution_process.id;
+        let session_id = ctx.session.id;
+        let profile = profile.clone();
+        let retry_action = action.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+
+            // Anything started in the session meanwhile replaces the retry
+            match ExecutionProcess::find_by_session_id(&container.db.pool, session_id, false).await
+            {
+                Ok(processes) if processes.last().is_some_and(|p| p.id == failed_id) => {}
+                Ok(_) => return,
+                Err(e) => {
+                    tracing::error!("Failed to check session before retrying: {}", e);
+                    return;
+                }
+            }
+
+            let result = async {
+                let ctx = ExecutionProcess::load_context(&container.db.pool, failed_id).await?;
+                let has_agent_session =
+                    CodingAgentTurn::find_latest_session_info(&container.db.pool, session_id)
+                        .await?
+                        .is_some();
+                // Without an agent session to continue, run the original request again
+                if !has_agent_session
+                    && matches!(
+                        retry_action.typ(),
+                        ExecutorActionType::CodingAgentInitialRequest(_)
+                    )
+                {
+                    container
+                        .start_execution(
+                            &ctx.workspace,
+                            &ctx.session,
+                            &retry_action,
+                            &ExecutionProcessRunReason::CodingAgent,
+                        )
+                        .await
+                } else {
+                    container
+                        .start_queued_follow_up(
+                            &ctx,
+                            &DraftFollowUpData {
+                                message: execution_retry::RETRY_PROMPT.to_string(),
+                                executor_profile_id: profile,
+                            },
+                        )
+                        .await
+                }
+            }
+            .await;
+            match result {
+                Ok(process) => {
+                    if let Err(e) = ExecutionProcess::set_retry_attempt(
+                        &container.db.pool,
+                        process.id,
+                        attempt.into(),
+                    )
+                    .await
+                    {
+                        tracing::error!(
+                            "Failed to record retry attempt of execution {}: {}",
+                            process.id,
+                            e
+                        );
+                    }
+                }
+                Err(e) => tracing::error!("Failed to retry execution {}: {}", failed_id, e),
+            }
+        });
+        true
+    }
+
     /// Start a follow-up execution from a queued message
     async fn start_queued_follow_up(
         &self,
@@ -893,6 +1492,60 @@ impl LocalContainerService {
     }
 }
 
+/// Repo directory a dev server script runs in, if the action is a dev server
+fn dev_server_working_dir(action: &ExecutorAction) -> Option<&str> {
+    match action.typ() {
+        ExecutorActionType::ScriptRequest(script) if script.context == ScriptContext::DevServer => {
+            script.working_dir.as_deref()
+        }
+        _ => None,
+    }
+}
+
+fn dev_server_repo<'a>(action: &ExecutorAction, repos: &'a [Repo]) -> Option<&'a Repo> {
+    let dir = dev_server_working_dir(action)?;
+    repos.iter().find(|repo| repo.name == dir)
+}
+
+/// Explain sandbox blocks in a process's output: connections refused by the
+/// allowlist proxy, and errors that look like the sandbox stopped a write or a
+/// network request. Each explanation is added once.
+fn spawn_sandbox_notices(
+    policy: SandboxPolicy,
+    mut blocked_connections: Option<mpsc::UnboundedReceiver<String>>,
+    msg_store: Arc<MsgStore>,
+) {
+    tokio::spawn(async move {
+        let mut stream = msg_store.history_plus_stream();
+        let mut shown = HashSet::new();
+        loop {
+            let blocked = async {
+                match blocked_connections.as_mut() {
+                    Some(rx) => rx.recv().await,
+                    None => std::future::pending().await,
+                }
+            };
+            let notice = tokio::select! {
+                msg = stream.next() => match msg {
+                    Some(Ok(LogMsg::Stdout(output) | LogMsg::Stderr(output)))
+                        if !output.starts_with(NOTICE_PREFIX) =>
+                    {
+                        policy.blocked_notice(&output).map(str::to_string)
+                    }
+                    Some(Ok(LogMsg::Finished)) | None => return,
+                    Some(_) => None,
+                },
+                Some(message) = blocked => Some(message),
+            };
+            if let Some(notice) = notice
+                && shown.insert(notice.clone())
+            {
+                msg_store.push_stderr(format!("{notice}\n"));
+            }
+        }
+    });
+}
+
 fn failure_exit_status() -> std::process::ExitStatus {
     #[cfg(unix)]
     {
@@ -924,6 +1577,10 @@ impl ContainerService for LocalContainerService {
         &self.notification_service
     }
 
+    fn scheduler(&self) -> &ExecutionScheduler {
+        &self.scheduler
+    }
+
     async fn store_db_stream_handle(&self, id: Uuid, handle: JoinHandle<()>) {
         self.add_db_stream_handle(id, handle).await;
     }
@@ -1099,7 +1756,9 @@ impl ContainerService for LocalContainerService {
                     | BaseCodingAgent::ClaudeCode
                     | BaseCodingAgent::Gemini
                     | BaseCodingAgent::QwenCode
-                    | BaseCodingAgent::Opencode,
+                    | BaseCodingAgent::Opencode
+                    | BaseCodingAgent::Acp
+                    | BaseCodingAgent::OpenaiCompatible,
                 ) => ExecutorApprovalBridge::new(
                     self.approvals.clone(),
                     self.db.clone(),
@@ -1145,20 +1804,164 @@ impl ContainerService for LocalContainerService {
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
         env.insert("VK_SESSION_ID", execution_process.session_id.to_string());
 
+        let instructions_config = self.config.read().await.agent_instructions.clone();
+        let instructions = agent_instructions::compose(Some(&project), &repos);
+        let mut delivery = instructions_config.delivery;
+        if delivery == InstructionsDelivery::File {
+            // Agents only read the instruction files of the repository they run
+            // in, so the prompt carries the instructions when they can't go there
+            let delivered = match repos.as_slice() {
+                [repo] => instruction_files::sync_instruction_files(
+                    &current_dir.join(&repo.name),
+                    instructions.as_deref(),
+                ),
+                _ => false,
+            };
+            if !delivered {
+                delivery = InstructionsDelivery::Prepend;
+            }
+        }
+        if delivery != InstructionsDelivery::File {
+            env.instructions = instructions.map(|text| AgentInstructions {
+                text,
+                delivery,
+                follow_ups: instructions_config.include_follow_ups,
+            });
+        }
+
+        let dev_server_repo = dev_server_repo(executor_action, &repos);
+        let dev_server_port = match dev_server_repo {
+            Some(repo) => {
+                self.assign_dev_server_port(workspace, execution_process, repo, &mut env)
+                    .await?
+            }
+            None => None,
+        };
+
+        let sandbox = self.config.read().await.sandbox.policy_for(executor_action);
+        let mut sandbox_proxy = None;
+        let mut blocked_connections = None;
+        if let Some(SandboxPolicy {
+            network: SandboxNetwork::Allowlist { hosts },
+            ..
+        }) = &sandbox
+        {
+            let (tx, rx) = mpsc::unbounded_channel();
+            let proxy = AllowlistProxy::start(execution_process.id, hosts.clone(), tx).await?;
+            env.sandbox_proxy = Some(proxy.socket().to_path_buf());
+            sandbox_proxy = Some(proxy);
+            blocked_connections = Some(rx);
+        }
+        if let Some(policy) = &sandbox {
+            for (key, value) in policy.proxy_env_vars() {
+                env.insert(key, value);
+            }
+        }
+        env.sandbox = sandbox.clone();
+
+        let (mut resource_limits, (max_runtime, max_idle)) = {
+            let config = self.config.read().await;
+            (
+                config.resource_limits.limits_for(executor_action),
+                config.execution_timeouts.limits_for(executor_action),
+            )
+        };
+        let launch = ProcessLaunch {
+            execution_process_id: execution_process.id,
+            workspace,
+            action: executor_action,
+            repos: &repos,
+        };
+        let replacement = self
+            .runtime
+            .prepare(&launch, &mut env, &mut resource_limits)
+            .await?;
+        let spawn_action = replacement.as_ref().unwrap_or(executor_action);
+        // The process joins its cgroup or sets its rlimits before it executes,
+        // so nothing it forks escapes the limits
+        let applied_limits = resource_limits
+            .and_then(|limits| AppliedLimits::prepare(execution_process.id, &limits, max_runtime));
+        #[cfg(target_os = "linux")]
+        if let Some(applied) = &applied_limits {
+            applied.apply_to(&mut env);
+        }
+
         // Create the child and stream, add to execution tracker with timeout
-        let mut spawned = tokio::time::timeout(
+        let spawned = tokio::time::timeout(
             Duration::from_secs(30),
-            executor_action.spawn(&current_dir, approvals_service, &env),
+            spawn_action.spawn(&current_dir, approvals_service, &env),
         )
         .await
         .map_err(|_| {
             ContainerError::Other(anyhow!(
                 "Timeout: process took more than 30 seconds to start"
             ))
-        })??;
+        })
+        .and_then(|result| result.map_err(ContainerError::from));
+        let mut spawned = match spawned {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                if let Some(applied) = applied_limits {
+                    applied.release().await;
+                }
+                self.runtime.release(execution_process.id, false).await;
+                return Err(e);
+            }
+        };
+
+        if let Some(applied) = applied_limits {
+            let applied = applied
+                .confirm(execution_process.id, spawned.child.inner().id())
+                .await;
+            self.resource_limits
+                .write()
+                .await
+                .insert(execution_process.id, applied);
+        }
+
+        self.track_child_msgs_in_store(
+            execution_process.id,
+            &mut spawned.child,
+            spawned.msg_store.take(),
+        )
+        .await;
+
+        if let Some(proxy) = sandbox_proxy {
+            self.sandbox_proxies
+                .write()
+                .await
+                .insert(execution_process.id, proxy);
+        }
+        if let Some(policy) = sandbox
+            && let Some(msg_store) = self.get_msg_store_by_id(&execution_process.id).await
+        {
+            spawn_sandbox_notices(policy, blocked_connections, msg_store);
+        }
+
+        if let Some(repo) = dev_server_repo
+            && let Some(msg_store) = self.get_msg_store_by_id(&execution_process.id).await
+        {
+            self.dev_server_monitor.spawn_watcher(
+                self.db.pool.clone(),
+                execution_process.id,
+                repo,
+                dev_server_port,
+                msg_store,
+            );
+        }
 
-        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
+        if let Some(executor_profile_id) = executor_action.executor_profile_id()
+            && let Some(msg_store) = self.get_msg_store_by_id(&execution_process.id).await
+        {
+            self.spawn_budget_watcher(
+                execution_process.id,
+                task.id,
+                project.id,
+                executor_profile_id.clone(),
+                msg_store,
+            )
             .await;
+        }
 
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
@@ -1173,6 +1976,10 @@ impl ContainerService for LocalContainerService {
         let hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
         self.add_exit_monitor_handle(execution_process.id, hn).await;
 
+        if let Some(msg_store) = self.get_msg_store_by_id(&execution_process.id).await {
+            self.spawn_timeout_watchdog(execution_process.id, max_runtime, max_idle, msg_store);
+        }
+
         Ok(())
     }
 
@@ -1181,6 +1988,24 @@ impl ContainerService for LocalContainerService {
         execution_process: &ExecutionProcess,
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError> {
+        // A scheduled agent was never spawned, so only its record needs closing
+        if self.scheduler.cancel(execution_process.id).await? {
+            ExecutionProcess::update_completion(&self.db.pool, execution_process.id, status, None)
+                .await?;
+            if let Ok(ctx) =
+                ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
+                && let Err(e) =
+                    Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await
+            {
+                tracing::error!("Failed to update task status to InReview: {e}");
+            }
+            tracing::debug!(
+                "Scheduled execution process {} cancelled",
+                execution_process.id
+            );
+            return Ok(());
+        }
+
         let child = self
             .get_child_from_store(&execution_process.id)
             .await
@@ -1195,6 +2020,7 @@ impl ContainerService for LocalContainerService {
 
         ExecutionProcess::update_completion(&self.db.pool, execution_process.id, status, exit_code)
             .await?;
+        self.dev_server_monitor.forget(&execution_process.id).await;
 
         // Try graceful cancellation first, then force kill
         if let Some(cancel) = self.take_cancellation_token(&execution_process.id).await {
@@ -1230,6 +2056,19 @@ impl ContainerService for LocalContainerService {
         }
         self.remove_child_from_store(&execution_process.id).await;
 
+        // Only hand the port back once the process can no longer be holding it
+        if let Err(e) = self
+            .dev_server_ports
+            .release(&self.db.pool, execution_process.id)
+            .await
+        {
+            tracing::warn!(
+                "Failed to release dev server port for {}: {}",
+                execution_process.id,
+                e
+            );
+        }
+
         // Mark the process finished in the MsgStore and wait for DB persistence
         let db_stream_handle = self.take_db_stream_handle(&execution_process.id).await;
         if let Some(msg) = self.msg_stores.write().await.remove(&execution_process.id) {
@@ -1354,6 +2193,14 @@ impl ContainerService for LocalContainerService {
             .ok_or_else(|| ContainerError::Other(anyhow!("Container reference not found")))?;
         let workspace_root = PathBuf::from(container_ref);
 
+        if let Some(committed) = self
+            .runtime
+            .commit(&ctx.workspace, &workspace_root, &ctx.repos, &message)
+            .await
+        {
+            return committed;
+        }
+
         let repos_with_changes = self.check_repos_for_changes(&workspace_root, &ctx.repos)?;
         if repos_with_changes.is_empty() {
             tracing::debug!("No changes to commit in any repository");
@@ -1418,6 +2265,49 @@ impl ContainerService for LocalContainerService {
         Ok(())
     }
 }
+/// Wait until the process logging to `msg_store` runs longer than
+/// `max_runtime` or goes `max_idle` without writing to stdout or stderr, and
+/// return the limit it hit. Returns `None` if it finishes first.
+async fn wait_for_timeout(
+    msg_store: &MsgStore,
+    max_runtime: Option<Duration>,
+    max_idle: Option<Duration>,
+) -> Option<ExecutionProcessCompletionReason> {
+    let started_at = Instant::now();
+    let mut last_output_at = started_at;
+    let mut stream = msg_store.history_plus_stream();
+
+    loop {
+        let runtime_deadline = max_runtime.map(|limit| {
+            (
+                started_at + limit,
+                ExecutionProcessCompletionReason::TimedOut,
+            )
+        });
+        let idle_deadline = max_idle.map(|limit| {
+            (
+                last_output_at + limit,
+                ExecutionProcessCompletionReason::IdleTimeout,
+            )
+        });
+        let (deadline, reason) = [runtime_deadline, idle_deadline]
+            .into_iter()
+            .flatten()
+            .min_by_key(|(deadline, _)| *deadline)?;
+
+        tokio::select! {
+            msg = stream.next() => match msg {
+                Some(Ok(LogMsg::Stdout(_) | LogMsg::Stderr(_))) => {
+                    last_output_at = Instant::now();
+                }
+                Some(Ok(LogMsg::Finished)) | None => return None,
+                Some(_) => {}
+            },
+            _ = tokio::time::sleep_until(deadline) => return Some(reason),
+        }
+    }
+}
+
 fn success_exit_status() -> std::process::ExitStatus {
     #[cfg(unix)]
     {
@@ -1430,3 +2320,82 @@ fn success_exit_status() -> std::process::ExitStatus {
         ExitStatusExt::from_raw(0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a line to `msg_store` every `interval` until `until` has passed
+    fn spawn_output(msg_store: Arc<MsgStore>, interval: Duration, until: Duration) {
+        tokio::spawn(async move {
+            let started_at = Instant::now();
+            while started_at.elapsed() < until {
+                tokio::time::sleep(interval).await;
+                msg_store.push_stdout("still working\n");
+            }
+        });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn silent_process_hits_idle_timeout() {
+        let msg_store = MsgStore::new();
+        let started_at = Instant::now();
+
+        let reason = wait_for_timeout(
+            &msg_store,
+            Some(Duration::from_secs(600)),
+            Some(Duration::from_secs(30)),
+        )
+        .await;
+
+        assert_eq!(reason, Some(ExecutionProcessCompletionReason::IdleTimeout));
+        assert_eq!(started_at.elapsed().as_secs(), 30);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn output_resets_idle_timeout_until_runtime_runs_out() {
+        let msg_store = Arc::new(MsgStore::new());
+        let started_at = Instant::now();
+        spawn_output(
+            msg_store.clone(),
+            Duration::from_secs(20),
+            Duration::from_secs(600),
+        );
+
+        let reason = wait_for_timeout(
+            &msg_store,
+            Some(Duration::from_secs(120)),
+            Some(Duration::from_secs(30)),
+        )
+        .await;
+
+        assert_eq!(reason, Some(ExecutionProcessCompletionReason::TimedOut));
+        assert_eq!(started_at.elapsed().as_secs(), 120);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_timeout_counts_from_last_output() {
+        let msg_store = Arc::new(MsgStore::new());
+        let started_at = Instant::now();
+        spawn_output(
+            msg_store.clone(),
+            Duration::from_secs(20),
+            Duration::from_secs(60),
+        );
+
+        let reason = wait_for_timeout(&msg_store, None, Some(Duration::from_secs(30))).await;
+
+        assert_eq!(reason, Some(ExecutionProcessCompletionReason::IdleTimeout));
+        assert_eq!(started_at.elapsed().as_secs(), 90);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn finished_process_is_left_alone() {
+        let msg_store = MsgStore::new();
+        msg_store.push_finished();
+
+        let reason = wait_for_timeout(&msg_store, Some(Duration::from_secs(60)), None).await;
+
+        assert_eq!(reason, None);
+    }
+}
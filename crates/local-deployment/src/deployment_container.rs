@@ -0,0 +1,266 @@
+//! The container service a deployment runs with, picked at startup: worktrees
+//! and processes on this machine, processes in Docker or Podman containers
+//! when a container engine is configured, or processes and worktrees on an
+//! SSH host when a remote execution host is.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use db::{
+    DBService,
+    models::{
+        execution_process::{ExecutionContext, ExecutionProcess, ExecutionProcessStatus},
+        workspace::Workspace,
+    },
+};
+use executors::actions::ExecutorAction;
+use git::GitService;
+use services::services::{
+    container::{ContainerError, ContainerRef, ContainerService},
+    execution_scheduler::ExecutionScheduler,
+    notification::NotificationService,
+};
+use tokio::{sync::RwLock, task::JoinHandle};
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use crate::{
+    container::LocalContainerService, oci_container::OciContainerService,
+    remote_container::RemoteContainerService,
+};
+
+#[derive(Clone)]
+pub enum DeploymentContainer {
+    Local(LocalContainerService),
+    Oci(OciContainerService),
+    Remote(RemoteContainerService),
+}
+
+#[async_trait]
+impl ContainerService for DeploymentContainer {
+    fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>> {
+        match self {
+            DeploymentContainer::Local(container) => container.msg_stores(),
+            DeploymentContainer::Oci(container) => container.msg_stores(),
+            DeploymentContainer::Remote(container) => container.msg_stores(),
+        }
+    }
+
+    fn db(&self) -> &DBService {
+        match self {
+            DeploymentContainer::Local(container) => container.db(),
+            DeploymentContainer::Oci(container) => container.db(),
+            DeploymentContainer::Remote(container) => container.db(),
+        }
+    }
+
+    fn git(&self) -> &GitService {
+        match self {
+            DeploymentContainer::Local(container) => container.git(),
+            DeploymentContainer::Oci(container) => container.git(),
+            DeploymentContainer::Remote(container) => container.git(),
+        }
+    }
+
+    fn notification_service(&self) -> &NotificationService {
+        match self {
+            DeploymentContainer::Local(container) => container.notification_service(),
+            DeploymentContainer::Oci(container) => container.notification_service(),
+            DeploymentContainer::Remote(container) => container.notification_service(),
+        }
+    }
+
+    fn scheduler(&self) -> &ExecutionScheduler {
+        match self {
+            DeploymentContainer::Local(container) => container.scheduler(),
+            DeploymentContainer::Oci(container) => container.scheduler(),
+            DeploymentContainer::Remote(container) => container.scheduler(),
+        }
+    }
+
+    fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
+        match self {
+            DeploymentContainer::Local(container) => container.workspace_to_current_dir(workspace),
+            DeploymentContainer::Oci(container) => container.workspace_to_current_dir(workspace),
+            DeploymentContainer::Remote(container) => container.workspace_to_current_dir(workspace),
+        }
+    }
+
+    async fn store_db_stream_handle(&self, id: Uuid, handle: JoinHandle<()>) {
+        match self {
+            DeploymentContainer::Local(container) => {
+                container.store_db_stream_handle(id, handle).await
+            }
+            DeploymentContainer::Oci(container) => {
+                container.store_db_stream_handle(id, handle).await
+            }
+            DeploymentContainer::Remote(container) => {
+                container.store_db_stream_handle(id, handle).await
+            }
+        }
+    }
+
+    async fn take_db_stream_handle(&self, id: &Uuid) -> Option<JoinHandle<()>> {
+        match self {
+            DeploymentContainer::Local(container) => container.take_db_stream_handle(id).await,
+            DeploymentContainer::Oci(container) => container.take_db_stream_handle(id).await,
+            DeploymentContainer::Remote(container) => container.take_db_stream_handle(id).await,
+        }
+    }
+
+    async fn create(&self, workspace: &Workspace) -> Result<ContainerRef, ContainerError> {
+        match self {
+            DeploymentContainer::Local(container) => container.create(workspace).await,
+            DeploymentContainer::Oci(container) => container.create(workspace).await,
+            DeploymentContainer::Remote(container) => container.create(workspace).await,
+        }
+    }
+
+    async fn kill_all_running_processes(&self) -> Result<(), ContainerError> {
+        match self {
+            DeploymentContainer::Local(container) => container.kill_all_running_processes().await,
+            DeploymentContainer::Oci(container) => container.kill_all_running_processes().await,
+            DeploymentContainer::Remote(container) => container.kill_all_running_processes().await,
+        }
+    }
+
+    async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError> {
+        match self {
+            DeploymentContainer::Local(container) => container.delete(workspace).await,
+            DeploymentContainer::Oci(container) => container.delete(workspace).await,
+            DeploymentContainer::Remote(container) => container.delete(workspace).await,
+        }
+    }
+
+    async fn ensure_container_exists(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<ContainerRef, ContainerError> {
+        match self {
+            DeploymentContainer::Local(container) => {
+                container.ensure_container_exists(workspace).await
+            }
+            DeploymentContainer::Oci(container) => {
+                container.ensure_container_exists(workspace).await
+            }
+            DeploymentContainer::Remote(container) => {
+                container.ensure_container_exists(workspace).await
+            }
+        }
+    }
+
+    async fn is_container_clean(&self, workspace: &Workspace) -> Result<bool, ContainerError> {
+        match self {
+            DeploymentContainer::Local(container) => container.is_container_clean(workspace).await,
+            DeploymentContainer::Oci(container) => container.is_container_clean(workspace).await,
+            DeploymentContainer::Remote(container) => container.is_container_clean(workspace).await,
+        }
+    }
+
+    async fn start_execution_inner(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) -> Result<(), ContainerError> {
+        match self {
+            DeploymentContainer::Local(container) => {
+                container
+                    .start_execution_inner(workspace, execution_process, executor_action)
+                    .await
+            }
+            DeploymentContainer::Oci(container) => {
+                container
+                    .start_execution_inner(workspace, execution_process, executor_action)
+                    .await
+            }
+            DeploymentContainer::Remote(container) => {
+                container
+                    .start_execution_inner(workspace, execution_process, executor_action)
+                    .await
+            }
+        }
+    }
+
+    async fn stop_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+        status: ExecutionProcessStatus,
+    ) -> Result<(), ContainerError> {
+        match self {
+            DeploymentContainer::Local(container) => {
+                container.stop_execution(execution_process, status).await
+            }
+            DeploymentContainer::Oci(container) => {
+                container.stop_execution(execution_process, status).await
+            }
+            DeploymentContainer::Remote(container) => {
+                container.stop_execution(execution_process, status).await
+            }
+        }
+    }
+
+    async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
+        match self {
+            DeploymentContainer::Local(container) => container.try_commit_changes(ctx).await,
+            DeploymentContainer::Oci(container) => container.try_commit_changes(ctx).await,
+            DeploymentContainer::Remote(container) => container.try_commit_changes(ctx).await,
+        }
+    }
+
+    async fn copy_project_files(
+        &self,
+        source_dir: &Path,
+        target_dir: &Path,
+        copy_files: &str,
+    ) -> Result<(), ContainerError> {
+        match self {
+            DeploymentContainer::Local(container) => {
+                container
+                    .copy_project_files(source_dir, target_dir, copy_files)
+                    .await
+            }
+            DeploymentContainer::Oci(container) => {
+                container
+                    .copy_project_files(source_dir, target_dir, copy_files)
+                    .await
+            }
+            DeploymentContainer::Remote(container) => {
+                container
+                    .copy_project_files(source_dir, target_dir, copy_files)
+                    .await
+            }
+        }
+    }
+
+    async fn stream_diff(
+        &self,
+        workspace: &Workspace,
+        stats_only: bool,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, ContainerError>
+    {
+        match self {
+            DeploymentContainer::Local(container) => {
+                container.stream_diff(workspace, stats_only).await
+            }
+            DeploymentContainer::Oci(container) => {
+                container.stream_diff(workspace, stats_only).await
+            }
+            DeploymentContainer::Remote(container) => {
+                container.stream_diff(workspace, stats_only).await
+            }
+        }
+    }
+
+    async fn git_branch_prefix(&self) -> String {
+        match self {
+            DeploymentContainer::Local(container) => container.git_branch_prefix().await,
+            DeploymentContainer::Oci(container) => container.git_branch_prefix().await,
+            DeploymentContainer::Remote(container) => container.git_branch_prefix().await,
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Agent instructions delivered through the native instruction files of a
+//! workspace's repository worktree.
+
+use std::{fs, io, path::Path};
+
+use executors::instructions::INSTRUCTION_FILES;
+
+/// First line of the instruction files written here. Files without it belong
+/// to the repository or the user and are never touched.
+const GENERATED_HEADER: &str = "<!-- Written by Vibe Kanban from the project and repository agent instructions; local changes are overwritten -->";
+
+/// Write `instructions` into each agent instruction file of `worktree` that is
+/// missing or was written here before. Files written here before are removed
+/// when there are no instructions.
+/// Returns whether every instruction file carries the instructions, which is
+/// not the case when the repository has its own.
+pub(crate) fn sync_instruction_files(worktree: &Path, instructions: Option<&str>) -> bool {
+    let content = instructions.map(|text| format!("{GENERATED_HEADER}\n\n{text}\n"));
+    let mut delivered = true;
+
+    for file in INSTRUCTION_FILES {
+        let path = worktree.join(file);
+        let existing = match fs::read_to_string(&path) {
+            Ok(existing) => Some(existing),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(_) => {
+                delivered = false;
+                continue;
+            }
+        };
+        if existing
+            .as_deref()
+            .is_some_and(|existing| !existing.starts_with(GENERATED_HEADER))
+        {
+            tracing::debug!(
+                "{} has its own {}, leaving it alone",
+                worktree.display(),
+                file
+            );
+            delivered = false;
+            continue;
+        }
+
+        let Some(content) = &content else {
+            if existing.is_some()
+                && let Err(e) = fs::remove_file(&path)
+            {
+                tracing::warn!("Failed to remove {}: {}", path.display(), e);
+            }
+            continue;
+        };
+        if existing.as_ref() != Some(content)
+            && let Err(e) = fs::write(&path, content)
+        {
+            tracing::warn!("Failed to write {}: {}", path.display(), e);
+            delivered = false;
+        }
+    }
+
+    delivered
+}
+
+/// Whether `path`, relative to the worktree root, is an instruction file
+/// written here. Those stay out of the task's commits.
+pub(crate) fn is_generated(worktree: &Path, path: &str) -> bool {
+    INSTRUCTION_FILES.contains(&path)
+        && fs::read_to_string(worktree.join(path))
+            .is_ok_and(|content| content.starts_with(GENERATED_HEADER))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn writes_missing_and_generated_files_only() {
+        let worktree = TempDir::new().unwrap();
+        fs::write(worktree.path().join("CLAUDE.md"), "Repo conventions\n").unwrap();
+
+        assert!(!sync_instruction_files(
+            worktree.path(),
+            Some("Run make lint.")
+        ));
+        assert_eq!(
+            fs::read_to_string(worktree.path().join("CLAUDE.md")).unwrap(),
+            "Repo conventions\n"
+        );
+        let agents = fs::read_to_string(worktree.path().join("AGENTS.md")).unwrap();
+        assert!(agents.starts_with(GENERATED_HEADER));
+        assert!(agents.ends_with("\n\nRun make lint.\n"));
+        assert!(is_generated(worktree.path(), "AGENTS.md"));
+        assert!(!is_generated(worktree.path(), "CLAUDE.md"));
+
+        sync_instruction_files(worktree.path(), Some("Run make test."));
+        let agents = fs::read_to_string(worktree.path().join("AGENTS.md")).unwrap();
+        assert!(agents.ends_with("\n\nRun make test.\n"));
+
+        sync_instruction_files(worktree.path(), None);
+        assert!(worktree.path().join("CLAUDE.md").exists());
+        assert!(!worktree.path().join("AGENTS.md").exists());
+        assert!(!worktree.path().join("GEMINI.md").exists());
+    }
+}
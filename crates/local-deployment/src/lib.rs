@@ -24,23 +24,34 @@ use services::services::{
     worktree_manager::WorktreeManager,
 };
 use tokio::sync::RwLock;
-use utils::{
-    assets::config_path,
-    msg_store::MsgStore,
-};
+use utils::{assets::config_path, msg_store::MsgStore};
 
-use crate::{container::LocalContainerService, pty::PtyService};
+use crate::{
+    container::LocalContainerService, deployment_container::DeploymentContainer,
+    oci_container::OciContainerService, pty::PtyService, remote_container::RemoteContainerService,
+};
+mod agent_files;
 mod command;
 pub mod container;
 mod copy;
+pub mod deployment_container;
+mod instruction_files;
+pub mod oci_container;
+mod oci_engine;
+mod oci_runtime;
 pub mod pty;
+pub mod remote_container;
+mod remote_runtime;
+mod resource_limits;
+mod runtime;
+mod sandbox_proxy;
 
 #[derive(Clone)]
 pub struct LocalDeployment {
     config: Arc<RwLock<Config>>,
     user_id: String,
     db: DBService,
-    container: LocalContainerService,
+    container: DeploymentContainer,
     git: GitService,
     project: ProjectService,
     repo: RepoService,
@@ -119,18 +130,59 @@ impl Deployment for LocalDeployment {
         }
 
         let approvals = Approvals::new(msg_stores.clone());
-        let queued_message_service = QueuedMessageService::new();
-
-        let container = LocalContainerService::new(
-            db.clone(),
-            msg_stores.clone(),
-            config.clone(),
-            git.clone(),
-            image.clone(),
-            approvals.clone(),
-            queued_message_service.clone(),
-        )
-        .await;
+        let queued_message_service = QueuedMessageService::new(db.pool.clone());
+
+        // A configured remote host runs workspaces' processes and worktrees,
+        // and a configured container engine runs their processes in
+        // containers; the choice holds until the next start
+        let remote_host = config
+            .read()
+            .await
+            .remote_execution
+            .host
+            .clone()
+            .filter(|host| !host.trim().is_empty());
+        let engine = config.read().await.container_runtime.engine;
+        let container = match (remote_host, engine) {
+            (Some(host), _) => DeploymentContainer::Remote(
+                RemoteContainerService::new(
+                    host,
+                    db.clone(),
+                    msg_stores.clone(),
+                    config.clone(),
+                    git.clone(),
+                    image.clone(),
+                    approvals.clone(),
+                    queued_message_service.clone(),
+                )
+                .await,
+            ),
+            (None, Some(engine)) => DeploymentContainer::Oci(
+                OciContainerService::new(
+                    engine,
+                    db.clone(),
+                    msg_stores.clone(),
+                    config.clone(),
+                    git.clone(),
+                    image.clone(),
+                    approvals.clone(),
+                    queued_message_service.clone(),
+                )
+                .await,
+            ),
+            (None, None) => DeploymentContainer::Local(
+                LocalContainerService::new(
+                    db.clone(),
+                    msg_stores.clone(),
+                    config.clone(),
+                    git.clone(),
+                    image.clone(),
+                    approvals.clone(),
+                    queued_message_service.clone(),
+                )
+                .await,
+            ),
+        };
 
         let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
 
@@ -0,0 +1,220 @@
+//! Container service of the container deployment. Workspaces keep their
+//! worktrees on this machine, bind-mounted into the containers their scripts
+//! and agents run in, so logs, stopping processes and the diff stream work as
+//! they do for the host; the containers themselves are created from each
+//! repository's base image and managed over the Docker or Podman socket.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use db::{
+    DBService,
+    models::{
+        execution_process::{ExecutionContext, ExecutionProcess, ExecutionProcessStatus},
+        workspace::Workspace,
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use executors::{actions::ExecutorAction, oci::ContainerEngine};
+use git::GitService;
+use services::services::{
+    approvals::Approvals,
+    config::Config,
+    container::{ContainerError, ContainerRef, ContainerService},
+    execution_scheduler::ExecutionScheduler,
+    image::ImageService,
+    notification::NotificationService,
+    queued_message::QueuedMessageService,
+};
+use tokio::{sync::RwLock, task::JoinHandle};
+use utils::{log_msg::LogMsg, msg_store::MsgStore, path::expand_tilde};
+use uuid::Uuid;
+
+use crate::{
+    container::LocalContainerService,
+    oci_engine::{self, EngineClient},
+    oci_runtime::OciRuntime,
+};
+
+#[derive(Clone)]
+pub struct OciContainerService {
+    /// Worktrees, process tracking and logs, spawning through `runtime`
+    local: LocalContainerService,
+    runtime: Arc<OciRuntime>,
+}
+
+impl OciContainerService {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new(
+        engine: ContainerEngine,
+        db: DBService,
+        msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+        config: Arc<RwLock<Config>>,
+        git: GitService,
+        image_service: ImageService,
+        approvals: Approvals,
+        queued_message_service: QueuedMessageService,
+    ) -> Self {
+        let socket = config
+            .read()
+            .await
+            .container_runtime
+            .socket
+            .as_deref()
+            .map(expand_tilde)
+            .unwrap_or_else(|| oci_engine::default_socket(engine));
+        let runtime = Arc::new(OciRuntime::new(
+            engine,
+            EngineClient::new(socket),
+            config.clone(),
+        ));
+        let local = LocalContainerService::with_runtime(
+            db,
+            msg_stores,
+            config,
+            git,
+            image_service,
+            approvals,
+            queued_message_service,
+            runtime.clone(),
+        )
+        .await;
+
+        // Containers still around belong to processes of a previous run
+        {
+            let runtime = runtime.clone();
+            tokio::spawn(async move { runtime.remove_orphans().await });
+        }
+
+        Self { local, runtime }
+    }
+
+    async fn pull_images(&self, workspace: &Workspace) -> Result<(), ContainerError> {
+        let repos =
+            WorkspaceRepo::find_repos_for_workspace(&self.local.db().pool, workspace.id).await?;
+        self.runtime.pull_images(&repos).await
+    }
+}
+
+#[async_trait]
+impl ContainerService for OciContainerService {
+    fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>> {
+        self.local.msg_stores()
+    }
+
+    fn db(&self) -> &DBService {
+        self.local.db()
+    }
+
+    fn git(&self) -> &GitService {
+        self.local.git()
+    }
+
+    fn notification_service(&self) -> &NotificationService {
+        self.local.notification_service()
+    }
+
+    fn scheduler(&self) -> &ExecutionScheduler {
+        self.local.scheduler()
+    }
+
+    fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
+        self.local.workspace_to_current_dir(workspace)
+    }
+
+    async fn store_db_stream_handle(&self, id: Uuid, handle: JoinHandle<()>) {
+        self.local.store_db_stream_handle(id, handle).await
+    }
+
+    async fn take_db_stream_handle(&self, id: &Uuid) -> Option<JoinHandle<()>> {
+        self.local.take_db_stream_handle(id).await
+    }
+
+    /// The workspace's worktrees, with the base images of its repositories
+    /// pulled so its first process doesn't wait on them
+    async fn create(&self, workspace: &Workspace) -> Result<ContainerRef, ContainerError> {
+        let container_ref = self.local.create(workspace).await?;
+        self.pull_images(workspace).await?;
+        Ok(container_ref)
+    }
+
+    async fn kill_all_running_processes(&self) -> Result<(), ContainerError> {
+        self.local.kill_all_running_processes().await
+    }
+
+    async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError> {
+        self.local.delete(workspace).await?;
+        if let Err(e) = self.runtime.remove_workspace_containers(workspace.id).await {
+            tracing::warn!(
+                "Failed to remove containers of workspace {}: {}",
+                workspace.id,
+                e
+            );
+        }
+        Ok(())
+    }
+
+    async fn ensure_container_exists(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<ContainerRef, ContainerError> {
+        let container_ref = self.local.ensure_container_exists(workspace).await?;
+        self.pull_images(workspace).await?;
+        Ok(container_ref)
+    }
+
+    async fn is_container_clean(&self, workspace: &Workspace) -> Result<bool, ContainerError> {
+        self.local.is_container_clean(workspace).await
+    }
+
+    async fn start_execution_inner(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) -> Result<(), ContainerError> {
+        self.local
+            .start_execution_inner(workspace, execution_process, executor_action)
+            .await
+    }
+
+    async fn stop_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+        status: ExecutionProcessStatus,
+    ) -> Result<(), ContainerError> {
+        self.local.stop_execution(execution_process, status).await
+    }
+
+    async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
+        self.local.try_commit_changes(ctx).await
+    }
+
+    async fn copy_project_files(
+        &self,
+        source_dir: &Path,
+        target_dir: &Path,
+        copy_files: &str,
+    ) -> Result<(), ContainerError> {
+        self.local
+            .copy_project_files(source_dir, target_dir, copy_files)
+            .await
+    }
+
+    async fn stream_diff(
+        &self,
+        workspace: &Workspace,
+        stats_only: bool,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, ContainerError>
+    {
+        self.local.stream_diff(workspace, stats_only).await
+    }
+
+    async fn git_branch_prefix(&self) -> String {
+        self.local.git_branch_prefix().await
+    }
+}
@@ -0,0 +1,345 @@
+//! Client for the Docker Engine API on the engine's Unix socket. Podman serves
+//! the same API on its own socket, so both engines are driven the same way.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use bytes::Bytes;
+use executors::oci::ContainerEngine;
+use futures::TryStreamExt;
+use http_body_util::{BodyExt, Full, StreamBody, combinators::BoxBody};
+use hyper::{Method, Request, StatusCode, body::Frame, header};
+use hyper_util::rt::TokioIo;
+use serde_json::Value;
+use tokio_util::io::ReaderStream;
+
+/// Oldest API version both Docker and Podman's compatibility API serve
+const API_VERSION: &str = "v1.41";
+
+type Body = BoxBody<Bytes, io::Error>;
+
+#[derive(Debug, Clone)]
+pub struct EngineClient {
+    socket: PathBuf,
+}
+
+impl EngineClient {
+    pub fn new(socket: PathBuf) -> Self {
+        Self { socket }
+    }
+
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    /// Whether the engine has `image` locally
+    pub async fn image_exists(&self, image: &str) -> io::Result<bool> {
+        let (status, body) = self
+            .send(Method::GET, &format!("/images/{image}/json"), None, empty())
+            .await?;
+        match status {
+            StatusCode::OK => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            _ => Err(engine_error(status, &body)),
+        }
+    }
+
+    /// Pull `image`, which may carry a tag or digest
+    pub async fn pull(&self, image: &str) -> io::Result<()> {
+        let (status, body) = self
+            .send(
+                Method::POST,
+                &format!("/images/create?fromImage={}", encode(image)),
+                None,
+                empty(),
+            )
+            .await?;
+        if !status.is_success() {
+            return Err(engine_error(status, &body));
+        }
+        // Failures part-way through arrive as progress messages
+        match pull_error(&body) {
+            Some(message) => Err(io::Error::other(format!(
+                "failed to pull {image}: {message}"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the kernel OOM killer ended the container's process
+    pub async fn oom_killed(&self, container: &str) -> io::Result<bool> {
+        let (status, body) = self
+            .send(
+                Method::GET,
+                &format!("/containers/{}/json", encode(container)),
+                None,
+                empty(),
+            )
+            .await?;
+        if !status.is_success() {
+            return Err(engine_error(status, &body));
+        }
+        let inspect: Value = serde_json::from_slice(&body).map_err(io::Error::other)?;
+        Ok(inspect
+            .pointer("/State/OOMKilled")
+            .and_then(Value::as_bool)
+            .unwrap_or(false))
+    }
+
+    /// Names of the containers, running or not, carrying `label`, given as
+    /// `key` or `key=value`
+    pub async fn containers_with_label(&self, label: &str) -> io::Result<Vec<String>> {
+        let filters = serde_json::json!({ "label": [label] }).to_string();
+        let (status, body) = self
+            .send(
+                Method::GET,
+                &format!("/containers/json?all=true&filters={}", encode(&filters)),
+                None,
+                empty(),
+            )
+            .await?;
+        if !status.is_success() {
+            return Err(engine_error(status, &body));
+        }
+        let containers: Vec<Value> = serde_json::from_slice(&body).map_err(io::Error::other)?;
+        Ok(containers
+            .iter()
+            .filter_map(|container| {
+                container
+                    .pointer("/Names/0")
+                    .and_then(Value::as_str)
+                    .map(|name| name.trim_start_matches('/').to_string())
+            })
+            .collect())
+    }
+
+    /// Extract the tar `archive` into the container's filesystem at `/`.
+    /// Works on stopped containers.
+    pub async fn put_archive(&self, container: &str, archive: &Path) -> io::Result<()> {
+        let file = tokio::fs::File::open(archive).await?;
+        let body = StreamBody::new(ReaderStream::new(file).map_ok(Frame::data)).boxed();
+        let (status, response) = self
+            .send(
+                Method::PUT,
+                &format!("/containers/{}/archive?path=%2F", encode(container)),
+                Some("application/x-tar"),
+                body,
+            )
+            .await?;
+        if !status.is_success() {
+            return Err(engine_error(status, &response));
+        }
+        Ok(())
+    }
+
+    /// Save the container's filesystem as `image` (`repository:tag`),
+    /// replacing an older image with the same tag
+    pub async fn commit(&self, container: &str, image: &str) -> io::Result<()> {
+        let (repository, tag) = image.rsplit_once(':').unwrap_or((image, "latest"));
+        let (status, body) = self
+            .send(
+                Method::POST,
+                &format!(
+                    "/commit?container={}&repo={}&tag={}",
+                    encode(container),
+                    encode(repository),
+                    encode(tag)
+                ),
+                None,
+                empty(),
+            )
+            .await?;
+        if !status.is_success() {
+            return Err(engine_error(status, &body));
+        }
+        Ok(())
+    }
+
+    /// Force-remove the container, stopping whatever still runs in it.
+    /// Removing a container that doesn't exist succeeds.
+    pub async fn remove(&self, container: &str) -> io::Result<()> {
+        let (status, body) = self
+            .send(
+                Method::DELETE,
+                &format!("/containers/{}?force=true", encode(container)),
+                None,
+                empty(),
+            )
+            .await?;
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            return Err(engine_error(status, &body));
+        }
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        content_type: Option<&str>,
+        body: Body,
+    ) -> io::Result<(StatusCode, Bytes)> {
+        let stream = connect(&self.socket).await?;
+        let (mut sender, connection) =
+            hyper::client::conn::http1::handshake::<_, Body>(TokioIo::new(stream))
+                .await
+                .map_err(io::Error::other)?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::debug!("Container engine connection failed: {}", e);
+            }
+        });
+
+        let mut request = Request::builder()
+            .method(method)
+            .uri(format!("/{API_VERSION}{path}"))
+            .header(header::HOST, "localhost");
+        if let Some(content_type) = content_type {
+            request = request.header(header::CONTENT_TYPE, content_type);
+        }
+        let request = request.body(body).map_err(io::Error::other)?;
+        let response = sender
+            .send_request(request)
+            .await
+            .map_err(io::Error::other)?;
+        let status = response.status();
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .map_err(io::Error::other)?
+            .to_bytes();
+        Ok((status, body))
+    }
+}
+
+/// API socket of `engine` when none is configured
+pub fn default_socket(engine: ContainerEngine) -> PathBuf {
+    let from_env = |var: &str| {
+        std::env::var(var)
+            .ok()
+            .and_then(|host| host.strip_prefix("unix://").map(PathBuf::from))
+    };
+    match engine {
+        ContainerEngine::Docker => {
+            from_env("DOCKER_HOST").unwrap_or_else(|| PathBuf::from("/var/run/docker.sock"))
+        }
+        ContainerEngine::Podman => from_env("CONTAINER_HOST")
+            .or_else(|| {
+                std::env::var("XDG_RUNTIME_DIR")
+                    .ok()
+                    .map(|dir| Path::new(&dir).join("podman/podman.sock"))
+                    .filter(|socket| socket.exists())
+            })
+            .unwrap_or_else(|| PathBuf::from("/run/podman/podman.sock")),
+    }
+}
+
+#[cfg(unix)]
+async fn connect(socket: &Path) -> io::Result<tokio::net::UnixStream> {
+    tokio::net::UnixStream::connect(socket).await
+}
+
+#[cfg(not(unix))]
+async fn connect(_socket: &Path) -> io::Result<tokio::net::TcpStream> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the container engine API needs Unix sockets",
+    ))
+}
+
+fn empty() -> Body {
+    Full::new(Bytes::new())
+        .map_err(|never| match never {})
+        .boxed()
+}
+
+fn engine_error(status: StatusCode, body: &[u8]) -> io::Error {
+    let message = serde_json::from_slice::<Value>(body)
+        .ok()
+        .and_then(|error| error.get("message")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| String::from_utf8_lossy(body).trim().to_string());
+    io::Error::other(format!("container engine returned {status}: {message}"))
+}
+
+/// First error in a pull's progress stream of JSON messages
+fn pull_error(body: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(body)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .find_map(|message| message.get("error")?.as_str().map(str::to_string))
+}
+
+/// Percent-encode a query or path value
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_query_values() {
+        assert_eq!(encode("node:22"), "node%3A22");
+        assert_eq!(
+            encode(r#"{"label":["vibe-kanban.execution"]}"#),
+            "%7B%22label%22%3A%5B%22vibe-kanban.execution%22%5D%7D"
+        );
+    }
+
+    #[test]
+    fn finds_errors_in_pull_progress() {
+        let ok =
+            b"{\"status\":\"Pulling from library/node\"}\n{\"status\":\"Digest: sha256:abc\"}\n";
+        assert_eq!(pull_error(ok), None);
+        let failed = b"{\"status\":\"Pulling fs layer\"}\n{\"errorDetail\":{\"message\":\"no space left\"},\"error\":\"no space left\"}\n";
+        assert_eq!(pull_error(failed).as_deref(), Some("no space left"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn talks_http_over_the_socket() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("engine.sock");
+        let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut head = Vec::new();
+            while !head.ends_with(b"\r\n\r\n") {
+                let mut byte = [0u8; 1];
+                stream.read_exact(&mut byte).await.unwrap();
+                head.push(byte[0]);
+            }
+            let body = r#"{"State":{"OOMKilled":true}}"#;
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+            String::from_utf8(head).unwrap()
+        });
+
+        let client = EngineClient::new(socket);
+        assert!(client.oom_killed("vk-1").await.unwrap());
+        let head = server.await.unwrap();
+        assert!(head.starts_with("GET /v1.41/containers/vk-1/json HTTP/1.1\r\n"));
+    }
+}
@@ -0,0 +1,409 @@
+//! Runtime of the container deployment: each execution process runs in a
+//! container of its own, started through the engine CLI and otherwise managed
+//! over the engine API. A successful setup script is cached as an image that
+//! holds both what it installed in the container and what it wrote into the
+//! repository, so later setups of the repository restore that output instead
+//! of running the script again.
+
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use db::models::{execution_process::ExecutionProcessCompletionReason, repo::Repo};
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType,
+        script::{ScriptContext, ScriptRequest},
+    },
+    env::ExecutionEnv,
+    oci::{
+        CONTAINER_LABEL, ContainerEngine, OciContainer, SETUP_CACHE_DIR, WORKSPACE_LABEL,
+        setup_cache_tag,
+    },
+};
+use services::services::{
+    config::{Config, ResourceLimits},
+    container::ContainerError,
+};
+use tokio::{process::Command, sync::RwLock};
+use uuid::Uuid;
+
+use crate::{
+    oci_engine::EngineClient,
+    runtime::{ProcessLaunch, ProcessRuntime},
+};
+
+/// A container started for a running execution process
+struct RunningContainer {
+    container: OciContainer,
+    /// Where to cache the setup when the container's setup script succeeds
+    setup_cache: Option<SetupCache>,
+}
+
+struct SetupCache {
+    image: String,
+    repo_name: String,
+    repo_dir: PathBuf,
+}
+
+pub struct OciRuntime {
+    engine: ContainerEngine,
+    client: EngineClient,
+    config: Arc<RwLock<Config>>,
+    containers: RwLock<HashMap<Uuid, RunningContainer>>,
+}
+
+impl OciRuntime {
+    pub fn new(engine: ContainerEngine, client: EngineClient, config: Arc<RwLock<Config>>) -> Self {
+        Self {
+            engine,
+            client,
+            config,
+            containers: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Pull the base images of `repos` the engine doesn't have yet
+    pub async fn pull_images(&self, repos: &[Repo]) -> Result<(), ContainerError> {
+        let runtime = self.config.read().await.container_runtime.clone();
+        let mut images: Vec<&str> = repos
+            .iter()
+            .filter_map(|repo| runtime.image_for(&repo.name, &repo.path))
+            .collect();
+        images.sort_unstable();
+        images.dedup();
+        for image in images {
+            if !self.client.image_exists(image).await? {
+                tracing::info!("Pulling container image {}", image);
+                self.client.pull(image).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the containers carrying `label`
+    pub async fn remove_labelled(&self, label: &str) -> io::Result<()> {
+        for name in self.client.containers_with_label(label).await? {
+            tracing::info!("Removing execution container {}", name);
+            self.client.remove(&name).await?;
+        }
+        Ok(())
+    }
+
+    /// Remove execution containers left behind by a previous run of the server
+    pub async fn remove_orphans(&self) {
+        if let Err(e) = self.remove_labelled(CONTAINER_LABEL).await {
+            tracing::warn!("Failed to remove orphaned execution containers: {}", e);
+        }
+    }
+
+    /// Remove the containers of a workspace's processes
+    pub async fn remove_workspace_containers(&self, workspace_id: Uuid) -> io::Result<()> {
+        self.remove_labelled(&format!("{WORKSPACE_LABEL}={workspace_id}"))
+            .await
+    }
+
+    /// Add what the setup script wrote into the repository to the exited
+    /// container, then commit it as the setup image
+    async fn cache_setup(&self, container: &str, cache: &SetupCache) -> io::Result<()> {
+        let outputs = setup_outputs(&cache.repo_dir).await?;
+        let archive = utils::path::get_vibe_kanban_temp_dir()
+            .join("setup-cache")
+            .join(format!("{container}.tar"));
+        {
+            let archive = archive.clone();
+            let repo_dir = cache.repo_dir.clone();
+            let repo_name = cache.repo_name.clone();
+            tokio::task::spawn_blocking(move || {
+                write_archive(&archive, &repo_dir, &repo_name, &outputs)
+            })
+            .await
+            .map_err(io::Error::other)??;
+        }
+        let result = async {
+            self.client.put_archive(container, &archive).await?;
+            self.client.commit(container, &cache.image).await
+        }
+        .await;
+        let _ = tokio::fs::remove_file(&archive).await;
+        result
+    }
+}
+
+#[async_trait]
+impl ProcessRuntime for OciRuntime {
+    /// Setup scripts start from the repository's base image and are cached on
+    /// success when setup caching is on; once the cache exists, setup restores
+    /// it and every other process starts from it.
+    async fn prepare(
+        &self,
+        launch: &ProcessLaunch<'_>,
+        env: &mut ExecutionEnv,
+        limits: &mut Option<ResourceLimits>,
+    ) -> Result<Option<ExecutorAction>, ContainerError> {
+        let runtime = self.config.read().await.container_runtime.clone();
+
+        let script = match launch.action.typ() {
+            ExecutorActionType::ScriptRequest(script) => Some(script),
+            _ => None,
+        };
+        let repo = script
+            .and_then(|script| script.working_dir.as_deref())
+            .and_then(|dir| launch.repos.iter().find(|repo| repo.name == dir))
+            .or(launch.repos.first())
+            .ok_or_else(|| ContainerError::Other(anyhow!("Workspace has no repositories")))?;
+        let base_image = runtime
+            .image_for(&repo.name, &repo.path)
+            .ok_or_else(|| {
+                ContainerError::Other(anyhow!(
+                    "No container image configured for repository {}",
+                    repo.name
+                ))
+            })?
+            .to_string();
+
+        let cache_tag = repo
+            .setup_script
+            .as_deref()
+            .filter(|setup| runtime.cache_setup && !setup.trim().is_empty())
+            .map(|setup| setup_cache_tag(&base_image, setup));
+        let cached = match &cache_tag {
+            Some(tag) => self.client.image_exists(tag).await.unwrap_or_else(|e| {
+                tracing::warn!("Failed to look up setup image {}: {}", tag, e);
+                false
+            }),
+            None => false,
+        };
+        let setup = script.filter(|script| script.context == ScriptContext::SetupScript);
+        let (image, setup_cache, replacement) = match (cache_tag, setup) {
+            (Some(tag), setup) if cached => {
+                let replacement = setup.map(|setup| restore_setup(setup, &repo.name));
+                (tag, None, replacement)
+            }
+            (Some(tag), Some(_)) => (
+                base_image,
+                Some(SetupCache {
+                    image: tag,
+                    repo_name: repo.name.clone(),
+                    repo_dir: env.repo_context.workspace_root.join(&repo.name),
+                }),
+                None,
+            ),
+            _ => (base_image, None, None),
+        };
+
+        let mut container = OciContainer {
+            engine: self.engine,
+            socket: self.client.socket().to_path_buf(),
+            image,
+            name: format!("vk-{}", launch.execution_process_id),
+            workspace_id: launch.workspace.id.to_string(),
+            network: runtime.network,
+            env_file: utils::path::get_vibe_kanban_temp_dir()
+                .join("container-env")
+                .join(launch.execution_process_id.to_string()),
+            run_args: runtime.run_args,
+        };
+        // The engine enforces the limits on the container itself
+        if let Some(limits) = limits.take() {
+            container.run_args.extend(limit_args(&limits));
+        }
+        env.container = Some(container.clone());
+        self.containers.write().await.insert(
+            launch.execution_process_id,
+            RunningContainer {
+                container,
+                setup_cache,
+            },
+        );
+        Ok(replacement)
+    }
+
+    /// Remove the exited process's container, caching it first when it ran a
+    /// successful setup script, and report whether it ran out of memory
+    async fn release(
+        &self,
+        execution_process_id: Uuid,
+        succeeded: bool,
+    ) -> Option<ExecutionProcessCompletionReason> {
+        let RunningContainer {
+            container,
+            setup_cache,
+        } = self
+            .containers
+            .write()
+            .await
+            .remove(&execution_process_id)?;
+        let reason = match self.client.oom_killed(&container.name).await {
+            Ok(oom_killed) => oom_killed.then_some(ExecutionProcessCompletionReason::OutOfMemory),
+            Err(e) => {
+                tracing::debug!("Failed to inspect container {}: {}", container.name, e);
+                None
+            }
+        };
+        if succeeded && let Some(cache) = setup_cache {
+            match self.cache_setup(&container.name, &cache).await {
+                Ok(()) => tracing::info!("Cached setup of {} as {}", cache.repo_name, cache.image),
+                Err(e) => tracing::warn!("Failed to cache setup of {}: {}", cache.repo_name, e),
+            }
+        }
+        if let Err(e) = self.client.remove(&container.name).await {
+            tracing::warn!("Failed to remove container {}: {}", container.name, e);
+        }
+        let _ = tokio::fs::remove_file(&container.env_file).await;
+        reason
+    }
+}
+
+/// `run` arguments enforcing `limits` on the container
+pub fn limit_args(limits: &ResourceLimits) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(cpus) = limits.cpus.filter(|cpus| *cpus > 0.0) {
+        args.push(format!("--cpus={cpus}"));
+    }
+    if let Some(mb) = limits.memory_mb.filter(|mb| *mb > 0) {
+        // Equal memory and swap limits leave the container no swap
+        args.push(format!("--memory={mb}m"));
+        args.push(format!("--memory-swap={mb}m"));
+    }
+    if let Some(max) = limits.max_processes.filter(|max| *max > 0) {
+        args.push(format!("--pids-limit={max}"));
+    }
+    args
+}
+
+/// Setup script copying the cached output of `setup` into the repository,
+/// keeping files that are already there
+fn restore_setup(setup: &ScriptRequest, repo_name: &str) -> ExecutorAction {
+    let source = format!("{SETUP_CACHE_DIR}/{repo_name}/.");
+    let source = shlex::try_quote(&source)
+        .map(|quoted| quoted.into_owned())
+        .unwrap_or(source);
+    ExecutorAction::new(
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            script: format!(
+                "echo 'Restoring the cached setup' && cp -a -n {source} . || [ ! -e {source} ]"
+            ),
+            language: setup.language.clone(),
+            context: setup.context.clone(),
+            working_dir: setup.working_dir.clone(),
+        }),
+        None,
+    )
+}
+
+/// Untracked and ignored paths in the repository, directories collapsed and
+/// ending in `/`
+async fn setup_outputs(repo_dir: &Path) -> io::Result<Vec<String>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .args(["ls-files", "-z", "--others", "--directory"])
+        .kill_on_drop(true)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git ls-files failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output
+        .stdout
+        .split(|byte| *byte == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| String::from_utf8_lossy(path).into_owned())
+        .collect())
+}
+
+/// Tar of `outputs` under the setup cache directory of `repo_name`, to extract
+/// at the container's root
+fn write_archive(
+    archive: &Path,
+    repo_dir: &Path,
+    repo_name: &str,
+    outputs: &[String],
+) -> io::Result<()> {
+    if let Some(parent) = archive.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut builder = tar::Builder::new(std::fs::File::create(archive)?);
+    builder.follow_symlinks(false);
+    let root = Path::new(SETUP_CACHE_DIR.trim_start_matches('/')).join(repo_name);
+    for output in outputs {
+        let relative = output.trim_end_matches('/');
+        let source = repo_dir.join(relative);
+        let name = root.join(relative);
+        if std::fs::symlink_metadata(&source)?.is_dir() {
+            builder.append_dir_all(&name, &source)?;
+        } else {
+            builder.append_path_with_name(&source, &name)?;
+        }
+    }
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_limits_become_run_flags() {
+        let limits = ResourceLimits {
+            cpus: Some(2.5),
+            memory_mb: Some(4096),
+            max_processes: Some(0),
+        };
+        assert_eq!(
+            limit_args(&limits),
+            vec!["--cpus=2.5", "--memory=4096m", "--memory-swap=4096m"]
+        );
+        assert!(limit_args(&ResourceLimits::default()).is_empty());
+    }
+
+    #[test]
+    fn archives_setup_output_under_the_cache_directory() {
+        let repo = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(repo.path().join("node_modules/left-pad")).unwrap();
+        std::fs::write(repo.path().join("node_modules/left-pad/index.js"), "pad").unwrap();
+        std::fs::write(repo.path().join(".env"), "KEY=1").unwrap();
+        let archive = repo.path().join("out/setup.tar");
+
+        write_archive(
+            &archive,
+            repo.path(),
+            "app",
+            &["node_modules/".to_string(), ".env".to_string()],
+        )
+        .unwrap();
+
+        let mut entries: Vec<String> = tar::Archive::new(std::fs::File::open(&archive).unwrap())
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let path = entry
+                    .unwrap()
+                    .path()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned();
+                path.trim_end_matches('/').to_string()
+            })
+            .collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                "var/cache/vibe-kanban/setup/app/.env",
+                "var/cache/vibe-kanban/setup/app/node_modules",
+                "var/cache/vibe-kanban/setup/app/node_modules/left-pad",
+                "var/cache/vibe-kanban/setup/app/node_modules/left-pad/index.js",
+            ]
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Container service of the remote deployment. Processes run on an SSH host
+//! in worktrees created there; this machine keeps a worktree of each
+//! repository at the same path, following the host's commits, for the diff
+//! view, merges and pull requests. Uncommitted changes exist only on the
+//! host, so the diff view shows a process's work once it is committed when
+//! the process exits.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use db::{
+    DBService,
+    models::{
+        execution_process::{ExecutionContext, ExecutionProcess, ExecutionProcessStatus},
+        workspace::Workspace,
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use executors::{actions::ExecutorAction, remote::RemoteHost};
+use git::GitService;
+use services::services::{
+    approvals::Approvals,
+    config::Config,
+    container::{ContainerError, ContainerRef, ContainerService},
+    execution_scheduler::ExecutionScheduler,
+    image::ImageService,
+    notification::NotificationService,
+    queued_message::QueuedMessageService,
+};
+use tokio::{sync::RwLock, task::JoinHandle};
+use utils::{log_msg::LogMsg, msg_store::MsgStore, path::expand_tilde};
+use uuid::Uuid;
+
+use crate::{container::LocalContainerService, remote_runtime::RemoteRuntime};
+
+#[derive(Clone)]
+pub struct RemoteContainerService {
+    /// This machine's worktrees, process tracking and logs, spawning through
+    /// `runtime`
+    local: LocalContainerService,
+    runtime: Arc<RemoteRuntime>,
+}
+
+impl RemoteContainerService {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new(
+        destination: String,
+        db: DBService,
+        msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+        config: Arc<RwLock<Config>>,
+        git: GitService,
+        image_service: ImageService,
+        approvals: Approvals,
+        queued_message_service: QueuedMessageService,
+    ) -> Self {
+        let remote = config.read().await.remote_execution.clone();
+        let runtime = Arc::new(RemoteRuntime::new(RemoteHost {
+            destination,
+            port: remote.port,
+            identity_file: remote.identity_file.as_deref().map(expand_tilde),
+            ssh_options: remote.ssh_options,
+            name: String::new(),
+        }));
+        let local = LocalContainerService::with_runtime(
+            db,
+            msg_stores,
+            config,
+            git,
+            image_service,
+            approvals,
+            queued_message_service,
+            runtime.clone(),
+        )
+        .await;
+
+        Self { local, runtime }
+    }
+
+    /// Create the worktrees of `workspace` on the host from this machine's
+    async fn create_on_host(
+        &self,
+        workspace: &Workspace,
+        container_ref: &str,
+    ) -> Result<(), ContainerError> {
+        let repos =
+            WorkspaceRepo::find_repos_for_workspace(&self.local.db().pool, workspace.id).await?;
+        self.runtime
+            .create_worktrees(Path::new(container_ref), &repos, &workspace.branch)
+            .await
+            .map_err(|e| {
+                ContainerError::Other(anyhow!(
+                    "Failed to create worktrees on {}: {}",
+                    self.runtime.destination(),
+                    e
+                ))
+            })
+    }
+}
+
+#[async_trait]
+impl ContainerService for RemoteContainerService {
+    fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>> {
+        self.local.msg_stores()
+    }
+
+    fn db(&self) -> &DBService {
+        self.local.db()
+    }
+
+    fn git(&self) -> &GitService {
+        self.local.git()
+    }
+
+    fn notification_service(&self) -> &NotificationService {
+        self.local.notification_service()
+    }
+
+    fn scheduler(&self) -> &ExecutionScheduler {
+        self.local.scheduler()
+    }
+
+    fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
+        self.local.workspace_to_current_dir(workspace)
+    }
+
+    async fn store_db_stream_handle(&self, id: Uuid, handle: JoinHandle<()>) {
+        self.local.store_db_stream_handle(id, handle).await
+    }
+
+    async fn take_db_stream_handle(&self, id: &Uuid) -> Option<JoinHandle<()>> {
+        self.local.take_db_stream_handle(id).await
+    }
+
+    /// The workspace's worktrees here and on the host
+    async fn create(&self, workspace: &Workspace) -> Result<ContainerRef, ContainerError> {
+        let container_ref = self.local.create(workspace).await?;
+        self.create_on_host(workspace, &container_ref).await?;
+        Ok(container_ref)
+    }
+
+    async fn kill_all_running_processes(&self) -> Result<(), ContainerError> {
+        self.local.kill_all_running_processes().await
+    }
+
+    async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError> {
+        let repos =
+            WorkspaceRepo::find_repos_for_workspace(&self.local.db().pool, workspace.id).await?;
+        self.local.delete(workspace).await?;
+        if let Some(container_ref) = &workspace.container_ref
+            && let Err(e) = self
+                .runtime
+                .remove_worktrees(Path::new(container_ref), &repos, &workspace.branch)
+                .await
+        {
+            tracing::warn!(
+                "Failed to remove worktrees of workspace {} from {}: {}",
+                workspace.id,
+                self.runtime.destination(),
+                e
+            );
+        }
+        Ok(())
+    }
+
+    async fn ensure_container_exists(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<ContainerRef, ContainerError> {
+        let container_ref = self.local.ensure_container_exists(workspace).await?;
+        self.create_on_host(workspace, &container_ref).await?;
+        Ok(container_ref)
+    }
+
+    /// Changes are only left uncommitted on the host
+    async fn is_container_clean(&self, workspace: &Workspace) -> Result<bool, ContainerError> {
+        let Some(container_ref) = &workspace.container_ref else {
+            return Ok(true);
+        };
+        let repos =
+            WorkspaceRepo::find_repos_for_workspace(&self.local.db().pool, workspace.id).await?;
+        self.runtime
+            .is_clean(Path::new(container_ref), &repos)
+            .await
+            .map_err(|e| ContainerError::Other(anyhow!(e)))
+    }
+
+    async fn start_execution_inner(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) -> Result<(), ContainerError> {
+        self.local
+            .start_execution_inner(workspace, execution_process, executor_action)
+            .await
+    }
+
+    async fn stop_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+        status: ExecutionProcessStatus,
+    ) -> Result<(), ContainerError> {
+        self.local.stop_execution(execution_process, status).await
+    }
+
+    async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
+        self.local.try_commit_changes(ctx).await
+    }
+
+    async fn copy_project_files(
+        &self,
+        source_dir: &Path,
+        target_dir: &Path,
+        copy_files: &str,
+    ) -> Result<(), ContainerError> {
+        self.local
+            .copy_project_files(source_dir, target_dir, copy_files)
+            .await
+    }
+
+    async fn stream_diff(
+        &self,
+        workspace: &Workspace,
+        stats_only: bool,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, ContainerError>
+    {
+        self.local.stream_diff(workspace, stats_only).await
+    }
+
+    async fn git_branch_prefix(&self) -> String {
+        self.local.git_branch_prefix().await
+    }
+}
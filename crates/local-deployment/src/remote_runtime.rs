@@ -0,0 +1,746 @@
+//! Runtime of the remote deployment. Processes run on an SSH host in worktrees
+//! created there, at the same paths as on this machine, from a bare copy of
+//! each repository kept on the host. Commits travel between the host's
+//! worktrees and this machine's with git over ssh: an exited process's
+//! commits are fetched here, and commits made here, such as a rebase, are
+//! pushed to the host before the next process starts.
+
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    process::{Output, Stdio},
+};
+
+use async_trait::async_trait;
+use db::models::{
+    execution_process::ExecutionProcessCompletionReason, repo::Repo, workspace::Workspace,
+};
+use executors::{actions::ExecutorAction, env::ExecutionEnv, remote::RemoteHost};
+use services::services::{config::ResourceLimits, container::ContainerError};
+use tokio::{
+    io::AsyncWriteExt,
+    process::Command,
+    sync::{Mutex, RwLock},
+};
+use uuid::Uuid;
+
+use crate::runtime::{ProcessLaunch, ProcessRuntime};
+
+/// Directory on the host, relative to the login's home, holding a bare copy
+/// of each repository
+const REPOS_DIR: &str = ".vibe-kanban/repos";
+
+/// A process running on the host and the worktrees it runs in
+struct RemoteProcess {
+    host: RemoteHost,
+    worktrees: Vec<PathBuf>,
+    branch: String,
+}
+
+pub struct RemoteRuntime {
+    host: RemoteHost,
+    repos_dir: String,
+    /// Directory the host's worktree paths are nested in. Deployments leave
+    /// it unset, so they match this machine's; tests on a single machine set
+    /// it to keep the two apart.
+    host_root: Option<PathBuf>,
+    running: RwLock<HashMap<Uuid, RemoteProcess>>,
+    /// Syncs check out commits in worktrees that processes share
+    sync_lock: Mutex<()>,
+}
+
+impl RemoteRuntime {
+    pub fn new(host: RemoteHost) -> Self {
+        Self {
+            host,
+            repos_dir: REPOS_DIR.to_string(),
+            host_root: None,
+            running: RwLock::new(HashMap::new()),
+            sync_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn destination(&self) -> &str {
+        &self.host.destination
+    }
+
+    /// Create the workspace's worktrees on the host where they are missing,
+    /// from the branch checked out in this machine's, then copy the files git
+    /// doesn't track there: copied project files, task images and instruction
+    /// files. Files on the host newer than this machine's are kept.
+    pub async fn create_worktrees(
+        &self,
+        workspace_root: &Path,
+        repos: &[Repo],
+        branch: &str,
+    ) -> io::Result<()> {
+        for repo in repos {
+            self.create_worktree(
+                &workspace_root.join(&repo.name),
+                &self.repo_dir(repo.id),
+                branch,
+            )
+            .await?;
+        }
+        let names: Vec<_> = repos.iter().map(|repo| repo.name.clone()).collect();
+        self.send_untracked(workspace_root, &names).await
+    }
+
+    /// Remove the workspace's worktrees, and the branch behind them, from the
+    /// host
+    pub async fn remove_worktrees(
+        &self,
+        workspace_root: &Path,
+        repos: &[Repo],
+        branch: &str,
+    ) -> io::Result<()> {
+        let mut script = repos
+            .iter()
+            .map(|repo| {
+                self.remove_script(
+                    &workspace_root.join(&repo.name),
+                    &self.repo_dir(repo.id),
+                    branch,
+                )
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        script.push(format!(
+            "rm -rf {}",
+            quote_path(&self.on_host(workspace_root))?
+        ));
+        self.ssh(&script.join("\n")).await.map(drop)
+    }
+
+    /// Whether the workspace's worktrees on the host have nothing uncommitted
+    pub async fn is_clean(&self, workspace_root: &Path, repos: &[Repo]) -> io::Result<bool> {
+        let worktrees: Vec<_> = repos
+            .iter()
+            .map(|repo| workspace_root.join(&repo.name))
+            .collect();
+        self.worktrees_clean(&worktrees).await
+    }
+
+    async fn worktrees_clean(&self, worktrees: &[PathBuf]) -> io::Result<bool> {
+        let mut script = Vec::new();
+        for worktree in worktrees {
+            let worktree = quote_path(&self.on_host(worktree))?;
+            script.push(format!(
+                "if [ -e {worktree}/.git ]; then git -C {worktree} status --porcelain || exit 1; fi"
+            ));
+        }
+        Ok(self.ssh(&script.join("\n")).await?.trim().is_empty())
+    }
+
+    fn remove_script(&self, worktree: &Path, repo_dir: &str, branch: &str) -> io::Result<String> {
+        let repo = quote(repo_dir)?;
+        let git = format!("git --git-dir={repo}");
+        Ok(format!(
+            "if [ -d {repo} ]; then {git} worktree remove --force {worktree} 2>/dev/null; {git} worktree prune; {git} branch -q -D {branch} 2>/dev/null; {git} update-ref -d {synced}; {git} update-ref -d {pushed}; fi",
+            worktree = quote_path(&self.on_host(worktree))?,
+            branch = quote(branch)?,
+            synced = quote(&synced_ref(branch))?,
+            pushed = quote(&pushed_ref(branch))?,
+        ))
+    }
+
+    async fn create_worktree(
+        &self,
+        worktree: &Path,
+        repo_dir: &str,
+        branch: &str,
+    ) -> io::Result<()> {
+        let host_worktree = self.on_host(worktree);
+        let repo = quote(repo_dir)?;
+        let quoted_worktree = quote_path(&host_worktree)?;
+        let present = self
+            .ssh(&format!(
+                "[ -d {repo} ] || git init -q --bare {repo}\nif [ -e {quoted_worktree}/.git ]; then echo present; fi"
+            ))
+            .await?;
+        if present.trim() == "present" {
+            return Ok(());
+        }
+
+        self.git(
+            worktree,
+            &[
+                "push",
+                "-q",
+                "--force",
+                &self.url(repo_dir),
+                &format!("HEAD:refs/heads/{branch}"),
+            ],
+        )
+        .await?;
+        let parent = host_worktree.parent().unwrap_or(Path::new("/"));
+        self.ssh(&format!(
+            "git --git-dir={repo} worktree prune && mkdir -p {parent} && git --git-dir={repo} worktree add -q {quoted_worktree} {branch} && git -C {quoted_worktree} update-ref {synced} HEAD",
+            parent = quote_path(parent)?,
+            branch = quote(branch)?,
+            synced = quote(&synced_ref(branch))?,
+        ))
+        .await
+        .map(drop)
+    }
+
+    /// Commit what processes left uncommitted in the worktrees on the host,
+    /// then bring the commits here. Returns whether anything was committed.
+    async fn commit_worktrees(&self, worktrees: &[PathBuf], branch: &str, message: &str) -> bool {
+        let mut committed = false;
+        for worktree in worktrees {
+            match self.commit_worktree(worktree, message).await {
+                Ok(true) => {
+                    committed = true;
+                    tracing::info!("Committed changes in {} on the host", worktree.display());
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to commit in {} on {}: {}",
+                        worktree.display(),
+                        self.host.destination,
+                        e
+                    );
+                }
+            }
+        }
+        self.sync(worktrees, branch).await;
+        committed
+    }
+
+    /// Falls back to the identity `GitService` commits with when the host's
+    /// git config has none
+    async fn commit_worktree(&self, worktree: &Path, message: &str) -> io::Result<bool> {
+        let output = self
+            .ssh(&format!(
+                "cd {worktree} && if [ -n \"$(git status --porcelain)\" ]; then {{ git config user.name >/dev/null || git config user.name 'Vibe Kanban'; }} && {{ git config user.email >/dev/null || git config user.email noreply@vibekanban.com; }} && git add -A && git commit -q -m {message} && echo committed; fi",
+                worktree = quote_path(&self.on_host(worktree))?,
+                message = quote(message)?,
+            ))
+            .await?;
+        Ok(output.trim() == "committed")
+    }
+
+    /// Carry commits between the host's worktrees and this machine's, in
+    /// whichever direction has new ones. Failures are logged and leave both
+    /// sides as they were.
+    async fn sync(&self, worktrees: &[PathBuf], branch: &str) {
+        let _guard = self.sync_lock.lock().await;
+        for worktree in worktrees {
+            if let Err(e) = self.sync_worktree(worktree, branch).await {
+                tracing::warn!(
+                    "Failed to sync {} with {}: {}",
+                    worktree.display(),
+                    self.host.destination,
+                    e
+                );
+            }
+        }
+    }
+
+    /// The host records the commit both sides last agreed on. The side still
+    /// at that commit takes the other's; when both moved on, neither is
+    /// touched.
+    async fn sync_worktree(&self, worktree: &Path, branch: &str) -> io::Result<()> {
+        let host_worktree = self.on_host(worktree);
+        let quoted_worktree = quote_path(&host_worktree)?;
+        let synced = quote(&synced_ref(branch))?;
+        let heads = self
+            .ssh(&format!(
+                "cd {quoted_worktree} && git rev-parse HEAD && {{ git rev-parse -q --verify {synced} || true; }}"
+            ))
+            .await?;
+        let mut heads = heads.lines().map(str::to_string);
+        let host_head = heads.next().unwrap_or_default();
+        let local_head = self.git(worktree, &["rev-parse", "HEAD"]).await?;
+        let local_head = local_head.trim().to_string();
+        // Without a record the host's commits win
+        let agreed = heads.next().unwrap_or_else(|| local_head.clone());
+
+        let record = |head: &str| -> io::Result<String> {
+            Ok(format!(
+                "git -C {quoted_worktree} update-ref {synced} {}",
+                quote(head)?
+            ))
+        };
+        if host_head == local_head {
+            if agreed != host_head {
+                self.ssh(&record(&host_head)?).await?;
+            }
+        } else if local_head == agreed {
+            self.git(
+                worktree,
+                &[
+                    "fetch",
+                    "-q",
+                    &self.url(&host_worktree.to_string_lossy()),
+                    "HEAD",
+                ],
+            )
+            .await?;
+            self.git(worktree, &["reset", "-q", "--keep", &host_head])
+                .await?;
+            self.ssh(&record(&host_head)?).await?;
+        } else if host_head == agreed {
+            self.git(
+                worktree,
+                &[
+                    "push",
+                    "-q",
+                    "--force",
+                    &self.url(&host_worktree.to_string_lossy()),
+                    &format!("HEAD:{}", pushed_ref(branch)),
+                ],
+            )
+            .await?;
+            self.ssh(&format!(
+                "cd {quoted_worktree} && git reset -q --keep {head} && {record}",
+                head = quote(&local_head)?,
+                record = record(&local_head)?,
+            ))
+            .await?;
+        } else {
+            return Err(io::Error::other(
+                "both sides have new commits; rebase or reset one of them",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Copy the untracked and ignored files of this machine's worktrees, and
+    /// the files next to them in the workspace, to the host, keeping files
+    /// there that are newer
+    async fn send_untracked(&self, workspace_root: &Path, worktrees: &[String]) -> io::Result<()> {
+        let mut paths = Vec::new();
+        let mut entries = tokio::fs::read_dir(workspace_root).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !worktrees.contains(&name) {
+                paths.push(name);
+            }
+        }
+        for name in worktrees {
+            let untracked = self
+                .git(
+                    &workspace_root.join(name),
+                    &["ls-files", "-z", "--others", "--directory"],
+                )
+                .await?;
+            paths.extend(
+                untracked
+                    .split('\0')
+                    .filter(|path| !path.is_empty())
+                    .map(|path| format!("{name}/{path}")),
+            );
+        }
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let mut archive = Command::new("tar")
+            .arg("-C")
+            .arg(workspace_root)
+            .args(["--null", "-T", "-", "-cf", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stream: Stdio = archive
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::other("tar has no stdout"))?
+            .try_into()?;
+        let root = quote_path(&self.on_host(workspace_root))?;
+        let extract = Command::new("ssh")
+            .args(self.host.ssh_args())
+            .arg(sh(&format!(
+                "mkdir -p {root} && tar -C {root} --keep-newer-files --warning=no-ignore-newer -xf -"
+            ))?)
+            .stdin(stream)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let mut list = archive
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::other("tar has no stdin"))?;
+        list.write_all(paths.join("\0").as_bytes()).await?;
+        drop(list);
+
+        let (archived, extracted) =
+            tokio::join!(archive.wait_with_output(), extract.wait_with_output());
+        check(archived?, "tar")?;
+        check(extracted?, "ssh tar").map(drop)
+    }
+
+    /// Kill the process group the process led on the host, in case losing
+    /// the connection left it running
+    async fn kill(&self, host: &RemoteHost) {
+        let pid_file = host.pid_file();
+        let script = format!(
+            "if [ -f {pid_file} ]; then pgid=$(cat {pid_file}); kill -TERM -- -$pgid 2>/dev/null; sleep 1; kill -KILL -- -$pgid 2>/dev/null; rm -f {pid_file}; fi"
+        );
+        if let Err(e) = self.ssh(&script).await {
+            tracing::warn!("Failed to clean up on {}: {}", host.destination, e);
+        }
+    }
+
+    /// Run a `sh` script on the host and return what it printed
+    async fn ssh(&self, script: &str) -> io::Result<String> {
+        let output = Command::new("ssh")
+            .args(self.host.ssh_args())
+            .arg(sh(script)?)
+            .kill_on_drop(true)
+            .output()
+            .await?;
+        check(output, "ssh")
+    }
+
+    /// Run git in `dir` on this machine, reaching the host with the same
+    /// options as the processes
+    async fn git(&self, dir: &Path, args: &[&str]) -> io::Result<String> {
+        let ssh_command = std::iter::once("ssh".to_string())
+            .chain(self.host.connection_args())
+            .map(|arg| quote(&arg))
+            .collect::<io::Result<Vec<_>>>()?
+            .join(" ");
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .env("GIT_SSH_COMMAND", ssh_command)
+            .kill_on_drop(true)
+            .output()
+            .await?;
+        check(output, &format!("git {}", args.first().unwrap_or(&"")))
+    }
+
+    fn repo_dir(&self, repo_id: Uuid) -> String {
+        format!("{}/{repo_id}.git", self.repos_dir)
+    }
+
+    fn on_host(&self, path: &Path) -> PathBuf {
+        match &self.host_root {
+            Some(root) => root.join(path.strip_prefix("/").unwrap_or(path)),
+            None => path.to_path_buf(),
+        }
+    }
+
+    /// Git URL of `path` on the host; relative paths start at the home
+    /// directory
+    fn url(&self, path: &str) -> String {
+        format!("{}:{}", self.host.destination, path)
+    }
+}
+
+#[async_trait]
+impl ProcessRuntime for RemoteRuntime {
+    async fn prepare(
+        &self,
+        launch: &ProcessLaunch<'_>,
+        env: &mut ExecutionEnv,
+        _limits: &mut Option<ResourceLimits>,
+    ) -> Result<Option<ExecutorAction>, ContainerError> {
+        let process = RemoteProcess {
+            host: RemoteHost {
+                name: format!("vk-{}", launch.execution_process_id),
+                ..self.host.clone()
+            },
+            worktrees: launch
+                .repos
+                .iter()
+                .map(|repo| env.repo_context.workspace_root.join(&repo.name))
+                .collect(),
+            branch: launch.workspace.branch.clone(),
+        };
+        // Commits made here since the last process, such as a rebase, reach
+        // the host before this one starts
+        self.sync(&process.worktrees, &process.branch).await;
+        env.remote = Some(process.host.clone());
+        self.running
+            .write()
+            .await
+            .insert(launch.execution_process_id, process);
+        Ok(None)
+    }
+
+    async fn release(
+        &self,
+        execution_process_id: Uuid,
+        _succeeded: bool,
+    ) -> Option<ExecutionProcessCompletionReason> {
+        let process = self.running.write().await.remove(&execution_process_id)?;
+        self.kill(&process.host).await;
+        self.sync(&process.worktrees, &process.branch).await;
+        None
+    }
+
+    async fn commit(
+        &self,
+        workspace: &Workspace,
+        workspace_root: &Path,
+        repos: &[Repo],
+        message: &str,
+    ) -> Option<Result<bool, ContainerError>> {
+        let worktrees: Vec<_> = repos
+            .iter()
+            .map(|repo| workspace_root.join(&repo.name))
+            .collect();
+        Some(Ok(self
+            .commit_worktrees(&worktrees, &workspace.branch, message)
+            .await))
+    }
+}
+
+/// Ref on the host holding the commit both sides last agreed on
+fn synced_ref(branch: &str) -> String {
+    format!("refs/vibe-kanban/synced/{branch}")
+}
+
+/// Ref on the host receiving commits made on this machine
+fn pushed_ref(branch: &str) -> String {
+    format!("refs/vibe-kanban/pushed/{branch}")
+}
+
+/// The command line running `script` with `sh`, whatever the login shell
+fn sh(script: &str) -> io::Result<String> {
+    Ok(format!("sh -c {}", quote(script)?))
+}
+
+fn quote(value: &str) -> io::Result<String> {
+    shlex::try_quote(value)
+        .map(|quoted| quoted.into_owned())
+        .map_err(io::Error::other)
+}
+
+fn quote_path(path: &Path) -> io::Result<String> {
+    quote(&path.to_string_lossy())
+}
+
+fn check(output: Output, what: &str) -> io::Result<String> {
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(io::Error::other(format!(
+            "{what} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{TcpListener, TcpStream},
+        process::Command as StdCommand,
+        time::Duration,
+    };
+
+    use super::*;
+
+    /// OpenSSH's server on a free localhost port, letting the current user in
+    /// with a throwaway key
+    struct Sshd {
+        child: std::process::Child,
+        port: u16,
+        key: PathBuf,
+    }
+
+    impl Drop for Sshd {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    /// `None` when `sshd` isn't installed
+    async fn start_sshd(dir: &Path) -> Option<Sshd> {
+        let sshd = ["/usr/sbin/sshd", "/usr/bin/sshd", "/usr/local/sbin/sshd"]
+            .into_iter()
+            .map(PathBuf::from)
+            .find(|path| path.exists())?;
+        for key in ["host_key", "client_key"] {
+            let status = StdCommand::new("ssh-keygen")
+                .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+                .arg(dir.join(key))
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+        std::fs::copy(dir.join("client_key.pub"), dir.join("authorized_keys")).unwrap();
+
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = format!(
+            "ListenAddress 127.0.0.1:{port}\nHostKey {dir}/host_key\nAuthorizedKeysFile {dir}/authorized_keys\nPidFile {dir}/sshd.pid\nStrictModes no\nPasswordAuthentication no\nKbdInteractiveAuthentication no\n",
+            dir = dir.display()
+        );
+        std::fs::write(dir.join("sshd_config"), config).unwrap();
+        let child = StdCommand::new(sshd)
+            .args(["-D", "-e", "-f"])
+            .arg(dir.join("sshd_config"))
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        for _ in 0..50 {
+            if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        Some(Sshd {
+            child,
+            port,
+            key: dir.join("client_key"),
+        })
+    }
+
+    fn run_git(dir: &Path, args: &[&str]) -> String {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {args:?}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    #[tokio::test]
+    async fn worktrees_live_on_the_host_and_commits_travel_both_ways() {
+        let dir = tempfile::tempdir().unwrap();
+        let Some(sshd) = start_sshd(dir.path()).await else {
+            return;
+        };
+        let host_root = dir.path().join("host");
+        let runtime = RemoteRuntime {
+            repos_dir: host_root.join("repos").to_string_lossy().into_owned(),
+            host_root: Some(host_root.clone()),
+            ..RemoteRuntime::new(RemoteHost {
+                destination: "127.0.0.1".to_string(),
+                port: Some(sshd.port),
+                identity_file: Some(sshd.key.clone()),
+                ssh_options: [
+                    "-o",
+                    "StrictHostKeyChecking=no",
+                    "-o",
+                    "UserKnownHostsFile=/dev/null",
+                    "-o",
+                    "LogLevel=ERROR",
+                ]
+                .map(str::to_string)
+                .to_vec(),
+                name: "vk-test".to_string(),
+            })
+        };
+
+        // This machine: a repository and the workspace's worktree of it, with
+        // a copied file git ignores and an instruction file beside it
+        let repo = dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        run_git(&repo, &["init", "-q", "-b", "main"]);
+        std::fs::write(repo.join(".gitignore"), ".env\n").unwrap();
+        std::fs::write(repo.join("app.txt"), "v1\n").unwrap();
+        run_git(&repo, &["add", "-A"]);
+        run_git(&repo, &["commit", "-q", "-m", "init"]);
+        let workspace_root = dir.path().join("workspace");
+        let worktree = workspace_root.join("app");
+        run_git(
+            &repo,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "vk/test",
+                &worktree.to_string_lossy(),
+            ],
+        );
+        std::fs::write(worktree.join(".env"), "TOKEN=1\n").unwrap();
+        std::fs::write(workspace_root.join("AGENTS.md"), "Be brief.\n").unwrap();
+
+        let repo_dir = runtime.repo_dir(Uuid::new_v4());
+        runtime
+            .create_worktree(&worktree, &repo_dir, "vk/test")
+            .await
+            .unwrap();
+        runtime
+            .send_untracked(&workspace_root, &["app".to_string()])
+            .await
+            .unwrap();
+        let host_worktree = runtime.on_host(&worktree);
+        assert_eq!(
+            std::fs::read_to_string(host_worktree.join("app.txt")).unwrap(),
+            "v1\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(host_worktree.join(".env")).unwrap(),
+            "TOKEN=1\n"
+        );
+        assert!(runtime.on_host(&workspace_root).join("AGENTS.md").exists());
+        assert_eq!(
+            run_git(&host_worktree, &["branch", "--show-current"]),
+            "vk/test"
+        );
+
+        // An agent's uncommitted change on the host is committed there and
+        // comes back here
+        std::fs::write(host_worktree.join("app.txt"), "v2\n").unwrap();
+        let worktrees = vec![worktree.clone()];
+        assert!(!runtime.worktrees_clean(&worktrees).await.unwrap());
+        assert!(
+            runtime
+                .commit_worktrees(&worktrees, "vk/test", "agent work")
+                .await
+        );
+        assert!(runtime.worktrees_clean(&worktrees).await.unwrap());
+        assert_eq!(
+            std::fs::read_to_string(worktree.join("app.txt")).unwrap(),
+            "v2\n"
+        );
+        assert_eq!(
+            run_git(&worktree, &["rev-parse", "HEAD"]),
+            run_git(&host_worktree, &["rev-parse", "HEAD"])
+        );
+
+        // A commit made here reaches the host, which didn't move meanwhile
+        std::fs::write(worktree.join("app.txt"), "v3\n").unwrap();
+        run_git(&worktree, &["commit", "-q", "-am", "resolve conflicts"]);
+        runtime.sync(&worktrees, "vk/test").await;
+        assert_eq!(
+            std::fs::read_to_string(host_worktree.join("app.txt")).unwrap(),
+            "v3\n"
+        );
+
+        // When both sides moved on, neither is touched
+        run_git(&worktree, &["commit", "-q", "--allow-empty", "-m", "here"]);
+        run_git(
+            &host_worktree,
+            &["commit", "-q", "--allow-empty", "-m", "there"],
+        );
+        let here = run_git(&worktree, &["rev-parse", "HEAD"]);
+        let there = run_git(&host_worktree, &["rev-parse", "HEAD"]);
+        runtime.sync(&worktrees, "vk/test").await;
+        assert_eq!(run_git(&worktree, &["rev-parse", "HEAD"]), here);
+        assert_eq!(run_git(&host_worktree, &["rev-parse", "HEAD"]), there);
+
+        let script = runtime
+            .remove_script(&worktree, &repo_dir, "vk/test")
+            .unwrap();
+        runtime.ssh(&script).await.unwrap();
+        assert!(!host_worktree.exists());
+        assert!(worktree.join("app.txt").exists());
+    }
+}
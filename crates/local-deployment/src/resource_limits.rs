@@ -0,0 +1,454 @@
+//! CPU, memory and process-count limits for execution processes. On Linux each
+//! process gets its own cgroup v2 group, which it joins before it executes so
+//! nothing it forks can escape the limits. Where cgroup v2 can't be used, or
+//! joining the group fails, the process sets rlimits on itself instead: the
+//! memory limit caps the address space of each process, the process limit
+//! counts every process of the user, and CPU time is capped only together
+//! with a runtime limit.
+
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+use std::time::Duration;
+
+use db::models::execution_process::ExecutionProcessCompletionReason;
+#[cfg(target_os = "linux")]
+use executors::env::{ExecutionEnv, Rlimits};
+use services::services::config::ResourceLimits;
+use uuid::Uuid;
+
+/// Limits prepared for one execution process
+pub enum AppliedLimits {
+    /// The process joins this cgroup when it is spawned, and sets `fallback`
+    /// on itself if it can't
+    #[cfg(target_os = "linux")]
+    Cgroup { dir: PathBuf, fallback: Rlimits },
+    /// The process sets these rlimits on itself when it is spawned
+    #[cfg(target_os = "linux")]
+    Rlimits(Rlimits),
+}
+
+impl AppliedLimits {
+    /// Prepare the cgroup, or the rlimits when there is none, enforcing
+    /// `limits` for an execution process that may run for `max_runtime`
+    pub fn prepare(
+        execution_process_id: Uuid,
+        limits: &ResourceLimits,
+        max_runtime: Option<Duration>,
+    ) -> Option<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            linux::prepare(execution_process_id, limits, max_runtime)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (limits, max_runtime);
+            tracing::warn!(
+                "Resource limits are only supported on Linux; execution {} runs unlimited",
+                execution_process_id
+            );
+            None
+        }
+    }
+
+    /// Have the process spawned with `env` join the cgroup or set the rlimits
+    #[cfg(target_os = "linux")]
+    pub fn apply_to(&self, env: &mut ExecutionEnv) {
+        match self {
+            AppliedLimits::Cgroup { dir, fallback } => {
+                env.cgroup = Some(dir.clone());
+                env.rlimits = Some(*fallback);
+            }
+            AppliedLimits::Rlimits(rlimits) => {
+                env.cgroup = None;
+                env.rlimits = Some(*rlimits);
+            }
+        }
+    }
+
+    /// Check which limits the spawned process `pid` ended up under and log
+    /// them. A process that couldn't join its cgroup runs under the fallback
+    /// rlimits, and the cgroup is removed.
+    pub async fn confirm(self, execution_process_id: Uuid, pid: Option<u32>) -> Self {
+        #[cfg(not(target_os = "linux"))]
+        let _ = (execution_process_id, pid);
+        match self {
+            #[cfg(target_os = "linux")]
+            AppliedLimits::Cgroup { dir, fallback } => {
+                if pid.is_some_and(|pid| !linux::is_member(&dir, pid)) {
+                    tracing::warn!(
+                        "Execution {} could not join cgroup {}, limited by rlimits: {:?}",
+                        execution_process_id,
+                        dir.display(),
+                        fallback
+                    );
+                    linux::remove_cgroup(&dir).await;
+                    return AppliedLimits::Rlimits(fallback);
+                }
+                tracing::info!(
+                    "Execution {} limited by cgroup {}",
+                    execution_process_id,
+                    dir.display()
+                );
+                AppliedLimits::Cgroup { dir, fallback }
+            }
+            #[cfg(target_os = "linux")]
+            AppliedLimits::Rlimits(rlimits) => {
+                tracing::info!(
+                    "Execution {} limited by rlimits: {:?}",
+                    execution_process_id,
+                    rlimits
+                );
+                AppliedLimits::Rlimits(rlimits)
+            }
+        }
+    }
+
+    /// The limit that ended the process, checked once it has exited. Only
+    /// cgroups record this; rlimit failures look like ordinary errors.
+    pub fn exceeded(&self) -> Option<ExecutionProcessCompletionReason> {
+        match *self {
+            #[cfg(target_os = "linux")]
+            AppliedLimits::Cgroup { ref dir, .. } => linux::exceeded(dir),
+            #[cfg(target_os = "linux")]
+            AppliedLimits::Rlimits(_) => None,
+        }
+    }
+
+    /// Remove the cgroup, killing anything the process left behind in it
+    pub async fn release(self) {
+        match self {
+            #[cfg(target_os = "linux")]
+            AppliedLimits::Cgroup { dir, .. } => linux::remove_cgroup(&dir).await,
+            #[cfg(target_os = "linux")]
+            AppliedLimits::Rlimits(_) => {}
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        fs, io,
+        path::{Path, PathBuf},
+        sync::OnceLock,
+        time::Duration,
+    };
+
+    use super::*;
+
+    const CGROUP_FS: &str = "/sys/fs/cgroup";
+    const CONTROLLERS: [&str; 3] = ["cpu", "memory", "pids"];
+    const CPU_PERIOD_USECS: u64 = 100_000;
+
+    /// Parent of the per-process cgroups, set up on first use
+    static EXECUTIONS_CGROUP: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+    pub(super) fn prepare(
+        execution_process_id: Uuid,
+        limits: &ResourceLimits,
+        max_runtime: Option<Duration>,
+    ) -> Option<AppliedLimits> {
+        if limits.cpus.is_none() && limits.memory_mb.is_none() && limits.max_processes.is_none() {
+            return None;
+        }
+        let fallback = rlimits(limits, max_runtime);
+
+        if let Some(executions) = executions_cgroup() {
+            let dir = executions.join(execution_process_id.to_string());
+            match create_cgroup(&dir, limits) {
+                Ok(()) => return Some(AppliedLimits::Cgroup { dir, fallback }),
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to create cgroup for execution {}, using rlimits: {}",
+                        execution_process_id,
+                        e
+                    );
+                    let _ = fs::remove_dir(&dir);
+                }
+            }
+        }
+
+        if fallback.is_empty() {
+            tracing::warn!(
+                "Execution {} runs unlimited: its CPU limit needs cgroup v2 or a runtime limit",
+                execution_process_id
+            );
+            return None;
+        }
+        Some(AppliedLimits::Rlimits(fallback))
+    }
+
+    /// The rlimits standing in for `limits`. A CPU share has no rlimit, but
+    /// together with a runtime limit it bounds the CPU time of each process.
+    pub(super) fn rlimits(limits: &ResourceLimits, max_runtime: Option<Duration>) -> Rlimits {
+        Rlimits {
+            address_space: memory_bytes(limits),
+            processes: limits.max_processes.filter(|max| *max > 0).map(u64::from),
+            cpu_secs: limits
+                .cpus
+                .filter(|cpus| *cpus > 0.0)
+                .zip(max_runtime)
+                .map(|(cpus, runtime)| (cpus * runtime.as_secs_f64()).ceil().max(1.0) as u64),
+        }
+    }
+
+    /// Whether process `pid` runs in the cgroup `dir`. A process that already
+    /// exited counts as a member, having had no chance to run elsewhere.
+    pub(super) fn is_member(dir: &Path, pid: u32) -> bool {
+        let Ok(own) = fs::read_to_string(format!("/proc/{pid}/cgroup")) else {
+            return true;
+        };
+        own.lines()
+            .find_map(|line| line.strip_prefix("0::"))
+            .is_some_and(|relative| {
+                Path::new(CGROUP_FS).join(relative.trim_start_matches('/')) == dir
+            })
+    }
+
+    pub(super) fn exceeded(dir: &Path) -> Option<ExecutionProcessCompletionReason> {
+        if event_count(&dir.join("memory.events"), "oom_kill") > 0 {
+            Some(ExecutionProcessCompletionReason::OutOfMemory)
+        } else if event_count(&dir.join("pids.events"), "max") > 0 {
+            Some(ExecutionProcessCompletionReason::ProcessLimit)
+        } else {
+            None
+        }
+    }
+
+    pub(super) async fn remove_cgroup(dir: &Path) {
+        // cgroup.kill exists since Linux 5.14; older kernels rely on the
+        // process group having been killed already
+        let _ = fs::write(dir.join("cgroup.kill"), "1");
+        for _ in 0..20 {
+            match fs::remove_dir(dir) {
+                Ok(()) => return,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+                Err(_) => tokio::time::sleep(Duration::from_millis(100)).await,
+            }
+        }
+        tracing::warn!("Failed to remove cgroup {}", dir.display());
+    }
+
+    fn executions_cgroup() -> Option<&'static Path> {
+        EXECUTIONS_CGROUP
+            .get_or_init(|| match init_executions_cgroup() {
+                Ok(dir) => Some(dir),
+                Err(e) => {
+                    tracing::warn!("cgroup v2 limits unavailable, using rlimits: {}", e);
+                    None
+                }
+            })
+            .as_deref()
+    }
+
+    /// cgroup v2 only hands controllers to the children of a group without
+    /// processes of its own, so the server moves itself into a leaf group next
+    /// to the one holding the execution processes. This needs the server's
+    /// cgroup to be delegated to it, e.g. a systemd unit with `Delegate=yes`.
+    /// Every change is undone if a later step fails.
+    fn init_executions_cgroup() -> io::Result<PathBuf> {
+        let own = fs::read_to_string("/proc/self/cgroup")?;
+        let relative = own
+            .lines()
+            .find_map(|line| line.strip_prefix("0::"))
+            .ok_or_else(|| io::Error::other("cgroup v2 is not mounted"))?;
+        let base = Path::new(CGROUP_FS).join(relative.trim_start_matches('/'));
+
+        let available = fs::read_to_string(base.join("cgroup.controllers"))?;
+        if let Some(missing) = CONTROLLERS
+            .iter()
+            .find(|controller| !available.split_whitespace().any(|c| c == **controller))
+        {
+            return Err(io::Error::other(format!(
+                "the {missing} controller is not available in {}",
+                base.display()
+            )));
+        }
+
+        let mut setup = CgroupSetup {
+            base,
+            server_created: false,
+            server_joined: false,
+            enabled: Vec::new(),
+            executions_created: false,
+        };
+        match setup.run() {
+            Ok(executions) => Ok(executions),
+            Err(e) => {
+                setup.roll_back();
+                Err(e)
+            }
+        }
+    }
+
+    /// Steps of [`init_executions_cgroup`] taken so far, so a failure can undo them
+    struct CgroupSetup {
+        base: PathBuf,
+        server_created: bool,
+        server_joined: bool,
+        /// Controllers this setup enabled for the children of `base`
+        enabled: Vec<&'static str>,
+        executions_created: bool,
+    }
+
+    impl CgroupSetup {
+        fn server(&self) -> PathBuf {
+            self.base.join("vibe-kanban-server")
+        }
+
+        fn executions(&self) -> PathBuf {
+            self.base.join("vibe-kanban-executions")
+        }
+
+        fn run(&mut self) -> io::Result<PathBuf> {
+            let server = self.server();
+            if !server.exists() {
+                fs::create_dir(&server)?;
+                self.server_created = true;
+            }
+            fs::write(server.join("cgroup.procs"), std::process::id().to_string())?;
+            self.server_joined = true;
+
+            let subtree = fs::read_to_string(self.base.join("cgroup.subtree_control"))?;
+            for controller in CONTROLLERS {
+                if !subtree.split_whitespace().any(|c| c == controller) {
+                    fs::write(
+                        self.base.join("cgroup.subtree_control"),
+                        format!("+{controller}"),
+                    )?;
+                    self.enabled.push(controller);
+                }
+            }
+
+            let executions = self.executions();
+            if !executions.exists() {
+                fs::create_dir(&executions)?;
+                self.executions_created = true;
+            }
+            let enable = CONTROLLERS
+                .map(|controller| format!("+{controller}"))
+                .join(" ");
+            fs::write(executions.join("cgroup.subtree_control"), enable)?;
+            Ok(executions)
+        }
+
+        fn roll_back(&self) {
+            if self.executions_created {
+                let _ = fs::remove_dir(self.executions());
+            }
+            for controller in self.enabled.iter().rev() {
+                let _ = fs::write(
+                    self.base.join("cgroup.subtree_control"),
+                    format!("-{controller}"),
+                );
+            }
+            if self.server_joined
+                && let Err(e) = fs::write(
+                    self.base.join("cgroup.procs"),
+                    std::process::id().to_string(),
+                )
+            {
+                tracing::warn!(
+                    "Failed to move the server back to {}: {}",
+                    self.base.display(),
+                    e
+                );
+            }
+            if self.server_created {
+                let _ = fs::remove_dir(self.server());
+            }
+        }
+    }
+
+    fn create_cgroup(dir: &Path, limits: &ResourceLimits) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        if let Some(cpus) = limits.cpus.filter(|cpus| *cpus > 0.0) {
+            let quota = ((cpus * CPU_PERIOD_USECS as f64).round() as u64).max(1000);
+            fs::write(dir.join("cpu.max"), format!("{quota} {CPU_PERIOD_USECS}"))?;
+        }
+        if let Some(bytes) = memory_bytes(limits) {
+            fs::write(dir.join("memory.max"), bytes.to_string())?;
+            // Without swap the OOM killer steps in instead of the host swapping;
+            // the file is missing when swap accounting is off
+            let _ = fs::write(dir.join("memory.swap.max"), "0");
+        }
+        if let Some(max) = limits.max_processes.filter(|max| *max > 0) {
+            fs::write(dir.join("pids.max"), max.to_string())?;
+        }
+        Ok(())
+    }
+
+    fn memory_bytes(limits: &ResourceLimits) -> Option<u64> {
+        limits
+            .memory_mb
+            .filter(|mb| *mb > 0)
+            .map(|mb| u64::from(mb) * 1024 * 1024)
+    }
+
+    /// Counter `key` of a cgroup `*.events` file
+    fn event_count(path: &Path, key: &str) -> u64 {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|events| {
+                events.lines().find_map(|line| {
+                    let (name, count) = line.split_once(' ')?;
+                    if name == key {
+                        count.trim().parse().ok()
+                    } else {
+                        None
+                    }
+                })
+            })
+            .unwrap_or(0)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn rlimits_stand_in_for_limits() {
+            let limits = ResourceLimits {
+                cpus: Some(1.5),
+                memory_mb: Some(512),
+                max_processes: Some(64),
+            };
+            assert_eq!(
+                rlimits(&limits, Some(Duration::from_secs(60))),
+                Rlimits {
+                    address_space: Some(512 * 1024 * 1024),
+                    processes: Some(64),
+                    cpu_secs: Some(90),
+                }
+            );
+
+            let cpu_only = ResourceLimits {
+                cpus: Some(2.0),
+                ..Default::default()
+            };
+            assert!(rlimits(&cpu_only, None).is_empty());
+        }
+
+        #[test]
+        fn reports_oom_kills_before_process_limits() {
+            let dir = tempfile::tempdir().unwrap();
+            assert_eq!(exceeded(dir.path()), None);
+
+            fs::write(dir.path().join("pids.events"), "max 3\n").unwrap();
+            assert_eq!(
+                exceeded(dir.path()),
+                Some(ExecutionProcessCompletionReason::ProcessLimit)
+            );
+
+            fs::write(
+                dir.path().join("memory.events"),
+                "low 0\nhigh 0\nmax 12\noom 1\noom_kill 1\noom_group_kill 0\n",
+            )
+            .unwrap();
+            assert_eq!(
+                exceeded(dir.path()),
+                Some(ExecutionProcessCompletionReason::OutOfMemory)
+            );
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Where the local container service runs execution processes. The host
+//! runtime spawns them on this machine; the container and remote deployments
+//! place them in containers or on an SSH host through their own runtimes.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use db::models::{
+    execution_process::ExecutionProcessCompletionReason, repo::Repo, workspace::Workspace,
+};
+use executors::{actions::ExecutorAction, env::ExecutionEnv};
+use services::services::{config::ResourceLimits, container::ContainerError};
+use uuid::Uuid;
+
+/// A process the local container service is about to spawn
+pub struct ProcessLaunch<'a> {
+    pub execution_process_id: Uuid,
+    pub workspace: &'a Workspace,
+    pub action: &'a ExecutorAction,
+    pub repos: &'a [Repo],
+}
+
+#[async_trait]
+pub trait ProcessRuntime: Send + Sync {
+    /// Point `env` at where the process runs. Limits the runtime enforces
+    /// itself are taken out of `limits`. Returns the action to spawn in place
+    /// of the requested one, if the runtime replaces it.
+    async fn prepare(
+        &self,
+        launch: &ProcessLaunch<'_>,
+        env: &mut ExecutionEnv,
+        limits: &mut Option<ResourceLimits>,
+    ) -> Result<Option<ExecutorAction>, ContainerError>;
+
+    /// Clean up after the process exited or failed to spawn, and report why
+    /// it failed when the runtime knows
+    async fn release(
+        &self,
+        execution_process_id: Uuid,
+        succeeded: bool,
+    ) -> Option<ExecutionProcessCompletionReason>;
+
+    /// Commit the changes processes left in the workspace's worktrees, for
+    /// runtimes whose processes work in worktrees elsewhere. Returns whether
+    /// anything was committed, or `None` to commit this machine's worktrees.
+    async fn commit(
+        &self,
+        _workspace: &Workspace,
+        _workspace_root: &Path,
+        _repos: &[Repo],
+        _message: &str,
+    ) -> Option<Result<bool, ContainerError>> {
+        None
+    }
+}
+
+/// Spawns processes directly on this machine
+pub struct HostRuntime;
+
+#[async_trait]
+impl ProcessRuntime for HostRuntime {
+    async fn prepare(
+        &self,
+        _launch: &ProcessLaunch<'_>,
+        _env: &mut ExecutionEnv,
+        _limits: &mut Option<ResourceLimits>,
+    ) -> Result<Option<ExecutorAction>, ContainerError> {
+        Ok(None)
+    }
+
+    async fn release(
+        &self,
+        _execution_process_id: Uuid,
+        _succeeded: bool,
+    ) -> Option<ExecutionProcessCompletionReason> {
+        None
+    }
+}
@@ -0,0 +1,240 @@
+//! Filtering proxy for sandboxes with a network allowlist. The proxy listens
+//! on a Unix socket that is bound into the sandbox, whose network namespace
+//! has no other way out; sandboxed processes reach it through
+//! `HTTP_PROXY`/`HTTPS_PROXY`. Connections to hosts outside the allowlist are
+//! refused and reported in the process's output.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use executors::sandbox::{NOTICE_PREFIX, host_allowed};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, copy_bidirectional},
+    net::TcpStream,
+    sync::mpsc,
+    task::JoinHandle,
+};
+use uuid::Uuid;
+
+/// Largest request head accepted before the connection is dropped
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Proxy serving one sandboxed execution process, stopped and its socket
+/// removed when dropped
+pub struct AllowlistProxy {
+    socket: PathBuf,
+    handle: JoinHandle<()>,
+}
+
+impl AllowlistProxy {
+    /// Listen on a socket for `execution_process_id`. Each refused connection
+    /// is described on `blocked`.
+    pub async fn start(
+        execution_process_id: Uuid,
+        hosts: Vec<String>,
+        blocked: mpsc::UnboundedSender<String>,
+    ) -> io::Result<Self> {
+        let socket =
+            std::env::temp_dir().join(format!("vibe-kanban-proxy-{execution_process_id}.sock"));
+        let hosts = Arc::new(hosts);
+        let handle = listen(&socket, hosts, blocked)?;
+        Ok(Self { socket, handle })
+    }
+
+    /// Host path of the socket to bind into the sandbox
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+}
+
+#[cfg(unix)]
+fn listen(
+    socket: &Path,
+    hosts: Arc<Vec<String>>,
+    blocked: mpsc::UnboundedSender<String>,
+) -> io::Result<JoinHandle<()>> {
+    // A socket left behind by a crashed run would make the bind fail
+    let _ = std::fs::remove_file(socket);
+    let listener = tokio::net::UnixListener::bind(socket)?;
+    Ok(tokio::spawn(async move {
+        while let Ok((client, _)) = listener.accept().await {
+            let hosts = hosts.clone();
+            let blocked = blocked.clone();
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(client, &hosts, &blocked).await {
+                    tracing::debug!("Sandbox proxy connection failed: {}", e);
+                }
+            });
+        }
+    }))
+}
+
+#[cfg(not(unix))]
+fn listen(
+    _socket: &Path,
+    _hosts: Arc<Vec<String>>,
+    _blocked: mpsc::UnboundedSender<String>,
+) -> io::Result<JoinHandle<()>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the sandbox proxy needs Unix sockets",
+    ))
+}
+
+impl Drop for AllowlistProxy {
+    fn drop(&mut self) {
+        self.handle.abort();
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+/// Where a proxied request goes
+#[derive(Debug, PartialEq, Eq)]
+struct ProxyTarget {
+    host: String,
+    port: u16,
+    /// `CONNECT` tunnel rather than a plain HTTP request
+    tunnel: bool,
+}
+
+async fn handle_connection<S: AsyncRead + AsyncWrite + Unpin>(
+    mut client: S,
+    hosts: &[String],
+    blocked: &mpsc::UnboundedSender<String>,
+) -> io::Result<()> {
+    let mut buffered = Vec::new();
+    let head_len = loop {
+        if let Some(end) = buffered.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+        if buffered.len() > MAX_HEAD_BYTES {
+            return Err(io::Error::other("request head too large"));
+        }
+        let mut chunk = [0u8; 4096];
+        let read = client.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        buffered.extend_from_slice(&chunk[..read]);
+    };
+
+    let Some(target) = parse_target(&String::from_utf8_lossy(&buffered[..head_len])) else {
+        client
+            .write_all(b"HTTP/1.1 400 Bad Request\r\nConnection: close\r\n\r\n")
+            .await?;
+        return Ok(());
+    };
+
+    if !host_allowed(hosts, &target.host) {
+        let _ = blocked.send(format!(
+            "{NOTICE_PREFIX} Blocked a connection to {}:{}: the host isn't in the network allowlist.",
+            target.host, target.port
+        ));
+        client
+            .write_all(
+                b"HTTP/1.1 403 Forbidden\r\nContent-Type: text/plain\r\nConnection: close\r\n\r\nBlocked by the sandbox network allowlist\n",
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let mut upstream = match TcpStream::connect((target.host.as_str(), target.port)).await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            client
+                .write_all(b"HTTP/1.1 502 Bad Gateway\r\nConnection: close\r\n\r\n")
+                .await?;
+            return Err(e);
+        }
+    };
+    if target.tunnel {
+        client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+        upstream.write_all(&buffered[head_len..]).await?;
+    } else {
+        // Origin servers accept the absolute-form request line as is
+        upstream.write_all(&buffered).await?;
+    }
+    copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+/// Target of a proxy request from its head: `CONNECT host:port` or a request
+/// with an absolute `http://` URL
+fn parse_target(head: &str) -> Option<ProxyTarget> {
+    let mut request_line = head.lines().next()?.split_whitespace();
+    let method = request_line.next()?;
+    let target = request_line.next()?;
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_authority(target, 443)?;
+        return Some(ProxyTarget {
+            host,
+            port,
+            tunnel: true,
+        });
+    }
+    let authority = target.strip_prefix("http://")?.split('/').next()?;
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let (host, port) = split_authority(authority, 80)?;
+    Some(ProxyTarget {
+        host,
+        port,
+        tunnel: false,
+    })
+}
+
+fn split_authority(authority: &str, default_port: u16) -> Option<(String, u16)> {
+    let (host, port) = match authority.strip_prefix('[') {
+        Some(bracketed) => {
+            let (host, rest) = bracketed.split_once(']')?;
+            (host, rest.strip_prefix(':'))
+        }
+        None => match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        },
+    };
+    let port = match port {
+        Some(port) => port.parse().ok()?,
+        None => default_port,
+    };
+    (!host.is_empty()).then(|| (host.to_string(), port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_connect_and_absolute_targets() {
+        assert_eq!(
+            parse_target("CONNECT api.anthropic.com:443 HTTP/1.1\r\nHost: x\r\n\r\n"),
+            Some(ProxyTarget {
+                host: "api.anthropic.com".to_string(),
+                port: 443,
+                tunnel: true,
+            })
+        );
+        assert_eq!(
+            parse_target("GET http://user@registry.npmjs.org/react HTTP/1.1\r\n\r\n"),
+            Some(ProxyTarget {
+                host: "registry.npmjs.org".to_string(),
+                port: 80,
+                tunnel: false,
+            })
+        );
+        assert_eq!(
+            parse_target("CONNECT [::1]:8443 HTTP/1.1\r\n\r\n").map(|t| (t.host, t.port)),
+            Some(("::1".to_string(), 8443))
+        );
+        assert_eq!(parse_target("GET /relative HTTP/1.1\r\n\r\n"), None);
+        assert_eq!(parse_target("CONNECT host:notaport HTTP/1.1\r\n\r\n"), None);
+    }
+}
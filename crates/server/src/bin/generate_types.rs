@@ -57,7 +57,15 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_process::DevServerHealth::decl(),
+        db::models::execution_process::ExecutionProcessCompletionReason::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
+        db::models::execution_process_port::ExecutionProcessPort::decl(),
+        db::models::execution_process_logs::ProjectLogStorage::decl(),
+        db::models::budget::Budget::decl(),
+        db::models::budget::BudgetUnit::decl(),
+        db::models::budget::CreateBudget::decl(),
+        db::models::budget::UpdateBudget::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
@@ -93,6 +101,7 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::OpenEditorRequest::decl(),
         server::routes::task_attempts::OpenEditorResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
+        server::routes::tasks::TaskDetails::decl(),
         server::routes::task_attempts::pr::CreatePrApiRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         server::routes::images::ImageMetadata::decl(),
@@ -123,6 +132,7 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::pr::CreateFromPrError::decl(),
         server::routes::task_attempts::RepoBranchStatus::decl(),
         server::routes::task_attempts::UpdateWorkspace::decl(),
+        server::routes::task_attempts::WorkspaceDetails::decl(),
         server::routes::task_attempts::workspace_summary::WorkspaceSummaryRequest::decl(),
         server::routes::task_attempts::workspace_summary::WorkspaceSummary::decl(),
         server::routes::task_attempts::workspace_summary::WorkspaceSummaryResponse::decl(),
@@ -130,8 +140,35 @@ fn generate_types_content() -> String {
         services::services::filesystem::DirectoryEntry::decl(),
         services::services::filesystem::DirectoryListResponse::decl(),
         services::services::file_search::SearchMode::decl(),
+        services::services::transcript::TranscriptFormat::decl(),
+        services::services::transcript::SessionTranscript::decl(),
+        services::services::token_usage::TokenUsageReport::decl(),
+        services::services::token_usage::TokenUsageLine::decl(),
+        services::services::token_usage::ExecutorTokenUsage::decl(),
         services::services::config::Config::decl(),
         services::services::config::NotificationConfig::decl(),
+        services::services::config::DevServerPortConfig::decl(),
+        services::services::config::LogRetentionConfig::decl(),
+        services::services::config::TokenPrice::decl(),
+        services::services::config::ExecutionTimeouts::decl(),
+        services::services::config::ScriptTimeout::decl(),
+        services::services::config::ConcurrencyLimits::decl(),
+        services::services::config::ExecutorConcurrencyLimit::decl(),
+        services::services::config::ExecutionRetries::decl(),
+        services::services::config::ScriptResourceLimits::decl(),
+        services::services::config::ExecutionResourceLimits::decl(),
+        services::services::executor_defaults::ExecutorProfileSource::decl(),
+        services::services::executor_defaults::EffectiveExecutorProfile::decl(),
+        executors::sandbox::SandboxNetwork::decl(),
+        executors::sandbox::SandboxPolicy::decl(),
+        services::services::config::ScriptSandbox::decl(),
+        services::services::config::ExecutionSandbox::decl(),
+        executors::oci::ContainerEngine::decl(),
+        services::services::config::RepoContainerImage::decl(),
+        services::services::config::ContainerRuntimeConfig::decl(),
+        services::services::config::RemoteExecutionConfig::decl(),
+        executors::instructions::InstructionsDelivery::decl(),
+        services::services::config::AgentInstructionsConfig::decl(),
         services::services::config::ThemeMode::decl(),
         services::services::config::EditorConfig::decl(),
         services::services::config::EditorType::decl(),
@@ -142,8 +179,10 @@ fn generate_types_content() -> String {
         services::services::config::ShowcaseState::decl(),
         services::services::config::SendMessageShortcut::decl(),
         git::GitBranch::decl(),
-        services::services::queued_message::QueuedMessage::decl(),
+        db::models::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
+        services::services::execution_scheduler::ExecutionQueueEntry::decl(),
+        server::routes::execution_queue::UpdateQueuedExecution::decl(),
         git::ConflictOp::decl(),
         executors::actions::ExecutorAction::decl(),
         executors::mcp_config::McpConfig::decl(),
@@ -158,6 +197,8 @@ fn generate_types_content() -> String {
         executors::command::CommandBuilder::decl(),
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorConfig::decl(),
+        executors::profile::ResourceLimits::decl(),
+        executors::profile::ProfileSettings::decl(),
         executors::profile::ExecutorConfigs::decl(),
         executors::executors::BaseAgentCapability::decl(),
         executors::executors::claude::ClaudeCode::decl(),
@@ -178,6 +219,17 @@ fn generate_types_content() -> String {
         executors::executors::auggie::AuggieModel::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
+        executors::executors::aider::Aider::decl(),
+        executors::executors::aider::EditFormat::decl(),
+        executors::executors::acp::AcpAgent::decl(),
+        executors::executors::openai_compatible::OpenaiCompatible::decl(),
+        executors::executors::custom::Custom::decl(),
+        executors::executors::custom::PromptInput::decl(),
+        executors::executors::custom::Output::decl(),
+        executors::executors::custom::normalize_logs::JsonlMapping::decl(),
+        executors::executors::custom::normalize_logs::JsonlRule::decl(),
+        executors::executors::custom::normalize_logs::JsonlEntry::decl(),
+        executors::executors::custom::normalize_logs::JsonlAction::decl(),
         executors::executors::AppendPrompt::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
@@ -185,9 +237,11 @@ fn generate_types_content() -> String {
         executors::actions::review::RepoReviewContext::decl(),
         executors::logs::CommandExitStatus::decl(),
         executors::logs::CommandRunResult::decl(),
+        executors::logs::NormalizedConversation::decl(),
         executors::logs::NormalizedEntry::decl(),
         executors::logs::NormalizedEntryType::decl(),
         executors::logs::TokenUsageInfo::decl(),
+        executors::logs::ModelTokenUsage::decl(),
         executors::logs::FileChange::decl(),
         executors::logs::ActionType::decl(),
         executors::logs::TodoItem::decl(),
@@ -285,6 +339,22 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "auggie",
             generate_json_schema::<executors::executors::auggie::Auggie>()?,
         ),
+        (
+            "aider",
+            generate_json_schema::<executors::executors::aider::Aider>()?,
+        ),
+        (
+            "acp",
+            generate_json_schema::<executors::executors::acp::AcpAgent>()?,
+        ),
+        (
+            "openai_compatible",
+            generate_json_schema::<executors::executors::openai_compatible::OpenaiCompatible>()?,
+        ),
+        (
+            "custom",
+            generate_json_schema::<executors::executors::custom::Custom>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
@@ -5,7 +5,7 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use db::models::{
-    execution_process::ExecutionProcessError, project::ProjectError,
+    budget::BudgetError, execution_process::ExecutionProcessError, project::ProjectError,
     project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
     workspace::WorkspaceError,
 };
@@ -17,9 +17,11 @@ use local_deployment::pty::PtyError;
 use services::services::{
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
+    dev_server_ports::DevServerPortError,
     git_host::GitHostError,
     image::ImageError,
     project::ProjectServiceError,
+    queued_message::QueuedMessageError,
     repo::RepoError as RepoServiceError,
     worktree_manager::WorktreeError,
 };
@@ -40,6 +42,10 @@ pub enum ApiError {
     #[error(transparent)]
     ScratchError(#[from] ScratchError),
     #[error(transparent)]
+    Budget(#[from] BudgetError),
+    #[error(transparent)]
+    QueuedMessage(#[from] QueuedMessageError),
+    #[error(transparent)]
     ExecutionProcess(#[from] ExecutionProcessError),
     #[error(transparent)]
     GitService(#[from] GitServiceError),
@@ -186,6 +192,25 @@ impl IntoResponse for ApiError {
                 )
             }
 
+            ApiError::Budget(BudgetError::Database(_)) => ErrorInfo::internal("BudgetError"),
+            ApiError::Budget(BudgetError::NotFound) => {
+                ErrorInfo::not_found("BudgetError", "Budget not found.")
+            }
+            ApiError::Budget(BudgetError::ValidationError(msg)) => {
+                ErrorInfo::bad_request("BudgetError", msg.clone())
+            }
+
+            ApiError::QueuedMessage(QueuedMessageError::Database(_)) => {
+                ErrorInfo::internal("QueuedMessageError")
+            }
+            ApiError::QueuedMessage(QueuedMessageError::NotFound) => {
+                ErrorInfo::not_found("QueuedMessageError", "Queued message not found.")
+            }
+            ApiError::QueuedMessage(QueuedMessageError::InvalidOrder) => ErrorInfo::bad_request(
+                "QueuedMessageError",
+                "The new order must list every queued message exactly once.",
+            ),
+
             ApiError::ScratchError(ScratchError::Database(_)) => {
                 ErrorInfo::internal("ScratchError")
             }
@@ -311,6 +336,13 @@ impl IntoResponse for ApiError {
             ),
 
             ApiError::Deployment(_) => ErrorInfo::internal("DeploymentError"),
+            ApiError::Container(ContainerError::DevServerPort(
+                e
+                @ (DevServerPortError::Exhausted { .. } | DevServerPortError::InvalidRange { .. }),
+            )) => ErrorInfo::conflict(
+                "DevServerPortError",
+                format!("{}. Adjust the dev server port range in settings.", e),
+            ),
             ApiError::Container(_) => ErrorInfo::internal("ContainerError"),
             ApiError::Executor(_) => ErrorInfo::internal("ExecutorError"),
             ApiError::CommandBuilder(_) => ErrorInfo::internal("CommandBuildError"),
@@ -276,9 +276,9 @@ pub struct StartWorkspaceSessionRequest {
     #[schemars(description = "The ID of the task to start")]
     pub task_id: Uuid,
     #[schemars(
-        description = "The coding agent executor to run ('CLAUDE_CODE', 'AMP', 'GEMINI', 'CODEX', 'OPENCODE', 'CURSOR_AGENT', 'QWEN_CODE', 'COPILOT', 'DROID')"
+        description = "Optional coding agent executor to run ('CLAUDE_CODE', 'AMP', 'GEMINI', 'CODEX', 'OPENCODE', 'CURSOR_AGENT', 'QWEN_CODE', 'COPILOT', 'DROID', 'AUGGIE', 'AIDER', 'ACP', 'OPENAI_COMPATIBLE', 'CUSTOM'). Defaults to the repository, project or global default profile"
     )]
-    pub executor: String,
+    pub executor: Option<String>,
     #[schemars(description = "Optional executor variant, if needed")]
     pub variant: Option<String>,
     #[schemars(description = "Base branch for each repository in the project")]
@@ -855,22 +855,6 @@ impl TaskServer {
             );
         }
 
-        let executor_trimmed = executor.trim();
-        if executor_trimmed.is_empty() {
-            return Self::err("Executor must not be empty.".to_string(), None::<String>);
-        }
-
-        let normalized_executor = executor_trimmed.replace('-', "_").to_ascii_uppercase();
-        let base_executor = match BaseCodingAgent::from_str(&normalized_executor) {
-            Ok(exec) => exec,
-            Err(_) => {
-                return Self::err(
-                    format!("Unknown executor '{executor_trimmed}'."),
-                    None::<String>,
-                );
-            }
-        };
-
         let variant = variant.and_then(|v| {
             let trimmed = v.trim();
             if trimmed.is_empty() {
@@ -880,9 +864,31 @@ impl TaskServer {
             }
         });
 
-        let executor_profile_id = ExecutorProfileId {
-            executor: base_executor,
-            variant,
+        // Without an executor the server picks the repo, project or global default
+        let executor_profile_id = match executor.as_deref().map(str::trim) {
+            Some(executor_trimmed) if !executor_trimmed.is_empty() => {
+                let normalized_executor = executor_trimmed.replace('-', "_").to_ascii_uppercase();
+                let base_executor = match BaseCodingAgent::from_str(&normalized_executor) {
+                    Ok(exec) => exec,
+                    Err(_) => {
+                        return Self::err(
+                            format!("Unknown executor '{executor_trimmed}'."),
+                            None::<String>,
+                        );
+                    }
+                };
+                Some(ExecutorProfileId {
+                    executor: base_executor,
+                    variant,
+                })
+            }
+            _ if variant.is_some() => {
+                return Self::err(
+                    "A variant requires an executor.".to_string(),
+                    None::<String>,
+                );
+            }
+            _ => None,
         };
 
         let workspace_repos: Vec<WorkspaceRepoInput> = repos
@@ -0,0 +1,63 @@
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::budget::{Budget, CreateBudget, UpdateBudget};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_budget_middleware};
+
+pub async fn get_budgets(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Budget>>>, ApiError> {
+    let budgets = Budget::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(budgets)))
+}
+
+pub async fn create_budget(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateBudget>,
+) -> Result<ResponseJson<ApiResponse<Budget>>, ApiError> {
+    let budget = Budget::create(&deployment.db().pool, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(budget)))
+}
+
+pub async fn update_budget(
+    Extension(budget): Extension<Budget>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateBudget>,
+) -> Result<ResponseJson<ApiResponse<Budget>>, ApiError> {
+    let budget = Budget::update(&deployment.db().pool, budget.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(budget)))
+}
+
+pub async fn delete_budget(
+    Extension(budget): Extension<Budget>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = Budget::delete(&deployment.db().pool, budget.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let budget_router = Router::new()
+        .route("/", put(update_budget).delete(delete_budget))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_budget_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_budgets).post(create_budget))
+        .nest("/{budget_id}", budget_router);
+
+    Router::new().nest("/budgets", inner)
+}
@@ -17,7 +17,7 @@ use executors::{
         AvailabilityInfo, BaseAgentCapability, BaseCodingAgent, StandardCodingAgentExecutor,
     },
     mcp_config::{McpConfig, read_agent_config, write_agent_config},
-    profile::{ExecutorConfigs, ExecutorProfileId},
+    profile::{ExecutorConfigs, ExecutorProfileId, RawExecutorConfigs},
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -353,7 +353,10 @@ fn set_mcp_servers_in_config_path(
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProfilesContent {
+    /// Profiles as written, with `extends` kept
     pub content: String,
+    /// Profiles with inheritance resolved, as executors run them
+    pub resolved: String,
     pub path: String,
 }
 
@@ -363,16 +366,21 @@ async fn get_profiles(
     let profiles_path = utils::assets::profiles_path();
 
     // Use cached data to ensure consistency with runtime and PUT updates
-    let profiles = ExecutorConfigs::get_cached();
-
-    let content = serde_json::to_string_pretty(&profiles).unwrap_or_else(|e| {
-        tracing::error!("Failed to serialize profiles to JSON: {}", e);
-        serde_json::to_string_pretty(&ExecutorConfigs::from_defaults())
-            .unwrap_or_else(|_| "{}".to_string())
-    });
+    let content =
+        serde_json::to_string_pretty(&RawExecutorConfigs::get_cached()).unwrap_or_else(|e| {
+            tracing::error!("Failed to serialize profiles to JSON: {}", e);
+            serde_json::to_string_pretty(&RawExecutorConfigs::from_defaults())
+                .unwrap_or_else(|_| "{}".to_string())
+        });
+    let resolved =
+        serde_json::to_string_pretty(&ExecutorConfigs::get_cached()).unwrap_or_else(|e| {
+            tracing::error!("Failed to serialize resolved profiles to JSON: {}", e);
+            "{}".to_string()
+        });
 
     ResponseJson(ApiResponse::success(ProfilesContent {
         content,
+        resolved,
         path: profiles_path.display().to_string(),
     }))
 }
@@ -381,8 +389,8 @@ async fn update_profiles(
     State(_deployment): State<DeploymentImpl>,
     body: String,
 ) -> ResponseJson<ApiResponse<String>> {
-    // Try to parse as ExecutorProfileConfigs format
-    match serde_json::from_str::<ExecutorConfigs>(&body) {
+    // Parse as written so variants keep their `extends`
+    match serde_json::from_str::<RawExecutorConfigs>(&body) {
         Ok(executor_profiles) => {
             // Save the profiles to file
             match executor_profiles.save_overrides() {
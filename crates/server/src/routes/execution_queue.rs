@@ -0,0 +1,57 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    container::ContainerService,
+    execution_scheduler::{ExecutionQueueEntry, ExecutionScheduler},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateQueuedExecution {
+    /// Higher priorities are dispatched first; new starts get 0
+    #[ts(type = "number")]
+    pub priority: i64,
+}
+
+/// Coding agent starts waiting for a free slot, in dispatch order. Stop the
+/// execution process to cancel one.
+pub async fn get_execution_queue(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionQueueEntry>>>, ApiError> {
+    let entries = ExecutionScheduler::queue_entries(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+pub async fn update_queued_execution(
+    Path(execution_process_id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateQueuedExecution>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionQueueEntry>>>, ApiError> {
+    deployment
+        .container()
+        .scheduler()
+        .set_priority(execution_process_id, payload.priority)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+
+    let entries = ExecutionScheduler::queue_entries(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    let inner = Router::new()
+        .route("/", get(get_execution_queue))
+        .route("/{execution_process_id}", put(update_queued_execution));
+
+    Router::new().nest("/execution-queue", inner)
+}
@@ -1,5 +1,6 @@
 use axum::{
     Router,
+    middleware::from_fn_with_state,
     routing::{IntoMakeService, get},
 };
 use tower_http::validate_request::ValidateRequestHeaderLayer;
@@ -7,15 +8,18 @@ use tower_http::validate_request::ValidateRequestHeaderLayer;
 use crate::{DeploymentImpl, middleware};
 
 pub mod approvals;
+pub mod budgets;
 pub mod config;
 pub mod containers;
 pub mod filesystem;
 // pub mod github;
 pub mod events;
 pub mod execution_processes;
+pub mod execution_queue;
 pub mod frontend;
 pub mod health;
 pub mod images;
+pub mod preview;
 pub mod projects;
 pub mod repo;
 pub mod scratch;
@@ -25,17 +29,23 @@ pub mod tags;
 pub mod task_attempts;
 pub mod tasks;
 pub mod terminal;
+pub mod usage;
 
 pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
+    let preview_routes = preview::router().with_state(deployment.clone());
+    let preview_host_layer = from_fn_with_state(deployment.clone(), preview::route_preview_host);
+
     // Create routers with different middleware layers
     let base_routes = Router::new()
         .route("/health", get(health::health_check))
+        .merge(budgets::router(&deployment))
         .merge(config::router())
         .merge(containers::router(&deployment))
         .merge(projects::router(&deployment))
         .merge(tasks::router(&deployment))
         .merge(task_attempts::router(&deployment))
         .merge(execution_processes::router(&deployment))
+        .merge(execution_queue::router())
         .merge(tags::router(&deployment))
         .merge(filesystem::router())
         .merge(repo::router())
@@ -45,6 +55,7 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(search::router(&deployment))
         .merge(sessions::router(&deployment))
         .merge(terminal::router())
+        .merge(usage::router())
         .nest("/images", images::routes())
         .layer(ValidateRequestHeaderLayer::custom(
             middleware::validate_origin,
@@ -55,5 +66,7 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .route("/", get(frontend::serve_frontend_root))
         .route("/{*path}", get(frontend::serve_frontend))
         .nest("/api", base_routes)
+        .merge(preview_routes)
+        .layer(preview_host_layer)
         .into_make_service()
 }
@@ -0,0 +1,477 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::OnceLock,
+};
+
+use axum::{
+    Router,
+    body::Body,
+    extract::{
+        FromRequestParts, Path, Request, State,
+        ws::{self, WebSocket, WebSocketUpgrade},
+    },
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Redirect, Response},
+    routing::any,
+};
+use db::models::{
+    execution_process::ExecutionProcess, execution_process_port::ExecutionProcessPort,
+};
+use deployment::Deployment;
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    WebSocketStream,
+    tungstenite::{self, client::IntoClientRequest, protocol::frame::coding::CloseCode},
+};
+use tower_http::validate_request::ValidateRequestHeaderLayer;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, middleware};
+
+const PREVIEW_PREFIX: &str = "/preview";
+
+/// Headers that describe a single connection and must not be forwarded.
+const HOP_BY_HOP_HEADERS: [HeaderName; 8] = [
+    header::CONNECTION,
+    HeaderName::from_static("keep-alive"),
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+    header::TE,
+    header::TRAILER,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+];
+
+/// Headers copied onto the upstream WebSocket handshake.
+const WEBSOCKET_FORWARDED_HEADERS: [HeaderName; 3] = [
+    header::SEC_WEBSOCKET_PROTOCOL,
+    header::COOKIE,
+    header::AUTHORIZATION,
+];
+
+#[derive(Debug, Error)]
+enum PreviewError {
+    #[error("No dev server with an allocated port is running for this workspace")]
+    NoDevServer,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Dev server is unreachable: {0}")]
+    Upstream(String),
+}
+
+impl IntoResponse for PreviewError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            PreviewError::NoDevServer => StatusCode::NOT_FOUND,
+            PreviewError::Database(e) => {
+                tracing::error!("Preview proxy database error: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            PreviewError::Upstream(e) => {
+                tracing::debug!("Preview proxy upstream error: {}", e);
+                StatusCode::BAD_GATEWAY
+            }
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PreviewPath {
+    workspace_id: Uuid,
+}
+
+async fn redirect_to_preview_root(Path(path): Path<PreviewPath>) -> Redirect {
+    // Relative asset URLs only resolve under the prefix with a trailing slash
+    Redirect::permanent(&format!("{PREVIEW_PREFIX}/{}/", path.workspace_id))
+}
+
+async fn proxy_preview_path(
+    State(deployment): State<DeploymentImpl>,
+    Path(path): Path<PreviewPath>,
+    req: Request,
+) -> Response {
+    let prefix = format!("{PREVIEW_PREFIX}/{}", path.workspace_id);
+    let upstream_path = req
+        .uri()
+        .path()
+        .strip_prefix(PREVIEW_PREFIX)
+        .and_then(|rest| rest.get(1..))
+        .and_then(|rest| rest.find('/').map(|i| &rest[i..]))
+        .unwrap_or("/")
+        .to_string();
+
+    proxy(
+        &deployment,
+        path.workspace_id,
+        req,
+        upstream_path,
+        Some(prefix),
+    )
+    .await
+    .unwrap_or_else(IntoResponse::into_response)
+}
+
+/// Middleware for host-based previews. When `VK_PREVIEW_HOST_SUFFIX` is set
+/// (e.g. `preview.localhost`), requests for `{workspace_id}.{suffix}` are
+/// proxied to that workspace's dev server with their path untouched, which
+/// suits apps that assume they are served from `/`.
+pub async fn route_preview_host(
+    State(deployment): State<DeploymentImpl>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let Some(workspace_id) =
+        preview_host_suffix().and_then(|suffix| workspace_id_from_host(req.headers(), suffix))
+    else {
+        return next.run(req).await;
+    };
+
+    if let Err(response) = middleware::validate_origin(&mut req) {
+        return response;
+    }
+
+    let upstream_path = req.uri().path().to_string();
+    proxy(&deployment, workspace_id, req, upstream_path, None)
+        .await
+        .unwrap_or_else(IntoResponse::into_response)
+}
+
+fn preview_host_suffix() -> Option<&'static str> {
+    static SUFFIX: OnceLock<Option<String>> = OnceLock::new();
+    SUFFIX
+        .get_or_init(|| {
+            std::env::var("VK_PREVIEW_HOST_SUFFIX")
+                .ok()
+                .map(|suffix| suffix.trim().trim_matches('.').to_ascii_lowercase())
+                .filter(|suffix| !suffix.is_empty())
+        })
+        .as_deref()
+}
+
+fn workspace_id_from_host(headers: &HeaderMap, suffix: &str) -> Option<Uuid> {
+    let host = headers.get(header::HOST)?.to_str().ok()?.trim();
+    let authority: axum::http::uri::Authority = host.parse().ok()?;
+    let hostname = authority.host().to_ascii_lowercase();
+    let label = hostname.strip_suffix(suffix)?.strip_suffix('.')?;
+    Uuid::parse_str(label).ok()
+}
+
+async fn proxy(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+    req: Request,
+    upstream_path: String,
+    prefix: Option<String>,
+) -> Result<Response, PreviewError> {
+    let port = find_preview_port(deployment, workspace_id).await?;
+    let path_and_query = match req.uri().query() {
+        Some(query) => format!("{upstream_path}?{query}"),
+        None => upstream_path,
+    };
+
+    let (mut parts, body) = req.into_parts();
+    if is_websocket_upgrade(&parts.headers) {
+        return match WebSocketUpgrade::from_request_parts(&mut parts, &()).await {
+            Ok(ws) => forward_websocket(ws, port, &path_and_query, &parts.headers).await,
+            Err(rejection) => Ok(rejection.into_response()),
+        };
+    }
+
+    forward_http(parts, body, port, &path_and_query, prefix.as_deref()).await
+}
+
+/// The newest running dev server of the workspace that has an allocated port.
+async fn find_preview_port(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+) -> Result<u16, PreviewError> {
+    let pool = &deployment.db().pool;
+    let dev_servers =
+        ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace_id).await?;
+    for dev_server in dev_servers {
+        if let Some(allocation) =
+            ExecutionProcessPort::find_by_execution_process_id(pool, dev_server.id).await?
+            && let Ok(port) = u16::try_from(allocation.port)
+        {
+            return Ok(port);
+        }
+    }
+    Err(PreviewError::NoDevServer)
+}
+
+fn is_websocket_upgrade(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"))
+}
+
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            // Redirects are the browser's business, not ours
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("failed to build preview proxy client")
+    })
+}
+
+async fn forward_http(
+    parts: Parts,
+    body: Body,
+    port: u16,
+    path_and_query: &str,
+    prefix: Option<&str>,
+) -> Result<Response, PreviewError> {
+    let mut headers = parts.headers;
+    let original_host = headers.remove(header::HOST);
+    strip_hop_by_hop(&mut headers);
+    if let Some(host) = original_host {
+        headers.insert(HeaderName::from_static("x-forwarded-host"), host);
+    }
+    if let Some(prefix) = prefix
+        && let Ok(value) = HeaderValue::from_str(prefix)
+    {
+        headers.insert(HeaderName::from_static("x-forwarded-prefix"), value);
+    }
+
+    let upstream = http_client()
+        .request(
+            parts.method,
+            format!("http://{}{path_and_query}", upstream_addr(port)),
+        )
+        .headers(headers)
+        .body(reqwest::Body::wrap_stream(body.into_data_stream()))
+        .send()
+        .await
+        .map_err(|e| PreviewError::Upstream(e.to_string()))?;
+
+    let mut response = Response::builder().status(upstream.status());
+    if let Some(response_headers) = response.headers_mut() {
+        response_headers.extend(upstream.headers().clone());
+        strip_hop_by_hop(response_headers);
+        if let Some(prefix) = prefix {
+            rewrite_location(response_headers, prefix);
+        }
+    }
+
+    response
+        .body(Body::from_stream(upstream.bytes_stream()))
+        .map_err(|e| PreviewError::Upstream(e.to_string()))
+}
+
+fn strip_hop_by_hop(headers: &mut HeaderMap) {
+    for name in &HOP_BY_HOP_HEADERS {
+        headers.remove(name);
+    }
+}
+
+/// Keep root-relative redirects from the dev server inside the preview prefix.
+fn rewrite_location(headers: &mut HeaderMap, prefix: &str) {
+    let Some(location) = headers
+        .get(header::LOCATION)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| v.starts_with('/') && !v.starts_with("//") && !is_under_prefix(v, prefix))
+    else {
+        return;
+    };
+
+    if let Ok(value) = HeaderValue::from_str(&format!("{prefix}{location}")) {
+        headers.insert(header::LOCATION, value);
+    }
+}
+
+/// Whether `path` is `prefix` itself or a path below it, so `/preview/12` is
+/// not mistaken for a path under `/preview/1`.
+fn is_under_prefix(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?']))
+}
+
+async fn forward_websocket(
+    ws: WebSocketUpgrade,
+    port: u16,
+    path_and_query: &str,
+    headers: &HeaderMap,
+) -> Result<Response, PreviewError> {
+    let addr = upstream_addr(port);
+    let stream = TcpStream::connect(addr)
+        .await
+        .map_err(|e| PreviewError::Upstream(e.to_string()))?;
+
+    let mut request = format!("ws://{addr}{path_and_query}")
+        .into_client_request()
+        .map_err(|e| PreviewError::Upstream(e.to_string()))?;
+    for name in &WEBSOCKET_FORWARDED_HEADERS {
+        if let Some(value) = headers.get(name) {
+            request.headers_mut().insert(name.clone(), value.clone());
+        }
+    }
+
+    let (upstream, handshake) = tokio_tungstenite::client_async(request, stream)
+        .await
+        .map_err(|e| PreviewError::Upstream(e.to_string()))?;
+
+    // Dev servers such as Vite refuse HMR sockets without their subprotocol,
+    // so answer the client with whatever the upstream accepted.
+    let ws = match handshake
+        .headers()
+        .get(header::SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(protocol) => ws.protocols([protocol.to_string()]),
+        None => ws,
+    };
+
+    Ok(ws.on_upgrade(move |socket| relay_websocket(socket, upstream)))
+}
+
+async fn relay_websocket(client: WebSocket, upstream: WebSocketStream<TcpStream>) {
+    let (mut client_tx, mut client_rx) = client.split();
+    let (mut upstream_tx, mut upstream_rx) = upstream.split();
+
+    let client_to_upstream = async {
+        while let Some(Ok(msg)) = client_rx.next().await {
+            let is_close = matches!(msg, ws::Message::Close(_));
+            if upstream_tx.send(to_upstream_message(msg)).await.is_err() || is_close {
+                break;
+            }
+        }
+    };
+
+    let upstream_to_client = async {
+        while let Some(Ok(msg)) = upstream_rx.next().await {
+            let Some(msg) = to_client_message(msg) else {
+                continue;
+            };
+            let is_close = matches!(msg, ws::Message::Close(_));
+            if client_tx.send(msg).await.is_err() || is_close {
+                break;
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = client_to_upstream => {}
+        _ = upstream_to_client => {}
+    }
+}
+
+fn to_upstream_message(msg: ws::Message) -> tungstenite::Message {
+    match msg {
+        ws::Message::Text(text) => tungstenite::Message::text(text.as_str().to_owned()),
+        ws::Message::Binary(data) => tungstenite::Message::Binary(data),
+        ws::Message::Ping(data) => tungstenite::Message::Ping(data),
+        ws::Message::Pong(data) => tungstenite::Message::Pong(data),
+        ws::Message::Close(frame) => {
+            tungstenite::Message::Close(frame.map(|frame| tungstenite::protocol::CloseFrame {
+                code: CloseCode::from(frame.code),
+                reason: frame.reason.as_str().to_owned().into(),
+            }))
+        }
+    }
+}
+
+fn to_client_message(msg: tungstenite::Message) -> Option<ws::Message> {
+    Some(match msg {
+        tungstenite::Message::Text(text) => ws::Message::Text(text.as_str().to_owned().into()),
+        tungstenite::Message::Binary(data) => ws::Message::Binary(data),
+        tungstenite::Message::Ping(data) => ws::Message::Ping(data),
+        tungstenite::Message::Pong(data) => ws::Message::Pong(data),
+        tungstenite::Message::Close(frame) => {
+            ws::Message::Close(frame.map(|frame| ws::CloseFrame {
+                code: frame.code.into(),
+                reason: frame.reason.as_str().to_owned().into(),
+            }))
+        }
+        // Raw frames are never yielded when reading
+        tungstenite::Message::Frame(_) => return None,
+    })
+}
+
+fn upstream_addr(port: u16) -> SocketAddr {
+    SocketAddr::from((Ipv4Addr::LOCALHOST, port))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/preview/{workspace_id}", any(redirect_to_preview_root))
+        .route("/preview/{workspace_id}/", any(proxy_preview_path))
+        .route("/preview/{workspace_id}/{*path}", any(proxy_preview_path))
+        .layer(ValidateRequestHeaderLayer::custom(
+            middleware::validate_origin,
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host_headers(host: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, HeaderValue::from_str(host).unwrap());
+        headers
+    }
+
+    #[test]
+    fn workspace_id_is_parsed_from_preview_host() {
+        let id = Uuid::new_v4();
+        let cases = [
+            format!("{id}.preview.localhost"),
+            format!("{id}.preview.localhost:3000"),
+            format!("{}.PREVIEW.localhost:3000", id.to_string().to_uppercase()),
+        ];
+        for host in cases {
+            assert_eq!(
+                workspace_id_from_host(&host_headers(&host), "preview.localhost"),
+                Some(id),
+                "{host}"
+            );
+        }
+    }
+
+    #[test]
+    fn non_preview_hosts_are_ignored() {
+        let id = Uuid::new_v4();
+        let cases = [
+            "localhost:3000".to_string(),
+            "preview.localhost".to_string(),
+            format!("{id}preview.localhost"),
+            format!("{id}.other.localhost"),
+            "not-a-uuid.preview.localhost".to_string(),
+        ];
+        for host in cases {
+            assert_eq!(
+                workspace_id_from_host(&host_headers(&host), "preview.localhost"),
+                None,
+                "{host}"
+            );
+        }
+    }
+
+    #[test]
+    fn root_relative_redirects_stay_under_prefix() {
+        let prefix = "/preview/abc";
+        let cases = [
+            ("/login", "/preview/abc/login"),
+            ("/preview/abc/login", "/preview/abc/login"),
+            ("/preview/abc", "/preview/abc"),
+            ("/preview/abc?next=1", "/preview/abc?next=1"),
+            ("/preview/abcd/login", "/preview/abc/preview/abcd/login"),
+            ("//cdn.example.com/x", "//cdn.example.com/x"),
+            ("https://example.com/", "https://example.com/"),
+        ];
+        for (location, expected) in cases {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::LOCATION, HeaderValue::from_str(location).unwrap());
+            rewrite_location(&mut headers, prefix);
+            assert_eq!(headers[header::LOCATION], expected, "{location}");
+        }
+    }
+}
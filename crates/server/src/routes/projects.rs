@@ -13,17 +13,21 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    execution_process_logs::{ExecutionProcessLogs, ProjectLogStorage},
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
     project_repo::{CreateProjectRepo, ProjectRepo},
     repo::Repo,
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
-use services::services::{file_search::SearchQuery, project::ProjectServiceError};
+use services::services::{
+    executor_defaults::EffectiveExecutorProfile, file_search::SearchQuery,
+    project::ProjectServiceError,
+};
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware, routes::usage};
 
 pub async fn get_projects(
     State(deployment): State<DeploymentImpl>,
@@ -263,6 +267,48 @@ pub async fn get_project_repositories(
     Ok(ResponseJson(ApiResponse::success(repositories)))
 }
 
+#[derive(Debug, serde::Deserialize)]
+pub struct ExecutorProfileQuery {
+    /// Comma-separated ids of the repositories a new workspace would use
+    #[serde(default)]
+    pub repo_ids: String,
+}
+
+/// Profile a new workspace of the project would start with when none is requested
+pub async fn get_project_executor_profile(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ExecutorProfileQuery>,
+) -> Result<ResponseJson<ApiResponse<EffectiveExecutorProfile>>, ApiError> {
+    let repo_ids: Vec<Uuid> = query
+        .repo_ids
+        .split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| s.trim().parse::<Uuid>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ApiError::BadRequest("Invalid repo_id format".to_string()))?;
+
+    let global_profile = deployment.config().read().await.executor_profile.clone();
+    let executor_profile = EffectiveExecutorProfile::resolve(
+        &deployment.db().pool,
+        None,
+        project.id,
+        &repo_ids,
+        &global_profile,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(executor_profile)))
+}
+
+pub async fn get_project_storage(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectLogStorage>>, ApiError> {
+    let storage =
+        ExecutionProcessLogs::storage_for_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(storage)))
+}
+
 pub async fn add_project_repository(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
@@ -392,6 +438,9 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
         )
+        .route("/storage", get(get_project_storage))
+        .route("/executor-profile", get(get_project_executor_profile))
+        .route("/usage", get(usage::get_project_usage))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
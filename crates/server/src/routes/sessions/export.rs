@@ -0,0 +1,138 @@
+use anyhow::anyhow;
+use axum::{
+    Extension,
+    extract::{Query, State},
+    http::header,
+    response::IntoResponse,
+};
+use chrono::Utc;
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    session::Session,
+    task::Task,
+    workspace::{Workspace, WorkspaceError},
+};
+use deployment::Deployment;
+use executors::logs::NormalizedConversation;
+use serde::Deserialize;
+use services::services::{
+    container::{ContainerError, ContainerService},
+    transcript::{SessionTranscript, TranscriptFormat, entries_from_patches, redact_tool_outputs},
+};
+use utils::text::short_uuid;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct ExportTranscriptQuery {
+    #[serde(default)]
+    pub format: TranscriptFormat,
+    #[serde(default)]
+    pub redact_tool_output: bool,
+    #[serde(default)]
+    pub include_diff: bool,
+}
+
+/// Download the session's coding agent conversations as Markdown, HTML or JSON
+pub async fn export_transcript(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ExportTranscriptQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+    let task_title = Task::find_by_id(pool, workspace.task_id)
+        .await?
+        .map(|task| task.title)
+        .unwrap_or_else(|| format!("Session {}", short_uuid(&session.id)));
+
+    let mut conversations = session_conversations(&deployment, session.id).await?;
+    if query.redact_tool_output {
+        for conversation in &mut conversations {
+            redact_tool_outputs(&mut conversation.entries);
+        }
+    }
+
+    let diff = if query.include_diff {
+        Some(
+            deployment
+                .container()
+                .workspace_unified_diff(&workspace)
+                .await?,
+        )
+    } else {
+        None
+    };
+
+    let transcript = SessionTranscript {
+        session_id: session.id,
+        workspace_id: workspace.id,
+        task_title,
+        exported_at: Utc::now(),
+        conversations,
+        diff,
+    };
+    let body = transcript
+        .render(query.format)
+        .map_err(|e| ContainerError::Other(anyhow!(e)))?;
+
+    let disposition = format!(
+        "attachment; filename=\"session-{}.{}\"",
+        short_uuid(&session.id),
+        query.format.extension()
+    );
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                query.format.content_type().to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
+
+/// The session's coding agent conversations, one per coding agent run, oldest first
+pub async fn session_conversations(
+    deployment: &DeploymentImpl,
+    session_id: Uuid,
+) -> Result<Vec<NormalizedConversation>, ApiError> {
+    let pool = &deployment.db().pool;
+    let processes = ExecutionProcess::find_by_session_id(pool, session_id, false).await?;
+    let mut conversations = Vec::new();
+    for process in processes
+        .into_iter()
+        .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent)
+    {
+        let patches = deployment
+            .container()
+            .normalized_patches(&process.id, false)
+            .await
+            .unwrap_or_default();
+
+        let turn = CodingAgentTurn::find_by_execution_process_id(pool, process.id).await?;
+        let executor_type = process
+            .executor_action()
+            .ok()
+            .and_then(|action| action.base_executor())
+            .map(|executor| executor.to_string())
+            .unwrap_or_default();
+
+        conversations.push(NormalizedConversation {
+            entries: entries_from_patches(&patches),
+            session_id: turn.as_ref().and_then(|t| t.agent_session_id.clone()),
+            executor_type,
+            prompt: turn.as_ref().and_then(|t| t.prompt.clone()),
+            summary: turn.and_then(|t| t.summary),
+        });
+    }
+    Ok(conversations)
+}
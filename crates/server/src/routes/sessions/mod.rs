@@ -1,3 +1,4 @@
+pub mod export;
 pub mod queue;
 pub mod review;
 
@@ -13,6 +14,7 @@ use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     scratch::{Scratch, ScratchType},
     session::{CreateSession, Session, SessionError},
+    task::Task,
     workspace::{Workspace, WorkspaceError},
     workspace_repo::WorkspaceRepo,
 };
@@ -24,12 +26,14 @@ use executors::{
     profile::ExecutorProfileId,
 };
 use serde::Deserialize;
-use services::services::container::ContainerService;
+use services::services::{
+    container::ContainerService, diff_stream::compute_diff_stats, handoff::SessionHandoff,
+};
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{response::ApiResponse, text::short_uuid};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
+use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware, routes::usage};
 
 #[derive(Debug, Deserialize)]
 pub struct SessionQuery {
@@ -90,6 +94,9 @@ pub struct CreateFollowUpAttempt {
     pub retry_process_id: Option<Uuid>,
     pub force_when_dirty: Option<bool>,
     pub perform_git_reset: Option<bool>,
+    /// Start `executor_profile_id` fresh with a summary of the session so far,
+    /// even when the session ran another executor
+    pub handoff: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -121,6 +128,7 @@ pub async fn follow_up(
         .await?;
 
     let executor_profile_id = payload.executor_profile_id;
+    let handoff = payload.handoff.unwrap_or(false);
 
     // Validate executor matches session if session has prior executions
     let expected_executor: Option<String> =
@@ -129,7 +137,9 @@ pub async fn follow_up(
             .map(|profile| profile.executor.to_string())
             .or_else(|| session.executor.clone());
 
-    if let Some(expected) = expected_executor {
+    if let Some(expected) = expected_executor
+        && !handoff
+    {
         let actual = executor_profile_id.executor.to_string();
         if expected != actual {
             return Err(ApiError::Session(SessionError::ExecutorMismatch {
@@ -139,7 +149,7 @@ pub async fn follow_up(
         }
     }
 
-    if session.executor.is_none() {
+    if session.executor.is_none() || handoff {
         Session::update_executor(pool, session.id, &executor_profile_id.executor.to_string())
             .await?;
     }
@@ -153,9 +163,17 @@ pub async fn follow_up(
             .await?;
     }
 
-    let latest_session_info = CodingAgentTurn::find_latest_session_info(pool, session.id).await?;
-
-    let prompt = payload.prompt;
+    let (latest_session_info, prompt) = if handoff {
+        (
+            None,
+            handoff_prompt(&deployment, &session, &workspace, &payload.prompt).await?,
+        )
+    } else {
+        (
+            CodingAgentTurn::find_latest_session_info(pool, session.id).await?,
+            payload.prompt,
+        )
+    };
 
     let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
     let cleanup_action = deployment.container().cleanup_actions_for_repos(&repos);
@@ -211,6 +229,30 @@ pub async fn follow_up(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+/// Summary of the session's conversations and current changes for the agent
+/// taking it over, followed by `prompt`
+async fn handoff_prompt(
+    deployment: &DeploymentImpl,
+    session: &Session,
+    workspace: &Workspace,
+    prompt: &str,
+) -> Result<String, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, workspace.task_id).await?;
+    let conversations = export::session_conversations(deployment, session.id).await?;
+    let diff_stats = compute_diff_stats(pool, deployment.git(), workspace).await;
+
+    let handoff = SessionHandoff::new(
+        task.as_ref()
+            .map(|task| task.title.clone())
+            .unwrap_or_else(|| format!("Session {}", short_uuid(&session.id))),
+        task.and_then(|task| task.description),
+        &conversations,
+        diff_stats,
+    );
+    Ok(handoff.render(prompt))
+}
+
 pub async fn reset_process(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
@@ -237,6 +279,8 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_session))
         .route("/follow-up", post(follow_up))
         .route("/reset", post(reset_process))
+        .route("/export", get(export::export_transcript))
+        .route("/usage", get(usage::get_session_usage))
         .route("/review", post(review::start_review))
         .layer(from_fn_with_state(
             deployment.clone(),
@@ -1,6 +1,9 @@
 use axum::{
-    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
-    response::Json as ResponseJson, routing::get,
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post, put},
 };
 use db::models::{scratch::DraftFollowUpData, session::Session};
 use deployment::Deployment;
@@ -9,17 +12,26 @@ use serde::Deserialize;
 use services::services::queued_message::QueueStatus;
 use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
 
-/// Request body for queueing a follow-up message
+/// Request body for queueing or editing a follow-up message
 #[derive(Debug, Deserialize, TS)]
 pub struct QueueMessageRequest {
     pub message: String,
     pub executor_profile_id: ExecutorProfileId,
 }
 
-/// Queue a follow-up message to be executed when the current execution finishes
+/// Request body for reordering a session's queue
+#[derive(Debug, Deserialize, TS)]
+pub struct ReorderQueueRequest {
+    /// Every queued message id, next to be sent first
+    pub message_ids: Vec<Uuid>,
+}
+
+/// Add a follow-up message to the end of the queue; queued messages are sent one
+/// at a time as each execution finishes
 pub async fn queue_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
@@ -30,23 +42,22 @@ pub async fn queue_message(
         executor_profile_id: payload.executor_profile_id,
     };
 
-    let queued = deployment
-        .queued_message_service()
-        .queue_message(session.id, data);
+    let queue = deployment.queued_message_service();
+    queue.queue_message(session.id, data).await?;
+    let status = queue.get_status(session.id).await?;
 
-    Ok(ResponseJson(ApiResponse::success(QueueStatus::Queued {
-        message: queued,
-    })))
+    Ok(ResponseJson(ApiResponse::success(status)))
 }
 
-/// Cancel a queued follow-up message
+/// Cancel every queued follow-up message
 pub async fn cancel_queued_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
     deployment
         .queued_message_service()
-        .cancel_queued(session.id);
+        .cancel_queued(session.id)
+        .await?;
 
     Ok(ResponseJson(ApiResponse::success(QueueStatus::Empty)))
 }
@@ -56,21 +67,87 @@ pub async fn get_queue_status(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
-    let status = deployment.queued_message_service().get_status(session.id);
+    let status = deployment
+        .queued_message_service()
+        .get_status(session.id)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+pub async fn reorder_queue(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ReorderQueueRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let queue = deployment.queued_message_service();
+    queue.reorder(session.id, &payload.message_ids).await?;
+    let status = queue.get_status(session.id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+pub async fn update_queued_message(
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<QueueMessageRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let data = DraftFollowUpData {
+        message: payload.message,
+        executor_profile_id: payload.executor_profile_id,
+    };
+
+    let queue = deployment.queued_message_service();
+    queue.update_message(session_id, message_id, data).await?;
+    let status = queue.get_status(session_id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+pub async fn delete_queued_message(
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let queue = deployment.queued_message_service();
+    queue.remove_message(session_id, message_id).await?;
+    let status = queue.get_status(session_id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Move a queued message to the front so it is sent when the current execution finishes
+pub async fn send_next(
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let queue = deployment.queued_message_service();
+    queue.send_next(session_id, message_id).await?;
+    let status = queue.get_status(session_id).await?;
 
     Ok(ResponseJson(ApiResponse::success(status)))
 }
 
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
-    Router::new()
+    let queue_router = Router::new()
         .route(
             "/",
             get(get_queue_status)
                 .post(queue_message)
                 .delete(cancel_queued_message),
         )
+        .route("/reorder", post(reorder_queue))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
-        ))
+        ));
+
+    // The session middleware reads a single path id, so message routes take both
+    let message_router = Router::new()
+        .route(
+            "/{message_id}",
+            put(update_queued_message).delete(delete_queued_message),
+        )
+        .route("/{message_id}/send-next", post(send_next));
+
+    queue_router.merge(message_router)
 }
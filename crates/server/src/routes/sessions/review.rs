@@ -18,7 +18,9 @@ use executors::{
     profile::ExecutorProfileId,
 };
 use serde::{Deserialize, Serialize};
-use services::services::container::ContainerService;
+use services::services::{
+    container::ContainerService, executor_defaults::EffectiveExecutorProfile,
+};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 
@@ -26,7 +28,8 @@ use crate::{DeploymentImpl, error::ApiError};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct StartReviewRequest {
-    pub executor_profile_id: ExecutorProfileId,
+    /// Falls back to the repo, project and global defaults when omitted
+    pub executor_profile_id: Option<ExecutorProfileId>,
     pub additional_prompt: Option<String>,
     #[serde(default)]
     pub use_all_workspace_commits: bool,
@@ -99,11 +102,21 @@ pub async fn start_review(
         None
     };
 
+    let global_profile = deployment.config().read().await.executor_profile.clone();
+    let executor_profile_id = EffectiveExecutorProfile::resolve_for_workspace(
+        pool,
+        payload.executor_profile_id.clone(),
+        &workspace,
+        &global_profile,
+    )
+    .await?
+    .executor_profile_id;
+
     let prompt = build_review_prompt(context.as_deref(), payload.additional_prompt.as_deref());
 
     let action = ExecutorAction::new(
         ExecutorActionType::ReviewRequest(ReviewAction {
-            executor_profile_id: payload.executor_profile_id.clone(),
+            executor_profile_id,
             context,
             prompt,
             session_id: agent_session_id,
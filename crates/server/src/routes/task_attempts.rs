@@ -24,6 +24,7 @@ use axum::{
 use db::models::{
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_process_port::ExecutionProcessPort,
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
@@ -44,7 +45,7 @@ use git::{ConflictOp, GitCliError, GitService, GitServiceError};
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
-    container::ContainerService,
+    container::ContainerService, executor_defaults::EffectiveExecutorProfile,
     workspace_manager::WorkspaceManager,
 };
 use sqlx::Error as SqlxError;
@@ -111,6 +112,17 @@ pub struct UpdateWorkspace {
     pub name: Option<String>,
 }
 
+/// A workspace together with the ports its running dev servers were given and
+/// the profile new coding agent runs in it use when none is requested
+#[derive(Debug, Serialize, TS)]
+pub struct WorkspaceDetails {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub workspace: Workspace,
+    pub dev_server_ports: Vec<ExecutionProcessPort>,
+    pub executor_profile: EffectiveExecutorProfile,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DeleteWorkspaceQuery {
     #[serde(default)]
@@ -136,8 +148,20 @@ pub async fn get_workspace_count(
 
 pub async fn get_task_attempt(
     Extension(workspace): Extension<Workspace>,
-) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
-    Ok(ResponseJson(ApiResponse::success(workspace)))
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceDetails>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let dev_server_ports =
+        ExecutionProcessPort::find_running_by_workspace_id(pool, workspace.id).await?;
+    let global_profile = deployment.config().read().await.executor_profile.clone();
+    let executor_profile =
+        EffectiveExecutorProfile::resolve_for_workspace(pool, None, &workspace, &global_profile)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(WorkspaceDetails {
+        workspace,
+        dev_server_ports,
+        executor_profile,
+    })))
 }
 
 pub async fn update_workspace(
@@ -170,7 +194,8 @@ pub async fn update_workspace(
 #[derive(Debug, Serialize, Deserialize, ts_rs::TS)]
 pub struct CreateTaskAttemptBody {
     pub task_id: Uuid,
-    pub executor_profile_id: ExecutorProfileId,
+    /// Falls back to the repo, project and global defaults when omitted
+    pub executor_profile_id: Option<ExecutorProfileId>,
     pub repos: Vec<WorkspaceRepoInput>,
 }
 
@@ -193,8 +218,6 @@ pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTaskAttemptBody>,
 ) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
-    let executor_profile_id = payload.executor_profile_id.clone();
-
     if payload.repos.is_empty() {
         return Err(ApiError::BadRequest(
             "At least one repository is required".to_string(),
@@ -206,6 +229,18 @@ pub async fn create_task_attempt(
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
+    let global_profile = deployment.config().read().await.executor_profile.clone();
+    let repo_ids: Vec<Uuid> = payload.repos.iter().map(|r| r.repo_id).collect();
+    let executor_profile_id = EffectiveExecutorProfile::resolve(
+        pool,
+        payload.executor_profile_id.clone(),
+        task.project_id,
+        &repo_ids,
+        &global_profile,
+    )
+    .await?
+    .executor_profile_id;
+
     // Compute agent_working_dir based on repo count:
     // - Single repo: join repo name with default_working_dir (if set), or just repo name
     // - Multiple repos: use None (agent runs in workspace root)
@@ -1199,21 +1234,28 @@ pub async fn start_dev_server(
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
-    // Stop any existing dev servers for this project
-    let existing_dev_servers =
-        match ExecutionProcess::find_running_dev_servers_by_project(pool, project.id).await {
-            Ok(servers) => servers,
-            Err(e) => {
-                tracing::error!(
-                    "Failed to find running dev servers for project {}: {}",
-                    project.id,
-                    e
-                );
-                return Err(ApiError::Workspace(WorkspaceError::ValidationError(
-                    e.to_string(),
-                )));
-            }
-        };
+    // With port allocation each workspace's dev server gets its own port, so
+    // only this workspace's servers need stopping. Otherwise they would all
+    // fight over the same port, so stop every dev server in the project.
+    let ports_enabled = deployment.config().read().await.dev_server_ports.enabled;
+    let existing_dev_servers = if ports_enabled {
+        ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace.id).await
+    } else {
+        ExecutionProcess::find_running_dev_servers_by_project(pool, project.id).await
+    };
+    let existing_dev_servers = match existing_dev_servers {
+        Ok(servers) => servers,
+        Err(e) => {
+            tracing::error!(
+                "Failed to find running dev servers for project {}: {}",
+                project.id,
+                e
+            );
+            return Err(ApiError::Workspace(WorkspaceError::ValidationError(
+                e.to_string(),
+            )));
+        }
+    };
 
     for dev_server in existing_dev_servers {
         tracing::info!(
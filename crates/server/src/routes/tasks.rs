@@ -24,15 +24,20 @@ use deployment::Deployment;
 use executors::profile::ExecutorProfileId;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use services::services::{container::ContainerService, workspace_manager::WorkspaceManager};
+use services::services::{
+    container::ContainerService, executor_defaults::EffectiveExecutorProfile,
+    workspace_manager::WorkspaceManager,
+};
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_task_middleware,
-    routes::task_attempts::WorkspaceRepoInput,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_task_middleware,
+    routes::{task_attempts::WorkspaceRepoInput, usage},
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -98,11 +103,28 @@ async fn handle_tasks_ws(
     Ok(())
 }
 
+/// A task together with the profile a new workspace of it would start with
+/// when none is requested
+#[derive(Debug, Serialize, TS)]
+pub struct TaskDetails {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub task: Task,
+    pub executor_profile: EffectiveExecutorProfile,
+}
+
 pub async fn get_task(
     Extension(task): Extension<Task>,
-    State(_deployment): State<DeploymentImpl>,
-) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
-    Ok(ResponseJson(ApiResponse::success(task)))
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskDetails>>, ApiError> {
+    let global_profile = deployment.config().read().await.executor_profile.clone();
+    let executor_profile =
+        EffectiveExecutorProfile::resolve_for_task(&deployment.db().pool, &task, &global_profile)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(TaskDetails {
+        task,
+        executor_profile,
+    })))
 }
 
 pub async fn create_task(
@@ -129,7 +151,8 @@ pub async fn create_task(
 #[derive(Debug, Deserialize, TS)]
 pub struct CreateAndStartTaskRequest {
     pub task: CreateTask,
-    pub executor_profile_id: ExecutorProfileId,
+    /// Falls back to the repo, project and global defaults when omitted
+    pub executor_profile_id: Option<ExecutorProfileId>,
     pub repos: Vec<WorkspaceRepoInput>,
 }
 
@@ -145,6 +168,17 @@ pub async fn create_task_and_start(
 
     let pool = &deployment.db().pool;
 
+    let global_profile = deployment.config().read().await.executor_profile.clone();
+    let repo_ids: Vec<Uuid> = payload.repos.iter().map(|r| r.repo_id).collect();
+    let executor_profile = EffectiveExecutorProfile::resolve(
+        pool,
+        payload.executor_profile_id.clone(),
+        payload.task.project_id,
+        &repo_ids,
+        &global_profile,
+    )
+    .await?;
+
     let task_id = Uuid::new_v4();
     let task = Task::create(pool, &payload.task, task_id).await?;
 
@@ -199,7 +233,7 @@ pub async fn create_task_and_start(
 
     let is_attempt_running = deployment
         .container()
-        .start_workspace(&workspace, payload.executor_profile_id.clone())
+        .start_workspace(&workspace, executor_profile.executor_profile_id.clone())
         .await
         .inspect_err(|err| tracing::error!("Failed to start task attempt: {}", err))
         .is_ok();
@@ -212,7 +246,7 @@ pub async fn create_task_and_start(
         task,
         has_in_progress_attempt: is_attempt_running,
         last_attempt_failed: false,
-        executor: payload.executor_profile_id.executor.to_string(),
+        executor: executor_profile.executor_profile_id.executor.to_string(),
     })))
 }
 
@@ -386,6 +420,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
+        .route("/usage", get(usage::get_task_usage))
         .merge(task_actions_router)
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
@@ -0,0 +1,106 @@
+use axum::{
+    Extension, Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process_token_usage::{ExecutionProcessTokenUsage, TokenUsageFilter},
+    project::Project,
+    session::Session,
+    task::Task,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::token_usage::TokenUsageReport;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Date range over execution process start times, `to` exclusive
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub project_id: Option<Uuid>,
+}
+
+async fn usage_report(
+    deployment: &DeploymentImpl,
+    filter: TokenUsageFilter,
+) -> Result<TokenUsageReport, ApiError> {
+    let totals = ExecutionProcessTokenUsage::totals(&deployment.db().pool, &filter).await?;
+    let prices = deployment.config().read().await.token_prices.clone();
+    Ok(TokenUsageReport::new(
+        totals,
+        &prices,
+        filter.from,
+        filter.to,
+    ))
+}
+
+/// Usage across all projects, or one with `project_id`, broken down by executor
+pub async fn get_usage(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<TokenUsageReport>>, ApiError> {
+    let filter = TokenUsageFilter {
+        project_id: query.project_id,
+        from: query.from,
+        to: query.to,
+        ..Default::default()
+    };
+    let report = usage_report(&deployment, filter).await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub async fn get_project_usage(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<TokenUsageReport>>, ApiError> {
+    let filter = TokenUsageFilter {
+        project_id: Some(project.id),
+        from: query.from,
+        to: query.to,
+        ..Default::default()
+    };
+    let report = usage_report(&deployment, filter).await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub async fn get_task_usage(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<TokenUsageReport>>, ApiError> {
+    let filter = TokenUsageFilter {
+        task_id: Some(task.id),
+        from: query.from,
+        to: query.to,
+        ..Default::default()
+    };
+    let report = usage_report(&deployment, filter).await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub async fn get_session_usage(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<TokenUsageReport>>, ApiError> {
+    let filter = TokenUsageFilter {
+        session_id: Some(session.id),
+        from: query.from,
+        to: query.to,
+        ..Default::default()
+    };
+    let report = usage_report(&deployment, filter).await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/usage", get(get_usage))
+}
@@ -0,0 +1,54 @@
+//! Combining the agent instructions of a project and its repositories into
+//! the document handed to coding agents.
+
+use db::models::{project::Project, repo::Repo};
+
+/// The project's instructions followed by a section per repository that has
+/// its own; `None` when neither has any
+pub fn compose(project: Option<&Project>, repos: &[Repo]) -> Option<String> {
+    let mut sections = Vec::new();
+    if let Some(text) = project.and_then(|p| non_empty(p.agent_instructions.as_deref())) {
+        sections.push(text.to_string());
+    }
+    for repo in repos {
+        if let Some(text) = non_empty(repo.agent_instructions.as_deref()) {
+            sections.push(format!("## {}\n\n{text}", repo.display_name));
+        }
+    }
+    (!sections.is_empty()).then(|| sections.join("\n\n"))
+}
+
+fn non_empty(text: Option<&str>) -> Option<&str> {
+    text.map(str::trim).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_fixtures;
+
+    fn repo(name: &str, agent_instructions: Option<&str>) -> Repo {
+        Repo {
+            agent_instructions: agent_instructions.map(str::to_string),
+            ..test_fixtures::repo(name)
+        }
+    }
+
+    #[test]
+    fn project_instructions_then_repo_sections() {
+        let project = Project {
+            agent_instructions: Some("Never touch migrations/.\n".to_string()),
+            ..test_fixtures::project()
+        };
+        let repos = [
+            repo("backend", Some("Run make lint.")),
+            repo("frontend", Some("  ")),
+        ];
+
+        assert_eq!(
+            compose(Some(&project), &repos).as_deref(),
+            Some("Never touch migrations/.\n\n## backend\n\nRun make lint.")
+        );
+        assert_eq!(compose(None, &repos[1..]), None);
+    }
+}
@@ -0,0 +1,350 @@
+use std::{collections::HashMap, sync::Arc};
+
+use db::models::{
+    budget::{Budget, BudgetUnit},
+    execution_process_token_usage::{
+        ExecutionProcessTokenUsage, TokenUsageFilter, TokenUsageTotal,
+    },
+};
+use executors::{
+    logs::{
+        ModelTokenUsage, NormalizedEntry, NormalizedEntryType,
+        utils::{
+            ConversationPatch, EntryIndexProvider, patch::extract_normalized_entry_from_patch,
+        },
+    },
+    profile::ExecutorProfileId,
+};
+use futures::StreamExt;
+use sqlx::SqlitePool;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use crate::services::{
+    config::TokenPrice,
+    token_usage::{cost, find_price},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetLevel {
+    Soft,
+    Hard,
+}
+
+/// A budget a process went over, with the usage counted against it
+#[derive(Debug, Clone)]
+pub struct BudgetBreach {
+    pub budget: Budget,
+    pub level: BudgetLevel,
+    pub used: f64,
+}
+
+impl BudgetBreach {
+    pub fn message(&self) -> String {
+        let scope = if self.budget.project_id.is_some() {
+            "Project".to_string()
+        } else if self.budget.task_id.is_some() {
+            "Task".to_string()
+        } else {
+            match (&self.budget.executor, &self.budget.variant) {
+                (Some(executor), Some(variant)) => format!("{executor}:{variant}"),
+                (Some(executor), None) => executor.clone(),
+                _ => "Profile".to_string(),
+            }
+        };
+        let (limit, status, action) = match self.level {
+            BudgetLevel::Soft => (self.budget.soft_limit, "warning", ""),
+            BudgetLevel::Hard => (self.budget.hard_limit, "exceeded", ", stopping the agent"),
+        };
+        let limit = limit.unwrap_or_default();
+        match self.budget.unit {
+            BudgetUnit::Tokens => format!(
+                "{scope} budget {status}: {} of {} tokens used{action}",
+                self.used.round(),
+                limit.round()
+            ),
+            BudgetUnit::Usd => format!(
+                "{scope} budget {status}: ${:.2} of ${:.2} spent{action}",
+                self.used, limit
+            ),
+        }
+    }
+}
+
+/// Amount of `totals` counted against a budget in `unit`. Usage without a
+/// configured price costs nothing.
+pub fn budget_usage(unit: BudgetUnit, totals: &[TokenUsageTotal], prices: &[TokenPrice]) -> f64 {
+    totals
+        .iter()
+        .map(|total| match unit {
+            BudgetUnit::Tokens => (total.input_tokens + total.output_tokens) as f64,
+            BudgetUnit::Usd => find_price(
+                prices,
+                &total.executor,
+                total.variant.as_deref(),
+                &total.model,
+            )
+            .map(|price| {
+                cost(
+                    price,
+                    total.input_tokens,
+                    total.output_tokens,
+                    total.cached_input_tokens,
+                )
+            })
+            .unwrap_or(0.0),
+        })
+        .sum()
+}
+
+/// Highest limit of `budget` that `used` has reached
+pub fn breached_level(budget: &Budget, used: f64) -> Option<BudgetLevel> {
+    if budget.hard_limit.is_some_and(|limit| used >= limit) {
+        Some(BudgetLevel::Hard)
+    } else if budget.soft_limit.is_some_and(|limit| used >= limit) {
+        Some(BudgetLevel::Soft)
+    } else {
+        None
+    }
+}
+
+/// Follows the token usage a coding agent reports and checks it against the
+/// budgets covering its process.
+pub struct BudgetWatcher {
+    pool: SqlitePool,
+    execution_process_id: Uuid,
+    task_id: Uuid,
+    project_id: Uuid,
+    executor_profile_id: ExecutorProfileId,
+    budgets: Vec<Budget>,
+    prices: Vec<TokenPrice>,
+}
+
+impl BudgetWatcher {
+    /// `None` when no budget covers the process
+    pub async fn load(
+        pool: SqlitePool,
+        execution_process_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+        executor_profile_id: ExecutorProfileId,
+        prices: Vec<TokenPrice>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let budgets = Budget::find_applicable(
+            &pool,
+            project_id,
+            task_id,
+            &executor_profile_id.executor.to_string(),
+            executor_profile_id.variant.as_deref(),
+        )
+        .await?;
+        if budgets.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            pool,
+            execution_process_id,
+            task_id,
+            project_id,
+            executor_profile_id,
+            budgets,
+            prices,
+        }))
+    }
+
+    /// Watch the process until it finishes or goes over a hard limit, which is
+    /// returned. Soft limits are reported once each as a system message in the
+    /// conversation.
+    pub async fn run(self, msg_store: Arc<MsgStore>) -> Option<BudgetBreach> {
+        let mut stream = msg_store.history_plus_stream();
+        let mut run_usage: HashMap<String, ModelTokenUsage> = HashMap::new();
+        let mut warned = vec![false; self.budgets.len()];
+
+        while let Some(Ok(msg)) = stream.next().await {
+            let patch = match msg {
+                LogMsg::JsonPatch(patch) => patch,
+                LogMsg::Finished => break,
+                _ => continue,
+            };
+            let Some((_, entry)) = extract_normalized_entry_from_patch(&patch) else {
+                continue;
+            };
+            let NormalizedEntryType::TokenUsageInfo(usage) = entry.entry_type else {
+                continue;
+            };
+            if usage.run_usage.is_empty() {
+                continue;
+            }
+            for model_usage in usage.run_usage {
+                run_usage.insert(model_usage.model.clone(), model_usage);
+            }
+
+            for (index, breach) in self.check(&run_usage).await {
+                if breach.level == BudgetLevel::Soft && warned[index] {
+                    continue;
+                }
+                warned[index] = true;
+                push_system_message(&msg_store, breach.message());
+                if breach.level == BudgetLevel::Hard {
+                    return Some(breach);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Breached budgets by index, hard breaches first so the first one found stops the process
+    async fn check(
+        &self,
+        run_usage: &HashMap<String, ModelTokenUsage>,
+    ) -> Vec<(usize, BudgetBreach)> {
+        let executor = self.executor_profile_id.executor.to_string();
+        let current: Vec<TokenUsageTotal> = run_usage
+            .values()
+            .map(|usage| TokenUsageTotal {
+                executor: executor.clone(),
+                variant: self.executor_profile_id.variant.clone(),
+                model: usage.model.clone(),
+                execution_process_count: 1,
+                input_tokens: usage.input_tokens as i64,
+                output_tokens: usage.output_tokens as i64,
+                cached_input_tokens: usage.cached_input_tokens as i64,
+            })
+            .collect();
+
+        let mut breaches = Vec::new();
+        for (index, budget) in self.budgets.iter().enumerate() {
+            // Earlier processes are read from the database; this one's usage is
+            // taken from the stream since recording it may still be in flight.
+            let filter = if budget.project_id.is_some() {
+                Some(TokenUsageFilter {
+                    project_id: Some(self.project_id),
+                    exclude_execution_process_id: Some(self.execution_process_id),
+                    ..Default::default()
+                })
+            } else if budget.task_id.is_some() {
+                Some(TokenUsageFilter {
+                    task_id: Some(self.task_id),
+                    exclude_execution_process_id: Some(self.execution_process_id),
+                    ..Default::default()
+                })
+            } else {
+                None
+            };
+            let previous = match filter {
+                Some(filter) => match ExecutionProcessTokenUsage::totals(&self.pool, &filter).await
+                {
+                    Ok(totals) => budget_usage(budget.unit, &totals, &self.prices),
+                    Err(e) => {
+                        tracing::error!("Failed to load usage for budget {}: {}", budget.id, e);
+                        continue;
+                    }
+                },
+                None => 0.0,
+            };
+            let used = previous + budget_usage(budget.unit, &current, &self.prices);
+
+            if let Some(level) = breached_level(budget, used) {
+                breaches.push((
+                    index,
+                    BudgetBreach {
+                        budget: budget.clone(),
+                        level,
+                        used,
+                    },
+                ));
+            }
+        }
+        breaches.sort_by_key(|(_, breach)| breach.level != BudgetLevel::Hard);
+        breaches
+    }
+}
+
+fn push_system_message(msg_store: &MsgStore, content: String) {
+    let entry = NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::SystemMessage,
+        content,
+        metadata: None,
+    };
+    // Shares the normalizer's index provider, so the notice never takes an index
+    // the normalizer is about to use
+    let index = EntryIndexProvider::start_from(msg_store).next();
+    msg_store.push_patch(ConversationPatch::add_normalized_entry(index, entry));
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn budget(unit: BudgetUnit, soft_limit: Option<f64>, hard_limit: Option<f64>) -> Budget {
+        Budget {
+            id: Uuid::new_v4(),
+            project_id: Some(Uuid::new_v4()),
+            task_id: None,
+            executor: None,
+            variant: None,
+            unit,
+            soft_limit,
+            hard_limit,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn total(model: &str, input_tokens: i64, cached_input_tokens: i64) -> TokenUsageTotal {
+        TokenUsageTotal {
+            executor: "CLAUDE_CODE".to_string(),
+            variant: None,
+            model: model.to_string(),
+            execution_process_count: 1,
+            input_tokens,
+            output_tokens: 1_000,
+            cached_input_tokens,
+        }
+    }
+
+    #[test]
+    fn token_budgets_skip_cache_reads_and_usd_budgets_skip_unpriced_models() {
+        let prices = vec![TokenPrice {
+            model: Some("claude-".to_string()),
+            executor: None,
+            variant: None,
+            input_per_million: 3.0,
+            output_per_million: 15.0,
+            cached_input_per_million: 0.3,
+        }];
+        let totals = vec![
+            total("claude-sonnet-4", 1_000_000, 1_000_000),
+            total("gpt-5", 1_000_000, 0),
+        ];
+
+        let tokens = budget_usage(BudgetUnit::Tokens, &totals, &prices);
+        assert_eq!(tokens, 2_002_000.0);
+        let usd = budget_usage(BudgetUnit::Usd, &totals, &prices);
+        assert!((usd - 3.315).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hard_limit_outranks_soft_limit() {
+        let budget = budget(BudgetUnit::Usd, Some(5.0), Some(10.0));
+
+        assert_eq!(breached_level(&budget, 4.99), None);
+        assert_eq!(breached_level(&budget, 5.0), Some(BudgetLevel::Soft));
+        assert_eq!(breached_level(&budget, 12.0), Some(BudgetLevel::Hard));
+
+        let warning = BudgetBreach {
+            budget: budget.clone(),
+            level: BudgetLevel::Soft,
+            used: 5.5,
+        };
+        assert_eq!(
+            warning.message(),
+            "Project budget warning: $5.50 of $5.00 spent"
+        );
+    }
+}
@@ -31,7 +31,8 @@ Generate a commit message following this format:
 
 Base the commit message on the actual code changes shown in the diff."#;
 
-pub const DEFAULT_MERGE_COMMIT_MESSAGE_TEMPLATE: &str = "{title} (vibe-kanban {id})\n\n{description}";
+pub const DEFAULT_MERGE_COMMIT_MESSAGE_TEMPLATE: &str =
+    "{title} (vibe-kanban {id})\n\n{description}";
 
 #[derive(Debug, Error)]
 pub enum ConfigError {
@@ -53,6 +54,23 @@ pub type GitHubConfig = versions::v8::GitHubConfig;
 pub type UiLanguage = versions::v8::UiLanguage;
 pub type ShowcaseState = versions::v8::ShowcaseState;
 pub type SendMessageShortcut = versions::v8::SendMessageShortcut;
+pub type DevServerPortConfig = versions::v8::DevServerPortConfig;
+pub type LogRetentionConfig = versions::v8::LogRetentionConfig;
+pub type TokenPrice = versions::v8::TokenPrice;
+pub type ExecutionTimeouts = versions::v8::ExecutionTimeouts;
+pub type ScriptTimeout = versions::v8::ScriptTimeout;
+pub type ConcurrencyLimits = versions::v8::ConcurrencyLimits;
+pub type ExecutorConcurrencyLimit = versions::v8::ExecutorConcurrencyLimit;
+pub type ExecutionRetries = versions::v8::ExecutionRetries;
+pub type ResourceLimits = executors::profile::ResourceLimits;
+pub type ScriptResourceLimits = versions::v8::ScriptResourceLimits;
+pub type ExecutionResourceLimits = versions::v8::ExecutionResourceLimits;
+pub type ScriptSandbox = versions::v8::ScriptSandbox;
+pub type ExecutionSandbox = versions::v8::ExecutionSandbox;
+pub type RepoContainerImage = versions::v8::RepoContainerImage;
+pub type ContainerRuntimeConfig = versions::v8::ContainerRuntimeConfig;
+pub type RemoteExecutionConfig = versions::v8::RemoteExecutionConfig;
+pub type AgentInstructionsConfig = versions::v8::AgentInstructionsConfig;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
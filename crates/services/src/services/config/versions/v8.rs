@@ -1,5 +1,14 @@
+use std::{path::Path, time::Duration};
+
 use anyhow::Error;
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use executors::{
+    actions::{ExecutorAction, ExecutorActionType, script::ScriptContext},
+    executors::BaseCodingAgent,
+    instructions::InstructionsDelivery,
+    oci::ContainerEngine,
+    profile::{ExecutorConfigs, ExecutorProfileId, ResourceLimits},
+    sandbox::SandboxPolicy,
+};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 pub use v7::{
@@ -21,6 +30,348 @@ fn default_commit_reminder_enabled() -> bool {
     true
 }
 
+fn default_container_network() -> String {
+    "host".to_string()
+}
+
+fn default_cache_setup() -> bool {
+    true
+}
+
+fn default_retry_base_delay_secs() -> u32 {
+    30
+}
+
+fn default_retry_max_delay_secs() -> u32 {
+    600
+}
+
+/// Port range handed out to dev servers so parallel workspaces don't collide.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct DevServerPortConfig {
+    pub enabled: bool,
+    pub range_start: u16,
+    pub range_end: u16,
+    /// Extra variable set alongside `PORT`, e.g. `VITE_PORT`
+    pub env_var: Option<String>,
+}
+
+impl Default for DevServerPortConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            range_start: 4100,
+            range_end: 4999,
+            env_var: None,
+        }
+    }
+}
+
+/// How long execution process logs are kept before being pruned to save space.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct LogRetentionConfig {
+    pub enabled: bool,
+    /// Prune logs of processes that finished more than this many days ago
+    pub retention_days: u32,
+    /// Also prune logs of archived workspaces regardless of age
+    pub prune_archived: bool,
+    /// Keep the normalized conversation and drop only the raw output
+    pub keep_normalized: bool,
+}
+
+impl Default for LogRetentionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 30,
+            prune_archived: true,
+            keep_normalized: true,
+        }
+    }
+}
+
+/// Price in USD per million tokens. Applies to usage whose model starts with `model`
+/// and that came from `executor` and `variant`; unset fields match anything. The
+/// first matching entry wins, so list specific entries first.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq)]
+pub struct TokenPrice {
+    /// Model name or prefix, e.g. `claude-sonnet-4`
+    pub model: Option<String>,
+    /// Executor, e.g. `CLAUDE_CODE`
+    pub executor: Option<String>,
+    /// Profile variant, e.g. `PLAN`
+    pub variant: Option<String>,
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    pub cached_input_per_million: f64,
+}
+
+/// Runtime limits of scripts run in `context`
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq)]
+pub struct ScriptTimeout {
+    pub context: ScriptContext,
+    /// Stop the process after running this many seconds
+    pub max_runtime_secs: Option<u32>,
+    /// Stop the process after this many seconds without stdout or stderr output
+    pub max_idle_secs: Option<u32>,
+}
+
+/// Limits after which running scripts are stopped. Coding agents take theirs
+/// from the `max_runtime_secs` and `max_idle_secs` of their profile.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq)]
+pub struct ExecutionTimeouts {
+    #[serde(default)]
+    pub scripts: Vec<ScriptTimeout>,
+}
+
+impl ExecutionTimeouts {
+    /// Maximum runtime and maximum time without output for a process running `action`
+    pub fn limits_for(&self, action: &ExecutorAction) -> (Option<Duration>, Option<Duration>) {
+        self.limits_in(action, &ExecutorConfigs::get_cached())
+    }
+
+    fn limits_in(
+        &self,
+        action: &ExecutorAction,
+        profiles: &ExecutorConfigs,
+    ) -> (Option<Duration>, Option<Duration>) {
+        let limits = match action.typ() {
+            ExecutorActionType::ScriptRequest(request) => self
+                .scripts
+                .iter()
+                .find(|timeout| timeout.context == request.context)
+                .map(|timeout| (timeout.max_runtime_secs, timeout.max_idle_secs)),
+            _ => action.executor_profile_id().map(|profile| {
+                let settings = profiles.get_settings(profile);
+                (settings.max_runtime_secs, settings.max_idle_secs)
+            }),
+        };
+        let (max_runtime, max_idle) = limits.unwrap_or_default();
+        let to_duration = |secs: Option<u32>| {
+            secs.filter(|secs| *secs > 0)
+                .map(|secs| Duration::from_secs(secs.into()))
+        };
+        (to_duration(max_runtime), to_duration(max_idle))
+    }
+}
+
+/// Resource limits of scripts run in `context`
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq)]
+pub struct ScriptResourceLimits {
+    pub context: ScriptContext,
+    pub limits: ResourceLimits,
+}
+
+/// Resource limits applied to scripts on Linux. Coding agents take theirs
+/// from the `resource_limits` of their profile.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq)]
+pub struct ExecutionResourceLimits {
+    #[serde(default)]
+    pub scripts: Vec<ScriptResourceLimits>,
+}
+
+impl ExecutionResourceLimits {
+    /// Limits for a process running `action`, if any are configured
+    pub fn limits_for(&self, action: &ExecutorAction) -> Option<ResourceLimits> {
+        match action.typ() {
+            ExecutorActionType::ScriptRequest(request) => self
+                .scripts
+                .iter()
+                .find(|entry| entry.context == request.context)
+                .map(|entry| entry.limits.clone()),
+            _ => action.executor_profile_id().and_then(|profile| {
+                ExecutorConfigs::get_cached()
+                    .get_settings(profile)
+                    .resource_limits
+            }),
+        }
+    }
+}
+
+/// Sandbox for scripts run in `context`
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq)]
+pub struct ScriptSandbox {
+    pub context: ScriptContext,
+    pub policy: SandboxPolicy,
+}
+
+/// Which scripts run sandboxed with bubblewrap on Linux; scripts without an
+/// entry run unsandboxed. Coding agents take theirs from the
+/// `sandbox_policy` of their profile.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq)]
+pub struct ExecutionSandbox {
+    #[serde(default)]
+    pub scripts: Vec<ScriptSandbox>,
+}
+
+impl ExecutionSandbox {
+    /// Sandbox policy for a process running `action`, if it runs sandboxed
+    pub fn policy_for(&self, action: &ExecutorAction) -> Option<SandboxPolicy> {
+        match action.typ() {
+            ExecutorActionType::ScriptRequest(request) => self
+                .scripts
+                .iter()
+                .find(|entry| entry.context == request.context)
+                .map(|entry| entry.policy.clone()),
+            _ => action.executor_profile_id().and_then(|profile| {
+                ExecutorConfigs::get_cached()
+                    .get_settings(profile)
+                    .sandbox_policy
+            }),
+        }
+    }
+}
+
+/// Base image for the containers of one repository
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct RepoContainerImage {
+    /// Repository name or path
+    pub repo: String,
+    pub image: String,
+}
+
+/// Runs execution processes in OCI containers instead of on the host. Each
+/// process gets its own container with the workspace bind-mounted.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ContainerRuntimeConfig {
+    /// Engine to run containers with; unset runs processes on the host.
+    /// Takes effect on the next start.
+    #[serde(default)]
+    pub engine: Option<ContainerEngine>,
+    /// Engine API socket; unset uses the engine's default socket
+    #[serde(default)]
+    pub socket: Option<String>,
+    /// Image for repositories without an entry in `repos`
+    #[serde(default)]
+    pub default_image: Option<String>,
+    #[serde(default)]
+    pub repos: Vec<RepoContainerImage>,
+    /// Network containers join, `host` by default so loopback services stay
+    /// reachable
+    #[serde(default = "default_container_network")]
+    pub network: String,
+    /// Additional `run` arguments, e.g. extra `--volume` mounts
+    #[serde(default)]
+    pub run_args: Vec<String>,
+    /// Commit the container after a successful setup script and start the
+    /// repository's later containers from that image
+    #[serde(default = "default_cache_setup")]
+    pub cache_setup: bool,
+}
+
+impl Default for ContainerRuntimeConfig {
+    fn default() -> Self {
+        Self {
+            engine: None,
+            socket: None,
+            default_image: None,
+            repos: Vec::new(),
+            network: default_container_network(),
+            run_args: Vec::new(),
+            cache_setup: default_cache_setup(),
+        }
+    }
+}
+
+impl ContainerRuntimeConfig {
+    /// Base image for a repository, if containers are configured for it
+    pub fn image_for(&self, repo_name: &str, repo_path: &Path) -> Option<&str> {
+        self.repos
+            .iter()
+            .find(|entry| entry.repo == repo_name || Path::new(&entry.repo) == repo_path)
+            .map(|entry| entry.image.as_str())
+            .or(self.default_image.as_deref())
+    }
+}
+
+/// Runs execution processes on an SSH host, in worktrees created there,
+/// while the board and database stay on this machine
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct RemoteExecutionConfig {
+    /// `user@host` or a host alias from `~/.ssh/config`; unset runs
+    /// processes locally. Read when the server starts.
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub identity_file: Option<String>,
+    /// Additional `ssh` arguments, e.g. `["-o", "ProxyJump=bastion"]`
+    #[serde(default)]
+    pub ssh_options: Vec<String>,
+}
+
+/// How the project and repository agent instructions reach coding agents
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct AgentInstructionsConfig {
+    #[serde(default)]
+    pub delivery: InstructionsDelivery,
+    /// Add the instructions to follow-up prompts as well as the first one;
+    /// ignored with `file` delivery
+    #[serde(default)]
+    pub include_follow_ups: bool,
+}
+
+/// Maximum number of coding agents running at once with `executor`
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ExecutorConcurrencyLimit {
+    /// Executor, e.g. `CLAUDE_CODE`
+    pub executor: String,
+    pub max_running: u32,
+}
+
+/// Limits on coding agents running at the same time. Starts beyond a limit
+/// wait in the execution queue until a running agent finishes.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ConcurrencyLimits {
+    /// Maximum number of coding agents running at once across all executors
+    #[serde(default)]
+    pub max_running: Option<u32>,
+    #[serde(default)]
+    pub executors: Vec<ExecutorConcurrencyLimit>,
+}
+
+impl ConcurrencyLimits {
+    /// Limit for agents run with `executor`, if any
+    pub fn executor_limit(&self, executor: &str) -> Option<u32> {
+        self.executors
+            .iter()
+            .find(|limit| limit.executor == executor)
+            .map(|limit| limit.max_running)
+    }
+}
+
+/// Delays of the automatic follow-ups after a coding agent fails on a
+/// retryable provider error, such as a rate limit or an overloaded API. The
+/// delay doubles with each attempt; the number of retries is the
+/// `max_retries` of the agent's profile.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ExecutionRetries {
+    /// Delay before the first retry
+    #[serde(default = "default_retry_base_delay_secs")]
+    pub base_delay_secs: u32,
+    #[serde(default = "default_retry_max_delay_secs")]
+    pub max_delay_secs: u32,
+}
+
+impl Default for ExecutionRetries {
+    fn default() -> Self {
+        Self {
+            base_delay_secs: default_retry_base_delay_secs(),
+            max_delay_secs: default_retry_max_delay_secs(),
+        }
+    }
+}
+
+impl ExecutionRetries {
+    /// Delay before retry number `attempt`, starting at 1
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let base = Duration::from_secs(self.base_delay_secs.into());
+        base.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(Duration::from_secs(self.max_delay_secs.into()))
+    }
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
 pub enum SendMessageShortcut {
     #[default]
@@ -63,6 +414,28 @@ pub struct Config {
     pub merge_commit_message_template: Option<String>,
     #[serde(default)]
     pub send_message_shortcut: SendMessageShortcut,
+    #[serde(default)]
+    pub dev_server_ports: DevServerPortConfig,
+    #[serde(default)]
+    pub log_retention: LogRetentionConfig,
+    #[serde(default)]
+    pub token_prices: Vec<TokenPrice>,
+    #[serde(default)]
+    pub execution_timeouts: ExecutionTimeouts,
+    #[serde(default)]
+    pub concurrency_limits: ConcurrencyLimits,
+    #[serde(default)]
+    pub execution_retries: ExecutionRetries,
+    #[serde(default)]
+    pub resource_limits: ExecutionResourceLimits,
+    #[serde(default)]
+    pub sandbox: ExecutionSandbox,
+    #[serde(default)]
+    pub container_runtime: ContainerRuntimeConfig,
+    #[serde(default)]
+    pub remote_execution: RemoteExecutionConfig,
+    #[serde(default)]
+    pub agent_instructions: AgentInstructionsConfig,
 }
 
 impl Config {
@@ -90,6 +463,17 @@ impl Config {
             commit_reminder_prompt: None,
             merge_commit_message_template: None,
             send_message_shortcut: SendMessageShortcut::default(),
+            dev_server_ports: DevServerPortConfig::default(),
+            log_retention: LogRetentionConfig::default(),
+            token_prices: Vec::new(),
+            execution_timeouts: ExecutionTimeouts::default(),
+            concurrency_limits: ConcurrencyLimits::default(),
+            execution_retries: ExecutionRetries::default(),
+            resource_limits: ExecutionResourceLimits::default(),
+            sandbox: ExecutionSandbox::default(),
+            container_runtime: ContainerRuntimeConfig::default(),
+            remote_execution: RemoteExecutionConfig::default(),
+            agent_instructions: AgentInstructionsConfig::default(),
         }
     }
 
@@ -145,6 +529,134 @@ impl Default for Config {
             commit_reminder_prompt: None,
             merge_commit_message_template: None,
             send_message_shortcut: SendMessageShortcut::default(),
+            dev_server_ports: DevServerPortConfig::default(),
+            log_retention: LogRetentionConfig::default(),
+            token_prices: Vec::new(),
+            execution_timeouts: ExecutionTimeouts::default(),
+            concurrency_limits: ConcurrencyLimits::default(),
+            execution_retries: ExecutionRetries::default(),
+            resource_limits: ExecutionResourceLimits::default(),
+            sandbox: ExecutionSandbox::default(),
+            container_runtime: ContainerRuntimeConfig::default(),
+            remote_execution: RemoteExecutionConfig::default(),
+            agent_instructions: AgentInstructionsConfig::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use executors::{
+        actions::{
+            coding_agent_initial::CodingAgentInitialRequest,
+            script::{ScriptRequest, ScriptRequestLanguage},
+        },
+        profile::ProfileSettings,
+    };
+
+    use super::*;
+
+    fn agent_action(variant: Option<&str>) -> ExecutorAction {
+        let executor_profile_id = ExecutorProfileId {
+            executor: BaseCodingAgent::ClaudeCode,
+            variant: variant.map(str::to_string),
+        };
+        ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt: "Fix the build".to_string(),
+                executor_profile_id,
+                working_dir: None,
+            }),
+            None,
+        )
+    }
+
+    fn script_action(context: ScriptContext) -> ExecutorAction {
+        ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: "npm install".to_string(),
+                language: ScriptRequestLanguage::Bash,
+                context,
+                working_dir: None,
+            }),
+            None,
+        )
+    }
+
+    fn profiles_with(settings: &[(&str, Option<u32>, Option<u32>)]) -> ExecutorConfigs {
+        let mut profiles = ExecutorConfigs::from_defaults();
+        let variants = settings
+            .iter()
+            .map(|(variant, max_runtime_secs, max_idle_secs)| {
+                let settings = ProfileSettings {
+                    max_runtime_secs: *max_runtime_secs,
+                    max_idle_secs: *max_idle_secs,
+                    ..Default::default()
+                };
+                (variant.to_string(), settings)
+            })
+            .collect();
+        profiles
+            .settings
+            .insert(BaseCodingAgent::ClaudeCode, variants);
+        profiles
+    }
+
+    #[test]
+    fn agent_variant_overrides_default_profile() {
+        let profiles =
+            profiles_with(&[("DEFAULT", Some(3600), Some(600)), ("PLAN", Some(60), None)]);
+        let timeouts = ExecutionTimeouts::default();
+
+        assert_eq!(
+            timeouts.limits_in(&agent_action(Some("PLAN")), &profiles),
+            (Some(Duration::from_secs(60)), None)
+        );
+        assert_eq!(
+            timeouts.limits_in(&agent_action(None), &profiles),
+            (
+                Some(Duration::from_secs(3600)),
+                Some(Duration::from_secs(600))
+            )
+        );
+        // Unknown variants run with the default profile
+        assert_eq!(
+            timeouts.limits_in(&agent_action(Some("NO_SUCH_VARIANT")), &profiles),
+            (
+                Some(Duration::from_secs(3600)),
+                Some(Duration::from_secs(600))
+            )
+        );
+    }
+
+    #[test]
+    fn agents_without_settings_run_unlimited() {
+        let profiles = profiles_with(&[("PLAN", Some(60), Some(30))]);
+
+        assert_eq!(
+            ExecutionTimeouts::default().limits_in(&agent_action(Some("OPUS")), &profiles),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn scripts_take_the_limits_of_their_context() {
+        let profiles = profiles_with(&[("DEFAULT", Some(3600), Some(600))]);
+        let timeouts = ExecutionTimeouts {
+            scripts: vec![ScriptTimeout {
+                context: ScriptContext::SetupScript,
+                max_runtime_secs: Some(300),
+                max_idle_secs: Some(0),
+            }],
+        };
+
+        assert_eq!(
+            timeouts.limits_in(&script_action(ScriptContext::SetupScript), &profiles),
+            (Some(Duration::from_secs(300)), None)
+        );
+        assert_eq!(
+            timeouts.limits_in(&script_action(ScriptContext::DevServer), &profiles),
+            (None, None)
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{Error as AnyhowError, anyhow};
@@ -11,13 +12,18 @@ use db::{
     models::{
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
         execution_process::{
-            CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessError,
-            ExecutionProcessRunReason, ExecutionProcessStatus,
+            CreateExecutionProcess, ExecutionContext, ExecutionProcess,
+            ExecutionProcessCompletionReason, ExecutionProcessError, ExecutionProcessRunReason,
+            ExecutionProcessStatus,
         },
+        execution_process_conversation::ExecutionProcessConversation,
+        execution_process_log_chunk::{ExecutionProcessLogChunk, LogChunkError, LogChunkWriter},
         execution_process_logs::ExecutionProcessLogs,
+        execution_process_port::ExecutionProcessPort,
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        execution_process_token_usage::ExecutionProcessTokenUsage,
         repo::Repo,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
@@ -35,17 +41,24 @@ use executors::{
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
+    env::{ExecutionEnv, RepoContext},
     executors::{ExecutorError, StandardCodingAgentExecutor},
-    logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
+    logs::{
+        ModelTokenUsage, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        utils::{
+            ConversationPatch, EntryIndexProvider, patch::extract_normalized_entry_from_patch,
+        },
+    },
     profile::ExecutorProfileId,
 };
 use futures::{StreamExt, future, stream::BoxStream};
-use git::{GitService, GitServiceError};
+use git::{DiffTarget, GitService, GitServiceError};
 use json_patch::Patch;
-use sqlx::Error as SqlxError;
+use sqlx::{Error as SqlxError, SqlitePool};
 use thiserror::Error;
 use tokio::{sync::RwLock, task::JoinHandle};
 use utils::{
+    diff::create_unified_diff,
     log_msg::LogMsg,
     msg_store::MsgStore,
     text::{git_branch_id, short_uuid},
@@ -53,7 +66,15 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
-    notification::NotificationService, workspace_manager::WorkspaceError as WorkspaceManagerError,
+    config::LogRetentionConfig,
+    dev_server_ports::DevServerPortError,
+    execution_scheduler::ExecutionScheduler,
+    executor_fallback,
+    log_retention::{
+        NORMALIZATION_TIMEOUT, PRUNE_BATCH_SIZE, compact_conversation_patches, patches_to_jsonl,
+    },
+    notification::NotificationService,
+    workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
 pub type ContainerRef = String;
@@ -76,6 +97,10 @@ pub enum ContainerError {
     Session(#[from] SessionError),
     #[error(transparent)]
     ExecutionProcess(#[from] ExecutionProcessError),
+    #[error(transparent)]
+    DevServerPort(#[from] DevServerPortError),
+    #[error(transparent)]
+    LogStorage(#[from] LogChunkError),
     #[error("Io error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Failed to kill process: {0}")]
@@ -94,6 +119,8 @@ pub trait ContainerService {
 
     fn notification_service(&self) -> &NotificationService;
 
+    fn scheduler(&self) -> &ExecutionScheduler;
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf;
 
     async fn available_agent_slash_commands(
@@ -132,11 +159,19 @@ pub trait ContainerService {
         }
         .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
 
+        let mut env = ExecutionEnv::new(
+            RepoContext::new(agent_workdir.clone(), Vec::new()),
+            false,
+            String::new(),
+        );
+        env.sandbox = ExecutorConfigs::get_cached()
+            .get_settings(&executor_profile_id)
+            .sandbox_policy;
+
         #[cfg(feature = "qa-mode")]
         {
-            let _ = executor_profile_id;
             let agent = QaMockExecutor;
-            let stream = agent.available_slash_commands(&agent_workdir).await?;
+            let stream = agent.available_slash_commands(&agent_workdir, &env).await?;
             return Ok(Some(stream));
         }
         #[cfg(not(feature = "qa-mode"))]
@@ -144,7 +179,9 @@ pub trait ContainerService {
             let executor =
                 ExecutorConfigs::get_cached().get_coding_agent_or_default(&executor_profile_id);
 
-            let stream = executor.available_slash_commands(&agent_workdir).await?;
+            let stream = executor
+                .available_slash_commands(&agent_workdir, &env)
+                .await?;
             Ok(Some(stream))
         }
     }
@@ -227,6 +264,23 @@ pub trait ContainerService {
             tracing::error!("Failed to update task status to InReview: {e}");
         }
 
+        if let Some(reason) = ctx.execution_process.completion_reason {
+            let title = format!("Task Stopped: {}", ctx.task.title);
+            let cause = match reason {
+                ExecutionProcessCompletionReason::BudgetExceeded => "went over its budget",
+                ExecutionProcessCompletionReason::TimedOut => "reached its maximum runtime",
+                ExecutionProcessCompletionReason::IdleTimeout => "produced no output for too long",
+                ExecutionProcessCompletionReason::OutOfMemory => "went over its memory limit",
+                ExecutionProcessCompletionReason::ProcessLimit => "hit its process limit",
+            };
+            let message = format!(
+                "⛔ '{}' was stopped because it {}\nBranch: {:?}\nExecutor: {:?}",
+                ctx.task.title, cause, ctx.workspace.branch, ctx.session.executor
+            );
+            self.notification_service().notify(&title, &message).await;
+            return;
+        }
+
         // Skip notification if process was intentionally killed by user
         if matches!(ctx.execution_process.status, ExecutionProcessStatus::Killed) {
             return;
@@ -257,6 +311,10 @@ pub trait ContainerService {
     async fn cleanup_orphan_executions(&self) -> Result<(), ContainerError> {
         let running_processes = ExecutionProcess::find_running(&self.db().pool).await?;
         for process in running_processes {
+            // Scheduled coding agents were never spawned and stay queued
+            if self.scheduler().is_scheduled(process.id).await? {
+                continue;
+            }
             tracing::info!(
                 "Found orphaned execution process {} for session {}",
                 process.id,
@@ -325,6 +383,14 @@ pub trait ContainerService {
                 );
             }
         }
+        // Orphaned dev servers no longer hold their ports
+        match ExecutionProcessPort::delete_stale(&self.db().pool).await {
+            Ok(released) if released > 0 => {
+                tracing::info!("Released {} stale dev server port(s)", released);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!("Failed to release stale dev server ports: {}", e),
+        }
         Ok(())
     }
 
@@ -775,6 +841,70 @@ pub trait ContainerService {
         stats_only: bool,
     ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, ContainerError>;
 
+    /// Render the workspace's changes against each repo's target branch as a single
+    /// unified diff. Paths are prefixed with the repo name when there are several repos.
+    async fn workspace_unified_diff(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<String, ContainerError> {
+        let container_ref = self.ensure_container_exists(workspace).await?;
+        let workspace_root = PathBuf::from(container_ref);
+
+        let pool = &self.db().pool;
+        let target_branches: HashMap<_, _> =
+            WorkspaceRepo::find_by_workspace_id(pool, workspace.id)
+                .await?
+                .into_iter()
+                .map(|wr| (wr.repo_id, wr.target_branch))
+                .collect();
+        let repositories = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+        let prefix_paths = repositories.len() > 1;
+
+        let mut unified = String::new();
+        for repo in repositories {
+            let Some(target_branch) = target_branches.get(&repo.id) else {
+                continue;
+            };
+            let worktree_path = workspace_root.join(&repo.name);
+            let base_commit =
+                self.git()
+                    .get_base_commit(&repo.path, &workspace.branch, target_branch)?;
+            let diffs = self.git().get_diffs(
+                DiffTarget::Worktree {
+                    worktree_path: &worktree_path,
+                    base_commit: &base_commit,
+                },
+                None,
+            )?;
+
+            for diff in diffs {
+                let Some(path) = diff.new_path.as_ref().or(diff.old_path.as_ref()) else {
+                    continue;
+                };
+                let path = if prefix_paths {
+                    format!("{}/{}", repo.name, path)
+                } else {
+                    path.clone()
+                };
+                if diff.content_omitted {
+                    unified.push_str(&format!(
+                        "--- a/{path}\n+++ b/{path}\n# content omitted (+{} -{})\n",
+                        diff.additions.unwrap_or(0),
+                        diff.deletions.unwrap_or(0)
+                    ));
+                    continue;
+                }
+                unified.push_str(&create_unified_diff(
+                    &path,
+                    diff.old_content.as_deref().unwrap_or(""),
+                    diff.new_content.as_deref().unwrap_or(""),
+                ));
+            }
+        }
+
+        Ok(unified)
+    }
+
     /// Fetch the MsgStore for a given execution ID, panicking if missing.
     async fn get_msg_store_by_id(&self, uuid: &Uuid) -> Option<Arc<MsgStore>> {
         let map = self.msg_stores().read().await;
@@ -812,18 +942,27 @@ pub trait ContainerService {
                     .boxed(),
             );
         } else {
-            // Fallback: load from DB and create direct stream
-            let log_records =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
-                    Ok(records) if !records.is_empty() => records,
-                    Ok(_) => return None, // No logs exist
-                    Err(e) => {
-                        tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
-                        return None;
-                    }
-                };
+            // Fallback: replay from DB, legacy rows first, then chunk by chunk
+            let pool = self.db().pool.clone();
+            let log_records = match ExecutionProcessLogs::find_by_execution_id(&pool, *id).await {
+                Ok(records) => records,
+                Err(e) => {
+                    tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
+                    return None;
+                }
+            };
+            let first_chunk = match ExecutionProcessLogChunk::find_by_index(&pool, *id, 0).await {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    tracing::error!("Failed to fetch log chunks for execution {}: {}", id, e);
+                    return None;
+                }
+            };
+            if log_records.is_empty() && first_chunk.is_none() {
+                return None; // No logs exist
+            }
 
-            let messages = match ExecutionProcessLogs::parse_logs(&log_records) {
+            let legacy_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
                 Ok(msgs) => msgs,
                 Err(e) => {
                     tracing::error!("Failed to parse logs for execution {}: {}", id, e);
@@ -831,15 +970,20 @@ pub trait ContainerService {
                 }
             };
 
-            // Direct stream from parsed messages
-            let stream = futures::stream::iter(
-                messages
-                    .into_iter()
-                    .filter(|m| matches!(m, LogMsg::Stdout(_) | LogMsg::Stderr(_)))
-                    .chain(std::iter::once(LogMsg::Finished))
-                    .map(Ok::<_, std::io::Error>),
-            )
-            .boxed();
+            let chunk_messages = match first_chunk {
+                Some(chunk) => stream_log_chunks(pool, chunk),
+                None => futures::stream::empty().boxed(),
+            };
+
+            let stream = futures::stream::iter(legacy_messages.into_iter().map(Ok))
+                .chain(chunk_messages)
+                .filter(|msg| {
+                    future::ready(matches!(msg, Ok(LogMsg::Stdout(_) | LogMsg::Stderr(_))))
+                })
+                .chain(futures::stream::once(async {
+                    Ok::<_, std::io::Error>(LogMsg::Finished)
+                }))
+                .boxed();
 
             Some(stream)
         }
@@ -860,163 +1004,307 @@ pub trait ContainerService {
                     }))
                     .boxed(),
             )
+        } else if let Some(patches) = self.stored_conversation(id).await {
+            Some(
+                futures::stream::iter(
+                    patches
+                        .into_iter()
+                        .map(LogMsg::JsonPatch)
+                        .chain(std::iter::once(LogMsg::Finished))
+                        .map(Ok::<_, std::io::Error>),
+                )
+                .boxed(),
+            )
         } else {
             // Fallback: load from DB and normalize
-            let log_records =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
-                    Ok(records) if !records.is_empty() => records,
-                    Ok(_) => return None, // No logs exist
-                    Err(e) => {
-                        tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
-                        return None;
-                    }
-                };
+            self.normalize_stored_logs(id, true).await
+        }
+    }
 
-            let raw_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
-                Ok(msgs) => msgs,
-                Err(e) => {
-                    tracing::error!("Failed to parse logs for execution {}: {}", id, e);
-                    return None;
-                }
-            };
+    /// Rebuild the normalized conversation of a finished execution process from its
+    /// stored logs. `recreate_worktree` restores a cleaned-up worktree first so the
+    /// normalizer sees the same paths it did during the run. The stream ends once
+    /// every normalizer is done with the logs and has dropped the store they were
+    /// loaded into.
+    async fn normalize_stored_logs(
+        &self,
+        id: &Uuid,
+        recreate_worktree: bool,
+    ) -> Option<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>> {
+        let raw_messages = match ExecutionProcessLogs::load_messages(&self.db().pool, *id).await {
+            Ok(msgs) if !msgs.is_empty() => msgs,
+            Ok(_) => return None, // No logs exist
+            Err(e) => {
+                tracing::error!("Failed to load logs for execution {}: {}", id, e);
+                return None;
+            }
+        };
 
-            // Create temporary store and populate
-            // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
-            let temp_store = Arc::new(MsgStore::new());
-            for msg in raw_messages {
-                if matches!(
-                    msg,
-                    LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
-                ) {
-                    temp_store.push(msg);
-                }
+        // Pruned processes keep only their compacted conversation, which is replayed as-is
+        if !raw_messages
+            .iter()
+            .any(|msg| matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)))
+        {
+            return Some(
+                futures::stream::iter(
+                    raw_messages
+                        .into_iter()
+                        .filter(|msg| matches!(msg, LogMsg::JsonPatch(_)))
+                        .chain(std::iter::once(LogMsg::Finished))
+                        .map(Ok::<_, std::io::Error>),
+                )
+                .boxed(),
+            );
+        }
+
+        // Create temporary store and populate
+        // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
+        let temp_store = Arc::new(MsgStore::new());
+        for msg in raw_messages {
+            if matches!(
+                msg,
+                LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
+            ) {
+                temp_store.push(msg);
             }
-            temp_store.push_finished();
+        }
+        temp_store.push_finished();
 
-            let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
-                Ok(Some(process)) => process,
+        let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
+            Ok(Some(process)) => process,
+            Ok(None) => {
+                tracing::error!("No execution process found for ID: {}", id);
+                return None;
+            }
+            Err(e) => {
+                tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                return None;
+            }
+        };
+
+        // Get the workspace to determine correct directory
+        let (workspace, _session) =
+            match process.parent_workspace_and_session(&self.db().pool).await {
+                Ok(Some((workspace, session))) => (workspace, session),
                 Ok(None) => {
-                    tracing::error!("No execution process found for ID: {}", id);
+                    tracing::error!(
+                        "No workspace/session found for session ID: {}",
+                        process.session_id
+                    );
                     return None;
                 }
                 Err(e) => {
-                    tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                    tracing::error!(
+                        "Failed to fetch workspace for session {}: {}",
+                        process.session_id,
+                        e
+                    );
                     return None;
                 }
             };
 
-            // Get the workspace to determine correct directory
-            let (workspace, _session) =
-                match process.parent_workspace_and_session(&self.db().pool).await {
-                    Ok(Some((workspace, session))) => (workspace, session),
-                    Ok(None) => {
-                        tracing::error!(
-                            "No workspace/session found for session ID: {}",
-                            process.session_id
-                        );
-                        return None;
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to fetch workspace for session {}: {}",
-                            process.session_id,
-                            e
-                        );
-                        return None;
-                    }
-                };
-
-            if let Err(err) = self.ensure_container_exists(&workspace).await {
-                tracing::warn!(
-                    "Failed to recreate worktree before log normalization for workspace {}: {}",
-                    workspace.id,
-                    err
-                );
-            }
+        if recreate_worktree && let Err(err) = self.ensure_container_exists(&workspace).await {
+            tracing::warn!(
+                "Failed to recreate worktree before log normalization for workspace {}: {}",
+                workspace.id,
+                err
+            );
+        }
 
-            let current_dir = self.workspace_to_current_dir(&workspace);
+        let current_dir = self.workspace_to_current_dir(&workspace);
 
-            let executor_action = if let Ok(executor_action) = process.executor_action() {
-                executor_action
-            } else {
-                tracing::error!(
-                    "Failed to parse executor action: {:?}",
-                    process.executor_action()
-                );
-                return None;
-            };
+        let executor_action = if let Ok(executor_action) = process.executor_action() {
+            executor_action
+        } else {
+            tracing::error!(
+                "Failed to parse executor action: {:?}",
+                process.executor_action()
+            );
+            return None;
+        };
 
-            // Spawn normalizer on populated store
-            match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_profile_id);
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
+        // Spawn normalizer on populated store
+        match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                #[cfg(feature = "qa-mode")]
+                {
+                    let executor = QaMockExecutor;
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_profile_id);
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
+                #[cfg(not(feature = "qa-mode"))]
+                {
+                    let executor = ExecutorConfigs::get_cached()
+                        .get_coding_agent_or_default(&request.executor_profile_id);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
                 #[cfg(feature = "qa-mode")]
-                ExecutorActionType::ReviewRequest(_request) => {
+                {
                     let executor = QaMockExecutor;
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
                 #[cfg(not(feature = "qa-mode"))]
-                ExecutorActionType::ReviewRequest(request) => {
+                {
                     let executor = ExecutorConfigs::get_cached()
                         .get_coding_agent_or_default(&request.executor_profile_id);
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
-                _ => {
-                    tracing::debug!(
-                        "Executor action doesn't support log normalization: {:?}",
-                        process.executor_action()
-                    );
-                    return None;
+            }
+            #[cfg(feature = "qa-mode")]
+            ExecutorActionType::ReviewRequest(_request) => {
+                let executor = QaMockExecutor;
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            #[cfg(not(feature = "qa-mode"))]
+            ExecutorActionType::ReviewRequest(request) => {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_profile_id);
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            _ => {
+                tracing::debug!(
+                    "Executor action doesn't support log normalization: {:?}",
+                    process.executor_action()
+                );
+                return None;
+            }
+        }
+        Some(
+            temp_store
+                .history_plus_stream()
+                .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
+                .chain(futures::stream::once(async {
+                    Ok::<_, std::io::Error>(LogMsg::Finished)
+                }))
+                .boxed(),
+        )
+    }
+
+    /// Apply the log retention policy to the next batch of finished execution processes
+    /// and return how many were pruned. With `keep_normalized`, the compacted
+    /// conversation is stored and read back before the raw output is dropped;
+    /// processes whose conversation can't be kept that way keep their logs and are
+    /// added to `kept`, which later batches skip.
+    async fn prune_expired_logs(
+        &self,
+        config: &LogRetentionConfig,
+        kept: &mut HashSet<Uuid>,
+    ) -> Result<usize, ContainerError> {
+        let pool = &self.db().pool;
+        let expired = ExecutionProcessLogs::find_expired_execution_ids(
+            pool,
+            i64::from(config.retention_days),
+            config.prune_archived,
+            PRUNE_BATCH_SIZE + kept.len() as i64,
+        )
+        .await?;
+
+        let mut pruned = 0;
+        for id in expired.into_iter().filter(|id| !kept.contains(id)) {
+            let retained = if config.keep_normalized {
+                match self.retained_conversation(&id).await {
+                    Ok(retained) => retained,
+                    Err(e) => {
+                        tracing::warn!(
+                            "Keeping the logs of execution {}, its conversation could not be stored: {}",
+                            id,
+                            e
+                        );
+                        kept.insert(id);
+                        continue;
+                    }
+                }
+            } else {
+                None
+            };
+            ExecutionProcessLogs::prune(pool, id, retained.as_deref()).await?;
+            pruned += 1;
+        }
+
+        Ok(pruned)
+    }
+
+    /// Normalized conversation patches of a process: a snapshot of the live store while
+    /// it runs, then the conversation stored when it finished. Processes from before
+    /// conversations were stored are re-normalized from their logs.
+    async fn normalized_patches(&self, id: &Uuid, recreate_worktree: bool) -> Option<Vec<Patch>> {
+        if let Some(store) = self.get_msg_store_by_id(id).await {
+            return Some(json_patches(store.get_history()));
+        }
+
+        if let Some(patches) = self.stored_conversation(id).await {
+            return Some(patches);
+        }
+
+        let stream = self.normalize_stored_logs(id, recreate_worktree).await?;
+        collect_normalized_patches(stream)
+            .await
+            .inspect_err(|e| tracing::warn!("Failed to normalize logs for execution {}: {}", id, e))
+            .ok()
+    }
+
+    /// Conversation patches stored when the process finished, if any. Executors
+    /// that push their entries straight into the store have no logs to
+    /// re-normalize, so this is the only record of their conversation.
+    async fn stored_conversation(&self, id: &Uuid) -> Option<Vec<Patch>> {
+        match ExecutionProcessConversation::find_by_execution_id(&self.db().pool, *id).await {
+            Ok(Some(conversation)) => match conversation.messages() {
+                Ok(messages) => Some(json_patches(messages)),
+                Err(e) => {
+                    tracing::warn!("Failed to decode conversation for execution {}: {}", id, e);
+                    None
                 }
+            },
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Failed to load conversation for execution {}: {}", id, e);
+                None
             }
-            Some(
-                temp_store
-                    .history_plus_stream()
-                    .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
-                    .chain(futures::stream::once(async {
-                        Ok::<_, std::io::Error>(LogMsg::Finished)
-                    }))
-                    .boxed(),
-            )
         }
     }
 
+    /// The compacted conversation to keep in place of a finished process's logs,
+    /// or `None` for processes without one. A conversation that wasn't stored when
+    /// the process finished is re-normalized to completion from the logs, then
+    /// stored and read back, so the logs are only dropped once it is persisted.
+    async fn retained_conversation(&self, id: &Uuid) -> Result<Option<String>, ContainerError> {
+        let pool = &self.db().pool;
+        if let Some(conversation) =
+            ExecutionProcessConversation::find_by_execution_id(pool, *id).await?
+        {
+            let patches = json_patches(conversation.messages()?);
+            return Ok(Some(
+                patches_to_jsonl(patches).map_err(|e| ContainerError::Other(e.into()))?,
+            ));
+        }
+
+        let Some(stream) = self.normalize_stored_logs(id, false).await else {
+            return Ok(None);
+        };
+        let patches = collect_normalized_patches(stream).await?;
+        let patches = compact_conversation_patches(&patches).unwrap_or(patches);
+        if patches.is_empty() {
+            return Ok(None);
+        }
+        let jsonl = patches_to_jsonl(patches).map_err(|e| ContainerError::Other(e.into()))?;
+
+        ExecutionProcessConversation::upsert(pool, *id, &jsonl).await?;
+        let stored = ExecutionProcessConversation::find_by_execution_id(pool, *id)
+            .await?
+            .ok_or_else(|| anyhow!("the stored conversation is missing"))?;
+        if stored.messages()?.len() != jsonl.lines().count() {
+            return Err(
+                anyhow!("the stored conversation does not match the normalized one").into(),
+            );
+        }
+        Ok(Some(jsonl))
+    }
+
     fn spawn_stream_raw_logs_to_db(&self, execution_id: &Uuid) -> JoinHandle<()> {
         let execution_id = *execution_id;
         let msg_stores = self.msg_stores().clone();
@@ -1031,25 +1319,40 @@ pub trait ContainerService {
 
             if let Some(store) = store {
                 let mut stream = store.history_plus_stream();
+                let mut writer = LogChunkWriter::new(execution_id);
+                let mut executor_profile = None;
+                // Rewrite the open chunk regularly so recent output survives a crash
+                let mut flush_interval = tokio::time::interval(Duration::from_secs(1));
+
+                loop {
+                    let msg = tokio::select! {
+                        msg = stream.next() => msg,
+                        _ = flush_interval.tick() => {
+                            if let Err(e) = writer.flush(&db.pool).await {
+                                tracing::error!(
+                                    "Failed to write logs for execution {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                            }
+                            continue;
+                        }
+                    };
+                    let Some(Ok(msg)) = msg else {
+                        break;
+                    };
 
-                while let Some(Ok(msg)) = stream.next().await {
                     match &msg {
                         LogMsg::Stdout(_) | LogMsg::Stderr(_) => {
                             // Serialize this individual message as a JSONL line
                             match serde_json::to_string(&msg) {
                                 Ok(jsonl_line) => {
-                                    let jsonl_line_with_newline = format!("{jsonl_line}\n");
-
-                                    // Append this line to the database
-                                    if let Err(e) = ExecutionProcessLogs::append_log_line(
-                                        &db.pool,
-                                        execution_id,
-                                        &jsonl_line_with_newline,
-                                    )
-                                    .await
+                                    writer.push_line(&jsonl_line);
+                                    if writer.is_full()
+                                        && let Err(e) = writer.flush(&db.pool).await
                                     {
                                         tracing::error!(
-                                            "Failed to append log line for execution {}: {}",
+                                            "Failed to write logs for execution {}: {}",
                                             execution_id,
                                             e
                                         );
@@ -1100,9 +1403,28 @@ pub trait ContainerService {
                         LogMsg::Finished => {
                             break;
                         }
-                        LogMsg::JsonPatch(_) | LogMsg::Ready => continue,
+                        LogMsg::JsonPatch(patch) => {
+                            if let Some((_, entry)) = extract_normalized_entry_from_patch(patch)
+                                && let NormalizedEntryType::TokenUsageInfo(usage) = entry.entry_type
+                                && !usage.run_usage.is_empty()
+                            {
+                                record_token_usage(
+                                    &db.pool,
+                                    execution_id,
+                                    &mut executor_profile,
+                                    &usage.run_usage,
+                                )
+                                .await;
+                            }
+                        }
+                        LogMsg::Ready => continue,
                     }
                 }
+
+                if let Err(e) = writer.flush(&db.pool).await {
+                    tracing::error!("Failed to write logs for execution {}: {}", execution_id, e);
+                }
+                store_conversation(&db.pool, execution_id, &store).await;
             }
         })
     }
@@ -1127,6 +1449,23 @@ pub trait ContainerService {
             .await?
             .ok_or(SqlxError::RowNotFound)?;
 
+        // Start with a fallback right away when the selected agent isn't set up
+        let fallbacks = executor_fallback::chain_for(&executor_profile_id);
+        let requested_profile_id = executor_profile_id.clone();
+        let executor_profile_id = executor_fallback::first_available(
+            executor_profile_id,
+            &fallbacks,
+            executor_fallback::is_available,
+        );
+        if executor_profile_id != requested_profile_id {
+            tracing::info!(
+                "Executor {} is unavailable, starting workspace {} with {}",
+                requested_profile_id,
+                workspace.id,
+                executor_profile_id
+            );
+        }
+
         // Create a session for this workspace
         let session = Session::create(
             &self.db().pool,
@@ -1287,8 +1626,43 @@ pub trait ContainerService {
             .await?;
         }
 
+        // Coding agents wait in the execution queue while the concurrency limits are reached
+        if let Some(executor_profile_id) = executor_action.executor_profile_id() {
+            let dispatched = self
+                .scheduler()
+                .enqueue(
+                    execution_process.id,
+                    workspace.id,
+                    &executor_profile_id.executor.to_string(),
+                )
+                .await?;
+            for id in dispatched.iter().filter(|id| **id != execution_process.id) {
+                self.launch_scheduled(*id).await;
+            }
+            if !dispatched.contains(&execution_process.id) {
+                tracing::info!(
+                    "Execution {} queued until a coding agent slot frees up",
+                    execution_process.id
+                );
+                return Ok(execution_process);
+            }
+        }
+
+        self.launch_execution(workspace, &execution_process, executor_action, task.id)
+            .await?;
+        Ok(execution_process)
+    }
+
+    /// Spawn the process of an execution process record and stream its logs
+    async fn launch_execution(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+        task_id: Uuid,
+    ) -> Result<(), ContainerError> {
         if let Err(start_error) = self
-            .start_execution_inner(workspace, &execution_process, executor_action)
+            .start_execution_inner(workspace, execution_process, executor_action)
             .await
         {
             // Mark process as failed
@@ -1306,12 +1680,24 @@ pub trait ContainerService {
                     update_error
                 );
             }
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await?;
+            if let Err(e) = ExecutionProcessPort::delete_by_execution_process_id(
+                &self.db().pool,
+                execution_process.id,
+            )
+            .await
+            {
+                tracing::warn!(
+                    "Failed to release dev server port for {}: {}",
+                    execution_process.id,
+                    e
+                );
+            }
+            Task::update_status(&self.db().pool, task_id, TaskStatus::InReview).await?;
 
             // Emit stderr error message
             let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
             if let Ok(json_line) = serde_json::to_string(&log_message) {
-                let _ = ExecutionProcessLogs::append_log_line(
+                let _ = ExecutionProcessLogChunk::append(
                     &self.db().pool,
                     execution_process.id,
                     &format!("{json_line}\n"),
@@ -1320,10 +1706,18 @@ pub trait ContainerService {
             }
 
             // Emit NextAction with failure context for coding agent requests
-            if let ContainerError::ExecutorError(ExecutorError::ExecutableNotFound { program }) =
-                &start_error
-            {
-                let help_text = format!("The required executable `{program}` is not installed.");
+            let help_text = match &start_error {
+                ContainerError::ExecutorError(ExecutorError::ExecutableNotFound { program }) => {
+                    Some(format!(
+                        "The required executable `{program}` is not installed."
+                    ))
+                }
+                ContainerError::ExecutorError(ExecutorError::AuthRequired(message)) => {
+                    Some(message.clone())
+                }
+                _ => None,
+            };
+            if let Some(help_text) = help_text {
                 let error_message = NormalizedEntry {
                     timestamp: None,
                     entry_type: NormalizedEntryType::ErrorMessage {
@@ -1334,7 +1728,7 @@ pub trait ContainerService {
                 };
                 let patch = ConversationPatch::add_normalized_entry(2, error_message);
                 if let Ok(json_line) = serde_json::to_string::<LogMsg>(&LogMsg::JsonPatch(patch)) {
-                    let _ = ExecutionProcessLogs::append_log_line(
+                    let _ = ExecutionProcessLogChunk::append(
                         &self.db().pool,
                         execution_process.id,
                         &format!("{json_line}\n"),
@@ -1342,6 +1736,22 @@ pub trait ContainerService {
                     .await;
                 }
             };
+
+            if let ContainerError::ExecutorError(error) = &start_error
+                && executor_fallback::is_fallback_start_error(error)
+            {
+                let ctx =
+                    ExecutionProcess::load_context(&self.db().pool, execution_process.id).await?;
+                match self.start_executor_fallback(&ctx).await {
+                    Ok(Some(_)) => return Ok(()),
+                    Ok(None) => {}
+                    Err(e) => tracing::error!(
+                        "Failed to start fallback executor for {}: {}",
+                        execution_process.id,
+                        e
+                    ),
+                }
+            }
             return Err(start_error);
         }
 
@@ -1388,7 +1798,138 @@ pub trait ContainerService {
         let db_stream_handle = self.spawn_stream_raw_logs_to_db(&execution_process.id);
         self.store_db_stream_handle(execution_process.id, db_stream_handle)
             .await;
-        Ok(execution_process)
+        Ok(())
+    }
+
+    /// Start the coding agent request of a failed execution again with the
+    /// next profile of its fallback chain, recording the switch on the
+    /// session. Follow-ups aren't retried, as another agent can't continue
+    /// their session. Returns `None` once the chain is used up.
+    async fn start_executor_fallback(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        let action = ctx.execution_process.executor_action()?;
+        let ExecutorActionType::CodingAgentInitialRequest(request) = action.typ() else {
+            return Ok(None);
+        };
+
+        // Profiles already tried in this session; the chain is the first one's
+        let tried: Vec<ExecutorProfileId> =
+            ExecutionProcess::find_by_session_id(&self.db().pool, ctx.session.id, false)
+                .await?
+                .iter()
+                .filter_map(|process| match process.executor_action().ok()?.typ() {
+                    ExecutorActionType::CodingAgentInitialRequest(request) => {
+                        Some(request.executor_profile_id.clone())
+                    }
+                    _ => None,
+                })
+                .collect();
+        let Some(original) = tried.first() else {
+            return Ok(None);
+        };
+        let chain = executor_fallback::chain_for(original);
+        let Some(next) =
+            executor_fallback::next_profile(&chain, &tried, executor_fallback::is_available)
+        else {
+            return Ok(None);
+        };
+
+        tracing::info!(
+            "Execution {} failed with {}, retrying with {}",
+            ctx.execution_process.id,
+            request.executor_profile_id,
+            next
+        );
+        let notice = executor_fallback::switch_notice(&next);
+        match self.get_msg_store_by_id(&ctx.execution_process.id).await {
+            Some(msg_store) => {
+                let index = EntryIndexProvider::start_from(&msg_store).next();
+                msg_store.push_patch(ConversationPatch::add_normalized_entry(index, notice));
+            }
+            None => {
+                // The process never started; follow the entries stored with its start error
+                let stored = MsgStore::new();
+                match ExecutionProcessLogs::load_messages(&self.db().pool, ctx.execution_process.id)
+                    .await
+                {
+                    Ok(messages) => messages.into_iter().for_each(|msg| stored.push(msg)),
+                    Err(e) => tracing::warn!(
+                        "Failed to load logs of execution {}: {}",
+                        ctx.execution_process.id,
+                        e
+                    ),
+                }
+                let index = EntryIndexProvider::start_from(&stored).next();
+                let patch = ConversationPatch::add_normalized_entry(index, notice);
+                if let Ok(json_line) = serde_json::to_string::<LogMsg>(&LogMsg::JsonPatch(patch)) {
+                    let _ = ExecutionProcessLogChunk::append(
+                        &self.db().pool,
+                        ctx.execution_process.id,
+                        &format!("{json_line}\n"),
+                    )
+                    .await;
+                }
+            }
+        }
+        Session::update_executor(&self.db().pool, ctx.session.id, &next.executor.to_string())
+            .await?;
+
+        let fallback_action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                executor_profile_id: next,
+                ..request.clone()
+            }),
+            action.next_action().cloned().map(Box::new),
+        );
+        let execution_process = self
+            .start_execution(
+                &ctx.workspace,
+                &ctx.session,
+                &fallback_action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?;
+        Ok(Some(execution_process))
+    }
+
+    /// Start the scheduled coding agents that fit under the concurrency limits
+    /// now; call whenever a coding agent finishes
+    async fn dispatch_scheduled(&self) {
+        let dispatched = match self.scheduler().take_dispatchable().await {
+            Ok(dispatched) => dispatched,
+            Err(e) => {
+                tracing::error!("Failed to dispatch scheduled executions: {}", e);
+                return;
+            }
+        };
+        for id in dispatched {
+            self.launch_scheduled(id).await;
+        }
+    }
+
+    async fn launch_scheduled(&self, execution_process_id: Uuid) {
+        let result = async {
+            let ctx = ExecutionProcess::load_context(&self.db().pool, execution_process_id).await?;
+            let executor_action = ctx.execution_process.executor_action()?;
+            self.launch_execution(
+                &ctx.workspace,
+                &ctx.execution_process,
+                executor_action,
+                ctx.task.id,
+            )
+            .await
+        }
+        .await;
+        match result {
+            Ok(()) => tracing::info!("Started scheduled execution {}", execution_process_id),
+            Err(e) => tracing::error!(
+                "Failed to start scheduled execution {}: {}",
+                execution_process_id,
+                e
+            ),
+        }
     }
 
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
@@ -1426,3 +1967,161 @@ pub trait ContainerService {
         Ok(())
     }
 }
+
+/// Replay stored log chunks one at a time, so long logs are never decoded up front
+fn stream_log_chunks(
+    pool: SqlitePool,
+    first: ExecutionProcessLogChunk,
+) -> BoxStream<'static, Result<LogMsg, std::io::Error>> {
+    futures::stream::unfold(Some(first), move |chunk| {
+        let pool = pool.clone();
+        async move {
+            let chunk = chunk?;
+            let next = ExecutionProcessLogChunk::find_by_index(
+                &pool,
+                chunk.execution_id,
+                chunk.chunk_index + 1,
+            )
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!(
+                    "Failed to fetch log chunk for execution {}: {}",
+                    chunk.execution_id,
+                    e
+                );
+                None
+            });
+            let messages = chunk.messages().unwrap_or_else(|e| {
+                tracing::error!(
+                    "Failed to decode log chunk {} for execution {}: {}",
+                    chunk.chunk_index,
+                    chunk.execution_id,
+                    e
+                );
+                Vec::new()
+            });
+            Some((futures::stream::iter(messages.into_iter().map(Ok)), next))
+        }
+    })
+    .flatten()
+    .boxed()
+}
+
+/// Persist the latest per-model usage of an execution process. The process's executor
+/// profile is looked up on first use and cached in `executor_profile`.
+async fn record_token_usage(
+    pool: &SqlitePool,
+    execution_id: Uuid,
+    executor_profile: &mut Option<ExecutorProfileId>,
+    run_usage: &[ModelTokenUsage],
+) {
+    if executor_profile.is_none() {
+        *executor_profile = match ExecutionProcess::find_by_id(pool, execution_id).await {
+            Ok(Some(process)) => process
+                .executor_action()
+                .ok()
+                .and_then(|action| action.executor_profile_id().cloned()),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load execution process {} for token usage: {}",
+                    execution_id,
+                    e
+                );
+                None
+            }
+        };
+    }
+    let Some(profile) = executor_profile.as_ref() else {
+        return;
+    };
+
+    let executor = profile.executor.to_string();
+    for usage in run_usage {
+        if let Err(e) = ExecutionProcessTokenUsage::upsert(
+            pool,
+            execution_id,
+            &executor,
+            profile.variant.as_deref(),
+            usage,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to record token usage for execution {}: {}",
+                execution_id,
+                e
+            );
+        }
+    }
+}
+
+/// The conversation patches among `messages`
+fn json_patches(messages: Vec<LogMsg>) -> Vec<Patch> {
+    messages
+        .into_iter()
+        .filter_map(|msg| match msg {
+            LogMsg::JsonPatch(patch) => Some(patch),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Run a stream from [`ContainerService::normalize_stored_logs`] to its end and
+/// collect the patches. Fails on a stream error, or when the normalizers are not
+/// done within [`NORMALIZATION_TIMEOUT`], rather than returning partial output.
+async fn collect_normalized_patches(
+    mut stream: BoxStream<'static, Result<LogMsg, std::io::Error>>,
+) -> Result<Vec<Patch>, ContainerError> {
+    let collect = async {
+        let mut patches = Vec::new();
+        while let Some(msg) = stream.next().await {
+            match msg? {
+                LogMsg::JsonPatch(patch) => patches.push(patch),
+                LogMsg::Finished => break,
+                _ => {}
+            }
+        }
+        Ok::<_, ContainerError>(patches)
+    };
+    tokio::time::timeout(NORMALIZATION_TIMEOUT, collect)
+        .await
+        .map_err(|_| {
+            anyhow!(
+                "normalization did not finish within {}s",
+                NORMALIZATION_TIMEOUT.as_secs()
+            )
+        })?
+}
+
+/// Store the normalized conversation of a finished process so it can be read back
+/// without re-normalizing. Skipped when the store's history no longer holds every
+/// patch, in which case the stored logs remain the source.
+async fn store_conversation(pool: &SqlitePool, execution_id: Uuid, store: &MsgStore) {
+    let patches: Vec<Patch> = store
+        .get_history()
+        .into_iter()
+        .filter_map(|msg| match msg {
+            LogMsg::JsonPatch(patch) => Some(patch),
+            _ => None,
+        })
+        .collect();
+    if patches.is_empty() {
+        return;
+    }
+    let Some(patches) = compact_conversation_patches(&patches) else {
+        return;
+    };
+
+    let result = match patches_to_jsonl(patches) {
+        Ok(jsonl) => ExecutionProcessConversation::upsert(pool, execution_id, &jsonl).await,
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = result {
+        tracing::error!(
+            "Failed to store conversation for execution {}: {}",
+            execution_id,
+            e
+        );
+    }
+}
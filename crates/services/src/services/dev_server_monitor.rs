@@ -0,0 +1,462 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+
+use chrono::Utc;
+use db::models::{
+    execution_process::{DevServerHealth, ExecutionProcess},
+    repo::Repo,
+};
+use futures::StreamExt;
+use regex::Regex;
+use sqlx::SqlitePool;
+use tokio::{sync::RwLock, task::JoinHandle};
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+/// How often to probe the health path while waiting for readiness
+const READINESS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How often to probe the health path once the dev server is ready
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_secs(10);
+const HEALTH_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+const MAX_RESTART_ATTEMPTS: u32 = 5;
+const RESTART_BASE_DELAY: Duration = Duration::from_secs(1);
+const RESTART_MAX_DELAY: Duration = Duration::from_secs(60);
+/// A dev server that stayed up this long is considered stable, so a later
+/// crash starts the backoff from scratch.
+const STABLE_UPTIME: Duration = Duration::from_secs(120);
+
+static ANSI_ESCAPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").expect("valid ANSI regex"));
+
+/// Listening-line patterns of common dev servers, most specific first. Each
+/// pattern captures either a `url` or a bare `port`.
+static FRAMEWORK_PATTERNS: LazyLock<Vec<(&'static str, Regex)>> = LazyLock::new(|| {
+    [
+        // Vite, Next.js, Nuxt, Astro, SvelteKit, Create React App
+        ("local", r"(?i)\bLocal:\s+(?P<url>https?://\S+)"),
+        // Next.js < 13
+        (
+            "next",
+            r"(?i)started server on .*url:\s*(?P<url>https?://\S+)",
+        ),
+        // Angular CLI
+        ("angular", r"(?i)open your browser on (?P<url>https?://\S+)"),
+        // webpack-dev-server
+        (
+            "webpack",
+            r"(?i)project is running at:?\s*(?:Loopback:\s*)?(?P<url>https?://\S+)",
+        ),
+        // Django
+        (
+            "django",
+            r"(?i)starting development server at (?P<url>https?://\S+)",
+        ),
+        // Flask, Uvicorn
+        ("python", r"(?i)running on (?P<url>https?://\S+)"),
+        // Rails (Puma), Phoenix, Hugo
+        (
+            "listening",
+            r"(?i)(?:listening|serving|available) (?:on|at):?\s*(?P<url>https?://\S+)",
+        ),
+        // Express and friends: "Listening on port 3000"
+        (
+            "port",
+            r"(?i)\b(?:listening|running|started|serving)\b.*\bport:?\s*(?P<port>\d{2,5})\b",
+        ),
+        // Anything that prints a local URL
+        (
+            "generic",
+            r"(?P<url>https?://(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1?\]):\d{2,5}\S*)",
+        ),
+    ]
+    .into_iter()
+    .map(|(name, pattern)| (name, Regex::new(pattern).expect("valid dev server pattern")))
+    .collect()
+});
+
+/// Watches running dev servers: marks them ready once they print a listening
+/// URL (or answer their health check), keeps polling health, and decides how
+/// long to back off before an unexpected exit is restarted.
+#[derive(Clone, Default)]
+pub struct DevServerMonitor {
+    /// Restart attempt of each restarted dev server process, by process id
+    restart_attempts: Arc<RwLock<HashMap<Uuid, u32>>>,
+}
+
+impl DevServerMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Follow the output of a freshly spawned dev server until it finishes.
+    /// `port` is the port allocated to it, if any, and is preferred for health checks.
+    pub fn spawn_watcher(
+        &self,
+        pool: SqlitePool,
+        execution_process_id: Uuid,
+        repo: &Repo,
+        port: Option<u16>,
+        msg_store: Arc<MsgStore>,
+    ) -> JoinHandle<()> {
+        let custom_pattern = repo
+            .dev_server_ready_pattern
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .and_then(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    tracing::warn!(
+                        "Ignoring invalid dev server ready pattern for repo {}: {}",
+                        repo.name,
+                        e
+                    );
+                    None
+                }
+            });
+        let health_path = repo
+            .dev_server_health_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                if p.starts_with('/') {
+                    p.to_string()
+                } else {
+                    format!("/{p}")
+                }
+            });
+
+        tokio::spawn(async move {
+            let watcher = Watcher {
+                pool,
+                execution_process_id,
+                custom_pattern,
+                health_path,
+                port,
+            };
+            watcher.run(msg_store).await;
+        })
+    }
+
+    /// Delay before restarting a dev server that exited unexpectedly, with the
+    /// attempt number to record for the replacement. `None` once retries are exhausted.
+    pub async fn restart_backoff(&self, exited: &ExecutionProcess) -> Option<(Duration, u32)> {
+        let previous = self
+            .restart_attempts
+            .write()
+            .await
+            .remove(&exited.id)
+            .unwrap_or(0);
+        let uptime = exited.completed_at.unwrap_or_else(Utc::now) - exited.started_at;
+        let attempt = if uptime.to_std().is_ok_and(|uptime| uptime >= STABLE_UPTIME) {
+            1
+        } else {
+            previous + 1
+        };
+        if attempt > MAX_RESTART_ATTEMPTS {
+            return None;
+        }
+        Some((restart_delay(attempt), attempt))
+    }
+
+    pub async fn record_restart(&self, execution_process_id: Uuid, attempt: u32) {
+        self.restart_attempts
+            .write()
+            .await
+            .insert(execution_process_id, attempt);
+    }
+
+    /// Forget about a dev server that was stopped on purpose
+    pub async fn forget(&self, execution_process_id: &Uuid) {
+        self.restart_attempts
+            .write()
+            .await
+            .remove(execution_process_id);
+    }
+}
+
+fn restart_delay(attempt: u32) -> Duration {
+    RESTART_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(RESTART_MAX_DELAY)
+}
+
+struct Watcher {
+    pool: SqlitePool,
+    execution_process_id: Uuid,
+    custom_pattern: Option<Regex>,
+    health_path: Option<String>,
+    port: Option<u16>,
+}
+
+impl Watcher {
+    async fn run(self, msg_store: Arc<MsgStore>) {
+        let mut stream = msg_store.history_plus_stream();
+        let mut stdout = LineBuffer::default();
+        let mut stderr = LineBuffer::default();
+        let mut ready_url: Option<String> = None;
+        let mut health: Option<DevServerHealth> = None;
+
+        let mut ticker = tokio::time::interval(READINESS_POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                msg = stream.next() => {
+                    let lines = match msg {
+                        Some(Ok(LogMsg::Stdout(chunk))) => stdout.push(&chunk),
+                        Some(Ok(LogMsg::Stderr(chunk))) => stderr.push(&chunk),
+                        Some(Ok(LogMsg::Finished)) | None => break,
+                        _ => continue,
+                    };
+                    if ready_url.is_some() {
+                        continue;
+                    }
+                    if let Some(url) = lines
+                        .iter()
+                        .find_map(|line| detect_listening_url(line, self.custom_pattern.as_ref()))
+                    {
+                        self.mark_ready(&url).await;
+                        ready_url = Some(url);
+                        ticker = tokio::time::interval(HEALTH_POLL_INTERVAL);
+                    }
+                }
+                _ = ticker.tick(), if self.health_path.is_some() => {
+                    let Some(url) = self.health_url(ready_url.as_deref()) else {
+                        continue;
+                    };
+                    let status = probe(&url).await;
+                    if ready_url.is_none() && status == DevServerHealth::Healthy {
+                        // Quiet servers count as ready once their health check passes
+                        let base = self.base_url(None).unwrap_or_else(|| url.clone());
+                        self.mark_ready(&base).await;
+                        ready_url = Some(base);
+                        ticker = tokio::time::interval(HEALTH_POLL_INTERVAL);
+                    }
+                    if ready_url.is_some() && health != Some(status) {
+                        health = Some(status);
+                        if let Err(e) = ExecutionProcess::update_health_status(
+                            &self.pool,
+                            self.execution_process_id,
+                            status,
+                        )
+                        .await
+                        {
+                            tracing::warn!(
+                                "Failed to record health of dev server {}: {}",
+                                self.execution_process_id,
+                                e
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn mark_ready(&self, url: &str) {
+        tracing::info!(
+            "Dev server {} is ready at {}",
+            self.execution_process_id,
+            url
+        );
+        if let Err(e) =
+            ExecutionProcess::mark_ready(&self.pool, self.execution_process_id, url).await
+        {
+            tracing::warn!(
+                "Failed to mark dev server {} ready: {}",
+                self.execution_process_id,
+                e
+            );
+        }
+    }
+
+    fn base_url(&self, ready_url: Option<&str>) -> Option<String> {
+        if let Some(port) = self.port {
+            return Some(format!("http://localhost:{port}"));
+        }
+        let url = url::Url::parse(ready_url?).ok()?;
+        Some(format!(
+            "{}://{}:{}",
+            url.scheme(),
+            url.host_str()?,
+            url.port_or_known_default()?
+        ))
+    }
+
+    fn health_url(&self, ready_url: Option<&str>) -> Option<String> {
+        let path = self.health_path.as_deref()?;
+        Some(format!("{}{}", self.base_url(ready_url)?, path))
+    }
+}
+
+async fn probe(url: &str) -> DevServerHealth {
+    static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+        reqwest::Client::builder()
+            .timeout(HEALTH_REQUEST_TIMEOUT)
+            .build()
+            .expect("failed to build health check client")
+    });
+
+    match CLIENT.get(url).send().await {
+        Ok(response) if !response.status().is_server_error() => DevServerHealth::Healthy,
+        _ => DevServerHealth::Unhealthy,
+    }
+}
+
+/// Reassembles output chunks into whole lines
+#[derive(Default)]
+struct LineBuffer {
+    partial: String,
+}
+
+impl LineBuffer {
+    fn push(&mut self, chunk: &str) -> Vec<String> {
+        self.partial.push_str(chunk);
+        let Some(end) = self.partial.rfind('\n') else {
+            return Vec::new();
+        };
+        let rest = self.partial.split_off(end + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+        complete.lines().map(str::to_string).collect()
+    }
+}
+
+/// Find the address a dev server reports it is listening on. A custom pattern
+/// takes precedence over the built-in framework patterns.
+pub fn detect_listening_url(line: &str, custom_pattern: Option<&Regex>) -> Option<String> {
+    let line = ANSI_ESCAPE.replace_all(line, "");
+    custom_pattern
+        .into_iter()
+        .chain(FRAMEWORK_PATTERNS.iter().map(|(_, regex)| regex))
+        .find_map(|regex| url_from_captures(regex, &line))
+}
+
+fn url_from_captures(regex: &Regex, line: &str) -> Option<String> {
+    let captures = regex.captures(line)?;
+    if let Some(url) = captures.name("url") {
+        return Some(normalize_url(url.as_str()));
+    }
+    let port_or_url = captures
+        .name("port")
+        .or_else(|| captures.get(1))
+        .unwrap_or_else(|| captures.get(0).expect("group 0 always matches"))
+        .as_str();
+    if let Ok(port) = port_or_url.parse::<u16>() {
+        return Some(format!("http://localhost:{port}/"));
+    }
+    port_or_url
+        .starts_with("http")
+        .then(|| normalize_url(port_or_url))
+}
+
+/// Wildcard bind addresses are not browsable, so point them at localhost
+fn normalize_url(url: &str) -> String {
+    let url = url.trim_end_matches(['.', ',', ';', ')']);
+    ["0.0.0.0", "[::]"]
+        .iter()
+        .find_map(|wildcard| {
+            url.find(&format!("://{wildcard}")).map(|i| {
+                format!(
+                    "{}://localhost{}",
+                    &url[..i],
+                    &url[i + 3 + wildcard.len()..]
+                )
+            })
+        })
+        .unwrap_or_else(|| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_framework_listening_lines() {
+        let cases = [
+            (
+                "  \x1b[32m➜\x1b[39m  \x1b[1mLocal\x1b[22m:   \x1b[36mhttp://localhost:\x1b[1m5173\x1b[22m/\x1b[39m",
+                "http://localhost:5173/",
+            ),
+            (
+                "   - Local:        http://localhost:3000",
+                "http://localhost:3000",
+            ),
+            (
+                "ready - started server on 0.0.0.0:3000, url: http://localhost:3000",
+                "http://localhost:3000",
+            ),
+            (
+                "Starting development server at http://127.0.0.1:8000/",
+                "http://127.0.0.1:8000/",
+            ),
+            (
+                " * Running on http://127.0.0.1:5000",
+                "http://127.0.0.1:5000",
+            ),
+            (
+                "* Listening on http://0.0.0.0:3000",
+                "http://localhost:3000",
+            ),
+            ("Server listening on port 4100", "http://localhost:4100/"),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(
+                detect_listening_url(line, None).as_deref(),
+                Some(expected),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn ignores_unrelated_output() {
+        for line in [
+            "Compiling 42 modules...",
+            "warning: unused variable `port`",
+            "GET https://registry.npmjs.org/react 200",
+        ] {
+            assert_eq!(detect_listening_url(line, None), None, "{line}");
+        }
+    }
+
+    #[test]
+    fn custom_pattern_takes_precedence() {
+        let custom = Regex::new(r"app up \((?P<port>\d+)\)").unwrap();
+        assert_eq!(
+            detect_listening_url("app up (9000) - see http://localhost:1/", Some(&custom))
+                .as_deref(),
+            Some("http://localhost:9000/")
+        );
+
+        let unnamed = Regex::new(r"ready at (\S+)").unwrap();
+        assert_eq!(
+            detect_listening_url("ready at http://0.0.0.0:8080/app", Some(&unnamed)).as_deref(),
+            Some("http://localhost:8080/app")
+        );
+    }
+
+    #[test]
+    fn line_buffer_joins_split_chunks() {
+        let mut buffer = LineBuffer::default();
+        assert!(buffer.push("Local:   http://local").is_empty());
+        assert_eq!(
+            buffer.push("host:5173/\nnext"),
+            vec!["Local:   http://localhost:5173/".to_string()]
+        );
+        assert_eq!(buffer.push("\n"), vec!["next".to_string()]);
+    }
+
+    #[test]
+    fn restart_delay_backs_off_exponentially() {
+        assert_eq!(restart_delay(1), Duration::from_secs(1));
+        assert_eq!(restart_delay(2), Duration::from_secs(2));
+        assert_eq!(restart_delay(4), Duration::from_secs(8));
+        assert_eq!(restart_delay(10), RESTART_MAX_DELAY);
+    }
+}
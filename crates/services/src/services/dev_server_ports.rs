@@ -0,0 +1,217 @@
+use std::{collections::HashSet, net::TcpListener, sync::Arc};
+
+use db::models::execution_process_port::ExecutionProcessPort;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::services::config::DevServerPortConfig;
+
+#[derive(Debug, Error)]
+pub enum DevServerPortError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Invalid dev server port range {start}-{end}")]
+    InvalidRange { start: u16, end: u16 },
+    #[error("No free dev server port left in range {start}-{end}")]
+    Exhausted { start: u16, end: u16 },
+}
+
+/// Hands out ports from the configured range to dev servers, one per execution process.
+/// Allocations are persisted so the port survives until the process stops.
+#[derive(Clone, Default)]
+pub struct DevServerPortService {
+    // Serializes allocation so dev servers started together can't pick the same port
+    lock: Arc<Mutex<()>>,
+}
+
+impl DevServerPortService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocate the lowest port in range that is neither recorded against another
+    /// process nor currently bound by something outside vibe-kanban.
+    pub async fn allocate(
+        &self,
+        pool: &SqlitePool,
+        config: &DevServerPortConfig,
+        execution_process_id: Uuid,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<ExecutionProcessPort, DevServerPortError> {
+        let (start, end) = (config.range_start, config.range_end);
+        if start == 0 || start > end {
+            return Err(DevServerPortError::InvalidRange { start, end });
+        }
+
+        let _guard = self.lock.lock().await;
+        let taken: HashSet<i64> = ExecutionProcessPort::find_allocated_ports(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+        for port in start..=end {
+            if taken.contains(&i64::from(port)) || !is_port_free(port) {
+                continue;
+            }
+            let allocation = ExecutionProcessPort::create(
+                pool,
+                execution_process_id,
+                workspace_id,
+                repo_id,
+                i64::from(port),
+            )
+            .await?;
+            tracing::info!(
+                "Allocated port {} to dev server {} (workspace {})",
+                port,
+                execution_process_id,
+                workspace_id
+            );
+            return Ok(allocation);
+        }
+
+        Err(DevServerPortError::Exhausted { start, end })
+    }
+
+    /// Return the port held by an execution process to the pool
+    pub async fn release(
+        &self,
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<(), DevServerPortError> {
+        if ExecutionProcessPort::delete_by_execution_process_id(pool, execution_process_id).await?
+            > 0
+        {
+            tracing::debug!("Released dev server port for {}", execution_process_id);
+        }
+        Ok(())
+    }
+}
+
+fn is_port_free(port: u16) -> bool {
+    TcpListener::bind(("127.0.0.1", port)).is_ok() && TcpListener::bind(("0.0.0.0", port)).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    use super::*;
+
+    // Port rows reference processes, workspaces and repos; the allocator only cares
+    // about the ports table, so foreign keys are switched off instead of seeding parents.
+    async fn test_pool() -> SqlitePool {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::migrate!("../db/migrations").run(&pool).await.unwrap();
+        pool
+    }
+
+    fn free_port() -> u16 {
+        TcpListener::bind(("127.0.0.1", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    fn single_port_range(port: u16) -> DevServerPortConfig {
+        DevServerPortConfig {
+            range_start: port,
+            range_end: port,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn released_port_can_be_allocated_again() {
+        let pool = test_pool().await;
+        let service = DevServerPortService::new();
+        let port = free_port();
+        let config = single_port_range(port);
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let allocation = service
+            .allocate(&pool, &config, first, Uuid::new_v4(), Uuid::new_v4())
+            .await
+            .unwrap();
+        assert_eq!(allocation.port, i64::from(port));
+
+        let exhausted = service
+            .allocate(&pool, &config, second, Uuid::new_v4(), Uuid::new_v4())
+            .await;
+        assert!(matches!(
+            exhausted,
+            Err(DevServerPortError::Exhausted { .. })
+        ));
+
+        service.release(&pool, first).await.unwrap();
+        let allocation = service
+            .allocate(&pool, &config, second, Uuid::new_v4(), Uuid::new_v4())
+            .await
+            .unwrap();
+        assert_eq!(allocation.port, i64::from(port));
+    }
+
+    #[tokio::test]
+    async fn skips_ports_bound_outside_vibe_kanban() {
+        let pool = test_pool().await;
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let result = DevServerPortService::new()
+            .allocate(
+                &pool,
+                &single_port_range(port),
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+            )
+            .await;
+        assert!(matches!(result, Err(DevServerPortError::Exhausted { .. })));
+    }
+
+    #[tokio::test]
+    async fn rejects_inverted_range() {
+        let pool = test_pool().await;
+        let config = DevServerPortConfig {
+            range_start: 5000,
+            range_end: 4000,
+            ..Default::default()
+        };
+
+        let result = DevServerPortService::new()
+            .allocate(
+                &pool,
+                &config,
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(DevServerPortError::InvalidRange { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn releasing_without_allocation_is_a_no_op() {
+        let pool = test_pool().await;
+        DevServerPortService::new()
+            .release(&pool, Uuid::new_v4())
+            .await
+            .unwrap();
+    }
+}
@@ -13,6 +13,8 @@ use tokio::sync::RwLock;
 use utils::msg_store::MsgStore;
 use uuid::Uuid;
 
+use crate::services::execution_scheduler::ExecutionScheduler;
+
 #[path = "events/patches.rs"]
 pub mod patches;
 #[path = "events/streams.rs"]
@@ -21,7 +23,8 @@ mod streams;
 pub mod types;
 
 pub use patches::{
-    execution_process_patch, project_patch, scratch_patch, task_patch, workspace_patch,
+    execution_process_patch, execution_queue_patch, project_patch, scratch_patch, task_patch,
+    workspace_patch,
 };
 pub use types::{EventError, EventPatch, EventPatchInner, HookTables, RecordTypes};
 
@@ -177,6 +180,18 @@ impl EventService {
                         let rowid = hook.rowid;
                         runtime_handle.spawn(async move {
                             let record_type: RecordTypes = match (table, hook.operation.clone()) {
+                                (HookTables::ScheduledExecutions, _) => {
+                                    // Positions shift together, so resend the whole queue
+                                    match ExecutionScheduler::queue_entries(&db.pool).await {
+                                        Ok(entries) => msg_store_for_hook
+                                            .push_patch(execution_queue_patch::set(&entries)),
+                                        Err(e) => tracing::error!(
+                                            "Failed to fetch execution queue: {:?}",
+                                            e
+                                        ),
+                                    }
+                                    return;
+                                }
                                 (HookTables::Tasks, SqliteOperation::Delete)
                                 | (HookTables::Projects, SqliteOperation::Delete)
                                 | (HookTables::Workspaces, SqliteOperation::Delete)
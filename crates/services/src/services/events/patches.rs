@@ -5,6 +5,8 @@ use db::models::{
 use json_patch::{AddOperation, Patch, PatchOperation, RemoveOperation, ReplaceOperation};
 use uuid::Uuid;
 
+use crate::services::execution_scheduler::ExecutionQueueEntry;
+
 // Shared helper to escape JSON Pointer segments
 fn escape_pointer_segment(s: &str) -> String {
     s.replace('~', "~0").replace('/', "~1")
@@ -214,3 +216,22 @@ pub mod scratch_patch {
         })])
     }
 }
+
+/// Helper functions for creating execution queue patches.
+/// The whole queue is sent on every change since positions shift together.
+pub mod execution_queue_patch {
+    use super::*;
+
+    const EXECUTION_QUEUE_PATH: &str = "/execution_queue";
+
+    /// Create patch setting the queue, in dispatch order
+    pub fn set(entries: &[ExecutionQueueEntry]) -> Patch {
+        Patch(vec![PatchOperation::Add(AddOperation {
+            path: EXECUTION_QUEUE_PATH
+                .try_into()
+                .expect("Execution queue path should be valid"),
+            value: serde_json::to_value(entries)
+                .expect("Execution queue serialization should not fail"),
+        })])
+    }
+}
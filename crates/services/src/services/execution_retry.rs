@@ -0,0 +1,89 @@
+//! Automatic follow-ups for coding agent runs that failed on a transient
+//! provider error, such as a rate limit or an overloaded API.
+
+use std::time::Duration;
+
+use chrono::{TimeDelta, Utc};
+use executors::logs::{
+    NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+    utils::patch::extract_normalized_entry_from_patch,
+};
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+use crate::services::config::ExecutionRetries;
+
+/// Prompt of the follow-up that resumes a run after a retryable failure
+pub const RETRY_PROMPT: &str = "continue";
+
+/// Delay before retrying a run that was retry `failed_attempt` itself (0 for a
+/// run that wasn't a retry), with the attempt number to record on the retry.
+/// `None` once `max_retries` are exhausted.
+pub fn backoff(
+    failed_attempt: i64,
+    max_retries: u32,
+    retries: &ExecutionRetries,
+) -> Option<(Duration, u32)> {
+    let attempt = u32::try_from(failed_attempt).unwrap_or(0).saturating_add(1);
+    if attempt > max_retries {
+        return None;
+    }
+    Some((retries.delay(attempt), attempt))
+}
+
+/// Whether the normalized logs of a run report a retryable provider error
+pub fn has_retryable_error(msg_store: &MsgStore) -> bool {
+    msg_store
+        .get_history()
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::JsonPatch(patch) => extract_normalized_entry_from_patch(patch),
+            _ => None,
+        })
+        .any(|(_, entry)| {
+            matches!(
+                entry.entry_type,
+                NormalizedEntryType::ErrorMessage {
+                    error_type: NormalizedEntryError::Retryable,
+                }
+            )
+        })
+}
+
+/// Entry announcing a retry `delay` from now; `retry_at` in its metadata lets
+/// the conversation count down to it
+pub fn countdown_entry(delay: Duration, attempt: u32, max_retries: u32) -> NormalizedEntry {
+    let retry_at = Utc::now() + TimeDelta::from_std(delay).unwrap_or_default();
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::SystemMessage,
+        content: format!(
+            "The provider is rate limited or overloaded. Continuing automatically in {}s (retry {attempt} of {max_retries}).",
+            delay.as_secs()
+        ),
+        metadata: Some(serde_json::json!({
+            "retry_at": retry_at.to_rfc3339(),
+            "retry_attempt": attempt,
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_until_retries_run_out() {
+        let retries = ExecutionRetries {
+            base_delay_secs: 10,
+            max_delay_secs: 25,
+        };
+
+        assert_eq!(backoff(0, 2, &retries), Some((Duration::from_secs(10), 1)));
+        assert_eq!(backoff(1, 2, &retries), Some((Duration::from_secs(20), 2)));
+        assert_eq!(backoff(2, 2, &retries), None);
+        assert_eq!(backoff(2, 5, &retries), Some((Duration::from_secs(25), 3)));
+
+        // Profiles without `max_retries` aren't retried
+        assert_eq!(backoff(0, 0, &retries), None);
+    }
+}
@@ -0,0 +1,263 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    scheduled_execution::ScheduledExecution,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tokio::sync::{Mutex, RwLock};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::config::{ConcurrencyLimits, Config};
+
+/// A scheduled coding agent start and its place in the execution queue
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ExecutionQueueEntry {
+    pub execution_process_id: Uuid,
+    pub workspace_id: Uuid,
+    pub executor: String,
+    #[ts(type = "number")]
+    pub priority: i64,
+    pub queued_at: DateTime<Utc>,
+    /// 1-based position in dispatch order
+    pub position: u32,
+}
+
+/// Admits coding agent starts under the configured concurrency limits. Starts
+/// without a free slot are persisted as scheduled executions and dispatched
+/// by priority, then age, as running agents finish.
+#[derive(Clone)]
+pub struct ExecutionScheduler {
+    pool: SqlitePool,
+    config: Arc<RwLock<Config>>,
+    /// Serialises admission so two starts can't take the same slot
+    dispatch_lock: Arc<Mutex<()>>,
+}
+
+impl ExecutionScheduler {
+    pub fn new(pool: SqlitePool, config: Arc<RwLock<Config>>) -> Self {
+        Self {
+            pool,
+            config,
+            dispatch_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Admit a new coding agent start or schedule it behind the waiting
+    /// starts of the same priority. Returns the execution processes to spawn
+    /// now, which includes `execution_process_id` when it got a slot.
+    pub async fn enqueue(
+        &self,
+        execution_process_id: Uuid,
+        workspace_id: Uuid,
+        executor: &str,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let _guard = self.dispatch_lock.lock().await;
+        let limits = self.config.read().await.concurrency_limits.clone();
+        let mut queue = ScheduledExecution::find_all(&self.pool).await?;
+        let running = self
+            .running_agents(&queue, Some(execution_process_id))
+            .await?;
+
+        let at = queue
+            .iter()
+            .position(|scheduled| scheduled.priority < 0)
+            .unwrap_or(queue.len());
+        queue.insert(
+            at,
+            ScheduledExecution {
+                execution_process_id,
+                workspace_id,
+                executor: executor.to_string(),
+                priority: 0,
+                queued_at: Utc::now(),
+            },
+        );
+
+        let dispatched = dispatch_order(&limits, running, &queue);
+        if !dispatched.contains(&execution_process_id) {
+            ScheduledExecution::create(&self.pool, execution_process_id, workspace_id, executor)
+                .await?;
+        }
+        for id in dispatched.iter().filter(|id| **id != execution_process_id) {
+            ScheduledExecution::delete(&self.pool, *id).await?;
+        }
+        Ok(dispatched)
+    }
+
+    /// Remove and return the scheduled starts that fit under the limits now
+    pub async fn take_dispatchable(&self) -> Result<Vec<Uuid>, sqlx::Error> {
+        let _guard = self.dispatch_lock.lock().await;
+        let queue = ScheduledExecution::find_all(&self.pool).await?;
+        if queue.is_empty() {
+            return Ok(Vec::new());
+        }
+        let limits = self.config.read().await.concurrency_limits.clone();
+        let running = self.running_agents(&queue, None).await?;
+
+        let dispatched = dispatch_order(&limits, running, &queue);
+        for id in &dispatched {
+            ScheduledExecution::delete(&self.pool, *id).await?;
+        }
+        Ok(dispatched)
+    }
+
+    /// Drop a scheduled start; returns whether it was still waiting
+    pub async fn cancel(&self, execution_process_id: Uuid) -> Result<bool, sqlx::Error> {
+        let _guard = self.dispatch_lock.lock().await;
+        Ok(ScheduledExecution::delete(&self.pool, execution_process_id).await? > 0)
+    }
+
+    pub async fn is_scheduled(&self, execution_process_id: Uuid) -> Result<bool, sqlx::Error> {
+        Ok(
+            ScheduledExecution::find_by_execution_process_id(&self.pool, execution_process_id)
+                .await?
+                .is_some(),
+        )
+    }
+
+    pub async fn set_priority(
+        &self,
+        execution_process_id: Uuid,
+        priority: i64,
+    ) -> Result<Option<ScheduledExecution>, sqlx::Error> {
+        ScheduledExecution::set_priority(&self.pool, execution_process_id, priority).await
+    }
+
+    /// Scheduled starts in dispatch order
+    pub async fn queue_entries(pool: &SqlitePool) -> Result<Vec<ExecutionQueueEntry>, sqlx::Error> {
+        let queue = ScheduledExecution::find_all(pool).await?;
+        Ok(queue
+            .into_iter()
+            .zip(1..)
+            .map(|(scheduled, position)| ExecutionQueueEntry {
+                execution_process_id: scheduled.execution_process_id,
+                workspace_id: scheduled.workspace_id,
+                executor: scheduled.executor,
+                priority: scheduled.priority,
+                queued_at: scheduled.queued_at,
+                position,
+            })
+            .collect())
+    }
+
+    /// Coding agents holding a slot, per executor
+    async fn running_agents(
+        &self,
+        queue: &[ScheduledExecution],
+        exclude: Option<Uuid>,
+    ) -> Result<HashMap<String, u32>, sqlx::Error> {
+        let mut running = HashMap::new();
+        for process in ExecutionProcess::find_running(&self.pool).await? {
+            if process.run_reason != ExecutionProcessRunReason::CodingAgent
+                || Some(process.id) == exclude
+                || queue
+                    .iter()
+                    .any(|scheduled| scheduled.execution_process_id == process.id)
+            {
+                continue;
+            }
+            if let Some(profile) = process
+                .executor_action()
+                .ok()
+                .and_then(|action| action.executor_profile_id())
+            {
+                *running.entry(profile.executor.to_string()).or_default() += 1;
+            }
+        }
+        Ok(running)
+    }
+}
+
+/// Starts from `queue` (in dispatch order) that fit under `limits` given the
+/// agents already `running`. A start blocked only by its executor's limit
+/// doesn't hold back starts for other executors.
+fn dispatch_order(
+    limits: &ConcurrencyLimits,
+    mut running: HashMap<String, u32>,
+    queue: &[ScheduledExecution],
+) -> Vec<Uuid> {
+    let max_running = limits.max_running.filter(|max| *max > 0);
+    let mut total: u32 = running.values().sum();
+    let mut dispatched = Vec::new();
+    for scheduled in queue {
+        if max_running.is_some_and(|max| total >= max) {
+            break;
+        }
+        let count = running.entry(scheduled.executor.clone()).or_default();
+        if limits
+            .executor_limit(&scheduled.executor)
+            .filter(|max| *max > 0)
+            .is_some_and(|max| *count >= max)
+        {
+            continue;
+        }
+        *count += 1;
+        total += 1;
+        dispatched.push(scheduled.execution_process_id);
+    }
+    dispatched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::config::ExecutorConcurrencyLimit;
+
+    fn scheduled(executor: &str) -> ScheduledExecution {
+        ScheduledExecution {
+            execution_process_id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            executor: executor.to_string(),
+            priority: 0,
+            queued_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn dispatch_stops_at_global_limit() {
+        let limits = ConcurrencyLimits {
+            max_running: Some(3),
+            executors: Vec::new(),
+        };
+        let queue = vec![scheduled("CODEX"), scheduled("CODEX"), scheduled("AMP")];
+        let running = HashMap::from([("CLAUDE_CODE".to_string(), 1)]);
+
+        let dispatched = dispatch_order(&limits, running, &queue);
+        assert_eq!(
+            dispatched,
+            vec![queue[0].execution_process_id, queue[1].execution_process_id]
+        );
+        let unlimited = dispatch_order(&ConcurrencyLimits::default(), HashMap::new(), &queue);
+        assert_eq!(unlimited.len(), 3);
+    }
+
+    #[test]
+    fn executor_limit_skips_to_other_executors() {
+        let limits = ConcurrencyLimits {
+            max_running: None,
+            executors: vec![ExecutorConcurrencyLimit {
+                executor: "CLAUDE_CODE".to_string(),
+                max_running: 1,
+            }],
+        };
+        let queue = vec![
+            scheduled("CLAUDE_CODE"),
+            scheduled("CLAUDE_CODE"),
+            scheduled("CODEX"),
+        ];
+
+        let dispatched = dispatch_order(&limits, HashMap::new(), &queue);
+        assert_eq!(
+            dispatched,
+            vec![queue[0].execution_process_id, queue[2].execution_process_id]
+        );
+
+        let running = HashMap::from([("CLAUDE_CODE".to_string(), 1)]);
+        let dispatched = dispatch_order(&limits, running, &queue);
+        assert_eq!(dispatched, vec![queue[2].execution_process_id]);
+    }
+}
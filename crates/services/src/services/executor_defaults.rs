@@ -0,0 +1,174 @@
+//! Choosing the coding agent profile of a workspace from the request and the
+//! defaults set on its repositories, its project and the global config.
+
+use db::models::{
+    project::Project, project_repo::ProjectRepo, repo::Repo, task::Task, workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Where an effective executor profile came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutorProfileSource {
+    Request,
+    Repo,
+    Project,
+    Global,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct EffectiveExecutorProfile {
+    pub executor_profile_id: ExecutorProfileId,
+    pub source: ExecutorProfileSource,
+    /// Repository whose default applies, when `source` is `repo`
+    pub repo_id: Option<Uuid>,
+}
+
+impl EffectiveExecutorProfile {
+    /// `requested` when set, otherwise the default of the first of `repos`
+    /// that has one, then the project's, then `global`
+    pub fn choose(
+        requested: Option<ExecutorProfileId>,
+        repos: &[Repo],
+        project: Option<&Project>,
+        global: &ExecutorProfileId,
+    ) -> Self {
+        if let Some(executor_profile_id) = requested {
+            return Self::new(executor_profile_id, ExecutorProfileSource::Request, None);
+        }
+        if let Some((repo, default)) = repos.iter().find_map(|repo| {
+            repo.default_executor_profile_id
+                .as_ref()
+                .map(|default| (repo, default))
+        }) {
+            return Self::new(
+                default.0.clone(),
+                ExecutorProfileSource::Repo,
+                Some(repo.id),
+            );
+        }
+        if let Some(default) = project.and_then(|p| p.default_executor_profile_id.as_ref()) {
+            return Self::new(default.0.clone(), ExecutorProfileSource::Project, None);
+        }
+        Self::new(global.clone(), ExecutorProfileSource::Global, None)
+    }
+
+    /// Profile for a new workspace of `project_id` on `repo_ids`, in the
+    /// order the repositories were given
+    pub async fn resolve(
+        pool: &SqlitePool,
+        requested: Option<ExecutorProfileId>,
+        project_id: Uuid,
+        repo_ids: &[Uuid],
+        global: &ExecutorProfileId,
+    ) -> Result<Self, sqlx::Error> {
+        if let Some(executor_profile_id) = requested {
+            return Ok(Self::new(
+                executor_profile_id,
+                ExecutorProfileSource::Request,
+                None,
+            ));
+        }
+        let mut repos = Repo::find_by_ids(pool, repo_ids).await?;
+        repos.sort_by_key(|repo| repo_ids.iter().position(|id| *id == repo.id));
+        let project = Project::find_by_id(pool, project_id).await?;
+        Ok(Self::choose(None, &repos, project.as_ref(), global))
+    }
+
+    /// Profile for a new workspace of `task` on every repository of its project
+    pub async fn resolve_for_task(
+        pool: &SqlitePool,
+        task: &Task,
+        global: &ExecutorProfileId,
+    ) -> Result<Self, sqlx::Error> {
+        let repos = ProjectRepo::find_repos_for_project(pool, task.project_id).await?;
+        let project = Project::find_by_id(pool, task.project_id).await?;
+        Ok(Self::choose(None, &repos, project.as_ref(), global))
+    }
+
+    /// Profile for new coding agent runs in an existing workspace
+    pub async fn resolve_for_workspace(
+        pool: &SqlitePool,
+        requested: Option<ExecutorProfileId>,
+        workspace: &Workspace,
+        global: &ExecutorProfileId,
+    ) -> Result<Self, sqlx::Error> {
+        if let Some(executor_profile_id) = requested {
+            return Ok(Self::new(
+                executor_profile_id,
+                ExecutorProfileSource::Request,
+                None,
+            ));
+        }
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+        let project = match Task::find_by_id(pool, workspace.task_id).await? {
+            Some(task) => Project::find_by_id(pool, task.project_id).await?,
+            None => None,
+        };
+        Ok(Self::choose(None, &repos, project.as_ref(), global))
+    }
+
+    fn new(
+        executor_profile_id: ExecutorProfileId,
+        source: ExecutorProfileSource,
+        repo_id: Option<Uuid>,
+    ) -> Self {
+        Self {
+            executor_profile_id,
+            source,
+            repo_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+    use crate::services::test_fixtures;
+
+    fn repo(default: Option<ExecutorProfileId>) -> Repo {
+        Repo {
+            default_executor_profile_id: default.map(sqlx::types::Json),
+            ..test_fixtures::repo("repo")
+        }
+    }
+
+    #[test]
+    fn request_then_repo_then_project_then_global() {
+        let global = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+        let codex_high =
+            ExecutorProfileId::with_variant(BaseCodingAgent::Codex, "HIGH".to_string());
+        let gemini = ExecutorProfileId::new(BaseCodingAgent::Gemini);
+        let amp = ExecutorProfileId::new(BaseCodingAgent::Amp);
+        let project = Project {
+            default_executor_profile_id: Some(sqlx::types::Json(gemini.clone())),
+            ..test_fixtures::project()
+        };
+        let repos = [repo(None), repo(Some(codex_high.clone()))];
+
+        let requested =
+            EffectiveExecutorProfile::choose(Some(amp.clone()), &repos, Some(&project), &global);
+        assert_eq!(requested.executor_profile_id, amp);
+        assert_eq!(requested.source, ExecutorProfileSource::Request);
+
+        let from_repo = EffectiveExecutorProfile::choose(None, &repos, Some(&project), &global);
+        assert_eq!(from_repo.executor_profile_id, codex_high);
+        assert_eq!(from_repo.repo_id, Some(repos[1].id));
+
+        let from_project =
+            EffectiveExecutorProfile::choose(None, &repos[..1], Some(&project), &global);
+        assert_eq!(from_project.executor_profile_id, gemini);
+        assert_eq!(from_project.source, ExecutorProfileSource::Project);
+
+        let from_global = EffectiveExecutorProfile::choose(None, &repos[..1], None, &global);
+        assert_eq!(from_global.executor_profile_id, global);
+        assert_eq!(from_global.source, ExecutorProfileSource::Global);
+    }
+}
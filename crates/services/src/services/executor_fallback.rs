@@ -0,0 +1,176 @@
+//! Choosing the next profile of an executor fallback chain when a coding
+//! agent is unavailable or its run fails on authentication or a usage limit.
+
+use executors::{
+    executors::{ExecutorError, StandardCodingAgentExecutor},
+    logs::{
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        utils::patch::extract_normalized_entry_from_patch,
+    },
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+/// Lowercase fragments of agent error messages about authentication or usage
+/// limits
+const FALLBACK_ERROR_PATTERNS: &[&str] = &[
+    "rate limit",
+    "rate_limit",
+    "ratelimit",
+    "usage limit",
+    "too many requests",
+    "quota",
+    "unauthorized",
+    "authentication",
+    "invalid api key",
+    "not logged in",
+];
+
+/// Whether the agent of `profile` is installed or logged in
+pub fn is_available(profile: &ExecutorProfileId) -> bool {
+    ExecutorConfigs::get_cached()
+        .get_coding_agent(profile)
+        .is_some_and(|agent| agent.get_availability_info().is_available())
+}
+
+/// Profiles to try, in order, when a coding agent run with `profile` can't do
+/// the work
+pub fn chain_for(profile: &ExecutorProfileId) -> Vec<ExecutorProfileId> {
+    ExecutorConfigs::get_cached()
+        .get_settings(profile)
+        .fallbacks
+}
+
+/// `profile` when its agent is available, otherwise the first available
+/// profile of `chain`. Falls back to `profile` when none is.
+pub fn first_available(
+    profile: ExecutorProfileId,
+    chain: &[ExecutorProfileId],
+    is_available: impl Fn(&ExecutorProfileId) -> bool,
+) -> ExecutorProfileId {
+    if chain.is_empty() || is_available(&profile) {
+        return profile;
+    }
+    chain
+        .iter()
+        .find(|candidate| is_available(candidate))
+        .cloned()
+        .unwrap_or(profile)
+}
+
+/// First available profile of `chain` that hasn't been tried yet
+pub fn next_profile(
+    chain: &[ExecutorProfileId],
+    tried: &[ExecutorProfileId],
+    is_available: impl Fn(&ExecutorProfileId) -> bool,
+) -> Option<ExecutorProfileId> {
+    chain
+        .iter()
+        .find(|candidate| !tried.contains(candidate) && is_available(candidate))
+        .cloned()
+}
+
+/// Whether an executor failed to start in a way another agent may not
+pub fn is_fallback_start_error(error: &ExecutorError) -> bool {
+    matches!(error, ExecutorError::AuthRequired(_))
+}
+
+/// Whether a normalized entry reports missing authentication or a usage limit
+pub fn is_fallback_error(entry: &NormalizedEntry) -> bool {
+    match &entry.entry_type {
+        NormalizedEntryType::ErrorMessage {
+            error_type: NormalizedEntryError::SetupRequired,
+        } => true,
+        NormalizedEntryType::ErrorMessage { .. } => {
+            let content = entry.content.to_lowercase();
+            FALLBACK_ERROR_PATTERNS
+                .iter()
+                .any(|pattern| content.contains(pattern))
+        }
+        _ => false,
+    }
+}
+
+/// Whether the normalized logs of a run contain an error worth falling back on
+pub fn has_fallback_error(msg_store: &MsgStore) -> bool {
+    msg_store
+        .get_history()
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::JsonPatch(patch) => extract_normalized_entry_from_patch(patch),
+            _ => None,
+        })
+        .any(|(_, entry)| is_fallback_error(&entry))
+}
+
+/// Entry telling the user which profile the next attempt runs with
+pub fn switch_notice(next: &ExecutorProfileId) -> NormalizedEntry {
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::SystemMessage,
+        content: format!("Switching to fallback executor {next}"),
+        metadata: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+
+    fn error(error_type: NormalizedEntryError, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ErrorMessage { error_type },
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn next_profile_skips_tried_and_unavailable_profiles() {
+        let codex = ExecutorProfileId::new(BaseCodingAgent::Codex);
+        let gemini = ExecutorProfileId::new(BaseCodingAgent::Gemini);
+        let amp = ExecutorProfileId::new(BaseCodingAgent::Amp);
+        let chain = [codex.clone(), gemini.clone(), amp.clone()];
+        let tried = [ExecutorProfileId::new(BaseCodingAgent::ClaudeCode), codex];
+
+        assert_eq!(
+            next_profile(&chain, &tried, |profile| profile != &gemini),
+            Some(amp.clone())
+        );
+        assert_eq!(next_profile(&chain, &tried, |_| false), None);
+        assert_eq!(
+            first_available(amp.clone(), &chain, |profile| profile == &gemini),
+            gemini
+        );
+        assert_eq!(first_available(amp.clone(), &[], |_| false), amp);
+    }
+
+    #[test]
+    fn recognises_auth_and_limit_errors() {
+        assert!(is_fallback_error(&error(
+            NormalizedEntryError::SetupRequired,
+            "Please log in"
+        )));
+        assert!(is_fallback_error(&error(
+            NormalizedEntryError::Other,
+            "Claude AI usage limit reached|1760000000"
+        )));
+        assert!(is_fallback_error(&error(
+            NormalizedEntryError::Other,
+            "chat completion failed with 429 Too Many Requests: slow down"
+        )));
+        assert!(!is_fallback_error(&error(
+            NormalizedEntryError::Other,
+            "cargo test failed"
+        )));
+        assert!(!is_fallback_error(&NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: "Hit the rate limit earlier".to_string(),
+            metadata: None,
+        }));
+    }
+}
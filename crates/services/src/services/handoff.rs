@@ -0,0 +1,349 @@
+//! Summaries of a session's coding agent conversations, sent as the first
+//! prompt when another agent takes the session over.
+
+use executors::logs::{
+    ActionType, FileChange, NormalizedConversation, NormalizedEntryType, TodoItem, ToolStatus,
+};
+use utils::text::truncate_to_char_boundary;
+
+use crate::services::diff_stream::DiffStats;
+
+const HANDOFF_HEADING: &str = "# Handoff";
+const NEXT_STEP_HEADING: &str = "## Your next step";
+/// Number of the latest plans and agent messages kept in a handoff
+const MAX_DECISIONS: usize = 5;
+/// Longest excerpt of a plan or agent message, in bytes
+const MAX_DECISION_LEN: usize = 2000;
+
+/// What the agents of a session did so far
+#[derive(Debug, Clone, Default)]
+pub struct SessionHandoff {
+    pub task_title: String,
+    pub task_description: Option<String>,
+    /// Executors that worked on the session, in the order they first ran
+    pub executors: Vec<String>,
+    /// Prompts of the coding agent runs, oldest first
+    pub requests: Vec<String>,
+    /// Plans and the final message of each run, oldest first
+    pub decisions: Vec<String>,
+    /// Files the agents wrote, edited, renamed or deleted
+    pub files_touched: Vec<String>,
+    /// Open items of the latest todo list
+    pub pending_todos: Vec<TodoItem>,
+    pub diff_stats: Option<DiffStats>,
+}
+
+impl SessionHandoff {
+    pub fn new(
+        task_title: String,
+        task_description: Option<String>,
+        conversations: &[NormalizedConversation],
+        diff_stats: Option<DiffStats>,
+    ) -> Self {
+        let mut handoff = Self {
+            task_title,
+            task_description: task_description.filter(|d| !d.trim().is_empty()),
+            diff_stats,
+            ..Self::default()
+        };
+
+        for conversation in conversations {
+            if !conversation.executor_type.is_empty()
+                && !handoff.executors.contains(&conversation.executor_type)
+            {
+                handoff.executors.push(conversation.executor_type.clone());
+            }
+
+            let prompt = conversation.prompt.clone().or_else(|| {
+                conversation
+                    .entries
+                    .iter()
+                    .find(|e| matches!(e.entry_type, NormalizedEntryType::UserMessage))
+                    .map(|e| e.content.clone())
+            });
+            if let Some(prompt) = prompt.as_deref().map(strip_handoff).map(str::trim)
+                && !prompt.is_empty()
+            {
+                handoff.requests.push(prompt.to_string());
+            }
+
+            let mut final_message = None;
+            for entry in &conversation.entries {
+                match &entry.entry_type {
+                    NormalizedEntryType::AssistantMessage if !entry.content.trim().is_empty() => {
+                        final_message = Some(entry.content.trim());
+                    }
+                    NormalizedEntryType::ToolUse {
+                        action_type,
+                        status,
+                        ..
+                    } => handoff.record_action(action_type, status),
+                    _ => {}
+                }
+            }
+            if let Some(message) = final_message {
+                handoff.decisions.push(excerpt(message));
+            }
+        }
+
+        let skip = handoff.decisions.len().saturating_sub(MAX_DECISIONS);
+        handoff.decisions.drain(..skip);
+        handoff
+    }
+
+    fn record_action(&mut self, action_type: &ActionType, status: &ToolStatus) {
+        match action_type {
+            ActionType::FileEdit { path, changes }
+                if !matches!(status, ToolStatus::Failed | ToolStatus::Denied { .. }) =>
+            {
+                self.touch(path);
+                for change in changes {
+                    if let FileChange::Rename { new_path } = change {
+                        self.touch(new_path);
+                    }
+                }
+            }
+            ActionType::PlanPresentation { plan } if !plan.trim().is_empty() => {
+                self.decisions.push(excerpt(plan.trim()));
+            }
+            ActionType::TodoManagement { todos, .. } => {
+                self.pending_todos = todos
+                    .iter()
+                    .filter(|todo| !is_done(&todo.status))
+                    .cloned()
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+
+    fn touch(&mut self, path: &str) {
+        if !self.files_touched.iter().any(|p| p == path) {
+            self.files_touched.push(path.to_string());
+        }
+    }
+
+    /// The handoff prompt, ending with `next_step` for the new agent
+    pub fn render(&self, next_step: &str) -> String {
+        let mut sections = vec![format!(
+            "{HANDOFF_HEADING}\n\nYou are taking over this task from {} in the same workspace. \
+             Their changes are already in the working tree; the summary below covers what \
+             happened so far.",
+            match self.executors.as_slice() {
+                [] => "another coding agent".to_string(),
+                executors => format!("another coding agent ({})", executors.join(", ")),
+            }
+        )];
+
+        let mut task = format!("## Task\n\n{}", self.task_title);
+        if let Some(description) = &self.task_description {
+            task.push_str(&format!("\n\n{}", description.trim()));
+        }
+        sections.push(task);
+
+        if !self.requests.is_empty() {
+            sections.push(format!(
+                "## Requests so far\n\n{}",
+                numbered(&self.requests)
+            ));
+        }
+        if !self.decisions.is_empty() {
+            sections.push(format!(
+                "## Decisions and progress\n\n{}",
+                self.decisions
+                    .iter()
+                    .map(|decision| quoted(decision))
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            ));
+        }
+        if !self.files_touched.is_empty() {
+            sections.push(format!(
+                "## Files touched\n\n{}",
+                self.files_touched
+                    .iter()
+                    .map(|path| format!("- `{path}`"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+        if !self.pending_todos.is_empty() {
+            sections.push(format!(
+                "## Pending todos\n\n{}",
+                self.pending_todos
+                    .iter()
+                    .map(|todo| format!("- [ ] {} ({})", todo.content, todo.status))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+        if let Some(stats) = &self.diff_stats {
+            sections.push(format!(
+                "## Current changes\n\n{} files changed, +{} -{} lines against the target branches",
+                stats.files_changed, stats.lines_added, stats.lines_removed
+            ));
+        }
+
+        let next_step = match next_step.trim() {
+            "" => "Continue the task where the previous agent left off.",
+            next_step => next_step,
+        };
+        sections.push(format!("{NEXT_STEP_HEADING}\n\n{next_step}"));
+        sections.join("\n\n")
+    }
+}
+
+/// The request part of an earlier handoff prompt, so summaries don't nest
+fn strip_handoff(prompt: &str) -> &str {
+    if !prompt.starts_with(HANDOFF_HEADING) {
+        return prompt;
+    }
+    prompt
+        .rsplit_once(NEXT_STEP_HEADING)
+        .map_or("", |(_, next_step)| next_step)
+}
+
+fn is_done(status: &str) -> bool {
+    matches!(
+        status.to_ascii_lowercase().as_str(),
+        "completed" | "complete" | "done" | "cancelled" | "canceled"
+    )
+}
+
+fn excerpt(text: &str) -> String {
+    let cut = truncate_to_char_boundary(text, MAX_DECISION_LEN);
+    if cut.len() < text.len() {
+        format!("{cut}…")
+    } else {
+        cut.to_string()
+    }
+}
+
+fn numbered(items: &[String]) -> String {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| format!("{}. {}", i + 1, item.replace('\n', "\n   ")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn quoted(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("> {line}").trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::NormalizedEntry;
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn tool(action_type: ActionType, status: ToolStatus) -> NormalizedEntry {
+        entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "tool".to_string(),
+                action_type,
+                status,
+            },
+            "",
+        )
+    }
+
+    fn todo(content: &str, status: &str) -> TodoItem {
+        TodoItem {
+            content: content.to_string(),
+            status: status.to_string(),
+            priority: None,
+        }
+    }
+
+    #[test]
+    fn summarises_conversations_for_the_next_agent() {
+        let conversations = [
+            NormalizedConversation {
+                entries: vec![
+                    tool(
+                        ActionType::FileEdit {
+                            path: "src/lib.rs".to_string(),
+                            changes: vec![],
+                        },
+                        ToolStatus::Success,
+                    ),
+                    tool(
+                        ActionType::FileEdit {
+                            path: "src/old.rs".to_string(),
+                            changes: vec![],
+                        },
+                        ToolStatus::Failed,
+                    ),
+                    tool(
+                        ActionType::TodoManagement {
+                            todos: vec![
+                                todo("Parse config", "completed"),
+                                todo("Add tests", "pending"),
+                            ],
+                            operation: "write".to_string(),
+                        },
+                        ToolStatus::Success,
+                    ),
+                    entry(NormalizedEntryType::AssistantMessage, "Thinking out loud"),
+                    entry(NormalizedEntryType::AssistantMessage, "Parsing uses serde."),
+                ],
+                session_id: None,
+                executor_type: "CLAUDE_CODE".to_string(),
+                prompt: Some("Add config parsing".to_string()),
+                summary: None,
+            },
+            NormalizedConversation {
+                entries: vec![],
+                session_id: None,
+                executor_type: "CODEX".to_string(),
+                prompt: Some(format!(
+                    "{HANDOFF_HEADING}\n\nOld summary\n\n{NEXT_STEP_HEADING}\n\nNow the CLI flags"
+                )),
+                summary: None,
+            },
+        ];
+
+        let handoff = SessionHandoff::new(
+            "Config".to_string(),
+            Some(" ".to_string()),
+            &conversations,
+            Some(DiffStats {
+                files_changed: 2,
+                lines_added: 10,
+                lines_removed: 3,
+            }),
+        );
+
+        assert_eq!(handoff.executors, ["CLAUDE_CODE", "CODEX"]);
+        assert_eq!(
+            handoff.requests,
+            ["Add config parsing", "Now the CLI flags"]
+        );
+        assert_eq!(handoff.decisions, ["Parsing uses serde."]);
+        assert_eq!(handoff.files_touched, ["src/lib.rs"]);
+        assert_eq!(handoff.pending_todos.len(), 1);
+        assert_eq!(handoff.task_description, None);
+
+        let prompt = handoff.render("Write the tests");
+        assert!(prompt.starts_with(HANDOFF_HEADING));
+        assert!(prompt.contains("(CLAUDE_CODE, CODEX)"));
+        assert!(prompt.contains("- [ ] Add tests (pending)"));
+        assert!(prompt.contains("2 files changed, +10 -3 lines"));
+        assert!(prompt.ends_with(&format!("{NEXT_STEP_HEADING}\n\nWrite the tests")));
+        assert_eq!(strip_handoff(&prompt).trim(), "Write the tests");
+    }
+}
@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use json_patch::Patch;
+use serde_json::{Value, from_value, json};
+use utils::log_msg::LogMsg;
+
+/// Number of execution processes pruned per database round trip
+pub const PRUNE_BATCH_SIZE: i64 = 50;
+
+/// Re-normalizing stored logs ends once every normalizer has gone through them.
+/// A normalizer taking longer than this is given up on, and its partial output
+/// is never used in place of the logs.
+pub const NORMALIZATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Collapse the patches a normalizer produced into one `add` patch per final
+/// conversation entry, so a pruned process replays the same conversation from a
+/// fraction of the rows. Returns `None` if the patches don't apply cleanly, in which
+/// case callers should keep the original sequence.
+pub fn compact_conversation_patches(patches: &[Patch]) -> Option<Vec<Patch>> {
+    let mut conversation = json!({ "entries": [] });
+    for patch in patches {
+        if let Err(e) = json_patch::patch(&mut conversation, &patch.0) {
+            tracing::debug!("Conversation patch did not apply during compaction: {}", e);
+            return None;
+        }
+    }
+
+    let Value::Array(entries) = conversation["entries"].take() else {
+        return None;
+    };
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            from_value(json!([{
+                "op": "add",
+                "path": format!("/entries/{index}"),
+                "value": entry,
+            }]))
+            .ok()
+        })
+        .collect()
+}
+
+/// Serialize patches as the JSONL stored in `execution_process_logs`
+pub fn patches_to_jsonl(patches: Vec<Patch>) -> Result<String, serde_json::Error> {
+    let mut jsonl = String::new();
+    for patch in patches {
+        jsonl.push_str(&serde_json::to_string(&LogMsg::JsonPatch(patch))?);
+        jsonl.push('\n');
+    }
+    Ok(jsonl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stdout_patch(op: &str, index: usize, content: &str) -> Patch {
+        from_value(json!([{
+            "op": op,
+            "path": format!("/entries/{index}"),
+            "value": { "type": "STDOUT", "content": content },
+        }]))
+        .unwrap()
+    }
+
+    #[test]
+    fn compacts_replaced_entries_to_final_state() {
+        let patches = vec![
+            stdout_patch("add", 0, "a"),
+            stdout_patch("add", 1, "b"),
+            stdout_patch("replace", 1, "c"),
+        ];
+
+        let compacted = compact_conversation_patches(&patches).unwrap();
+        assert_eq!(compacted.len(), 2);
+
+        let mut replayed = json!({ "entries": [] });
+        for patch in &compacted {
+            json_patch::patch(&mut replayed, &patch.0).unwrap();
+        }
+        assert_eq!(
+            replayed,
+            json!({ "entries": [
+                { "type": "STDOUT", "content": "a" },
+                { "type": "STDOUT", "content": "c" },
+            ] })
+        );
+    }
+
+    #[test]
+    fn refuses_patches_that_do_not_apply() {
+        let patches = vec![stdout_patch("replace", 3, "x")];
+        assert!(compact_conversation_patches(&patches).is_none());
+    }
+
+    #[test]
+    fn serializes_patches_as_log_msg_lines() {
+        let jsonl = patches_to_jsonl(vec![stdout_patch("add", 0, "a")]).unwrap();
+        assert!(jsonl.starts_with("{\"JsonPatch\":"));
+        assert!(jsonl.ends_with('\n'));
+    }
+}
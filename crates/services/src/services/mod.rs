@@ -1,14 +1,24 @@
+pub mod agent_instructions;
 pub mod approvals;
+pub mod budget;
 pub mod config;
 pub mod container;
+pub mod dev_server_monitor;
+pub mod dev_server_ports;
 pub mod diff_stream;
 pub mod events;
+pub mod execution_retry;
+pub mod execution_scheduler;
+pub mod executor_defaults;
+pub mod executor_fallback;
 pub mod file_ranker;
 pub mod file_search;
 pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git_host;
+pub mod handoff;
 pub mod image;
+pub mod log_retention;
 pub mod notification;
 pub mod pr_monitor;
 pub mod project;
@@ -16,5 +26,9 @@ pub mod project;
 pub mod qa_repos;
 pub mod queued_message;
 pub mod repo;
+#[cfg(test)]
+pub(crate) mod test_fixtures;
+pub mod token_usage;
+pub mod transcript;
 pub mod workspace_manager;
 pub mod worktree_manager;
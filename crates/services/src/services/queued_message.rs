@@ -1,22 +1,21 @@
-use std::sync::Arc;
+use std::collections::HashSet;
 
-use chrono::{DateTime, Utc};
-use dashmap::DashMap;
+pub use db::models::queued_message::QueuedMessage;
 use db::models::scratch::DraftFollowUpData;
 use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
-/// Represents a queued follow-up message for a session
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
-#[ts(export)]
-pub struct QueuedMessage {
-    /// The session this message is queued for
-    pub session_id: Uuid,
-    /// The follow-up data (message + variant)
-    pub data: DraftFollowUpData,
-    /// Timestamp when the message was queued
-    pub queued_at: DateTime<Utc>,
+#[derive(Debug, Error)]
+pub enum QueuedMessageError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Queued message not found")]
+    NotFound,
+    #[error("The new order must list every queued message exactly once")]
+    InvalidOrder,
 }
 
 /// Status of the queue for a session (for frontend display)
@@ -26,67 +25,156 @@ pub struct QueuedMessage {
 pub enum QueueStatus {
     /// No message queued
     Empty,
-    /// Message is queued and waiting for execution to complete
-    Queued { message: QueuedMessage },
+    /// Messages waiting for execution to complete, next to be sent first
+    Queued { messages: Vec<QueuedMessage> },
 }
 
-/// In-memory service for managing queued follow-up messages.
-/// One queued message per session.
+/// Ordered, persistent queue of follow-up messages per session. Messages are
+/// sent one at a time as each coding agent turn finishes.
 #[derive(Clone)]
 pub struct QueuedMessageService {
-    queue: Arc<DashMap<Uuid, QueuedMessage>>,
+    pool: SqlitePool,
 }
 
 impl QueuedMessageService {
-    pub fn new() -> Self {
-        Self {
-            queue: Arc::new(DashMap::new()),
-        }
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
     }
 
-    /// Queue a message for a session. Replaces any existing queued message.
-    pub fn queue_message(&self, session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
-        let queued = QueuedMessage {
-            session_id,
-            data,
-            queued_at: Utc::now(),
-        };
-        self.queue.insert(session_id, queued.clone());
-        queued
+    /// Append a message to the end of a session's queue
+    pub async fn queue_message(
+        &self,
+        session_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<QueuedMessage, QueuedMessageError> {
+        Ok(QueuedMessage::create(&self.pool, session_id, &data).await?)
+    }
+
+    pub async fn update_message(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<QueuedMessage, QueuedMessageError> {
+        self.find_in_session(session_id, message_id).await?;
+        QueuedMessage::update(&self.pool, message_id, &data)
+            .await?
+            .ok_or(QueuedMessageError::NotFound)
     }
 
-    /// Cancel/remove a queued message for a session
-    pub fn cancel_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    pub async fn remove_message(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<(), QueuedMessageError> {
+        self.find_in_session(session_id, message_id).await?;
+        QueuedMessage::delete(&self.pool, message_id).await?;
+        Ok(())
     }
 
-    /// Get the queued message for a session (if any)
-    pub fn get_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.get(&session_id).map(|r| r.clone())
+    /// Make a message the next one to be sent
+    pub async fn send_next(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<(), QueuedMessageError> {
+        self.find_in_session(session_id, message_id).await?;
+        QueuedMessage::move_to_front(&self.pool, message_id).await?;
+        Ok(())
     }
 
-    /// Take (remove and return) the queued message for a session.
-    /// Used by finalization flow to consume the queued message.
-    pub fn take_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    pub async fn reorder(
+        &self,
+        session_id: Uuid,
+        message_ids: &[Uuid],
+    ) -> Result<(), QueuedMessageError> {
+        let queued = QueuedMessage::find_by_session_id(&self.pool, session_id).await?;
+        if !is_permutation(&queued, message_ids) {
+            return Err(QueuedMessageError::InvalidOrder);
+        }
+        QueuedMessage::reorder(&self.pool, session_id, message_ids).await?;
+        Ok(())
+    }
+
+    /// Remove every queued message of a session
+    pub async fn cancel_queued(&self, session_id: Uuid) -> Result<(), QueuedMessageError> {
+        QueuedMessage::delete_by_session_id(&self.pool, session_id).await?;
+        Ok(())
     }
 
-    /// Check if a session has a queued message
-    pub fn has_queued(&self, session_id: Uuid) -> bool {
-        self.queue.contains_key(&session_id)
+    /// Take (remove and return) the next queued message for a session.
+    /// Used by finalization flow to consume the queue one message at a time.
+    pub async fn take_next(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<QueuedMessage>, QueuedMessageError> {
+        Ok(QueuedMessage::take_next(&self.pool, session_id).await?)
+    }
+
+    /// Check if a session has queued messages
+    pub async fn has_queued(&self, session_id: Uuid) -> Result<bool, QueuedMessageError> {
+        Ok(!QueuedMessage::find_by_session_id(&self.pool, session_id)
+            .await?
+            .is_empty())
     }
 
     /// Get queue status for frontend display
-    pub fn get_status(&self, session_id: Uuid) -> QueueStatus {
-        match self.get_queued(session_id) {
-            Some(msg) => QueueStatus::Queued { message: msg },
-            None => QueueStatus::Empty,
-        }
+    pub async fn get_status(&self, session_id: Uuid) -> Result<QueueStatus, QueuedMessageError> {
+        let messages = QueuedMessage::find_by_session_id(&self.pool, session_id).await?;
+        Ok(if messages.is_empty() {
+            QueueStatus::Empty
+        } else {
+            QueueStatus::Queued { messages }
+        })
     }
+
+    async fn find_in_session(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<QueuedMessage, QueuedMessageError> {
+        QueuedMessage::find_by_id(&self.pool, message_id)
+            .await?
+            .filter(|message| message.session_id == session_id)
+            .ok_or(QueuedMessageError::NotFound)
+    }
+}
+
+fn is_permutation(queued: &[QueuedMessage], message_ids: &[Uuid]) -> bool {
+    let requested: HashSet<&Uuid> = message_ids.iter().collect();
+    requested.len() == message_ids.len()
+        && message_ids.len() == queued.len()
+        && queued.iter().all(|message| requested.contains(&message.id))
 }
 
-impl Default for QueuedMessageService {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+
+    use super::*;
+
+    fn queued(id: Uuid) -> QueuedMessage {
+        QueuedMessage {
+            id,
+            session_id: Uuid::nil(),
+            data: sqlx::types::Json(DraftFollowUpData {
+                message: "next".to_string(),
+                executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+            }),
+            queued_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn reorder_requires_every_message_once() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let queue = vec![queued(a), queued(b)];
+
+        assert!(is_permutation(&queue, &[b, a]));
+        assert!(!is_permutation(&queue, &[a]));
+        assert!(!is_permutation(&queue, &[a, a]));
+        assert!(!is_permutation(&queue, &[a, b, Uuid::new_v4()]));
     }
 }
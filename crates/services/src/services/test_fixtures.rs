@@ -0,0 +1,44 @@
+//! Project and repository records for unit tests, with every optional
+//! setting left unset.
+
+use std::path::PathBuf;
+
+use chrono::Utc;
+use db::models::{project::Project, repo::Repo};
+use uuid::Uuid;
+
+pub(crate) fn repo(name: &str) -> Repo {
+    Repo {
+        id: Uuid::new_v4(),
+        path: PathBuf::from("/tmp").join(name),
+        name: name.to_string(),
+        display_name: name.to_string(),
+        setup_script: None,
+        cleanup_script: None,
+        archive_script: None,
+        copy_files: None,
+        parallel_setup_script: false,
+        dev_server_script: None,
+        dev_server_ready_pattern: None,
+        dev_server_health_path: None,
+        dev_server_auto_restart: false,
+        default_target_branch: None,
+        default_working_dir: None,
+        default_executor_profile_id: None,
+        agent_instructions: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+pub(crate) fn project() -> Project {
+    Project {
+        id: Uuid::new_v4(),
+        name: "project".to_string(),
+        default_agent_working_dir: None,
+        default_executor_profile_id: None,
+        agent_instructions: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
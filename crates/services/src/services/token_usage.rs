@@ -0,0 +1,213 @@
+use chrono::{DateTime, Utc};
+use db::models::execution_process_token_usage::TokenUsageTotal;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::services::config::TokenPrice;
+
+/// Usage of one model through one executor variant
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TokenUsageLine {
+    pub executor: String,
+    pub variant: Option<String>,
+    pub model: String,
+    pub execution_process_count: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cached_input_tokens: i64,
+    /// `None` when no configured price matches
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ExecutorTokenUsage {
+    pub executor: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cached_input_tokens: i64,
+    /// Cost of the usage that has a configured price
+    pub cost_usd: f64,
+}
+
+/// Token usage and cost over a session, task, project or everything
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TokenUsageReport {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cached_input_tokens: i64,
+    /// Cost of the usage that has a configured price
+    pub cost_usd: f64,
+    /// Models used without a matching entry in `token_prices`
+    pub unpriced_models: Vec<String>,
+    pub by_executor: Vec<ExecutorTokenUsage>,
+    pub lines: Vec<TokenUsageLine>,
+}
+
+impl TokenUsageReport {
+    pub fn new(
+        totals: Vec<TokenUsageTotal>,
+        prices: &[TokenPrice],
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Self {
+        let mut report = Self {
+            from,
+            to,
+            input_tokens: 0,
+            output_tokens: 0,
+            cached_input_tokens: 0,
+            cost_usd: 0.0,
+            unpriced_models: Vec::new(),
+            by_executor: Vec::new(),
+            lines: Vec::with_capacity(totals.len()),
+        };
+
+        for total in totals {
+            let price = find_price(
+                prices,
+                &total.executor,
+                total.variant.as_deref(),
+                &total.model,
+            );
+            let cost_usd = price.map(|price| {
+                cost(
+                    price,
+                    total.input_tokens,
+                    total.output_tokens,
+                    total.cached_input_tokens,
+                )
+            });
+
+            report.input_tokens += total.input_tokens;
+            report.output_tokens += total.output_tokens;
+            report.cached_input_tokens += total.cached_input_tokens;
+            report.cost_usd += cost_usd.unwrap_or(0.0);
+            if cost_usd.is_none() && !report.unpriced_models.contains(&total.model) {
+                report.unpriced_models.push(total.model.clone());
+            }
+
+            // totals arrive ordered by executor
+            match report.by_executor.last_mut() {
+                Some(executor) if executor.executor == total.executor => {
+                    executor.input_tokens += total.input_tokens;
+                    executor.output_tokens += total.output_tokens;
+                    executor.cached_input_tokens += total.cached_input_tokens;
+                    executor.cost_usd += cost_usd.unwrap_or(0.0);
+                }
+                _ => report.by_executor.push(ExecutorTokenUsage {
+                    executor: total.executor.clone(),
+                    input_tokens: total.input_tokens,
+                    output_tokens: total.output_tokens,
+                    cached_input_tokens: total.cached_input_tokens,
+                    cost_usd: cost_usd.unwrap_or(0.0),
+                }),
+            }
+
+            report.lines.push(TokenUsageLine {
+                executor: total.executor,
+                variant: total.variant,
+                model: total.model,
+                execution_process_count: total.execution_process_count,
+                input_tokens: total.input_tokens,
+                output_tokens: total.output_tokens,
+                cached_input_tokens: total.cached_input_tokens,
+                cost_usd,
+            });
+        }
+
+        report
+    }
+}
+
+/// First price whose set fields all match the usage
+pub fn find_price<'a>(
+    prices: &'a [TokenPrice],
+    executor: &str,
+    variant: Option<&str>,
+    model: &str,
+) -> Option<&'a TokenPrice> {
+    prices.iter().find(|price| {
+        price
+            .model
+            .as_deref()
+            .is_none_or(|prefix| model.starts_with(prefix))
+            && price.executor.as_deref().is_none_or(|e| e == executor)
+            && price.variant.as_deref().is_none_or(|v| Some(v) == variant)
+    })
+}
+
+pub fn cost(
+    price: &TokenPrice,
+    input_tokens: i64,
+    output_tokens: i64,
+    cached_input_tokens: i64,
+) -> f64 {
+    (input_tokens as f64 * price.input_per_million
+        + output_tokens as f64 * price.output_per_million
+        + cached_input_tokens as f64 * price.cached_input_per_million)
+        / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(model: Option<&str>, variant: Option<&str>, input: f64) -> TokenPrice {
+        TokenPrice {
+            model: model.map(str::to_string),
+            executor: None,
+            variant: variant.map(str::to_string),
+            input_per_million: input,
+            output_per_million: 10.0,
+            cached_input_per_million: 0.5,
+        }
+    }
+
+    fn total(executor: &str, model: &str, input_tokens: i64) -> TokenUsageTotal {
+        TokenUsageTotal {
+            executor: executor.to_string(),
+            variant: None,
+            model: model.to_string(),
+            execution_process_count: 1,
+            input_tokens,
+            output_tokens: 0,
+            cached_input_tokens: 0,
+        }
+    }
+
+    #[test]
+    fn first_matching_price_wins() {
+        let prices = vec![
+            price(Some("claude-opus"), Some("PLAN"), 15.0),
+            price(Some("claude-"), None, 3.0),
+        ];
+
+        let plan = find_price(&prices, "CLAUDE_CODE", Some("PLAN"), "claude-opus-4-1");
+        assert_eq!(plan.unwrap().input_per_million, 15.0);
+        let default = find_price(&prices, "CLAUDE_CODE", None, "claude-opus-4-1");
+        assert_eq!(default.unwrap().input_per_million, 3.0);
+        assert!(find_price(&prices, "CODEX", None, "gpt-5").is_none());
+    }
+
+    #[test]
+    fn report_sums_cost_and_lists_unpriced_models() {
+        let prices = vec![price(Some("claude-"), None, 3.0)];
+        let report = TokenUsageReport::new(
+            vec![
+                total("CLAUDE_CODE", "claude-sonnet-4", 2_000_000),
+                total("CODEX", "gpt-5", 1_000_000),
+            ],
+            &prices,
+            None,
+            None,
+        );
+
+        assert_eq!(report.input_tokens, 3_000_000);
+        assert_eq!(report.cost_usd, 6.0);
+        assert_eq!(report.unpriced_models, vec!["gpt-5".to_string()]);
+        assert_eq!(report.by_executor.len(), 2);
+        assert_eq!(report.lines[1].cost_usd, None);
+    }
+}
@@ -0,0 +1,515 @@
+use chrono::{DateTime, Utc};
+use executors::logs::{
+    ActionType, FileChange, NormalizedConversation, NormalizedEntry, NormalizedEntryType,
+    ToolResult, ToolStatus,
+};
+use json_patch::Patch;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+const REDACTED: &str = "[redacted]";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    #[default]
+    Markdown,
+    Html,
+    Json,
+}
+
+impl TranscriptFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Markdown => "text/markdown; charset=utf-8",
+            TranscriptFormat::Html => "text/html; charset=utf-8",
+            TranscriptFormat::Json => "application/json",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Markdown => "md",
+            TranscriptFormat::Html => "html",
+            TranscriptFormat::Json => "json",
+        }
+    }
+}
+
+/// Everything an agent did in one session, in the lossless form used for JSON export
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SessionTranscript {
+    pub session_id: Uuid,
+    pub workspace_id: Uuid,
+    pub task_title: String,
+    pub exported_at: DateTime<Utc>,
+    /// One conversation per coding agent run, oldest first
+    pub conversations: Vec<NormalizedConversation>,
+    /// Unified diff of the workspace against its target branches, when requested
+    pub diff: Option<String>,
+}
+
+impl SessionTranscript {
+    pub fn render(&self, format: TranscriptFormat) -> Result<String, serde_json::Error> {
+        match format {
+            TranscriptFormat::Markdown => Ok(render_markdown(&self.blocks())),
+            TranscriptFormat::Html => Ok(render_html(&self.task_title, &self.blocks())),
+            TranscriptFormat::Json => serde_json::to_string_pretty(self),
+        }
+    }
+
+    fn blocks(&self) -> Vec<Block> {
+        let mut blocks = vec![
+            Block::Heading(1, self.task_title.clone()),
+            Block::Meta(format!(
+                "Session {} · exported {}",
+                self.session_id,
+                self.exported_at.format("%Y-%m-%d %H:%M UTC")
+            )),
+        ];
+
+        for (turn, conversation) in self.conversations.iter().enumerate() {
+            blocks.push(Block::Heading(
+                2,
+                format!("Turn {} · {}", turn + 1, conversation.executor_type),
+            ));
+            blocks.extend(conversation.entries.iter().flat_map(entry_blocks));
+        }
+
+        if let Some(diff) = &self.diff {
+            blocks.push(Block::Heading(2, "Final diff".to_string()));
+            blocks.push(Block::Code {
+                language: "diff",
+                code: diff.clone(),
+            });
+        }
+
+        blocks
+    }
+}
+
+/// Replay conversation patches and return the normalized entries they produce
+pub fn entries_from_patches(patches: &[Patch]) -> Vec<NormalizedEntry> {
+    let mut conversation = json!({ "entries": [] });
+    for patch in patches {
+        if let Err(e) = json_patch::patch(&mut conversation, &patch.0) {
+            tracing::debug!("Skipping conversation patch that did not apply: {}", e);
+        }
+    }
+
+    conversation["entries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry["type"] == "NORMALIZED_ENTRY")
+        .filter_map(|entry| serde_json::from_value(entry["content"].clone()).ok())
+        .collect()
+}
+
+/// Blank out tool results and command output, keeping what was run
+pub fn redact_tool_outputs(entries: &mut [NormalizedEntry]) {
+    for entry in entries {
+        let NormalizedEntryType::ToolUse { action_type, .. } = &mut entry.entry_type else {
+            continue;
+        };
+        match action_type {
+            ActionType::CommandRun {
+                result: Some(result),
+                ..
+            } => {
+                if result.output.is_some() {
+                    result.output = Some(REDACTED.to_string());
+                }
+            }
+            ActionType::Tool { result, .. } | ActionType::TaskCreate { result, .. } => {
+                if result.is_some() {
+                    *result = Some(ToolResult::markdown(REDACTED));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Renderer-independent structure shared by the Markdown and HTML output
+enum Block {
+    Heading(u8, String),
+    Meta(String),
+    Label(String),
+    Text(String),
+    Quote(String),
+    Code {
+        language: &'static str,
+        code: String,
+    },
+    Details {
+        summary: String,
+        blocks: Vec<Block>,
+    },
+}
+
+fn entry_blocks(entry: &NormalizedEntry) -> Vec<Block> {
+    let content = entry.content.clone();
+    match &entry.entry_type {
+        NormalizedEntryType::UserMessage => {
+            vec![Block::Heading(3, "User".to_string()), Block::Text(content)]
+        }
+        NormalizedEntryType::AssistantMessage => {
+            vec![
+                Block::Heading(3, "Assistant".to_string()),
+                Block::Text(content),
+            ]
+        }
+        NormalizedEntryType::Thinking => vec![Block::Details {
+            summary: "Thinking".to_string(),
+            blocks: vec![Block::Text(content)],
+        }],
+        NormalizedEntryType::UserFeedback { denied_tool } => vec![
+            Block::Label(format!("User denied {denied_tool}")),
+            Block::Quote(content),
+        ],
+        NormalizedEntryType::SystemMessage => vec![Block::Quote(content)],
+        NormalizedEntryType::ErrorMessage { .. } => vec![Block::Quote(format!("Error: {content}"))],
+        NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+            status,
+        } => tool_blocks(tool_name, action_type, status, content),
+        NormalizedEntryType::TokenUsageInfo(usage) => vec![Block::Meta(format!(
+            "Token usage: {} of {} context tokens",
+            usage.total_tokens, usage.model_context_window
+        ))],
+        NormalizedEntryType::Loading | NormalizedEntryType::NextAction { .. } => Vec::new(),
+    }
+}
+
+fn tool_blocks(
+    tool_name: &str,
+    action_type: &ActionType,
+    status: &ToolStatus,
+    content: String,
+) -> Vec<Block> {
+    let status = match status {
+        ToolStatus::Created => "started",
+        ToolStatus::Success => "succeeded",
+        ToolStatus::Failed => "failed",
+        ToolStatus::Denied { .. } => "denied",
+        ToolStatus::PendingApproval { .. } => "pending approval",
+        ToolStatus::TimedOut => "timed out",
+    };
+    let mut blocks = vec![Block::Label(format!("{tool_name} ({status})"))];
+
+    match action_type {
+        ActionType::FileRead { path } => blocks.push(Block::Text(format!("Read `{path}`"))),
+        ActionType::FileEdit { path, changes } => {
+            blocks.push(Block::Text(format!("Edited `{path}`")));
+            for change in changes {
+                match change {
+                    FileChange::Edit { unified_diff, .. } => blocks.push(Block::Code {
+                        language: "diff",
+                        code: unified_diff.clone(),
+                    }),
+                    FileChange::Write { content } => blocks.push(Block::Details {
+                        summary: "Written content".to_string(),
+                        blocks: vec![Block::Code {
+                            language: "",
+                            code: content.clone(),
+                        }],
+                    }),
+                    FileChange::Delete => blocks.push(Block::Text("Deleted".to_string())),
+                    FileChange::Rename { new_path } => {
+                        blocks.push(Block::Text(format!("Renamed to `{new_path}`")))
+                    }
+                }
+            }
+        }
+        ActionType::CommandRun { command, result } => {
+            blocks.push(Block::Code {
+                language: "sh",
+                code: format!("$ {command}"),
+            });
+            if let Some(output) = result.as_ref().and_then(|r| r.output.as_ref()) {
+                blocks.push(Block::Code {
+                    language: "",
+                    code: output.clone(),
+                });
+            }
+        }
+        ActionType::Search { query } => blocks.push(Block::Text(format!("Searched for `{query}`"))),
+        ActionType::WebFetch { url } => blocks.push(Block::Text(format!("Fetched {url}"))),
+        ActionType::Tool {
+            arguments, result, ..
+        } => {
+            if let Some(arguments) = arguments {
+                blocks.push(json_block(arguments));
+            }
+            if let Some(result) = result {
+                blocks.push(tool_result_block(result));
+            }
+        }
+        ActionType::TaskCreate {
+            description,
+            result,
+            ..
+        } => {
+            blocks.push(Block::Text(description.clone()));
+            if let Some(result) = result {
+                blocks.push(tool_result_block(result));
+            }
+        }
+        ActionType::PlanPresentation { plan } => blocks.push(Block::Text(plan.clone())),
+        ActionType::TodoManagement { todos, .. } => blocks.push(Block::Text(
+            todos
+                .iter()
+                .map(|todo| format!("- [{}] {}", todo.status, todo.content))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )),
+        ActionType::Other { .. } => blocks.push(Block::Text(content)),
+    }
+
+    blocks
+}
+
+fn json_block(value: &Value) -> Block {
+    Block::Code {
+        language: "json",
+        code: serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string()),
+    }
+}
+
+fn tool_result_block(result: &ToolResult) -> Block {
+    match &result.value {
+        Value::String(text) => Block::Text(text.clone()),
+        value => json_block(value),
+    }
+}
+
+fn render_markdown(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                out.push_str(&format!("{} {text}\n\n", "#".repeat(usize::from(*level))))
+            }
+            Block::Meta(text) => out.push_str(&format!("_{text}_\n\n")),
+            Block::Label(text) => out.push_str(&format!("**{text}**\n\n")),
+            Block::Text(text) => out.push_str(&format!("{}\n\n", text.trim_end())),
+            Block::Quote(text) => {
+                for line in text.trim_end().lines() {
+                    out.push_str(&format!("> {line}\n"));
+                }
+                out.push('\n');
+            }
+            Block::Code { language, code } => {
+                let fence = code_fence(code);
+                out.push_str(&format!(
+                    "{fence}{language}\n{}\n{fence}\n\n",
+                    code.trim_end()
+                ));
+            }
+            Block::Details { summary, blocks } => {
+                out.push_str(&format!("<details>\n<summary>{summary}</summary>\n\n"));
+                out.push_str(&render_markdown(blocks));
+                out.push_str("</details>\n\n");
+            }
+        }
+    }
+    out
+}
+
+/// A backtick fence longer than any run of backticks inside `code`
+fn code_fence(code: &str) -> String {
+    let longest_run = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest_run.max(2) + 1)
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:860px;margin:2rem auto;\
+padding:0 1rem;line-height:1.5;color:#1f2328}h1,h2,h3{line-height:1.25}\
+h2{border-bottom:1px solid #d0d7de;padding-bottom:.3rem;margin-top:2rem}\
+.meta{color:#656d76;font-size:.9rem}.text{white-space:pre-wrap}\
+blockquote{margin:0 0 1rem;padding:0 1rem;border-left:4px solid #d0d7de;color:#656d76;\
+white-space:pre-wrap}pre{background:#f6f8fa;padding:.75rem;overflow-x:auto;\
+border-radius:6px}details{margin-bottom:1rem}summary{cursor:pointer;color:#656d76}";
+
+fn render_html(title: &str, blocks: &[Block]) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        render_html_blocks(blocks)
+    )
+}
+
+fn render_html_blocks(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                out.push_str(&format!("<h{level}>{}</h{level}>\n", escape_html(text)))
+            }
+            Block::Meta(text) => {
+                out.push_str(&format!("<p class=\"meta\">{}</p>\n", escape_html(text)))
+            }
+            Block::Label(text) => {
+                out.push_str(&format!("<p><strong>{}</strong></p>\n", escape_html(text)))
+            }
+            Block::Text(text) => out.push_str(&format!(
+                "<div class=\"text\">{}</div>\n",
+                escape_html(text.trim_end())
+            )),
+            Block::Quote(text) => out.push_str(&format!(
+                "<blockquote>{}</blockquote>\n",
+                escape_html(text.trim_end())
+            )),
+            Block::Code { language, code } => out.push_str(&format!(
+                "<pre><code class=\"language-{language}\">{}</code></pre>\n",
+                escape_html(code.trim_end())
+            )),
+            Block::Details { summary, blocks } => out.push_str(&format!(
+                "<details>\n<summary>{}</summary>\n{}</details>\n",
+                escape_html(summary),
+                render_html_blocks(blocks)
+            )),
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::CommandRunResult;
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn command_entry(output: &str) -> NormalizedEntry {
+        entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "ls".to_string(),
+                    result: Some(CommandRunResult {
+                        exit_status: None,
+                        output: Some(output.to_string()),
+                    }),
+                },
+                status: ToolStatus::Success,
+            },
+            "ls",
+        )
+    }
+
+    fn transcript(entries: Vec<NormalizedEntry>) -> SessionTranscript {
+        SessionTranscript {
+            session_id: Uuid::nil(),
+            workspace_id: Uuid::nil(),
+            task_title: "Fix <bug>".to_string(),
+            exported_at: Utc::now(),
+            conversations: vec![NormalizedConversation {
+                entries,
+                session_id: None,
+                executor_type: "CLAUDE_CODE".to_string(),
+                prompt: None,
+                summary: None,
+            }],
+            diff: None,
+        }
+    }
+
+    #[test]
+    fn replays_patches_into_entries() {
+        let user = entry(NormalizedEntryType::UserMessage, "hi");
+        let reply = entry(NormalizedEntryType::AssistantMessage, "hello");
+        let patches: Vec<Patch> = vec![
+            serde_json::from_value(json!([{
+                "op": "add",
+                "path": "/entries/0",
+                "value": { "type": "NORMALIZED_ENTRY", "content": user },
+            }]))
+            .unwrap(),
+            serde_json::from_value(json!([{
+                "op": "add",
+                "path": "/entries/1",
+                "value": { "type": "STDOUT", "content": "noise" },
+            }]))
+            .unwrap(),
+            serde_json::from_value(json!([{
+                "op": "replace",
+                "path": "/entries/1",
+                "value": { "type": "NORMALIZED_ENTRY", "content": reply },
+            }]))
+            .unwrap(),
+        ];
+
+        let entries = entries_from_patches(&patches);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].content, "hello");
+    }
+
+    #[test]
+    fn redacts_command_output() {
+        let mut entries = vec![command_entry("secret")];
+        redact_tool_outputs(&mut entries);
+        let markdown = transcript(entries)
+            .render(TranscriptFormat::Markdown)
+            .unwrap();
+        assert!(markdown.contains("$ ls"));
+        assert!(markdown.contains(REDACTED));
+        assert!(!markdown.contains("secret"));
+    }
+
+    #[test]
+    fn markdown_fence_outgrows_backticks_in_output() {
+        let markdown = transcript(vec![command_entry("```\nnested\n```")])
+            .render(TranscriptFormat::Markdown)
+            .unwrap();
+        assert!(markdown.contains("````\n```\nnested\n```\n````"));
+    }
+
+    #[test]
+    fn html_escapes_content() {
+        let html = transcript(vec![entry(
+            NormalizedEntryType::AssistantMessage,
+            "<script>alert(1)</script>",
+        )])
+        .render(TranscriptFormat::Html)
+        .unwrap();
+        assert!(html.contains("<title>Fix &lt;bug&gt;</title>"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let original = transcript(vec![command_entry("out")]);
+        let json = original.render(TranscriptFormat::Json).unwrap();
+        let parsed: SessionTranscript = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.conversations[0].entries.len(), 1);
+    }
+}
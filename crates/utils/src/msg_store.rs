@@ -1,6 +1,6 @@
 use std::{
     collections::VecDeque,
-    sync::{Arc, RwLock},
+    sync::{Arc, OnceLock, RwLock, atomic::AtomicUsize},
 };
 
 use axum::response::sse::Event;
@@ -27,6 +27,7 @@ struct Inner {
 pub struct MsgStore {
     inner: RwLock<Inner>,
     sender: broadcast::Sender<LogMsg>,
+    entry_index: OnceLock<Arc<AtomicUsize>>,
 }
 
 impl Default for MsgStore {
@@ -44,6 +45,7 @@ impl MsgStore {
                 total_bytes: 0,
             }),
             sender,
+            entry_index: OnceLock::new(),
         }
     }
 
@@ -91,6 +93,14 @@ impl MsgStore {
         self.sender.subscribe()
     }
 
+    /// Counter for the next normalized entry index, shared by everything that adds
+    /// entries to this store. `seed` computes the starting value on first use.
+    pub fn entry_index(&self, seed: impl FnOnce() -> usize) -> Arc<AtomicUsize> {
+        self.entry_index
+            .get_or_init(|| Arc::new(AtomicUsize::new(seed())))
+            .clone()
+    }
+
     pub fn get_history(&self) -> Vec<LogMsg> {
         self.inner
             .read()
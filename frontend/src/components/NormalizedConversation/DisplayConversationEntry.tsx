@@ -34,6 +34,7 @@ import RawLogText from '../common/RawLogText';
 import UserMessage from './UserMessage';
 import PendingApprovalEntry from './PendingApprovalEntry';
 import { NextActionCard } from './NextActionCard';
+import { RetryCountdown, getRetryAt } from './RetryCountdown';
 import { cn } from '@/lib/utils';
 import { useRetryUi } from '@/contexts/RetryUiContext';
 import { Button } from '@/components/ui/button';
@@ -917,6 +918,7 @@ function DisplayConversationEntry({
   }
 
   if (isSystem || isError) {
+    const retryAt = isNormalizedEntry(entry) ? getRetryAt(entry) : null;
     return (
       <div
         className={`px-4 py-2 text-sm ${greyed ? 'opacity-50 pointer-events-none' : ''}`}
@@ -929,6 +931,7 @@ function DisplayConversationEntry({
           contentClassName={getContentClassName(entryType)}
           taskAttemptId={taskAttempt?.id}
         />
+        {retryAt && <RetryCountdown retryAt={retryAt} />}
       </div>
     );
   }
@@ -0,0 +1,38 @@
+import { useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import type { NormalizedEntry } from 'shared/types';
+
+// Entry metadata isn't part of the generated type; scheduled retries put
+// their start time there
+export const getRetryAt = (entry: NormalizedEntry): string | null => {
+  const { metadata } = entry as NormalizedEntry & {
+    metadata?: { retry_at?: unknown } | null;
+  };
+  return typeof metadata?.retry_at === 'string' ? metadata.retry_at : null;
+};
+
+const secondsUntil = (retryAt: string) =>
+  Math.max(0, Math.ceil((new Date(retryAt).getTime() - Date.now()) / 1000));
+
+export function RetryCountdown({ retryAt }: { retryAt: string }) {
+  const { t } = useTranslation('common');
+  const [seconds, setSeconds] = useState(() => secondsUntil(retryAt));
+
+  useEffect(() => {
+    setSeconds(secondsUntil(retryAt));
+    const interval = setInterval(() => {
+      const remaining = secondsUntil(retryAt);
+      setSeconds(remaining);
+      if (remaining === 0) clearInterval(interval);
+    }, 1000);
+    return () => clearInterval(interval);
+  }, [retryAt]);
+
+  return (
+    <div className="text-xs text-muted-foreground mt-1">
+      {seconds > 0
+        ? t('conversation.retryCountdown', { seconds })
+        : t('conversation.retrying')}
+    </div>
+  );
+}
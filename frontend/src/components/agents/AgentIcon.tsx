@@ -40,6 +40,14 @@ export function getAgentName(
       return 'Droid';
     case BaseCodingAgent.AUGGIE:
       return 'Auggie';
+    case BaseCodingAgent.AIDER:
+      return 'Aider';
+    case BaseCodingAgent.ACP:
+      return 'ACP Agent';
+    case BaseCodingAgent.OPENAI_COMPATIBLE:
+      return 'OpenAI Compatible';
+    case BaseCodingAgent.CUSTOM:
+      return 'Custom';
   }
 }
 
@@ -18,14 +18,14 @@ import {
   useAttempt,
   useRepoBranchSelection,
   useProjectRepos,
+  useProjectExecutorProfile,
 } from '@/hooks';
-import { useTaskAttemptsWithSessions } from '@/hooks/useTaskAttempts';
 import { useProject } from '@/contexts/ProjectContext';
 import { useUserSystem } from '@/components/ConfigProvider';
 import { paths } from '@/lib/paths';
 import NiceModal, { useModal } from '@ebay/nice-modal-react';
 import { defineModal } from '@/lib/modals';
-import type { ExecutorProfileId, BaseCodingAgent } from 'shared/types';
+import type { ExecutorProfileId } from 'shared/types';
 import { useKeySubmitTask, Scope } from '@/keyboard';
 
 export interface CreateAttemptDialogProps {
@@ -38,7 +38,7 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
     const navigate = useNavigateWithSearch();
     const { projectId } = useProject();
     const { t } = useTranslation('tasks');
-    const { profiles, config } = useUserSystem();
+    const { profiles } = useUserSystem();
     const { createAttempt, isCreating, error } = useAttemptCreation({
       taskId,
       onSuccess: (attempt) => {
@@ -51,12 +51,6 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
     const [userSelectedProfile, setUserSelectedProfile] =
       useState<ExecutorProfileId | null>(null);
 
-    const { data: attempts = [], isLoading: isLoadingAttempts } =
-      useTaskAttemptsWithSessions(taskId, {
-        enabled: modal.visible,
-        refetchInterval: 5000,
-      });
-
     const { data: task, isLoading: isLoadingTask } = useTask(taskId, {
       enabled: modal.visible,
     });
@@ -82,14 +76,14 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
       enabled: modal.visible && projectRepos.length > 0,
     });
 
-    const latestAttempt = useMemo(() => {
-      if (attempts.length === 0) return null;
-      return attempts.reduce((latest, attempt) =>
-        new Date(attempt.created_at) > new Date(latest.created_at)
-          ? attempt
-          : latest
-      );
-    }, [attempts]);
+    const repoIds = useMemo(
+      () => projectRepos.map((repo) => repo.id),
+      [projectRepos]
+    );
+    const { data: defaultExecutorProfile, isLoading: isLoadingProfile } =
+      useProjectExecutorProfile(projectId, repoIds, {
+        enabled: modal.visible && projectRepos.length > 0,
+      });
 
     useEffect(() => {
       if (!modal.visible) {
@@ -98,32 +92,17 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
       }
     }, [modal.visible, resetBranchSelection]);
 
-    const defaultProfile: ExecutorProfileId | null = useMemo(() => {
-      if (latestAttempt?.session?.executor) {
-        const lastExec = latestAttempt.session.executor as BaseCodingAgent;
-        // If the last attempt used the same executor as the user's current preference,
-        // we assume they want to use their preferred variant as well.
-        // Otherwise, we default to the "default" variant (null) since we don't know
-        // what variant they used last time (TaskAttempt doesn't store it).
-        const variant =
-          config?.executor_profile?.executor === lastExec
-            ? config.executor_profile.variant
-            : null;
-
-        return {
-          executor: lastExec,
-          variant,
-        };
-      }
-      return config?.executor_profile ?? null;
-    }, [latestAttempt?.session?.executor, config?.executor_profile]);
-
-    const effectiveProfile = userSelectedProfile ?? defaultProfile;
+    // Only a profile the user picked is sent; otherwise the server applies the
+    // same repo, project and global defaults shown here
+    const effectiveProfile =
+      userSelectedProfile ??
+      defaultExecutorProfile?.executor_profile_id ??
+      null;
 
     const isLoadingInitial =
       isLoadingRepos ||
       isLoadingBranches ||
-      isLoadingAttempts ||
+      isLoadingProfile ||
       isLoadingTask ||
       isLoadingParent;
 
@@ -150,7 +129,7 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
         const repos = getWorkspaceRepoInputs();
 
         await createAttempt({
-          profile: effectiveProfile,
+          profile: userSelectedProfile,
           repos,
         });
 
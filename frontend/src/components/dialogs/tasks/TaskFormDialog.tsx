@@ -34,6 +34,7 @@ import {
   useImageUpload,
   useTaskMutations,
   useProjectRepos,
+  useProjectExecutorProfile,
   useRepoBranchSelection,
 } from '@/hooks';
 import {
@@ -78,6 +79,7 @@ type TaskFormValues = {
   title: string;
   description: string;
   status: TaskStatus;
+  /** `null` until the user picks one, leaving it to the server's defaults */
   executorProfileId: ExecutorProfileId | null;
   repoBranches: RepoBranch[];
   autoStart: boolean;
@@ -90,7 +92,7 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
   const { t } = useTranslation(['tasks', 'common']);
   const { createTask, createAndStart, updateTask } =
     useTaskMutations(projectId);
-  const { system, profiles, loading: userSystemLoading } = useUserSystem();
+  const { profiles, loading: userSystemLoading } = useUserSystem();
   const { upload, uploadForTask } = useImageUpload();
   const { enableScope, disableScope } = useHotkeysContext();
 
@@ -108,6 +110,15 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
   const { data: projectRepos = [] } = useProjectRepos(projectId, {
     enabled: modal.visible,
   });
+  const repoIds = useMemo(
+    () => projectRepos.map((repo) => repo.id),
+    [projectRepos]
+  );
+  const { data: defaultExecutorProfile } = useProjectExecutorProfile(
+    projectId,
+    repoIds,
+    { enabled: modal.visible && !editMode && projectRepos.length > 0 }
+  );
   const initialBranch =
     mode === 'subtask' ? props.initialBaseBranch : undefined;
   const { configs: repoBranchConfigs, isLoading: branchesLoading } =
@@ -125,8 +136,6 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
 
   // Get default form values based on mode
   const defaultValues = useMemo((): TaskFormValues => {
-    const baseProfile = system.config?.executor_profile || null;
-
     switch (mode) {
       case 'edit': {
         // Merge title into description so the editor is the single source of truth
@@ -138,7 +147,7 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
           title: '',
           description: mergedDescription,
           status: props.task.status,
-          executorProfileId: baseProfile,
+          executorProfileId: null,
           repoBranches: defaultRepoBranches,
           autoStart: false,
         };
@@ -149,7 +158,7 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
           title: props.initialTask.title,
           description: props.initialTask.description || '',
           status: 'todo',
-          executorProfileId: baseProfile,
+          executorProfileId: null,
           repoBranches: defaultRepoBranches,
           autoStart: true,
         };
@@ -161,13 +170,13 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
           title: '',
           description: '',
           status: 'todo',
-          executorProfileId: baseProfile,
+          executorProfileId: null,
           repoBranches: defaultRepoBranches,
           autoStart:
             mode === 'create' && props.initialStatus === 'todo' ? false : true,
         };
     }
-  }, [mode, props, system.config?.executor_profile, defaultRepoBranches]);
+  }, [mode, props, defaultRepoBranches]);
 
   // Form submission handler
   const handleSubmit = async ({ value }: { value: TaskFormValues }) => {
@@ -212,7 +221,7 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
         await createAndStart.mutateAsync(
           {
             task,
-            executor_profile_id: value.executorProfileId!,
+            executor_profile_id: value.executorProfileId,
             repos,
           },
           { onSuccess: () => modal.remove() }
@@ -232,7 +241,6 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
       if (!normalizedTitle.trim().length) return 'need title';
     }
     if (value.autoStart && !forceCreateOnlyRef.current) {
-      if (!value.executorProfileId) return 'need executor profile';
       if (
         value.repoBranches.length === 0 ||
         value.repoBranches.some((rb) => !rb.branch)
@@ -529,7 +537,11 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
                         {(field) => (
                           <ExecutorProfileSelector
                             profiles={profiles}
-                            selectedProfile={field.state.value}
+                            selectedProfile={
+                              field.state.value ??
+                              defaultExecutorProfile?.executor_profile_id ??
+                              null
+                            }
                             onProfileSelect={(profile) =>
                               field.handleChange(profile)
                             }
@@ -252,6 +252,13 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
                           })}
                         </p>
                       )}
+                      {process.completion_reason && (
+                        <p className="text-xs text-muted-foreground mt-1">
+                          {t(
+                            `processes.completionReason.${process.completion_reason}`
+                          )}
+                        </p>
+                      )}
                     </div>
                   </div>
                   <div className="mt-3 text-xs text-muted-foreground">
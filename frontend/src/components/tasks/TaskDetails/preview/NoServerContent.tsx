@@ -10,7 +10,6 @@ import {
 } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import { Project } from 'shared/types';
-import { useUserSystem } from '@/components/ConfigProvider';
 import { useTaskMutations } from '@/hooks/useTaskMutations';
 import { useProjectRepos } from '@/hooks';
 import {
@@ -41,7 +40,6 @@ export function NoServerContent({
 }: NoServerContentProps) {
   const { t } = useTranslation('tasks');
   const navigate = useNavigate();
-  const { config } = useUserSystem();
 
   const { createAndStart } = useTaskMutations(project?.id);
   const { data: projectRepos = [] } = useProjectRepos(project?.id);
@@ -55,7 +53,7 @@ export function NoServerContent({
   };
 
   const handleInstallCompanion = () => {
-    if (!project || !config || projectRepos.length === 0) return;
+    if (!project || projectRepos.length === 0) return;
 
     const repos = projectRepos.map((repo) => ({
       repo_id: repo.id,
@@ -71,7 +69,7 @@ export function NoServerContent({
         parent_workspace_id: null,
         image_ids: null,
       },
-      executor_profile_id: config.executor_profile,
+      executor_profile_id: null,
       repos,
     });
   };
@@ -155,7 +153,7 @@ export function NoServerContent({
               <Button
                 size="sm"
                 onClick={handleInstallCompanion}
-                disabled={!project || !config || createAndStart.isPending}
+                disabled={!project || createAndStart.isPending}
                 className="gap-1"
                 variant="outline"
               >
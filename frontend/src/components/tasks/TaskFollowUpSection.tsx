@@ -8,6 +8,7 @@ import {
   Paperclip,
   Terminal,
   MessageSquare,
+  ArrowRightLeft,
 } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import { Alert, AlertDescription } from '@/components/ui/alert';
@@ -48,8 +49,10 @@ import { useRetryUi } from '@/contexts/RetryUiContext';
 import { useFollowUpSend } from '@/hooks/useFollowUpSend';
 import { useVariant } from '@/hooks/useVariant';
 import type {
+  BaseCodingAgent,
   DraftFollowUpData,
   ExecutorProfileId,
+  QueuedMessage,
   QueueStatus,
 } from 'shared/types';
 import { getLatestProfileFromProcesses } from '@/utils/executor';
@@ -65,6 +68,13 @@ import type { NormalizedComment } from '@/components/ui/wysiwyg/nodes/pr-comment
 import type { Session } from 'shared/types';
 import { buildAgentPrompt } from '@/utils/promptMessage';
 
+// The editor shows the most recently queued message
+function lastQueuedMessage(status?: QueueStatus): QueuedMessage | null {
+  return status?.status === 'queued'
+    ? (status.messages[status.messages.length - 1] ?? null)
+    : null;
+}
+
 interface TaskFollowUpSectionProps {
   task: TaskWithAttemptStatus;
   session?: Session;
@@ -161,6 +171,15 @@ export function TaskFollowUpSection({
     return profiles?.[latestProfileId.executor] ?? null;
   }, [latestProfileId, profiles]);
 
+  // Agents the session can be handed off to
+  const handoffAgents = useMemo(
+    () =>
+      (Object.keys(profiles ?? {}) as BaseCodingAgent[])
+        .filter((agent) => agent !== latestProfileId?.executor)
+        .sort(),
+    [profiles, latestProfileId?.executor]
+  );
+
   // Variant selection with priority: user selection > scratch > process
   const { selectedVariant, setSelectedVariant: setVariantFromHook } =
     useVariant({
@@ -254,9 +273,7 @@ export function TaskFollowUpSection({
   });
 
   const isQueued = queueStatus.status === 'queued';
-  const queuedMessage = isQueued
-    ? (queueStatus as Extract<QueueStatus, { status: 'queued' }>).message
-    : null;
+  const queuedMessage = lastQueuedMessage(queueStatus);
 
   const queueMutation = useMutation({
     mutationFn: ({
@@ -278,6 +295,14 @@ export function TaskFollowUpSection({
     },
   });
 
+  // Editing takes the displayed (most recently queued) message back out of the queue
+  const unqueueMutation = useMutation({
+    mutationFn: (messageId: string) => queueApi.remove(sessionId!, messageId),
+    onSuccess: (status) => {
+      queryClient.setQueryData([QUEUE_STATUS_KEY, sessionId], status);
+    },
+  });
+
   const queueMessage = useCallback(
     async (message: string, executorProfileId: ExecutorProfileId) => {
       if (!sessionId) return;
@@ -294,7 +319,10 @@ export function TaskFollowUpSection({
     await cancelMutation.mutateAsync();
   }, [sessionId, cancelMutation]);
 
-  const isQueueLoading = queueMutation.isPending || cancelMutation.isPending;
+  const isQueueLoading =
+    queueMutation.isPending ||
+    cancelMutation.isPending ||
+    unqueueMutation.isPending;
 
   // Track previous process count to detect new processes
   const prevProcessCountRef = useRef(processes.length);
@@ -345,23 +373,28 @@ export function TaskFollowUpSection({
   }, [entries]);
 
   // Send follow-up action
-  const { isSendingFollowUp, followUpError, setFollowUpError, onSendFollowUp } =
-    useFollowUpSend({
-      sessionId,
-      message: localMessage,
-      conflictMarkdown: conflictResolutionInstructions,
-      reviewMarkdown,
-      clickedMarkdown,
-      executor: latestProfileId?.executor ?? null,
-      variant: selectedVariant,
-      clearComments,
-      clearClickedElements,
-      onAfterSendCleanup: () => {
-        cancelDebouncedSave(); // Cancel any pending debounced save to avoid race condition
-        setLocalMessage(''); // Clear local state immediately
-        // Scratch deletion is handled by the backend when the queued message is consumed
-      },
-    });
+  const {
+    isSendingFollowUp,
+    followUpError,
+    setFollowUpError,
+    onSendFollowUp,
+    onHandoff,
+  } = useFollowUpSend({
+    sessionId,
+    message: localMessage,
+    conflictMarkdown: conflictResolutionInstructions,
+    reviewMarkdown,
+    clickedMarkdown,
+    executor: latestProfileId?.executor ?? null,
+    variant: selectedVariant,
+    clearComments,
+    clearClickedElements,
+    onAfterSendCleanup: () => {
+      cancelDebouncedSave(); // Cancel any pending debounced save to avoid race condition
+      setLocalMessage(''); // Clear local state immediately
+      // Scratch deletion is handled by the backend when the queued message is consumed
+    },
+  });
 
   // Separate logic for when textarea should be disabled vs when send button should be disabled
   const canTypeFollowUp = useMemo(() => {
@@ -499,10 +532,7 @@ export function TaskFollowUpSection({
       sessionId,
     ]);
     const queued = status?.status === 'queued';
-    const message = queued
-      ? (status as Extract<QueueStatus, { status: 'queued' }>).message
-      : null;
-    return { isQueued: queued, queuedMessage: message };
+    return { isQueued: queued, queuedMessage: lastQueuedMessage(status) };
   }, [queryClient, sessionId]);
 
   // Handle image paste - upload to container and insert markdown
@@ -522,7 +552,7 @@ export function TaskFollowUpSection({
             queuedMessage: currentQueuedMessage,
           } = getQueueState();
           if (currentlyQueued && currentQueuedMessage) {
-            cancelMutation.mutate();
+            unqueueMutation.mutate(currentQueuedMessage.id);
             const base = currentQueuedMessage.data.message;
             const newMessage = base
               ? `${base}\n\n${imageMarkdown}`
@@ -543,7 +573,7 @@ export function TaskFollowUpSection({
         }
       }
     },
-    [workspaceId, getQueueState, cancelMutation]
+    [workspaceId, getQueueState, unqueueMutation]
   );
 
   // Attachment button - file input ref and handlers
@@ -604,7 +634,7 @@ export function TaskFollowUpSection({
       const { isQueued: currentlyQueued, queuedMessage: currentQueuedMessage } =
         getQueueState();
       if (currentlyQueued && currentQueuedMessage) {
-        cancelMutation.mutate();
+        unqueueMutation.mutate(currentQueuedMessage.id);
         const base = currentQueuedMessage.data.message;
         const newMessage = base ? `${base}\n\n${markdown}` : markdown;
         setLocalMessage(newMessage);
@@ -617,21 +647,21 @@ export function TaskFollowUpSection({
         });
       }
     }
-  }, [workspaceId, getSelectedRepoId, getQueueState, cancelMutation]);
+  }, [workspaceId, getSelectedRepoId, getQueueState, unqueueMutation]);
 
   // Stable onChange handler for WYSIWYGEditor
   const handleEditorChange = useCallback(
     (value: string) => {
-      // Auto-cancel queue when user starts editing
-      const { isQueued: currentlyQueued } = getQueueState();
-      if (currentlyQueued) {
-        cancelMutation.mutate();
+      // Auto-unqueue the displayed message when user starts editing
+      const { queuedMessage: currentQueuedMessage } = getQueueState();
+      if (currentQueuedMessage) {
+        unqueueMutation.mutate(currentQueuedMessage.id);
       }
       setLocalMessage(value); // Immediate update for UI responsiveness
       setFollowUpMessageRef.current(value); // Debounced save to scratch
       if (followUpErrorRef.current) setFollowUpError(null);
     },
-    [setFollowUpError, getQueueState, cancelMutation]
+    [setFollowUpError, getQueueState, unqueueMutation]
   );
 
   // Memoize placeholder to avoid re-renders
@@ -938,6 +968,33 @@ export function TaskFollowUpSection({
                   {t('followUp.clearReviewComments')}
                 </Button>
               )}
+              {handoffAgents.length > 0 && (
+                <DropdownMenu>
+                  <DropdownMenuTrigger asChild>
+                    <Button
+                      disabled={!canSendFollowUp || !isEditable}
+                      size="sm"
+                      variant="outline"
+                      title={t('followUp.handoff.tooltip')}
+                    >
+                      <ArrowRightLeft className="h-4 w-4 mr-2" />
+                      {t('followUp.handoff.button')}
+                    </Button>
+                  </DropdownMenuTrigger>
+                  <DropdownMenuContent align="end">
+                    {handoffAgents.map((agent) => (
+                      <DropdownMenuItem
+                        key={agent}
+                        onClick={() =>
+                          onHandoff({ executor: agent, variant: null })
+                        }
+                      >
+                        {agent}
+                      </DropdownMenuItem>
+                    ))}
+                  </DropdownMenuContent>
+                </DropdownMenu>
+              )}
               <Button
                 onClick={onSendFollowUp}
                 disabled={!canSendFollowUp || !isEditable}
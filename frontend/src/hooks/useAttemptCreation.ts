@@ -7,7 +7,8 @@ import type {
 } from 'shared/types';
 
 type CreateAttemptArgs = {
-  profile: ExecutorProfileId;
+  /** `null` leaves the choice to the repo, project and global defaults */
+  profile: ExecutorProfileId | null;
   repos: WorkspaceRepoInput[];
 };
 
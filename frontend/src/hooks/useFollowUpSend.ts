@@ -1,6 +1,10 @@
 import { useCallback, useState } from 'react';
 import { sessionsApi } from '@/lib/api';
-import type { BaseCodingAgent, CreateFollowUpAttempt } from 'shared/types';
+import type {
+  BaseCodingAgent,
+  CreateFollowUpAttempt,
+  ExecutorProfileId,
+} from 'shared/types';
 import { buildAgentPrompt } from '@/utils/promptMessage';
 
 type Args = {
@@ -31,58 +35,73 @@ export function useFollowUpSend({
   const [isSendingFollowUp, setIsSendingFollowUp] = useState(false);
   const [followUpError, setFollowUpError] = useState<string | null>(null);
 
-  const onSendFollowUp = useCallback(async () => {
-    if (!sessionId || !executor) return;
-    const extraMessage = message.trim();
-    const { prompt, isSlashCommand } = buildAgentPrompt(extraMessage, [
-      conflictMarkdown,
-      clickedMarkdown?.trim(),
-      reviewMarkdown?.trim(),
-    ]);
+  const sendFollowUp = useCallback(
+    async (profile: ExecutorProfileId, handoff: boolean) => {
+      if (!sessionId) return;
+      const extraMessage = message.trim();
+      const { prompt, isSlashCommand } = buildAgentPrompt(extraMessage, [
+        conflictMarkdown,
+        clickedMarkdown?.trim(),
+        reviewMarkdown?.trim(),
+      ]);
 
-    if (!prompt) return;
-    try {
-      setIsSendingFollowUp(true);
-      setFollowUpError(null);
-      const body: CreateFollowUpAttempt = {
-        prompt: prompt,
-        executor_profile_id: { executor, variant },
-        retry_process_id: null,
-        force_when_dirty: null,
-        perform_git_reset: null,
-      };
-      await sessionsApi.followUp(sessionId, body);
-      if (!isSlashCommand) {
-        clearComments();
-        clearClickedElements?.();
+      if (!prompt) return;
+      try {
+        setIsSendingFollowUp(true);
+        setFollowUpError(null);
+        const body: CreateFollowUpAttempt = {
+          prompt: prompt,
+          executor_profile_id: profile,
+          retry_process_id: null,
+          force_when_dirty: null,
+          perform_git_reset: null,
+          handoff,
+        };
+        await sessionsApi.followUp(sessionId, body);
+        if (!isSlashCommand) {
+          clearComments();
+          clearClickedElements?.();
+        }
+        onAfterSendCleanup();
+        // Don't call jumpToLogsTab() - preserves focus on the follow-up editor
+      } catch (error: unknown) {
+        const err = error as { message?: string };
+        setFollowUpError(
+          `Failed to start follow-up execution: ${err.message ?? 'Unknown error'}`
+        );
+      } finally {
+        setIsSendingFollowUp(false);
       }
-      onAfterSendCleanup();
-      // Don't call jumpToLogsTab() - preserves focus on the follow-up editor
-    } catch (error: unknown) {
-      const err = error as { message?: string };
-      setFollowUpError(
-        `Failed to start follow-up execution: ${err.message ?? 'Unknown error'}`
-      );
-    } finally {
-      setIsSendingFollowUp(false);
-    }
-  }, [
-    sessionId,
-    message,
-    conflictMarkdown,
-    reviewMarkdown,
-    clickedMarkdown,
-    executor,
-    variant,
-    clearComments,
-    clearClickedElements,
-    onAfterSendCleanup,
-  ]);
+    },
+    [
+      sessionId,
+      message,
+      conflictMarkdown,
+      reviewMarkdown,
+      clickedMarkdown,
+      clearComments,
+      clearClickedElements,
+      onAfterSendCleanup,
+    ]
+  );
+
+  const onSendFollowUp = useCallback(async () => {
+    if (!executor) return;
+    await sendFollowUp({ executor, variant }, false);
+  }, [sendFollowUp, executor, variant]);
+
+  // Switch the session to another agent, which starts fresh with a summary
+  // of the session so far
+  const onHandoff = useCallback(
+    (profile: ExecutorProfileId) => sendFollowUp(profile, true),
+    [sendFollowUp]
+  );
 
   return {
     isSendingFollowUp,
     followUpError,
     setFollowUpError,
     onSendFollowUp,
+    onHandoff,
   } as const;
 }
@@ -0,0 +1,25 @@
+import { useQuery } from '@tanstack/react-query';
+import { projectsApi } from '@/lib/api';
+import type { EffectiveExecutorProfile } from 'shared/types';
+
+type Options = {
+  enabled?: boolean;
+};
+
+/**
+ * Profile a new workspace of the project on `repoIds` starts with when the
+ * request doesn't name one
+ */
+export function useProjectExecutorProfile(
+  projectId: string | undefined,
+  repoIds: string[],
+  opts?: Options
+) {
+  const enabled = (opts?.enabled ?? true) && !!projectId;
+
+  return useQuery<EffectiveExecutorProfile>({
+    queryKey: ['projectExecutorProfile', projectId, repoIds],
+    queryFn: () => projectsApi.getExecutorProfile(projectId!, repoIds),
+    enabled,
+  });
+}
@@ -10,11 +10,13 @@ import {
   CreatePrApiRequest,
   CreateTask,
   CreateAndStartTaskRequest,
+  EffectiveExecutorProfile,
   CreateTaskAttemptBody,
   CreateTag,
   DirectoryListResponse,
   DirectoryEntry,
   ExecutionProcess,
+  ProjectLogStorage,
   ExecutionProcessRepoState,
   GitBranch,
   Project,
@@ -26,6 +28,7 @@ import {
   SearchMode,
   SearchResult,
   Task,
+  TaskDetails,
   TaskRelationships,
   Tag,
   TagSearchParams,
@@ -69,7 +72,15 @@ import {
   AbortConflictsRequest,
   ContinueRebaseRequest,
   Session,
+  TranscriptFormat,
+  TokenUsageReport,
+  Budget,
+  CreateBudget,
+  UpdateBudget,
+  ExecutionQueueEntry,
+  UpdateQueuedExecution,
   Workspace,
+  WorkspaceDetails,
   StartReviewRequest,
   ReviewError,
   OpenPrInfo,
@@ -285,6 +296,21 @@ export const projectsApi = {
     return handleApiResponse<Repo[]>(response);
   },
 
+  getStorage: async (projectId: string): Promise<ProjectLogStorage> => {
+    const response = await makeRequest(`/api/projects/${projectId}/storage`);
+    return handleApiResponse<ProjectLogStorage>(response);
+  },
+
+  getExecutorProfile: async (
+    projectId: string,
+    repoIds: string[]
+  ): Promise<EffectiveExecutorProfile> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/executor-profile?repo_ids=${encodeURIComponent(repoIds.join(','))}`
+    );
+    return handleApiResponse<EffectiveExecutorProfile>(response);
+  },
+
   addRepository: async (
     projectId: string,
     data: CreateProjectRepo
@@ -315,9 +341,9 @@ export const projectsApi = {
 
 // Task Management APIs
 export const tasksApi = {
-  getById: async (taskId: string): Promise<Task> => {
+  getById: async (taskId: string): Promise<TaskDetails> => {
     const response = await makeRequest(`/api/tasks/${taskId}`);
-    return handleApiResponse<Task>(response);
+    return handleApiResponse<TaskDetails>(response);
   },
 
   create: async (data: CreateTask): Promise<Task> => {
@@ -338,6 +364,7 @@ export const tasksApi = {
     return handleApiResponse<TaskWithAttemptStatus>(response);
   },
 
+  /** Profile a new workspace on these repos starts with when none is chosen */
   update: async (taskId: string, data: UpdateTask): Promise<Task> => {
     const response = await makeRequest(`/api/tasks/${taskId}`, {
       method: 'PUT',
@@ -411,6 +438,19 @@ export const sessionsApi = {
     });
     return handleApiResponse<void>(response);
   },
+
+  getExportUrl: (
+    sessionId: string,
+    format: TranscriptFormat,
+    opts?: { redactToolOutput?: boolean; includeDiff?: boolean }
+  ): string => {
+    const params = new URLSearchParams();
+    params.set('format', format);
+    if (opts?.redactToolOutput) params.set('redact_tool_output', 'true');
+    if (opts?.includeDiff) params.set('include_diff', 'true');
+
+    return `/api/sessions/${sessionId}/export?${params.toString()}`;
+  },
 };
 
 // Task Attempts APIs
@@ -439,9 +479,10 @@ export const attemptsApi = {
     return handleApiResponse<number>(response);
   },
 
-  get: async (attemptId: string): Promise<Workspace> => {
+  /** Profile new coding agent runs use when none is chosen */
+  get: async (attemptId: string): Promise<WorkspaceDetails> => {
     const response = await makeRequest(`/api/task-attempts/${attemptId}`);
-    return handleApiResponse<Workspace>(response);
+    return handleApiResponse<WorkspaceDetails>(response);
   },
 
   update: async (
@@ -1035,9 +1076,17 @@ export const mcpServersApi = {
 
 // Profiles API
 export const profilesApi = {
-  load: async (): Promise<{ content: string; path: string }> => {
+  load: async (): Promise<{
+    content: string;
+    resolved: string;
+    path: string;
+  }> => {
     const response = await makeRequest('/api/profiles');
-    return handleApiResponse<{ content: string; path: string }>(response);
+    return handleApiResponse<{
+      content: string;
+      resolved: string;
+      path: string;
+    }>(response);
   },
   save: async (content: string): Promise<string> => {
     const response = await makeRequest('/api/profiles', {
@@ -1221,6 +1270,93 @@ export const agentsApi = {
   },
 };
 
+// Token usage API
+type UsageScope =
+  | { projectId?: string }
+  | { sessionId: string }
+  | { taskId: string };
+
+export const usageApi = {
+  getReport: async (
+    scope: UsageScope,
+    range?: { from?: string; to?: string }
+  ): Promise<TokenUsageReport> => {
+    const params = new URLSearchParams();
+    if (range?.from) params.set('from', range.from);
+    if (range?.to) params.set('to', range.to);
+
+    let path = '/api/usage';
+    if ('sessionId' in scope) {
+      path = `/api/sessions/${scope.sessionId}/usage`;
+    } else if ('taskId' in scope) {
+      path = `/api/tasks/${scope.taskId}/usage`;
+    } else if (scope.projectId) {
+      path = `/api/projects/${scope.projectId}/usage`;
+    }
+
+    const query = params.toString();
+    const response = await makeRequest(query ? `${path}?${query}` : path);
+    return handleApiResponse<TokenUsageReport>(response);
+  },
+};
+
+// Budgets API
+export const budgetsApi = {
+  list: async (): Promise<Budget[]> => {
+    const response = await makeRequest('/api/budgets');
+    return handleApiResponse<Budget[]>(response);
+  },
+
+  create: async (data: CreateBudget): Promise<Budget> => {
+    const response = await makeRequest('/api/budgets', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Budget>(response);
+  },
+
+  update: async (budgetId: string, data: UpdateBudget): Promise<Budget> => {
+    const response = await makeRequest(`/api/budgets/${budgetId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Budget>(response);
+  },
+
+  delete: async (budgetId: string): Promise<void> => {
+    const response = await makeRequest(`/api/budgets/${budgetId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Execution queue API for coding agents waiting on the concurrency limits
+export const executionQueueApi = {
+  list: async (): Promise<ExecutionQueueEntry[]> => {
+    const response = await makeRequest('/api/execution-queue');
+    return handleApiResponse<ExecutionQueueEntry[]>(response);
+  },
+
+  /**
+   * Change the priority of a scheduled start; stop the execution process to
+   * cancel it
+   */
+  update: async (
+    executionProcessId: string,
+    data: UpdateQueuedExecution
+  ): Promise<ExecutionQueueEntry[]> => {
+    const response = await makeRequest(
+      `/api/execution-queue/${executionProcessId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutionQueueEntry[]>(response);
+  },
+};
+
 // Queue API for session follow-up messages
 export const queueApi = {
   /**
@@ -1238,7 +1374,7 @@ export const queueApi = {
   },
 
   /**
-   * Cancel a queued follow-up message
+   * Cancel every queued follow-up message
    */
   cancel: async (sessionId: string): Promise<QueueStatus> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`, {
@@ -1247,6 +1383,73 @@ export const queueApi = {
     return handleApiResponse<QueueStatus>(response);
   },
 
+  /**
+   * Edit a queued follow-up message
+   */
+  update: async (
+    sessionId: string,
+    messageId: string,
+    data: { message: string; executor_profile_id: ExecutorProfileId }
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Remove one queued follow-up message
+   */
+  remove: async (
+    sessionId: string,
+    messageId: string
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Put the queue in the given order, next to be sent first
+   */
+  reorder: async (
+    sessionId: string,
+    messageIds: string[]
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/reorder`,
+      {
+        method: 'POST',
+        body: JSON.stringify({ message_ids: messageIds }),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Move a queued message to the front of the queue
+   */
+  sendNext: async (
+    sessionId: string,
+    messageId: string
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}/send-next`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
   /**
    * Get the current queue status for a session
    */
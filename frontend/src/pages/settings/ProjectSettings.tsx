@@ -25,17 +25,26 @@ import { Loader2, Plus, Trash2 } from 'lucide-react';
 import { useProjects } from '@/hooks/useProjects';
 import { useProjectMutations } from '@/hooks/useProjectMutations';
 import { RepoPickerDialog } from '@/components/dialogs/shared/RepoPickerDialog';
+import { ExecutorProfileSelector } from '@/components/settings';
+import { useUserSystem } from '@/components/ConfigProvider';
 import { projectsApi } from '@/lib/api';
 import { repoBranchKeys } from '@/hooks/useRepoBranches';
-import type { Project, Repo, UpdateProject } from 'shared/types';
+import type {
+  ExecutorProfileId,
+  Project,
+  Repo,
+  UpdateProject,
+} from 'shared/types';
 
 interface ProjectFormState {
   name: string;
+  defaultExecutorProfileId: ExecutorProfileId | null;
 }
 
 function projectToFormState(project: Project): ProjectFormState {
   return {
     name: project.name,
+    defaultExecutorProfileId: project.default_executor_profile_id,
   };
 }
 
@@ -45,6 +54,7 @@ export function ProjectSettings() {
   const projectIdParam = searchParams.get('projectId') ?? '';
   const { t } = useTranslation('settings');
   const queryClient = useQueryClient();
+  const { profiles } = useUserSystem();
 
   // Fetch all projects
   const {
@@ -292,6 +302,7 @@ export function ProjectSettings() {
     try {
       const updateData: UpdateProject = {
         name: draft.name.trim(),
+        default_executor_profile_id: draft.defaultExecutorProfileId,
       };
 
       updateProject.mutate({
@@ -425,6 +436,39 @@ export function ProjectSettings() {
                 </p>
               </div>
 
+              <div className="space-y-2">
+                <Label>
+                  {t('settings.projects.general.defaultExecutorProfile.label')}
+                </Label>
+                <div className="flex items-center gap-2">
+                  <ExecutorProfileSelector
+                    profiles={profiles}
+                    selectedProfile={draft.defaultExecutorProfileId}
+                    onProfileSelect={(profile) =>
+                      updateDraft({ defaultExecutorProfileId: profile })
+                    }
+                    showLabel={false}
+                    className="flex-1 flex-row"
+                  />
+                  {draft.defaultExecutorProfileId && (
+                    <Button
+                      variant="outline"
+                      size="sm"
+                      onClick={() =>
+                        updateDraft({ defaultExecutorProfileId: null })
+                      }
+                    >
+                      {t(
+                        'settings.projects.general.defaultExecutorProfile.clear'
+                      )}
+                    </Button>
+                  )}
+                </div>
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.general.defaultExecutorProfile.helper')}
+                </p>
+              </div>
+
               {/* Save Button */}
               <div className="flex items-center justify-between pt-4 border-t">
                 {hasUnsavedChanges ? (
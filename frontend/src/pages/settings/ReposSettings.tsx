@@ -25,9 +25,11 @@ import { Loader2 } from 'lucide-react';
 import { useScriptPlaceholders } from '@/hooks/useScriptPlaceholders';
 import { AutoExpandingTextarea } from '@/components/ui/auto-expanding-textarea';
 import { MultiFileSearchTextarea } from '@/components/ui/multi-file-search-textarea';
+import { ExecutorProfileSelector } from '@/components/settings';
+import { useUserSystem } from '@/components/ConfigProvider';
 import { repoApi } from '@/lib/api';
 import { useQuery, useQueryClient } from '@tanstack/react-query';
-import type { Repo, UpdateRepo } from 'shared/types';
+import type { ExecutorProfileId, Repo, UpdateRepo } from 'shared/types';
 
 interface RepoScriptsFormState {
   display_name: string;
@@ -36,6 +38,10 @@ interface RepoScriptsFormState {
   cleanup_script: string;
   copy_files: string;
   dev_server_script: string;
+  dev_server_ready_pattern: string;
+  dev_server_health_path: string;
+  dev_server_auto_restart: boolean;
+  default_executor_profile_id: ExecutorProfileId | null;
 }
 
 function repoToFormState(repo: Repo): RepoScriptsFormState {
@@ -46,6 +52,10 @@ function repoToFormState(repo: Repo): RepoScriptsFormState {
     cleanup_script: repo.cleanup_script ?? '',
     copy_files: repo.copy_files ?? '',
     dev_server_script: repo.dev_server_script ?? '',
+    dev_server_ready_pattern: repo.dev_server_ready_pattern ?? '',
+    dev_server_health_path: repo.dev_server_health_path ?? '',
+    dev_server_auto_restart: repo.dev_server_auto_restart,
+    default_executor_profile_id: repo.default_executor_profile_id,
   };
 }
 
@@ -54,6 +64,7 @@ export function ReposSettings() {
   const repoIdParam = searchParams.get('repoId') ?? '';
   const { t } = useTranslation('settings');
   const queryClient = useQueryClient();
+  const { profiles } = useUserSystem();
 
   // Fetch all repos
   const {
@@ -182,6 +193,10 @@ export function ReposSettings() {
         copy_files: draft.copy_files.trim() || null,
         parallel_setup_script: draft.parallel_setup_script,
         dev_server_script: draft.dev_server_script.trim() || null,
+        dev_server_ready_pattern: draft.dev_server_ready_pattern.trim() || null,
+        dev_server_health_path: draft.dev_server_health_path.trim() || null,
+        dev_server_auto_restart: draft.dev_server_auto_restart,
+        default_executor_profile_id: draft.default_executor_profile_id,
       };
 
       const updatedRepo = await repoApi.update(selectedRepo.id, updateData);
@@ -325,6 +340,37 @@ export function ReposSettings() {
                   {selectedRepo.path}
                 </div>
               </div>
+
+              <div className="space-y-2">
+                <Label>
+                  {t('settings.repos.general.defaultExecutorProfile.label')}
+                </Label>
+                <div className="flex items-center gap-2">
+                  <ExecutorProfileSelector
+                    profiles={profiles}
+                    selectedProfile={draft.default_executor_profile_id}
+                    onProfileSelect={(profile) =>
+                      updateDraft({ default_executor_profile_id: profile })
+                    }
+                    showLabel={false}
+                    className="flex-1 flex-row"
+                  />
+                  {draft.default_executor_profile_id && (
+                    <Button
+                      variant="outline"
+                      size="sm"
+                      onClick={() =>
+                        updateDraft({ default_executor_profile_id: null })
+                      }
+                    >
+                      {t('settings.repos.general.defaultExecutorProfile.clear')}
+                    </Button>
+                  )}
+                </div>
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.repos.general.defaultExecutorProfile.helper')}
+                </p>
+              </div>
             </CardContent>
           </Card>
 
@@ -355,6 +401,61 @@ export function ReposSettings() {
                 <p className="text-sm text-muted-foreground">
                   {t('settings.repos.scripts.devServer.helper')}
                 </p>
+
+                <div className="flex items-center space-x-2 pt-2">
+                  <Checkbox
+                    id="dev-server-auto-restart"
+                    checked={draft.dev_server_auto_restart}
+                    onCheckedChange={(checked) =>
+                      updateDraft({
+                        dev_server_auto_restart: checked === true,
+                      })
+                    }
+                    disabled={!draft.dev_server_script.trim()}
+                  />
+                  <Label
+                    htmlFor="dev-server-auto-restart"
+                    className="text-sm font-normal cursor-pointer"
+                  >
+                    {t('settings.repos.scripts.devServer.autoRestartLabel')}
+                  </Label>
+                </div>
+              </div>
+
+              <div className="space-y-2">
+                <Label htmlFor="dev-server-ready-pattern">
+                  {t('settings.repos.scripts.devServer.readyPattern.label')}
+                </Label>
+                <Input
+                  id="dev-server-ready-pattern"
+                  value={draft.dev_server_ready_pattern}
+                  onChange={(e) =>
+                    updateDraft({ dev_server_ready_pattern: e.target.value })
+                  }
+                  placeholder="Listening on (?<url>http://\S+)"
+                  className="font-mono"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.repos.scripts.devServer.readyPattern.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label htmlFor="dev-server-health-path">
+                  {t('settings.repos.scripts.devServer.healthPath.label')}
+                </Label>
+                <Input
+                  id="dev-server-health-path"
+                  value={draft.dev_server_health_path}
+                  onChange={(e) =>
+                    updateDraft({ dev_server_health_path: e.target.value })
+                  }
+                  placeholder="/healthz"
+                  className="font-mono"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.repos.scripts.devServer.healthPath.helper')}
+                </p>
               </div>
 
               <div className="space-y-2">
@@ -4,11 +4,20 @@
 
 // If you are an AI, and you absolutely have to edit this file, please confirm with the user first.
 
-export type Project = { id: string, name: string, default_agent_working_dir: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, default_agent_working_dir: string | null, 
+/**
+ * Coding agent profile for workspaces of this project, ahead of the
+ * global default
+ */
+default_executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Instructions for coding agents working on this project's tasks
+ */
+agent_instructions: string | null, created_at: Date, updated_at: Date, };
 
 export type CreateProject = { name: string, repositories: Array<CreateProjectRepo>, };
 
-export type UpdateProject = { name: string | null, };
+export type UpdateProject = { name: string | null, default_executor_profile_id?: ExecutorProfileId | null, agent_instructions?: string | null, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, 
 /**
@@ -18,9 +27,27 @@ score: bigint, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
-export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
+export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, 
+/**
+ * Custom regex for the dev server's "listening" line. A named `url` or
+ * `port` group (or the first group) locates the address.
+ */
+dev_server_ready_pattern: string | null, 
+/**
+ * Path polled over HTTP once the dev server is ready, e.g. `/healthz`
+ */
+dev_server_health_path: string | null, dev_server_auto_restart: boolean, default_target_branch: string | null, default_working_dir: string | null, 
+/**
+ * Coding agent profile for workspaces of this repo, ahead of the
+ * project and global defaults
+ */
+default_executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Instructions for coding agents working in this repo
+ */
+agent_instructions: string | null, created_at: Date, updated_at: Date, };
 
-export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, archive_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, default_working_dir?: string | null, };
+export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, archive_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, dev_server_ready_pattern?: string | null, dev_server_health_path?: string | null, dev_server_auto_restart?: boolean | null, default_target_branch?: string | null, default_working_dir?: string | null, default_executor_profile_id?: ExecutorProfileId | null, agent_instructions?: string | null, };
 
 export type ProjectRepo = { id: string, project_id: string, repo_id: string, };
 
@@ -30,7 +57,16 @@ export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string,
 
 export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
 
-export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
+export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, 
+/**
+ * Custom regex for the dev server's "listening" line. A named `url` or
+ * `port` group (or the first group) locates the address.
+ */
+dev_server_ready_pattern: string | null, 
+/**
+ * Path polled over HTTP once the dev server is ready, e.g. `/healthz`
+ */
+dev_server_health_path: string | null, dev_server_auto_restart: boolean, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
 
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -146,14 +182,70 @@ export type ExecutionProcess = { id: string, session_id: string, run_reason: Exe
  * history view (due to restore/trimming). Hidden from logs/timeline;
  * still listed in the Processes tab.
  */
-dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
+dropped: boolean, 
+/**
+ * Listening URL detected in a dev server's output; set once it is ready.
+ */
+ready_url: string | null, ready_at: string | null, 
+/**
+ * Only set for dev servers with a health check path configured.
+ */
+health_status: DevServerHealth | null, 
+/**
+ * Set when the process was stopped on a limit rather than by the user or on its own.
+ */
+completion_reason: ExecutionProcessCompletionReason | null, 
+/**
+ * Automatic retry after a transient provider error this run is, 0 if it isn't one
+ */
+retry_attempt: bigint, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
 export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
 
 export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "archivescript" | "codingagent" | "devserver";
 
+export type DevServerHealth = "healthy" | "unhealthy";
+
+export type ExecutionProcessCompletionReason = "budget_exceeded" | "timed_out" | "idle_timeout" | "out_of_memory" | "process_limit";
+
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
+export type ExecutionProcessPort = { execution_process_id: string, workspace_id: string, repo_id: string, port: bigint, created_at: Date, };
+
+export type ProjectLogStorage = { project_id: string, execution_process_count: bigint, 
+/**
+ * Processes whose raw logs were removed or compacted by the retention policy
+ */
+pruned_execution_process_count: bigint, log_row_count: bigint, 
+/**
+ * Uncompressed size of the stored logs
+ */
+log_bytes: bigint, 
+/**
+ * Space the logs take up in the database after compression
+ */
+stored_bytes: bigint, };
+
+export type Budget = { id: string, project_id: string | null, task_id: string | null, executor: string | null, 
+/**
+ * Only with `executor`; unset covers every variant
+ */
+variant: string | null, unit: BudgetUnit, 
+/**
+ * Usage at which the agent is warned
+ */
+soft_limit: number | null, 
+/**
+ * Usage at which the agent is stopped
+ */
+hard_limit: number | null, created_at: string, updated_at: string, };
+
+export type BudgetUnit = "tokens" | "usd";
+
+export type CreateBudget = { project_id: string | null, task_id: string | null, executor: string | null, variant: string | null, unit: BudgetUnit, soft_limit: number | null, hard_limit: number | null, };
+
+export type UpdateBudget = { unit: BudgetUnit, soft_limit: number | null, hard_limit: number | null, };
+
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
 export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
@@ -210,7 +302,12 @@ export type CheckEditorAvailabilityResponse = { available: boolean, };
 
 export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
 
-export type CreateFollowUpAttempt = { prompt: string, executor_profile_id: ExecutorProfileId, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
+export type CreateFollowUpAttempt = { prompt: string, executor_profile_id: ExecutorProfileId, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, 
+/**
+ * Start `executor_profile_id` fresh with a summary of the session so far,
+ * even when the session ran another executor
+ */
+handoff: boolean | null, };
 
 export type ResetProcessRequest = { process_id: string, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
 
@@ -226,7 +323,11 @@ export type RenameBranchRequest = { new_branch_name: string, };
 
 export type RenameBranchResponse = { branch: string, };
 
-export type StartReviewRequest = { executor_profile_id: ExecutorProfileId, additional_prompt: string | null, use_all_workspace_commits: boolean, };
+export type StartReviewRequest = { 
+/**
+ * Falls back to the repo, project and global defaults when omitted
+ */
+executor_profile_id: ExecutorProfileId | null, additional_prompt: string | null, use_all_workspace_commits: boolean, };
 
 export type ReviewError = { "type": "process_already_running" };
 
@@ -234,7 +335,13 @@ export type OpenEditorRequest = { editor_type: string | null, file_path: string
 
 export type OpenEditorResponse = { url: string | null, };
 
-export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CreateAndStartTaskRequest = { task: CreateTask, 
+/**
+ * Falls back to the repo, project and global defaults when omitted
+ */
+executor_profile_id: ExecutorProfileId | null, repos: Array<WorkspaceRepoInput>, };
+
+export type TaskDetails = { executor_profile: EffectiveExecutorProfile, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
 
 export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
 
@@ -242,7 +349,11 @@ export type ImageResponse = { id: string, file_path: string, original_name: stri
 
 export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
 
-export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CreateTaskAttemptBody = { task_id: string, 
+/**
+ * Falls back to the repo, project and global defaults when omitted
+ */
+executor_profile_id: ExecutorProfileId | null, repos: Array<WorkspaceRepoInput>, };
 
 export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
 
@@ -312,6 +423,8 @@ is_target_remote: boolean, };
 
 export type UpdateWorkspace = { archived: boolean | null, pinned: boolean | null, name: string | null, };
 
+export type WorkspaceDetails = { dev_server_ports: Array<ExecutionProcessPort>, executor_profile: EffectiveExecutorProfile, id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
+
 export type WorkspaceSummaryRequest = { archived: boolean, };
 
 export type WorkspaceSummary = { workspace_id: string, 
@@ -366,10 +479,217 @@ export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_pa
 
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder_enabled: boolean, commit_reminder_prompt: string | null, merge_commit_message_template: string | null, send_message_shortcut: SendMessageShortcut, };
+export type TranscriptFormat = "markdown" | "html" | "json";
+
+export type SessionTranscript = { session_id: string, workspace_id: string, task_title: string, exported_at: string, 
+/**
+ * One conversation per coding agent run, oldest first
+ */
+conversations: Array<NormalizedConversation>, 
+/**
+ * Unified diff of the workspace against its target branches, when requested
+ */
+diff: string | null, };
+
+export type TokenUsageReport = { from: string | null, to: string | null, input_tokens: bigint, output_tokens: bigint, cached_input_tokens: bigint, 
+/**
+ * Cost of the usage that has a configured price
+ */
+cost_usd: number, 
+/**
+ * Models used without a matching entry in `token_prices`
+ */
+unpriced_models: Array<string>, by_executor: Array<ExecutorTokenUsage>, lines: Array<TokenUsageLine>, };
+
+export type TokenUsageLine = { executor: string, variant: string | null, model: string, execution_process_count: bigint, input_tokens: bigint, output_tokens: bigint, cached_input_tokens: bigint, 
+/**
+ * `None` when no configured price matches
+ */
+cost_usd: number | null, };
+
+export type ExecutorTokenUsage = { executor: string, input_tokens: bigint, output_tokens: bigint, cached_input_tokens: bigint, 
+/**
+ * Cost of the usage that has a configured price
+ */
+cost_usd: number, };
+
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder_enabled: boolean, commit_reminder_prompt: string | null, merge_commit_message_template: string | null, send_message_shortcut: SendMessageShortcut, dev_server_ports: DevServerPortConfig, log_retention: LogRetentionConfig, token_prices: Array<TokenPrice>, execution_timeouts: ExecutionTimeouts, concurrency_limits: ConcurrencyLimits, execution_retries: ExecutionRetries, resource_limits: ExecutionResourceLimits, sandbox: ExecutionSandbox, container_runtime: ContainerRuntimeConfig, remote_execution: RemoteExecutionConfig, agent_instructions: AgentInstructionsConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
+export type DevServerPortConfig = { enabled: boolean, range_start: number, range_end: number, 
+/**
+ * Extra variable set alongside `PORT`, e.g. `VITE_PORT`
+ */
+env_var: string | null, };
+
+export type LogRetentionConfig = { enabled: boolean, 
+/**
+ * Prune logs of processes that finished more than this many days ago
+ */
+retention_days: number, 
+/**
+ * Also prune logs of archived workspaces regardless of age
+ */
+prune_archived: boolean, 
+/**
+ * Keep the normalized conversation and drop only the raw output
+ */
+keep_normalized: boolean, };
+
+export type TokenPrice = { 
+/**
+ * Model name or prefix, e.g. `claude-sonnet-4`
+ */
+model: string | null, 
+/**
+ * Executor, e.g. `CLAUDE_CODE`
+ */
+executor: string | null, 
+/**
+ * Profile variant, e.g. `PLAN`
+ */
+variant: string | null, input_per_million: number, output_per_million: number, cached_input_per_million: number, };
+
+export type ExecutionTimeouts = { scripts: Array<ScriptTimeout>, };
+
+export type ScriptTimeout = { context: ScriptContext, 
+/**
+ * Stop the process after running this many seconds
+ */
+max_runtime_secs: number | null, 
+/**
+ * Stop the process after this many seconds without stdout or stderr output
+ */
+max_idle_secs: number | null, };
+
+export type ConcurrencyLimits = { 
+/**
+ * Maximum number of coding agents running at once across all executors
+ */
+max_running: number | null, executors: Array<ExecutorConcurrencyLimit>, };
+
+export type ExecutorConcurrencyLimit = { 
+/**
+ * Executor, e.g. `CLAUDE_CODE`
+ */
+executor: string, max_running: number, };
+
+export type ExecutionRetries = { 
+/**
+ * Delay before the first retry
+ */
+base_delay_secs: number, max_delay_secs: number, };
+
+export type ResourceLimits = { 
+/**
+ * CPU time as a number of cores, e.g. `2.5`
+ */
+cpus: number | null, 
+/**
+ * Memory in MiB; going over it gets the process killed
+ */
+memory_mb: number | null, 
+/**
+ * Maximum number of processes and threads
+ */
+max_processes: number | null, };
+
+export type ScriptResourceLimits = { context: ScriptContext, limits: ResourceLimits, };
+
+export type ExecutionResourceLimits = { scripts: Array<ScriptResourceLimits>, };
+
+/**
+ * Where an effective executor profile came from
+ */
+export type ExecutorProfileSource = "request" | "repo" | "project" | "global";
+
+export type EffectiveExecutorProfile = { executor_profile_id: ExecutorProfileId, source: ExecutorProfileSource, 
+/**
+ * Repository whose default applies, when `source` is `repo`
+ */
+repo_id: string | null, };
+
+export type SandboxNetwork = { "mode": "allow" } | { "mode": "deny" } | { "mode": "allowlist", hosts: Array<string>, };
+
+export type SandboxPolicy = { 
+/**
+ * Paths writable besides the workspace, its git directories and the
+ * agents' own state directories
+ */
+writable_paths: Array<string>, 
+/**
+ * Paths hidden besides SSH keys, cloud credentials and Vibe Kanban's data
+ */
+hidden_paths: Array<string>, network: SandboxNetwork, };
+
+export type ScriptSandbox = { context: ScriptContext, policy: SandboxPolicy, };
+
+export type ExecutionSandbox = { scripts: Array<ScriptSandbox>, };
+
+export type ContainerEngine = "docker" | "podman";
+
+export type RepoContainerImage = { 
+/**
+ * Repository name or path
+ */
+repo: string, image: string, };
+
+export type ContainerRuntimeConfig = { 
+/**
+ * Engine to run containers with; unset runs processes on the host.
+ * Takes effect on the next start.
+ */
+engine: ContainerEngine | null, 
+/**
+ * Engine API socket; unset uses the engine's default socket
+ */
+socket: string | null, 
+/**
+ * Image for repositories without an entry in `repos`
+ */
+default_image: string | null, repos: Array<RepoContainerImage>, 
+/**
+ * Network containers join, `host` by default so loopback services stay
+ * reachable
+ */
+network: string, 
+/**
+ * Additional `run` arguments, e.g. extra `--volume` mounts
+ */
+run_args: Array<string>, 
+/**
+ * Commit the container after a successful setup script and start the
+ * repository's later containers from that image
+ */
+cache_setup: boolean, };
+
+export type RemoteExecutionConfig = { 
+/**
+ * `user@host` or a host alias from `~/.ssh/config`; unset runs
+ * processes locally. Read when the server starts.
+ */
+host: string | null, port: number | null, identity_file: string | null, 
+/**
+ * Additional `ssh` arguments, e.g. `["-o", "ProxyJump=bastion"]`
+ */
+ssh_options: Array<string>, };
+
+/**
+ * How instructions reach the agent
+ */
+export type InstructionsDelivery = "prepend" | "append" | "file";
+
+/**
+ * How the project and repository agent instructions reach coding agents
+ */
+export type AgentInstructionsConfig = { delivery: InstructionsDelivery, 
+/**
+ * Add the instructions to follow-up prompts as well as the first one;
+ * ignored with `file` delivery
+ */
+include_follow_ups: boolean, };
+
 export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM" }
 
 export type EditorConfig = { editor_type: EditorType, custom_command: string | null, remote_ssh_host: string | null, remote_ssh_user: string | null, };
@@ -390,7 +710,7 @@ export type SendMessageShortcut = "ModifierEnter" | "Enter";
 
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
-export type QueuedMessage = { 
+export type QueuedMessage = { id: string, 
 /**
  * The session this message is queued for
  */
@@ -402,9 +722,21 @@ data: DraftFollowUpData,
 /**
  * Timestamp when the message was queued
  */
-queued_at: string, };
+queued_at: string, updated_at: string, };
+
+export type QueueStatus = { "status": "empty" } | { "status": "queued", messages: Array<QueuedMessage>, };
+
+export type ExecutionQueueEntry = { execution_process_id: string, workspace_id: string, executor: string, priority: number, queued_at: string, 
+/**
+ * 1-based position in dispatch order
+ */
+position: number, };
 
-export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
+export type UpdateQueuedExecution = { 
+/**
+ * Higher priorities are dispatched first; new starts get 0
+ */
+priority: number, };
 
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
@@ -425,9 +757,9 @@ working_dir: string | null, };
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", AUGGIE = "AUGGIE" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", AUGGIE = "AUGGIE", AIDER = "AIDER", ACP = "ACP", OPENAI_COMPATIBLE = "OPENAI_COMPATIBLE", CUSTOM = "CUSTOM" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "AUGGIE": Auggie };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "AUGGIE": Auggie } | { "AIDER": Aider } | { "ACP": AcpAgent } | { "OPENAI_COMPATIBLE": OpenaiCompatible } | { "CUSTOM": Custom };
 
 export type SlashCommandDescription = { 
 /**
@@ -457,9 +789,40 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "AUGGIE": Auggie } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "AUGGIE": Auggie } | { "AIDER": Aider } | { "ACP": AcpAgent } | { "OPENAI_COMPATIBLE": OpenaiCompatible } | { "CUSTOM": Custom } };
 
-export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+export type ProfileSettings = { 
+/**
+ * Profiles tried in order when this one is unavailable or its run fails on
+ * authentication or a usage limit
+ */
+fallbacks: Array<ExecutorProfileId>, 
+/**
+ * Stop the agent after running this many seconds
+ */
+max_runtime_secs: number | null, 
+/**
+ * Stop the agent after this many seconds without stdout or stderr output
+ */
+max_idle_secs: number | null, 
+/**
+ * Caps applied to the agent on Linux
+ */
+resource_limits: ResourceLimits | null, 
+/**
+ * Runs the agent inside a bubblewrap sandbox on Linux
+ */
+sandbox_policy: SandboxPolicy | null, 
+/**
+ * Automatic retries after a transient provider error; none unless set
+ */
+max_retries: number, };
+
+export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, 
+/**
+ * Settings of the configurations that set any
+ */
+settings: { [key in BaseCodingAgent]?: { [key in string]?: ProfileSettings } }, };
 
 export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER", CONTEXT_USAGE = "CONTEXT_USAGE" }
 
@@ -507,6 +870,70 @@ export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
 
+export type Aider = { append_prompt: AppendPrompt, model?: string | null, edit_format?: AiderEditFormat | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+
+export type AiderEditFormat = "whole" | "diff" | "diff-fenced" | "udiff" | "architect";
+
+export type AcpAgent = { append_prompt: AppendPrompt, command: string, model?: string | null, mode?: string | null, auto_approve?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+
+export type OpenaiCompatible = { append_prompt: AppendPrompt, base_url: string, model: string, api_key_env?: string | null, temperature?: number | null, max_turns?: number | null, auto_approve?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+
+export type Custom = { append_prompt: AppendPrompt, command: string, prompt_input: CustomPromptInput, prompt_flag?: string | null, follow_up_args?: Array<string> | null, resume_args?: Array<string> | null, output: CustomOutput, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+
+export type CustomPromptInput = "stdin" | "argument" | "file";
+
+export type CustomOutput = { "format": "plain_text", 
+/**
+ * Regex whose first capture group is the session id on a stdout line
+ */
+session_id_pattern?: string | null, } | { "format": "jsonl" } & CustomJsonlMapping;
+
+export type CustomJsonlMapping = { 
+/**
+ * JSON pointer to the session id follow-ups continue, e.g. `/session_id`
+ */
+session_id?: string | null, 
+/**
+ * JSON pointer to a message id that resume arguments can refer to
+ */
+message_id?: string | null, 
+/**
+ * Checked in order; the first rule matching a line decides its entry.
+ * Lines no rule matches are dropped.
+ */
+rules: Array<CustomJsonlRule>, };
+
+export type CustomJsonlRule = { 
+/**
+ * Values the line must contain, keyed by JSON pointer. Strings are
+ * compared as is, other values by their JSON text.
+ */
+when: { [key in string]?: string }, entry: CustomJsonlEntry, 
+/**
+ * Text of the entry, or the output of a tool result
+ */
+content?: string | null, tool_name?: string | null, 
+/**
+ * What a tool use does
+ */
+action: CustomJsonlAction, 
+/**
+ * The path, command, query or URL of a tool use, depending on `action`
+ */
+target?: string | null, 
+/**
+ * Pairs a tool result with its tool use
+ */
+tool_call_id?: string | null, 
+/**
+ * Boolean marking a tool result as failed
+ */
+is_error?: string | null, };
+
+export type CustomJsonlEntry = "assistant_message" | "thinking" | "system_message" | "error_message" | "tool_use" | "tool_result" | "ignore";
+
+export type CustomJsonlAction = "file_read" | "file_edit" | "command_run" | "search" | "web_fetch" | "tool" | "other";
+
 export type AppendPrompt = string | null;
 
 export type CodingAgentInitialRequest = { prompt: string, 
@@ -547,11 +974,19 @@ export type CommandExitStatus = { "type": "exit_code", code: number, } | { "type
 
 export type CommandRunResult = { exit_status: CommandExitStatus | null, output: string | null, };
 
+export type NormalizedConversation = { entries: Array<NormalizedEntry>, session_id: string | null, executor_type: string, prompt: string | null, summary: string | null, };
+
 export type NormalizedEntry = { timestamp: string | null, entry_type: NormalizedEntryType, content: string, };
 
 export type NormalizedEntryType = { "type": "user_message" } | { "type": "user_feedback", denied_tool: string, } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, status: ToolStatus, } | { "type": "system_message" } | { "type": "error_message", error_type: NormalizedEntryError, } | { "type": "thinking" } | { "type": "loading" } | { "type": "next_action", failed: boolean, execution_processes: number, needs_setup: boolean, } | { "type": "token_usage_info" } & TokenUsageInfo;
 
-export type TokenUsageInfo = { total_tokens: number, model_context_window: number, };
+export type TokenUsageInfo = { total_tokens: number, model_context_window: number, 
+/**
+ * Tokens billed so far in this run, per model, when the agent reports them
+ */
+run_usage: Array<ModelTokenUsage>, };
+
+export type ModelTokenUsage = { model: string, input_tokens: number, output_tokens: number, cached_input_tokens: number, };
 
 export type FileChange = { "action": "write", content: string, } | { "action": "delete" } | { "action": "rename", new_path: string, } | { "action": "edit", 
 /**
@@ -567,7 +1002,7 @@ export type ActionType = { "action": "file_read", path: string, } | { "action":
 
 export type TodoItem = { content: string, status: string, priority: string | null, };
 
-export type NormalizedEntryError = { "type": "setup_required" } | { "type": "other" };
+export type NormalizedEntryError = { "type": "setup_required" } | { "type": "retryable" } | { "type": "other" };
 
 export type ToolResult = { type: ToolResultValueType, 
 /**